@@ -0,0 +1,95 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Error, Expr};
+
+use crate::{field_name, named_fields, optional_f64};
+
+/// The contents of a field's `#[config(...)]` attribute.
+#[derive(Default)]
+struct FieldAttrs {
+    default: Option<Expr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> Result<Self, Error> {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("config")) {
+            attr.parse_nested_meta(|meta| {
+                let slot = if meta.path.is_ident("default") {
+                    &mut parsed.default
+                } else if meta.path.is_ident("min") {
+                    &mut parsed.min
+                } else if meta.path.is_ident("max") {
+                    &mut parsed.max
+                } else {
+                    return Err(meta.error("expected `default`, `min`, or `max`"));
+                };
+                *slot = Some(meta.value()?.parse()?);
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = named_fields(input, "Config")?;
+
+    let mut infos = Vec::new();
+    let mut defaults = Vec::new();
+    let mut getters = Vec::new();
+    let mut setters = Vec::new();
+    for (index, field) in fields.named.iter().enumerate() {
+        let ident = field.ident.as_ref().unwrap();
+        let key = field_name(ident);
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+
+        let min = optional_f64(&attrs.min);
+        let max = optional_f64(&attrs.max);
+        infos.push(quote! {
+            ::pros::config::FieldInfo { name: #key, min: #min, max: #max }
+        });
+        defaults.push(match &attrs.default {
+            Some(default) => quote!(#ident: #default),
+            None => quote!(#ident: ::core::default::Default::default()),
+        });
+        getters.push(quote! {
+            #key => ::core::option::Option::Some(::pros::config::ConfigValue::to_value(&self.#ident))
+        });
+        setters.push(quote! {
+            #key => ::pros::config::set_field(&mut self.#ident, &Self::FIELDS[#index], value)
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::pros::config::Config for #name #ty_generics #where_clause {
+            const FIELDS: &'static [::pros::config::FieldInfo] = &[#(#infos),*];
+
+            fn defaults() -> Self {
+                Self { #(#defaults),* }
+            }
+
+            fn get(&self, name: &str) -> ::core::option::Option<::pros::config::Value> {
+                match name {
+                    #(#getters,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn set(
+                &mut self,
+                name: &str,
+                value: ::pros::config::Value,
+            ) -> ::core::result::Result<(), ::pros::config::ConfigError> {
+                match name {
+                    #(#setters,)*
+                    _ => ::core::result::Result::Err(::pros::config::ConfigError::unknown_field(name)),
+                }
+            }
+        }
+    })
+}
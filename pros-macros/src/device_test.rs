@@ -0,0 +1,29 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, ItemFn};
+
+pub fn expand(item: &ItemFn) -> Result<TokenStream, Error> {
+    let sig = &item.sig;
+    if !sig.inputs.is_empty() || sig.asyncness.is_some() || !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            sig,
+            "device tests must be synchronous functions without arguments or generics",
+        ));
+    }
+    let ident = &sig.ident;
+
+    // The test becomes a constant describing it, with the function moved inside,
+    // which is the form `#[test_case]` collects.
+    Ok(quote! {
+        #[test_case]
+        #[allow(non_upper_case_globals)]
+        const #ident: ::pros::testing::device::DeviceTest = {
+            #item
+
+            ::pros::testing::device::DeviceTest {
+                name: ::core::concat!(::core::module_path!(), "::", ::core::stringify!(#ident)),
+                run: || ::pros::testing::device::TestOutput::into_result(#ident()),
+            }
+        };
+    })
+}
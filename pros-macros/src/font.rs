@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, LitStr};
+
+/// The characters included in converted fonts.
+const CHARS: std::ops::RangeInclusive<u32> = 0x20..=0x7E;
+
+struct BdfGlyph {
+    advance: i32,
+    width: i32,
+    height: i32,
+    x_offset: i32,
+    y_offset: i32,
+    rows: Vec<Vec<u8>>,
+}
+
+/// The parts of a BDF font needed to draw it.
+struct Bdf {
+    ascent: i32,
+    descent: i32,
+    glyphs: Vec<(u32, BdfGlyph)>,
+}
+
+fn numbers(words: &[&str]) -> Result<Vec<i32>, String> {
+    words
+        .iter()
+        .map(|word| word.parse().map_err(|_| format!("`{word}` isn't a number")))
+        .collect()
+}
+
+fn parse_hex_row(row: &str) -> Result<Vec<u8>, String> {
+    (0..row.len())
+        .step_by(2)
+        .map(|at| {
+            row.get(at..at + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("`{row}` isn't a bitmap row"))
+        })
+        .collect()
+}
+
+fn parse(text: &str) -> Result<Bdf, String> {
+    let mut ascent = None;
+    let mut descent = None;
+    let mut bounding_box = None;
+    let mut glyphs = Vec::new();
+
+    let mut lines = text.lines();
+    let mut encoding = None;
+    let mut advance = 0;
+    let mut bbx = None;
+    while let Some(line) = lines.next() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["FONTBOUNDINGBOX", rest @ ..] => bounding_box = Some(numbers(rest)?),
+            ["FONT_ASCENT", value] => ascent = Some(numbers(&[value])?[0]),
+            ["FONT_DESCENT", value] => descent = Some(numbers(&[value])?[0]),
+            ["STARTCHAR", ..] => {
+                encoding = None;
+                advance = 0;
+                bbx = None;
+            }
+            ["ENCODING", value, ..] => encoding = Some(numbers(&[value])?[0]),
+            ["DWIDTH", x, ..] => advance = numbers(&[x])?[0],
+            ["BBX", rest @ ..] => bbx = Some(numbers(rest)?),
+            ["BITMAP"] => {
+                let [width, height, x_offset, y_offset] = bbx
+                    .as_deref()
+                    .and_then(|bbx| bbx.try_into().ok())
+                    .ok_or("a glyph is missing its BBX")?;
+                let mut rows = Vec::new();
+                for line in lines.by_ref() {
+                    let line = line.trim();
+                    if line == "ENDCHAR" {
+                        break;
+                    }
+                    rows.push(parse_hex_row(line)?);
+                }
+                if let Some(encoding) = encoding.filter(|&encoding| encoding >= 0) {
+                    glyphs.push((
+                        encoding as u32,
+                        BdfGlyph {
+                            advance,
+                            width,
+                            height,
+                            x_offset,
+                            y_offset,
+                            rows,
+                        },
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Fonts without the ascent and descent properties use their bounding box instead.
+    let bounding_box = bounding_box.ok_or("the font is missing its FONTBOUNDINGBOX")?;
+    let [_, box_height, _, box_y] = bounding_box[..] else {
+        return Err("the font's FONTBOUNDINGBOX should have four numbers".into());
+    };
+    Ok(Bdf {
+        ascent: ascent.unwrap_or(box_height + box_y),
+        descent: descent.unwrap_or(-box_y),
+        glyphs,
+    })
+}
+
+pub fn expand(path: &LitStr) -> Result<TokenStream, Error> {
+    // Paths are relative to the crate using the macro, like `include_bytes!` from the crate root.
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = PathBuf::from(root).join(path.value());
+    let text = std::fs::read_to_string(&full_path).map_err(|err| {
+        Error::new_spanned(
+            path,
+            format!("couldn't read `{}`: {err}", full_path.display()),
+        )
+    })?;
+    let font = parse(&text).map_err(|err| Error::new_spanned(path, err))?;
+
+    // Every glyph is placed in a cell as tall as the font, with the baseline `ascent` rows down.
+    let height = (font.ascent + font.descent).clamp(1, u8::MAX as i32);
+    let mut glyphs = Vec::new();
+    let mut bitmap: Vec<u8> = Vec::new();
+    for code in CHARS {
+        let Some((_, glyph)) = font.glyphs.iter().find(|(encoding, _)| *encoding == code) else {
+            // An empty glyph that takes no space marks the character as missing.
+            glyphs.push(quote!(::pros::screen::font::Glyph {
+                width: 0,
+                advance: 0,
+                offset: 0
+            }));
+            continue;
+        };
+        let width = (glyph.width + glyph.x_offset.max(0)).clamp(0, u8::MAX as i32);
+        let row_len = (width as usize).div_ceil(8);
+        let offset = bitmap.len() as u32;
+        let mut cell = vec![0u8; row_len * height as usize];
+        let top = font.ascent - glyph.y_offset - glyph.height;
+        for (row_index, row) in glyph.rows.iter().enumerate() {
+            let y = top + row_index as i32;
+            if y < 0 || y >= height {
+                continue;
+            }
+            for column in 0..glyph.width {
+                let lit = row
+                    .get(column as usize / 8)
+                    .is_some_and(|byte| byte & (0x80 >> (column % 8)) != 0);
+                let x = column + glyph.x_offset.max(0);
+                if lit && x < width {
+                    cell[y as usize * row_len + x as usize / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        bitmap.extend(cell);
+
+        let width = width as u8;
+        let advance = glyph.advance.clamp(0, u8::MAX as i32) as u8;
+        glyphs.push(quote!(::pros::screen::font::Glyph {
+            width: #width,
+            advance: #advance,
+            offset: #offset,
+        }));
+    }
+
+    let height = height as u8;
+    let line_height = height.saturating_add(height / 4);
+    let first_char = *CHARS.start() as u8;
+    let full_path = full_path.to_string_lossy();
+    Ok(quote! {
+        {
+            // Including the file makes Cargo rebuild when it changes.
+            const _: &[u8] = ::core::include_bytes!(#full_path);
+            ::pros::screen::font::Font {
+                height: #height,
+                line_height: #line_height,
+                first_char: #first_char,
+                glyphs: &[#(#glyphs),*],
+                bitmap: &[#(#bitmap),*],
+            }
+        }
+    })
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, LitStr};
+
+use crate::decode;
+
+pub fn expand(path: &LitStr) -> Result<TokenStream, Error> {
+    // Paths are relative to the crate using the macro, like `include_bytes!` from the crate root.
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = PathBuf::from(root).join(path.value());
+    let data = std::fs::read(&full_path).map_err(|err| {
+        Error::new_spanned(
+            path,
+            format!("couldn't read `{}`: {err}", full_path.display()),
+        )
+    })?;
+    let image = decode::decode(&data).map_err(|err| Error::new_spanned(path, err.to_string()))?;
+
+    let width = image.width;
+    let height = image.height;
+    let pixels = &image.pixels;
+    let full_path = full_path.to_string_lossy();
+    Ok(quote! {
+        {
+            // Including the file makes Cargo rebuild when it changes.
+            const _: &[u8] = ::core::include_bytes!(#full_path);
+            ::pros::image::Image::from_static(#width, #height, &[#(#pixels),*])
+        }
+    })
+}
@@ -0,0 +1,113 @@
+//! Derive macros for pros-rs. These are re-exported by `pros` and should be used from there.
+
+extern crate alloc;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, Fields, FieldsNamed, Ident, LitStr};
+
+mod config;
+// Shared with `pros` so that images decode the same way at build time as they do on the brain.
+#[allow(dead_code)]
+#[path = "../../pros/src/image/decode.rs"]
+mod decode;
+mod device_test;
+mod font;
+mod image;
+mod subsystem;
+mod telemetry;
+
+/// Derives `pros::config::Config`. See the `pros::config` module for details.
+#[proc_macro_derive(Config, attributes(config))]
+pub fn derive_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    config::derive(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `pros::diagnostics::telemetry::Telemetry`. See the `pros::diagnostics::telemetry` module for details.
+#[proc_macro_derive(Telemetry, attributes(telemetry))]
+pub fn derive_telemetry(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    telemetry::derive(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `pros::command::Subsystem` for a struct. See `pros::command::subsystem` for details.
+#[proc_macro_attribute]
+pub fn subsystem(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut parsed = subsystem::Args::default();
+    let parser = syn::meta::parser(|meta| parsed.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as DeriveInput);
+    subsystem::expand(parsed, &item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Marks a function as a test to run on the brain. See `pros::testing::device` for details.
+#[proc_macro_attribute]
+pub fn device_test(args: TokenStream, item: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return Error::new(
+            proc_macro2::Span::call_site(),
+            "`device_test` does not take arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let item = parse_macro_input!(item as syn::ItemFn);
+    device_test::expand(&item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Converts a BMP or PNG file to a `pros::image::Image` at build time. See `pros::image` for details.
+#[proc_macro]
+pub fn include_image(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    image::expand(&path)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Converts a BDF font file to a `pros::screen::font::Font` at build time. See `pros::screen::font` for details.
+#[proc_macro]
+pub fn include_font(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    font::expand(&path)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Returns the named fields of a struct, or an error pointing at the item for anything else.
+fn named_fields<'a>(input: &'a DeriveInput, derive: &str) -> Result<&'a FieldsNamed, Error> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields),
+            _ => Err(Error::new_spanned(
+                &input.ident,
+                format!("`{derive}` can only be derived for structs with named fields"),
+            )),
+        },
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            format!("`{derive}` can only be derived for structs"),
+        )),
+    }
+}
+
+/// Converts an optional expression to `Some(expr as f64)` or `None`.
+fn optional_f64(expr: &Option<Expr>) -> TokenStream2 {
+    match expr {
+        Some(expr) => quote!(::core::option::Option::Some((#expr) as f64)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+fn field_name(ident: &Ident) -> String {
+    ident.to_string().trim_start_matches("r#").to_string()
+}
@@ -0,0 +1,67 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{meta::ParseNestedMeta, Data, DeriveInput, Error, Ident, LitStr};
+
+/// The arguments to `#[subsystem(...)]`.
+#[derive(Default)]
+pub struct Args {
+    name: Option<LitStr>,
+    periodic: Option<Ident>,
+    default_command: Option<LitStr>,
+}
+
+impl Args {
+    pub fn parse(&mut self, meta: ParseNestedMeta<'_>) -> Result<(), Error> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("periodic") {
+            self.periodic = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("default_command") {
+            self.default_command = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `name`, `periodic`, or `default_command`"));
+        }
+        Ok(())
+    }
+}
+
+pub fn expand(args: Args, item: &DeriveInput) -> Result<TokenStream, Error> {
+    let ident = &item.ident;
+    if !matches!(item.data, Data::Struct(_)) {
+        return Err(Error::new_spanned(
+            ident,
+            "`subsystem` can only be applied to structs",
+        ));
+    }
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let name = match &args.name {
+        Some(name) => quote!(#name),
+        None => {
+            let name = ident.to_string();
+            quote!(#name)
+        }
+    };
+    let default_command = match &args.default_command {
+        Some(command) => quote!(::core::option::Option::Some(#command)),
+        None => quote!(::core::option::Option::None),
+    };
+    let periodic = args.periodic.map(|method| {
+        quote! {
+            fn periodic(&mut self) -> ::pros::Result {
+                ::pros::command::PeriodicOutput::into_result(Self::#method(self))
+            }
+        }
+    });
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::pros::command::Subsystem for #ident #ty_generics #where_clause {
+            const NAME: &'static str = #name;
+            const DEFAULT_COMMAND: ::core::option::Option<&'static str> = #default_command;
+
+            #periodic
+        }
+    })
+}
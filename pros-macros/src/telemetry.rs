@@ -0,0 +1,89 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Error, Expr};
+
+use crate::{field_name, named_fields};
+
+/// The contents of a field's `#[telemetry(...)]` attribute.
+#[derive(Default)]
+struct FieldAttrs {
+    skip: bool,
+    resolution: Option<Expr>,
+    priority: Option<Expr>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> Result<Self, Error> {
+        let mut parsed = Self::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("telemetry"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("resolution") {
+                    parsed.resolution = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("priority") {
+                    parsed.priority = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected `skip`, `resolution`, or `priority`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = named_fields(input, "Telemetry")?;
+
+    let mut infos = Vec::new();
+    let mut values = Vec::new();
+    for field in &fields.named {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let key = field_name(ident);
+        let index = values.len();
+
+        let resolution = match &attrs.resolution {
+            Some(resolution) => quote!((#resolution) as f64),
+            None => {
+                quote!(::pros::diagnostics::telemetry::TelemetryRecorder::DEFAULT_RESOLUTION)
+            }
+        };
+        let priority = match &attrs.priority {
+            Some(priority) => quote!(#priority),
+            None => quote!(0),
+        };
+        infos.push(quote! {
+            ::pros::diagnostics::telemetry::TelemetryField {
+                name: #key,
+                resolution: #resolution,
+                priority: #priority,
+            }
+        });
+        values.push(quote! {
+            #index => ::pros::diagnostics::telemetry::TelemetryValue::to_telemetry(&self.#ident)
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::pros::diagnostics::telemetry::Telemetry for #name #ty_generics #where_clause {
+            const FIELDS: &'static [::pros::diagnostics::telemetry::TelemetryField] = &[#(#infos),*];
+
+            fn field_value(&self, index: usize) -> f64 {
+                match index {
+                    #(#values,)*
+                    _ => f64::NAN,
+                }
+            }
+        }
+    })
+}
@@ -16,10 +16,10 @@ pub struct quaternion_s_t {
 
 #[repr(C)]
 pub struct imu_raw_s {
-    x: f64,
-    y: f64,
-    z: f64,
-    w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
 }
 
 pub type imu_gyro_s_t = imu_raw_s;
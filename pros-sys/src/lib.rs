@@ -5,7 +5,6 @@
 #![allow(dead_code)]
 
 pub mod adi;
-#[cfg(feature = "xapi")]
 pub mod apix;
 pub mod colors;
 pub mod distance;
@@ -19,6 +18,9 @@ pub mod misc;
 pub mod motor;
 pub mod rotation;
 pub mod rtos;
+pub mod screen;
+pub mod serial;
+pub mod stdio;
 pub mod vision;
 
 pub use adi::*;
@@ -34,12 +36,10 @@ pub use misc::*;
 pub use motor::*;
 pub use rotation::*;
 pub use rtos::*;
-pub use vision::*;
-
-#[cfg(feaute = "apix")]
+pub use screen::*;
 pub use serial::*;
-#[cfg(feaute = "apix")]
-pub mod serial;
+pub use stdio::*;
+pub use vision::*;
 
 pub const CLOCKS_PER_SEC: u32 = 1000;
 
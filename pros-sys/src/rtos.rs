@@ -24,6 +24,30 @@ pub type notify_action_e_t = core::ffi::c_uint;
 pub type task_t = *const core::ffi::c_void;
 pub type task_fn_t = Option<unsafe extern "C" fn(arg1: *mut ::core::ffi::c_void)>;
 pub type mutex_t = *const core::ffi::c_void;
+/// One word of a statically allocated task stack.
+pub type task_stack_t = u32;
+/**
+Storage for the control block of a statically allocated task.
+
+This mirrors FreeRTOS's StaticTask_t, which is opaque to user code. The buffer
+is larger than the kernel's task control block so it is always big enough.
+ */
+#[repr(C, align(8))]
+pub struct static_task_s_t {
+    _data: [u8; 512],
+}
+
+impl static_task_s_t {
+    pub const fn new() -> Self {
+        Self { _data: [0; 512] }
+    }
+}
+
+impl Default for static_task_s_t {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 const CURRENT_TASK: task_t = 0 as task_t;
 
@@ -68,6 +92,39 @@ extern "C" {
         stack_depth: u16,
         name: *const core::ffi::c_char,
     ) -> task_t;
+    /** Creates a new task using statically allocated memory for its stack and
+    control block, and adds it to the list of tasks that are ready to run.
+
+    \param task_code
+    Pointer to the task entry function
+    \param param
+    Pointer to memory that will be used as a parameter for the task being
+    created.
+    \param priority
+    The priority at which the task should run.
+    \param stack_size
+    The number of words in stack_buffer.
+    \param name
+    A descriptive name for the task.
+    \param stack_buffer
+    Memory for the task's stack. Must be at least stack_size words long and
+    stay valid for as long as the task exists.
+    \param task_buffer
+    Memory for the task's control block. Must stay valid for as long as the
+    task exists.
+
+    \return A handle by which the newly created task can be referenced. If an
+    error occurred, NULL will be returned and errno can be checked for hints as
+    to why task_create_static failed.*/
+    pub fn task_create_static(
+        task_code: task_fn_t,
+        param: *mut core::ffi::c_void,
+        priority: u32,
+        stack_size: u16,
+        name: *const core::ffi::c_char,
+        stack_buffer: *mut task_stack_t,
+        task_buffer: *mut static_task_s_t,
+    ) -> task_t;
     /** Removes a task from the RTOS real time kernel's management. The task being
     deleted will be removed from all ready, blocked, suspended and event lists.
 
@@ -0,0 +1,285 @@
+//! Contains prototypes for the brain screen drawing functions.
+
+use core::ffi::{c_char, c_uint};
+
+pub const E_TEXT_SMALL: c_uint = 0;
+pub const E_TEXT_MEDIUM: c_uint = 1;
+pub const E_TEXT_LARGE: c_uint = 2;
+pub const E_TEXT_MEDIUM_CENTER: c_uint = 3;
+pub const E_TEXT_LARGE_CENTER: c_uint = 4;
+pub type text_format_e_t = c_uint;
+
+pub const E_TOUCH_RELEASED: c_uint = 0;
+pub const E_TOUCH_PRESSED: c_uint = 1;
+pub const E_TOUCH_HELD: c_uint = 2;
+pub const E_TOUCH_ERROR: c_uint = 3;
+pub type last_touch_e_t = c_uint;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct screen_touch_status_s_t {
+    pub touch_status: last_touch_e_t,
+    pub x: i16,
+    pub y: i16,
+    pub press_count: i32,
+    pub release_count: i32,
+}
+
+pub type touch_event_cb_fn_t = Option<unsafe extern "C" fn()>;
+
+extern "C" {
+    /**
+    Set the pen color for subsequent graphics operations
+
+    This function uses the following values of errno when an error state is
+    reached:
+    EACCESS - Another resource is currently trying to access the screen mutex.
+
+    \param color    The pen color to set (it is recommended to use values
+                 from the enum defined in colors.h)
+
+    \return Returns 1 if the mutex was successfully returned, or PROS_ERR if
+    there was an error either taking or returning the screen mutex.
+    */
+    pub fn screen_set_pen(color: u32) -> u32;
+    /**
+    Set the eraser color for erasing and the current background.
+
+    This function uses the following values of errno when an error state is
+    reached:
+    EACCESS - Another resource is currently trying to access the screen mutex.
+
+    \param color    The background color to set (it is recommended to use values
+                     from the enum defined in colors.h)
+
+    \return Returns 1 if the mutex was successfully returned, or PROS_ERR
+    if there was an error either taking or returning the screen mutex.
+    */
+    pub fn screen_set_eraser(color: u32) -> u32;
+    /**
+    Get the current pen color.
+
+    \return The current pen color in the form of a value from the enum defined
+    in colors.h, or PROS_ERR if there was an error taking or returning the screen
+    mutex.
+    */
+    pub fn screen_get_pen() -> u32;
+    /**
+    Get the current eraser color.
+
+    \return The current eraser color in the form of a value from the enum
+    defined in colors.h, or PROS_ERR if there was an error taking or returning
+    the screen mutex.
+    */
+    pub fn screen_get_eraser() -> u32;
+    /**
+    Clear display with eraser color
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_erase() -> u32;
+    /**
+    Scroll lines on the display upwards.
+
+    \param start_line    The line from which scrolling will start
+    \param lines            The number of lines to scroll up
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_scroll(start_line: i16, lines: i16) -> u32;
+    /**
+    Scroll lines within a region on the display
+
+    This function behaves in the same way as `screen_scroll`, except that you
+    specify a rectangular region within which to scroll lines instead of a start
+    line.
+
+    \param x0, y0    The (x,y) coordinates of the first corner of the
+                         rectangular region
+    \param x1, y1    The (x,y) coordinates of the second corner of the
+                         rectangular region
+    \param lines     The number of lines to scroll upwards
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_scroll_area(x0: i16, y0: i16, x1: i16, y1: i16, lines: i16) -> u32;
+    /**
+    Copy a screen region (designated by a rectangle) from an off-screen buffer
+    to the screen
+
+    \param x0, y0     The (x,y) coordinates of the first corner of the
+                         rectangular region of the screen
+    \param x1, y1    The (x,y) coordinates of the second corner of the
+                         rectangular region of the screen
+    \param buf        Off-screen buffer containing screen data
+    \param stride    Off-screen buffer width in pixels, such that image size
+                         is stride-padding
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured taking
+    or returning the screen mutex.
+    */
+    pub fn screen_copy_area(x0: i16, y0: i16, x1: i16, y1: i16, buf: *mut u32, stride: i32) -> u32;
+    /**
+    Draw a single pixel on the screen using the current pen color
+
+    \param x, y     The (x,y) coordinates of the pixel
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_draw_pixel(x: i16, y: i16) -> u32;
+    /**
+    Erase a pixel from the screen (Sets the location)
+
+    \param x, y     The (x,y) coordinates of the erased
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_erase_pixel(x: i16, y: i16) -> u32;
+    /**
+    Draw a line on the screen using the current pen color
+
+    \param x0, y0    The (x, y) coordinates of the first point of the line
+    \param x1, y1     The (x, y) coordinates of the second point of the line
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_draw_line(x0: i16, y0: i16, x1: i16, y1: i16) -> u32;
+    /**
+    Erase a line on the screen using the current eraser color
+
+    \param x0, y0    The (x, y) coordinates of the first point of the line
+    \param x1, y1     The (x, y) coordinates of the second point of the line
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_erase_line(x0: i16, y0: i16, x1: i16, y1: i16) -> u32;
+    /**
+    Draw a rectangle on the screen using the current pen color
+
+    \param x0, y0     The (x,y) coordinates of the first point of the rectangle
+    \param x1, y1     The (x,y) coordinates of the second point of the rectangle
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_draw_rect(x0: i16, y0: i16, x1: i16, y1: i16) -> u32;
+    /**
+    Erase a rectangle on the screen using the current eraser color
+
+    \param x0, y0     The (x,y) coordinates of the first point of the rectangle
+    \param x1, y1     The (x,y) coordinates of the second point of the rectangle
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_erase_rect(x0: i16, y0: i16, x1: i16, y1: i16) -> u32;
+    /**
+    Fill a rectangular region of the screen using the current pen
+               color
+
+    \param x0, y0     The (x,y) coordinates of the first point of the rectangle
+    \param x1, y1     The (x,y) coordinates of the second point of the rectangle
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_fill_rect(x0: i16, y0: i16, x1: i16, y1: i16) -> u32;
+    /**
+    Draw a circle on the screen using the current pen color
+
+    \param x, y     The (x,y) coordinates of the center of the circle
+    \param r     The radius of the circle
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_draw_circle(x: i16, y: i16, radius: i16) -> u32;
+    /**
+    Erase a circle on the screen using the current eraser color
+
+    \param x, y     The (x,y) coordinates of the center of the circle
+    \param r     The radius of the circle
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_erase_circle(x: i16, y: i16, radius: i16) -> u32;
+    /**
+    Fill a circular region of the screen using the current pen
+               color
+
+    \param x, y     The (x,y) coordinates of the center of the circle
+    \param r     The radius of the circle
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_fill_circle(x: i16, y: i16, radius: i16) -> u32;
+    /**
+    Print a formatted string to the screen on the specified line
+
+    Will default to a medium sized font by default if invalid txt_fmt is given.
+
+    \param txt_fmt Text format enum that determines if the text is medium, large, medium_center, or large_center. (DOES NOT SUPPORT SMALL)
+    \param line The line number on which to print
+    \param text  Format string
+    \param ...  Optional list of arguments for the format string
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_print(txt_fmt: text_format_e_t, line: i16, text: *const c_char, ...) -> u32;
+    /**
+    Print a formatted string to the screen at the specified point
+
+    Will default to a medium sized font by default if invalid txt_fmt is given.
+
+    Text formats medium_center and large_center will default to medium and large respectively.
+
+    \param txt_fmt Text format enum that determines if the text is small, medium, or large.
+    \param x The y coordinate of the top left corner of the string
+    \param y The x coordinate of the top left corner of the string
+    \param text  Format string
+    \param ...  Optional list of arguments for the format string
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    taking or returning the screen mutex.
+    */
+    pub fn screen_print_at(
+        txt_fmt: text_format_e_t,
+        x: i16,
+        y: i16,
+        text: *const c_char,
+        ...
+    ) -> u32;
+    /**
+    Gets the touch status of the last touch of the screen.
+
+    \return The last_touch_e_t enum specifier that indicates the last touch status of the screen (E_TOUCH_EVENT_RELEASE, E_TOUCH_EVENT_PRESS, or E_TOUCH_EVENT_PRESS_AND_HOLD).
+    This will be released by default if no action was taken.
+    If an error occured, the screen_touch_status_s_t will have its
+    last_touch_e_t enum specifier set to E_TOUCH_ERR, and other values set to -1.
+    */
+    pub fn screen_touch_status() -> screen_touch_status_s_t;
+    /**
+    Assigns a callback function to be called when a certain touch event happens.
+
+    This function uses the following values of errno when an error state is
+    reached:
+    EACCESS - Another resource is currently trying to access the screen mutex.
+
+    \param cb Function pointer to callback when event type happens
+    \param event_type Touch event that will trigger the callback.
+
+    \return 1 if there were no errors, or PROS_ERR if an error occured
+    while taking or returning the screen mutex.
+    */
+    pub fn screen_touch_callback(cb: touch_event_cb_fn_t, event_type: last_touch_e_t) -> u32;
+}
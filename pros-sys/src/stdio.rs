@@ -0,0 +1,63 @@
+//! The subset of the C standard library's file IO used to access the SD card.
+//! Files on the SD card are opened with paths starting with `/usd/`.
+//! [`write`] and [`read`] can also send and receive raw bytes over the USB serial connection through stdout and stdin.
+
+use core::ffi::*;
+
+/// An open C file stream.
+#[repr(C)]
+pub struct FILE {
+    _private: [u8; 0],
+}
+
+pub const SEEK_SET: c_int = 0;
+pub const SEEK_CUR: c_int = 1;
+pub const SEEK_END: c_int = 2;
+
+/// The file descriptor for stdin, which is received from the computer over the USB serial connection.
+pub const STDIN_FILENO: c_int = 0;
+/// The file descriptor for stdout, which is sent to the computer over the USB serial connection.
+pub const STDOUT_FILENO: c_int = 1;
+
+extern "C" {
+    /** Opens the file at the given path with the given mode ("r", "w", "a", optionally followed by "b" and/or "+").
+
+    \return A pointer to the opened file, or NULL on failure, setting errno.*/
+    pub fn fopen(path: *const c_char, mode: *const c_char) -> *mut FILE;
+    /** Closes a file, flushing any buffered data.
+
+    \return 0 on success, or EOF (-1) on failure, setting errno.*/
+    pub fn fclose(file: *mut FILE) -> c_int;
+    /** Reads up to `count` items of `size` bytes from the file into `buffer`.
+
+    \return The number of items read, which is less than `count` at the end of the file or on failure.*/
+    pub fn fread(buffer: *mut c_void, size: usize, count: usize, file: *mut FILE) -> usize;
+    /** Writes `count` items of `size` bytes from `buffer` to the file.
+
+    \return The number of items written, which is less than `count` on failure.*/
+    pub fn fwrite(buffer: *const c_void, size: usize, count: usize, file: *mut FILE) -> usize;
+    /** Writes any buffered data to the file.
+
+    \return 0 on success, or EOF (-1) on failure, setting errno.*/
+    pub fn fflush(file: *mut FILE) -> c_int;
+    /** Moves the file position to `offset` relative to `whence` (SEEK_SET, SEEK_CUR, or SEEK_END).
+
+    \return 0 on success, or -1 on failure, setting errno.*/
+    pub fn fseek(file: *mut FILE, offset: c_long, whence: c_int) -> c_int;
+    /** \return The current file position, or -1 on failure, setting errno.*/
+    pub fn ftell(file: *mut FILE) -> c_long;
+    /** \return Nonzero if the file's error indicator is set.*/
+    pub fn ferror(file: *mut FILE) -> c_int;
+    /** Deletes the file at the given path.
+
+    \return 0 on success, or -1 on failure, setting errno.*/
+    pub fn remove(path: *const c_char) -> c_int;
+    /** Writes `count` bytes from `buffer` to a file descriptor, such as [`STDOUT_FILENO`].
+
+    \return The number of bytes written, or -1 on failure, setting errno.*/
+    pub fn write(fd: c_int, buffer: *const c_void, count: usize) -> isize;
+    /** Reads up to `count` bytes from a file descriptor, such as [`STDIN_FILENO`], into `buffer`.
+
+    \return The number of bytes read, or -1 on failure, setting errno.*/
+    pub fn read(fd: c_int, buffer: *mut c_void, count: usize) -> isize;
+}
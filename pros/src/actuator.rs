@@ -0,0 +1,114 @@
+//! Traits for commanding any kind of output the same way.
+//!
+//! Control loops and subsystems written against [`Actuator`] or [`VoltageOutput`]
+//! can drive smart motors, legacy motors, pneumatics, or a [`SimulatedOutput`] in tests.
+
+use core::convert::Infallible;
+
+#[cfg(feature = "alloc")]
+use crate::{adi::AdiMotorGroup, motor::MotorGroup};
+use crate::{
+    adi::{AdiDigitalOut, AdiError, AdiMotor},
+    motor::{Motor, MotorError},
+};
+
+/// An output that can be commanded from -1.0 to 1.0.
+pub trait Actuator {
+    type Error;
+
+    /// Sets the output from -1.0 (full reverse) to 1.0 (full forward).
+    /// Outputs that only have two states, like solenoids, are on for positive values and off otherwise.
+    fn set_output(&mut self, output: f32) -> Result<(), Self::Error>;
+
+    /// Stops the output.
+    fn stop(&mut self) -> Result<(), Self::Error> {
+        self.set_output(0.0)
+    }
+}
+
+/// An output that can be commanded with a voltage from -12 to 12 volts.
+pub trait VoltageOutput {
+    type Error;
+
+    /// Sets the voltage, which is clamped to -12 to 12 volts.
+    fn set_voltage(&mut self, voltage: f32) -> Result<(), Self::Error>;
+}
+
+impl<T: VoltageOutput> Actuator for T {
+    type Error = T::Error;
+
+    fn set_output(&mut self, output: f32) -> Result<(), Self::Error> {
+        self.set_voltage(output * 12.0)
+    }
+}
+
+impl VoltageOutput for Motor {
+    type Error = MotorError;
+
+    fn set_voltage(&mut self, voltage: f32) -> Result<(), Self::Error> {
+        Motor::set_voltage(self, voltage.clamp(-12.0, 12.0))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl VoltageOutput for MotorGroup {
+    type Error = MotorError;
+
+    fn set_voltage(&mut self, voltage: f32) -> Result<(), Self::Error> {
+        MotorGroup::set_voltage(self, voltage.clamp(-12.0, 12.0))
+    }
+}
+
+/// Legacy motors are driven by PWM rather than voltage,
+/// so the voltage is treated as a fraction of the battery's nominal 12 volts.
+impl VoltageOutput for AdiMotor {
+    type Error = AdiError;
+
+    fn set_voltage(&mut self, voltage: f32) -> Result<(), Self::Error> {
+        AdiMotor::set_output(self, voltage / 12.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl VoltageOutput for AdiMotorGroup {
+    type Error = AdiError;
+
+    fn set_voltage(&mut self, voltage: f32) -> Result<(), Self::Error> {
+        AdiMotorGroup::set_output(self, (voltage / 12.0).clamp(-1.0, 1.0))
+    }
+}
+
+/// A solenoid or other on/off device wired to a digital output.
+impl Actuator for AdiDigitalOut {
+    type Error = AdiError;
+
+    fn set_output(&mut self, output: f32) -> Result<(), Self::Error> {
+        self.set(output > 0.0)
+    }
+}
+
+/// An output that just remembers what it was last set to, for testing control code without hardware.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimulatedOutput {
+    voltage: f32,
+}
+
+impl SimulatedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the last voltage the output was set to.
+    pub fn voltage(&self) -> f32 {
+        self.voltage
+    }
+}
+
+impl VoltageOutput for SimulatedOutput {
+    type Error = Infallible;
+
+    fn set_voltage(&mut self, voltage: f32) -> Result<(), Self::Error> {
+        self.voltage = voltage.clamp(-12.0, 12.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use pros_sys::PROS_ERR;
+
+use super::{AdiError, AdiPort};
+use crate::error::bail_on;
+
+/// The measurement range selected by the jumper on a legacy accelerometer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccelerometerRange {
+    /// ±2 g, used when the sensitivity jumper is installed.
+    HighSensitivity,
+    /// ±6 g, used when the sensitivity jumper is removed.
+    LowSensitivity,
+}
+
+impl AccelerometerRange {
+    /// The nominal output of the sensor in volts per g.
+    pub fn volts_per_g(&self) -> f64 {
+        match self {
+            Self::HighSensitivity => 0.46,
+            Self::LowSensitivity => 0.46 / 3.0,
+        }
+    }
+}
+
+/// One axis of a legacy 3-wire accelerometer.
+/// Each axis of the sensor is plugged into its own ADI port.
+pub struct AdiAccelerometer {
+    port: AdiPort,
+    range: AccelerometerRange,
+}
+
+impl AdiAccelerometer {
+    /// Creates an accelerometer axis and calibrates it,
+    /// which blocks for about half a second and requires the robot to be still.
+    pub fn new(port: AdiPort, range: AccelerometerRange) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_ANALOG_IN)
+            );
+        }
+        let accelerometer = Self { port, range };
+        accelerometer.calibrate()?;
+        Ok(accelerometer)
+    }
+
+    /// Takes the current reading as zero acceleration.
+    /// This blocks for about half a second and requires the robot to be still.
+    pub fn calibrate(&self) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_analog_calibrate(*self.port));
+        }
+        Ok(())
+    }
+
+    pub fn range(&self) -> AccelerometerRange {
+        self.range
+    }
+
+    /// Changes the range used to convert readings, for when the jumper is moved.
+    pub fn set_range(&mut self, range: AccelerometerRange) {
+        self.range = range;
+    }
+
+    /// Returns the acceleration along this axis relative to the calibrated zero, in g.
+    pub fn acceleration(&self) -> Result<f64, AdiError> {
+        // The high resolution reading is the 12-bit value times 16.
+        let value = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_analog_read_calibrated_HR(*self.port)
+            )
+        } as f64
+            / 16.0;
+        Ok(value * 5.0 / 4095.0 / self.range.volts_per_g())
+    }
+}
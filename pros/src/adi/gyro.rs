@@ -0,0 +1,233 @@
+use core::time::Duration;
+
+use pros_sys::{PROS_ERR, PROS_ERR_F};
+
+use super::{AdiError, AdiPort};
+use crate::error::bail_on;
+
+/// A legacy yaw rate gyro on an ADI port.
+///
+/// Every gyro reads a little differently, so PROS scales its readings by a multiplier.
+/// [`GyroCalibration`] can measure the multiplier for a particular gyro
+/// so that it can be saved with [`GyroConfig`] and passed back in on startup.
+pub struct AdiGyro {
+    handle: pros_sys::adi_gyro_t,
+    expander: Option<u8>,
+    multiplier: f64,
+}
+
+impl AdiGyro {
+    /// How long a gyro calibrates for when its port is first configured. The robot must be still.
+    pub const CALIBRATION_TIME: Duration = Duration::from_millis(1300);
+
+    /// Sets up a gyro, blocking while it calibrates if the port wasn't already a gyro.
+    pub fn new(port: AdiPort, multiplier: f64) -> Result<Self, AdiError> {
+        let handle = unsafe { bail_on!(PROS_ERR, pros_sys::adi_gyro_init(*port, multiplier)) };
+        Ok(Self {
+            handle,
+            expander: None,
+            multiplier,
+        })
+    }
+
+    /// Sets up a gyro on a port of the ADI expander plugged into `smart_port`.
+    pub fn new_on_expander(
+        smart_port: u8,
+        port: AdiPort,
+        multiplier: f64,
+    ) -> Result<Self, AdiError> {
+        let handle = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::ext_adi_gyro_init(smart_port, *port, multiplier)
+            )
+        };
+        Ok(Self {
+            handle,
+            expander: Some(smart_port),
+            multiplier,
+        })
+    }
+
+    /// Returns the multiplier readings are scaled by.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Returns the angle turned since the gyro was calibrated or reset, in degrees.
+    pub fn angle(&self) -> Result<f64, AdiError> {
+        let tenths = unsafe {
+            match self.expander {
+                Some(_) => bail_on!(PROS_ERR_F, pros_sys::ext_adi_gyro_get(self.handle)),
+                None => bail_on!(PROS_ERR_F, pros_sys::adi_gyro_get(self.handle)),
+            }
+        };
+        Ok(tenths / 10.0)
+    }
+
+    /// Sets the angle back to zero.
+    pub fn reset(&self) -> Result<(), AdiError> {
+        unsafe {
+            match self.expander {
+                Some(_) => bail_on!(PROS_ERR, pros_sys::ext_adi_gyro_reset(self.handle)),
+                None => bail_on!(PROS_ERR, pros_sys::adi_gyro_reset(self.handle)),
+            };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub use calibration::*;
+
+#[cfg(feature = "alloc")]
+mod calibration {
+    use alloc::{format, string::String};
+    use core::time::Duration;
+
+    use snafu::Snafu;
+
+    use super::AdiGyro;
+    use crate::{
+        config::Config,
+        controller::{Button, Controller, ControllerError, ControllerLine},
+        fixed::Fixed,
+        sensors::imu::InertialSensor,
+        task::sleep,
+    };
+
+    /// A gyro's multiplier, saved to the SD card so it only needs to be measured once.
+    ///
+    /// ```rust
+    /// let config = GyroConfig::load("gyro.cfg")?;
+    /// let gyro = AdiGyro::new(AdiPort::new(1), config.multiplier)?;
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Config)]
+    pub struct GyroConfig {
+        #[config(default = 1.0, min = -4.0, max = 4.0)]
+        pub multiplier: f64,
+    }
+
+    /// Measures a gyro's multiplier by comparing it to a known turn.
+    ///
+    /// The further the robot turns, the less a small error in the turn matters,
+    /// so the default is ten full turns.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct GyroCalibration {
+        /// How far to turn, in degrees.
+        pub angle: f64,
+        /// The voltage to spin at when turning under power.
+        pub voltage: f32,
+        /// How long to wait for the turn before giving up.
+        pub timeout: Duration,
+    }
+
+    impl Default for GyroCalibration {
+        fn default() -> Self {
+            Self {
+                angle: 3600.0,
+                voltage: 4.0,
+                timeout: Duration::from_secs(60),
+            }
+        }
+    }
+
+    impl GyroCalibration {
+        /// Spins the robot with `turn` until the IMU has measured the turn, then returns the gyro's multiplier.
+        ///
+        /// `turn` is passed a voltage to turn counterclockwise at, and `0.0` to stop.
+        pub fn with_imu(
+            &self,
+            gyro: &AdiGyro,
+            imu: &InertialSensor,
+            mut turn: impl FnMut(f32) -> crate::Result,
+        ) -> crate::Result<f64> {
+            gyro.reset()?;
+            imu.set_rotation(0.0)?;
+
+            let start = unsafe { pros_sys::millis() };
+            let result = loop {
+                if libm::fabs(imu.rotation()?) >= self.angle {
+                    break Ok(());
+                }
+                if unsafe { pros_sys::millis() } - start > self.timeout.as_millis() as u32 {
+                    break Err(GyroCalibrationError::TimedOut);
+                }
+                turn(self.voltage)?;
+                sleep(Duration::from_millis(10));
+            };
+            turn(0.0)?;
+            result?;
+
+            // Let the robot come to a stop before comparing.
+            sleep(Duration::from_millis(500));
+            Ok(multiplier(gyro, gyro.angle()?, imu.rotation()?)?)
+        }
+
+        /// Has the operator turn the robot by hand, prompting on the controller,
+        /// then returns the gyro's multiplier.
+        ///
+        /// The operator lines the robot up, presses A, turns it counterclockwise by the calibration's angle,
+        /// lines it up again, and presses A. Pressing B cancels.
+        pub fn with_operator(&self, gyro: &AdiGyro, controller: Controller) -> crate::Result<f64> {
+            let line = controller.line(0);
+            line.try_print("Align, press A")?;
+            wait_for_a(controller, self.timeout)?;
+            gyro.reset()?;
+
+            prompt(&line, format!("Turn {}, A", Fixed::new(self.angle, 0)))?;
+            wait_for_a(controller, self.timeout)?;
+            let measured = gyro.angle()?;
+
+            let multiplier = multiplier(gyro, measured, self.angle)?;
+            prompt(&line, format!("Mult {}", Fixed::new(multiplier, 4)))?;
+            Ok(multiplier)
+        }
+    }
+
+    /// Prints to a controller line, cutting off anything past what fits.
+    fn prompt(line: &ControllerLine, mut text: String) -> Result<(), ControllerError> {
+        text.truncate(ControllerLine::MAX_TEXT_LEN);
+        line.try_print(text)
+    }
+
+    /// Waits for A to be pressed and released.
+    fn wait_for_a(controller: Controller, timeout: Duration) -> Result<(), GyroCalibrationError> {
+        let start = unsafe { pros_sys::millis() };
+        let mut was_pressed = controller.state().buttons.is_pressed(Button::A);
+        loop {
+            let buttons = controller.state().buttons;
+            if buttons.is_pressed(Button::B) {
+                return Err(GyroCalibrationError::Cancelled);
+            }
+            let pressed = buttons.is_pressed(Button::A);
+            if was_pressed && !pressed {
+                return Ok(());
+            }
+            was_pressed = pressed;
+            if unsafe { pros_sys::millis() } - start > timeout.as_millis() as u32 {
+                return Err(GyroCalibrationError::TimedOut);
+            }
+            sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// Returns the multiplier that would have made the gyro read `actual` instead of `measured`.
+    fn multiplier(gyro: &AdiGyro, measured: f64, actual: f64) -> Result<f64, GyroCalibrationError> {
+        if libm::fabs(measured) < 1.0 {
+            return Err(GyroCalibrationError::NoRotation);
+        }
+        Ok(gyro.multiplier() * actual / measured)
+    }
+
+    #[derive(Debug, Snafu)]
+    pub enum GyroCalibrationError {
+        #[snafu(display("The gyro calibration was cancelled."))]
+        Cancelled,
+        #[snafu(display("The turn didn't finish before the gyro calibration timed out."))]
+        TimedOut,
+        #[snafu(display("The gyro didn't measure any rotation. Check that it is plugged in."))]
+        NoRotation,
+    }
+    impl core::error::Error for GyroCalibrationError {}
+}
@@ -0,0 +1,64 @@
+//! [`embedded_hal`] implementations for ADI devices.
+//!
+//! embedded-hal 1.0 has no ADC trait, so [`AdiAnalogIn::value`](super::AdiAnalogIn::value)
+//! serves as the one-shot reading that drivers can be given as a closure.
+
+use embedded_hal::{digital, pwm};
+
+use super::{AdiDigitalIn, AdiDigitalOut, AdiError, AdiMotor};
+
+impl digital::Error for AdiError {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+impl pwm::Error for AdiError {
+    fn kind(&self) -> pwm::ErrorKind {
+        pwm::ErrorKind::Other
+    }
+}
+
+impl digital::ErrorType for AdiDigitalIn {
+    type Error = AdiError;
+}
+
+impl digital::InputPin for AdiDigitalIn {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        AdiDigitalIn::is_high(self)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!AdiDigitalIn::is_high(self)?)
+    }
+}
+
+impl digital::ErrorType for AdiDigitalOut {
+    type Error = AdiError;
+}
+
+impl digital::OutputPin for AdiDigitalOut {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        AdiDigitalOut::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        AdiDigitalOut::set_high(self)
+    }
+}
+
+impl pwm::ErrorType for AdiMotor {
+    type Error = AdiError;
+}
+
+/// The duty cycle maps onto forward output only, from stopped to full speed,
+/// since [`pwm::SetDutyCycle`] has no notion of direction.
+impl pwm::SetDutyCycle for AdiMotor {
+    fn max_duty_cycle(&self) -> u16 {
+        127
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.set_raw_output(duty.min(127) as i8)
+    }
+}
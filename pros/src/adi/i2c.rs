@@ -0,0 +1,247 @@
+//! A software I2C master on two ADI ports.
+//!
+//! This lets third-party I2C sensors be wired to the three-wire ports,
+//! and implements [`embedded_hal::i2c::I2c`] so existing driver crates can use it.
+//! The lines are driven open-drain by switching each port between a low output and an input,
+//! so SDA and SCL need pull-up resistors just like a normal I2C bus.
+//!
+//! VEXos updates the three-wire ports on a fixed interval rather than immediately,
+//! so the real bus speed is limited by that interval no matter what frequency is configured.
+//! This suits sensors that are read occasionally rather than streamed from.
+
+use core::time::Duration;
+
+use embedded_hal::i2c::{self, ErrorKind, NoAcknowledgeSource, Operation, SevenBitAddress};
+use pros_sys::PROS_ERR;
+use snafu::Snafu;
+
+use super::{AdiError, AdiPort};
+use crate::error::bail_on;
+
+/// A bit-banged I2C master using one ADI port for the clock and one for data.
+pub struct AdiI2c {
+    scl: AdiPort,
+    sda: AdiPort,
+    half_period: Duration,
+    clock_stretch_timeout: Duration,
+}
+
+impl AdiI2c {
+    /// The standard-mode I2C frequency in Hz.
+    pub const STANDARD_FREQUENCY: u32 = 100_000;
+
+    /// Creates a bus on the given ports running at `frequency` Hz and releases both lines.
+    pub fn new(scl: AdiPort, sda: AdiPort, frequency: u32) -> Result<Self, AdiError> {
+        let mut bus = Self {
+            scl,
+            sda,
+            half_period: Duration::ZERO,
+            clock_stretch_timeout: Duration::from_millis(10),
+        };
+        bus.set_frequency(frequency);
+        release(*bus.scl)?;
+        release(*bus.sda)?;
+        Ok(bus)
+    }
+
+    /// Sets the clock frequency in Hz.
+    pub fn set_frequency(&mut self, frequency: u32) {
+        self.half_period = Duration::from_micros((500_000 / frequency.max(1)) as u64);
+    }
+
+    /// Sets how long to wait for a device holding the clock low before giving up.
+    pub fn set_clock_stretch_timeout(&mut self, timeout: Duration) {
+        self.clock_stretch_timeout = timeout;
+    }
+
+    fn wait(&self) {
+        let start = unsafe { pros_sys::micros() };
+        while unsafe { pros_sys::micros() } - start < self.half_period.as_micros() as u64 {}
+    }
+
+    /// Releases the clock and waits for any device stretching it to let go.
+    fn release_scl(&self) -> Result<(), I2cError> {
+        release(*self.scl)?;
+        let start = unsafe { pros_sys::micros() };
+        while !is_high(*self.scl)? {
+            if unsafe { pros_sys::micros() } - start > self.clock_stretch_timeout.as_micros() as u64
+            {
+                return Err(I2cError::ClockStretchTimedOut);
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&self) -> Result<(), I2cError> {
+        if !is_high(*self.sda)? {
+            return Err(I2cError::BusBusy);
+        }
+        drive_low(*self.sda)?;
+        self.wait();
+        drive_low(*self.scl)?;
+        Ok(())
+    }
+
+    fn repeated_start(&self) -> Result<(), I2cError> {
+        release(*self.sda)?;
+        self.wait();
+        self.release_scl()?;
+        self.wait();
+        self.start()
+    }
+
+    fn stop(&self) -> Result<(), I2cError> {
+        drive_low(*self.sda)?;
+        self.wait();
+        self.release_scl()?;
+        self.wait();
+        release(*self.sda)?;
+        self.wait();
+        Ok(())
+    }
+
+    fn write_bit(&self, bit: bool) -> Result<(), I2cError> {
+        if bit {
+            release(*self.sda)?;
+        } else {
+            drive_low(*self.sda)?;
+        }
+        self.wait();
+        self.release_scl()?;
+        self.wait();
+        drive_low(*self.scl)?;
+        Ok(())
+    }
+
+    fn read_bit(&self) -> Result<bool, I2cError> {
+        release(*self.sda)?;
+        self.wait();
+        self.release_scl()?;
+        self.wait();
+        let bit = is_high(*self.sda)?;
+        drive_low(*self.scl)?;
+        Ok(bit)
+    }
+
+    /// Writes a byte and returns whether the device acknowledged it.
+    fn write_byte(&self, byte: u8) -> Result<bool, I2cError> {
+        for i in (0..8).rev() {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+        Ok(!self.read_bit()?)
+    }
+
+    fn read_byte(&self, ack: bool) -> Result<u8, I2cError> {
+        let mut byte = 0;
+        for _ in 0..8 {
+            byte = (byte << 1) | self.read_bit()? as u8;
+        }
+        self.write_bit(!ack)?;
+        Ok(byte)
+    }
+
+    fn run(&self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), I2cError> {
+        let mut reading = None;
+        for index in 0..operations.len() {
+            let read = matches!(operations[index], Operation::Read(_));
+            if reading != Some(read) {
+                if reading.is_some() {
+                    self.repeated_start()?;
+                } else {
+                    self.start()?;
+                }
+                if !self.write_byte((address << 1) | read as u8)? {
+                    return Err(I2cError::AddressNack);
+                }
+                reading = Some(read);
+            }
+
+            // The last byte read before a restart or stop is not acknowledged.
+            let last_read = !matches!(operations.get(index + 1), Some(Operation::Read(_)));
+            match &mut operations[index] {
+                Operation::Read(buf) => {
+                    let len = buf.len();
+                    for (i, byte) in buf.iter_mut().enumerate() {
+                        *byte = self.read_byte(!(last_read && i == len - 1))?;
+                    }
+                }
+                Operation::Write(buf) => {
+                    for &byte in buf.iter() {
+                        if !self.write_byte(byte)? {
+                            return Err(I2cError::DataNack);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl i2c::ErrorType for AdiI2c {
+    type Error = I2cError;
+}
+
+impl i2c::I2c<SevenBitAddress> for AdiI2c {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.run(address, operations);
+        // Always try to leave the bus idle, but report the first error.
+        let stop = self.stop();
+        result.and(stop)
+    }
+}
+
+fn release(port: u8) -> Result<(), AdiError> {
+    unsafe {
+        bail_on!(
+            PROS_ERR,
+            pros_sys::adi_port_set_config(port, pros_sys::E_ADI_DIGITAL_IN)
+        );
+    }
+    Ok(())
+}
+
+fn drive_low(port: u8) -> Result<(), AdiError> {
+    unsafe {
+        bail_on!(
+            PROS_ERR,
+            pros_sys::adi_port_set_config(port, pros_sys::E_ADI_DIGITAL_OUT)
+        );
+        bail_on!(PROS_ERR, pros_sys::adi_digital_write(port, false));
+    }
+    Ok(())
+}
+
+fn is_high(port: u8) -> Result<bool, AdiError> {
+    Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_digital_read(port)) } == 1)
+}
+
+#[derive(Debug, Snafu)]
+pub enum I2cError {
+    #[snafu(display("No device acknowledged the address."))]
+    AddressNack,
+    #[snafu(display("The device did not acknowledge a data byte."))]
+    DataNack,
+    #[snafu(display("Another device is holding the data line low."))]
+    BusBusy,
+    #[snafu(display("A device held the clock low for too long."))]
+    ClockStretchTimedOut,
+    #[snafu(display("{source}"), context(false))]
+    Adi { source: AdiError },
+}
+impl core::error::Error for I2cError {}
+
+impl i2c::Error for I2cError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::AddressNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Self::DataNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            Self::BusBusy => ErrorKind::ArbitrationLoss,
+            _ => ErrorKind::Other,
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use pros_sys::PROS_ERR;
+
+use super::{AdiError, AdiPort};
+use crate::error::bail_on;
+
+/// A color for an addressable LED.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+    pub const RED: Self = Self::new(255, 0, 0);
+    pub const GREEN: Self = Self::new(0, 255, 0);
+    pub const BLUE: Self = Self::new(0, 0, 255);
+    pub const YELLOW: Self = Self::new(255, 255, 0);
+    pub const ORANGE: Self = Self::new(255, 96, 0);
+    pub const PURPLE: Self = Self::new(128, 0, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a color from a hex code such as `0xFF8000`.
+    pub const fn from_hex(hex: u32) -> Self {
+        Self::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+
+    pub const fn to_hex(self) -> u32 {
+        (self.r as u32) << 16 | (self.g as u32) << 8 | self.b as u32
+    }
+
+    /// Creates a color from a hue in degrees and a saturation and value from 0.0 to 1.0.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let hue = libm::fmodf(libm::fmodf(hue, 360.0) + 360.0, 360.0) / 60.0;
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - libm::fabsf(libm::fmodf(hue, 2.0) - 1.0));
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        let channel = |c: f32| ((c + m) * 255.0 + 0.5) as u8;
+        Self::new(channel(r), channel(g), channel(b))
+    }
+
+    /// Multiplies the brightness by a factor from 0.0 to 1.0.
+    pub fn scale(self, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        let channel = |c: u8| (c as f32 * factor + 0.5) as u8;
+        Self::new(channel(self.r), channel(self.g), channel(self.b))
+    }
+
+    /// Blends toward `other`, where `t` is 0.0 for this color and 1.0 for `other`.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u8;
+        Self::new(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+        )
+    }
+}
+
+/// A current limit shared by several strips, such as every strip on one ADI expander.
+///
+/// Each strip sharing the budget is dimmed so that, together with what the others last drew,
+/// the total stays under the limit. Clones share the same budget.
+#[derive(Debug, Clone)]
+pub struct CurrentBudget {
+    state: Arc<BudgetState>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    limit: u32,
+    drawn: AtomicU32,
+}
+
+impl CurrentBudget {
+    /// Creates a budget of `milliamps` for every strip it is given to.
+    pub fn new(milliamps: u32) -> Self {
+        Self {
+            state: Arc::new(BudgetState {
+                limit: milliamps,
+                drawn: AtomicU32::new(0),
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.state.limit
+    }
+
+    /// Returns how much current the strips sharing this budget were last estimated to draw, in milliamps.
+    pub fn drawn(&self) -> u32 {
+        self.state.drawn.load(Ordering::Acquire)
+    }
+
+    /// Replaces one strip's share of the drawn current.
+    fn replace(&self, old: u32, new: u32) {
+        // Adding before subtracting keeps the total from wrapping below zero.
+        self.state.drawn.fetch_add(new, Ordering::AcqRel);
+        self.state.drawn.fetch_sub(old, Ordering::AcqRel);
+    }
+}
+
+/// A strip of addressable (WS2812 style) LEDs on an ADI port.
+///
+/// Colors are set in a buffer and sent to the strip with [`AddrLed::show`].
+/// Each LED can draw around 60 mA at full white, which adds up quickly on a long strip,
+/// so [`AddrLed::set_current_limit`] dims the whole strip when it would draw more than the limit.
+/// Strips that share a power source, like the ports of an ADI expander,
+/// can share a [`CurrentBudget`] with [`AddrLed::set_budget`] to keep their total under a limit instead.
+pub struct AddrLed {
+    handle: pros_sys::adi_led_t,
+    expander: Option<u8>,
+    pixels: Vec<Rgb>,
+    raw: Vec<u32>,
+    current_limit: Option<u32>,
+    budget: Option<CurrentBudget>,
+    drawn: u32,
+}
+
+impl AddrLed {
+    /// The most LEDs that can be driven from one port.
+    pub const MAX_LEN: usize = 64;
+    /// The current one color channel of one LED draws at full brightness, in milliamps.
+    pub const MILLIAMPS_PER_CHANNEL: u32 = 20;
+
+    /// Sets up a strip of `len` LEDs, up to [`AddrLed::MAX_LEN`].
+    pub fn new(port: AdiPort, len: usize) -> Result<Self, AdiError> {
+        let handle = unsafe { bail_on!(PROS_ERR, pros_sys::adi_led_init(*port)) };
+        Ok(Self::from_handle(handle, None, len))
+    }
+
+    /// Sets up a strip of `len` LEDs on a port of the ADI expander plugged into `smart_port`.
+    pub fn new_on_expander(smart_port: u8, port: AdiPort, len: usize) -> Result<Self, AdiError> {
+        let handle = unsafe { bail_on!(PROS_ERR, pros_sys::ext_adi_led_init(smart_port, *port)) };
+        Ok(Self::from_handle(handle, Some(smart_port), len))
+    }
+
+    fn from_handle(handle: pros_sys::adi_led_t, expander: Option<u8>, len: usize) -> Self {
+        let len = len.min(Self::MAX_LEN);
+        Self {
+            handle,
+            expander,
+            pixels: vec![Rgb::BLACK; len],
+            raw: vec![0; len],
+            current_limit: None,
+            budget: None,
+            drawn: 0,
+        }
+    }
+
+    /// Returns the smart port of the ADI expander the strip is on, if it is on one.
+    pub fn expander(&self) -> Option<u8> {
+        self.expander
+    }
+
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// Returns the colors that will be sent by the next [`AddrLed::show`].
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Rgb] {
+        &mut self.pixels
+    }
+
+    /// Sets the color of one LED. Indices past the end of the strip are ignored.
+    pub fn set_pixel(&mut self, index: usize, color: Rgb) {
+        if let Some(pixel) = self.pixels.get_mut(index) {
+            *pixel = color;
+        }
+    }
+
+    /// Sets every LED to one color.
+    pub fn fill(&mut self, color: Rgb) {
+        self.pixels.fill(color);
+    }
+
+    /// Limits how much current the strip may draw, in milliamps, or removes the limit.
+    pub fn set_current_limit(&mut self, milliamps: Option<u32>) {
+        self.current_limit = milliamps;
+    }
+
+    /// Shares a current budget with other strips, or stops sharing one.
+    /// The strip is dimmed by whichever of this and [`AddrLed::set_current_limit`] is stricter.
+    pub fn set_budget(&mut self, budget: Option<CurrentBudget>) {
+        if let Some(old) = self.budget.take() {
+            old.replace(self.drawn, 0);
+        }
+        if let Some(new) = &budget {
+            new.replace(0, self.drawn);
+        }
+        self.budget = budget;
+    }
+
+    /// Estimates how much current the buffered colors would draw, in milliamps.
+    pub fn estimated_current(&self) -> u32 {
+        let total: u32 = self
+            .pixels
+            .iter()
+            .map(|pixel| pixel.r as u32 + pixel.g as u32 + pixel.b as u32)
+            .sum();
+        total * Self::MILLIAMPS_PER_CHANNEL / 255
+    }
+
+    /// Returns how much current the strip was estimated to draw after it was last dimmed, in milliamps.
+    pub fn drawn_current(&self) -> u32 {
+        self.drawn
+    }
+
+    /// Sends the buffered colors to the strip, dimmed to stay under the current limit and budget.
+    pub fn show(&mut self) -> Result<(), AdiError> {
+        let mut limit = self.current_limit.unwrap_or(u32::MAX);
+        if let Some(budget) = &self.budget {
+            let others = budget.drawn().saturating_sub(self.drawn);
+            limit = limit.min(budget.limit().saturating_sub(others));
+        }
+        let current = self.estimated_current();
+        let scale = if current > limit {
+            limit as f32 / current as f32
+        } else {
+            1.0
+        };
+        for (raw, pixel) in self.raw.iter_mut().zip(&self.pixels) {
+            *raw = pixel.scale(scale).to_hex();
+        }
+        unsafe {
+            match self.expander {
+                Some(_) => bail_on!(
+                    PROS_ERR,
+                    pros_sys::ext_adi_led_set(
+                        self.handle,
+                        self.raw.as_mut_ptr(),
+                        self.raw.len() as u32
+                    )
+                ),
+                None => bail_on!(
+                    PROS_ERR,
+                    pros_sys::adi_led_set(self.handle, self.raw.as_ptr(), self.raw.len() as u32)
+                ),
+            };
+        }
+
+        let drawn = (current as f32 * scale) as u32;
+        if let Some(budget) = &self.budget {
+            budget.replace(self.drawn, drawn);
+        }
+        self.drawn = drawn;
+        Ok(())
+    }
+
+    /// Turns off every LED.
+    pub fn clear(&mut self) -> Result<(), AdiError> {
+        self.fill(Rgb::BLACK);
+        self.show()
+    }
+}
+
+impl Drop for AddrLed {
+    fn drop(&mut self) {
+        if let Some(budget) = &self.budget {
+            budget.replace(self.drawn, 0);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+use super::{AdiError, AdiMotor};
+
+/// Corrects for the nonlinear response of a Motor Controller 29 driving a legacy 2-wire motor.
+///
+/// An MC29 doesn't move the motor at all for small outputs, then speeds up quickly
+/// and flattens out well before full output.
+/// This curve maps a desired speed onto the output that approximately produces it,
+/// so legacy motors respond as evenly as smart motors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mc29Curve {
+    /// The smallest output, from 0.0 to 1.0, that makes the motor move.
+    /// Any nonzero speed is pushed past this.
+    pub deadband: f32,
+    /// How strongly the controller's response flattens out at high outputs, from 0.0 (linear) to 1.0.
+    pub saturation: f32,
+}
+
+impl Default for Mc29Curve {
+    fn default() -> Self {
+        Self {
+            deadband: 0.08,
+            saturation: 0.6,
+        }
+    }
+}
+
+impl Mc29Curve {
+    /// A curve that passes outputs through unchanged.
+    pub const LINEAR: Self = Self {
+        deadband: 0.0,
+        saturation: 0.0,
+    };
+
+    /// Returns the output, from -1.0 to 1.0, that drives the motor at the given fraction of its full speed.
+    pub fn apply(&self, speed: f32) -> f32 {
+        let magnitude = libm::fabsf(speed).min(1.0);
+        if magnitude == 0.0 {
+            return 0.0;
+        }
+
+        // The controller's speed is modeled as s = (1 + k)p - kp^2 for an output p past the deadband,
+        // which is inverted here to find the output for a speed.
+        let k = self.saturation.clamp(0.0, 1.0);
+        let output = if k == 0.0 {
+            magnitude
+        } else {
+            ((1.0 + k) - libm::sqrtf((1.0 + k) * (1.0 + k) - 4.0 * k * magnitude)) / (2.0 * k)
+        };
+        libm::copysignf(self.deadband + (1.0 - self.deadband) * output, speed)
+    }
+}
+
+/// A group of legacy motors on ADI ports, driven through MC29s, that are commanded together.
+pub struct AdiMotorGroup {
+    motors: Vec<(AdiMotor, bool)>,
+    curve: Mc29Curve,
+}
+
+impl AdiMotorGroup {
+    /// Creates a group with the [default](Mc29Curve::default) MC29 curve.
+    pub fn new(motors: Vec<AdiMotor>) -> Self {
+        Self {
+            motors: motors.into_iter().map(|motor| (motor, false)).collect(),
+            curve: Mc29Curve::default(),
+        }
+    }
+
+    /// Adds a motor that spins the opposite way from the others.
+    pub fn with_reversed(mut self, motor: AdiMotor) -> Self {
+        self.motors.push((motor, true));
+        self
+    }
+
+    pub fn with_curve(mut self, curve: Mc29Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn curve(&self) -> Mc29Curve {
+        self.curve
+    }
+
+    /// Sets the speed of every motor from -1.0 to 1.0, corrected by the group's [`Mc29Curve`].
+    pub fn set_output(&self, speed: f32) -> Result<(), AdiError> {
+        let output = self.curve.apply(speed);
+        for (motor, reversed) in &self.motors {
+            motor.set_output(if *reversed { -output } else { output })?;
+        }
+        Ok(())
+    }
+
+    pub fn stop(&self) -> Result<(), AdiError> {
+        for (motor, _) in &self.motors {
+            motor.stop()?;
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,34 @@ use core::{
     ops::{Deref, DerefMut},
 };
 
+use pros_sys::PROS_ERR;
+use snafu::Snafu;
+
+use crate::error::{bail_on, map_errno, PortError};
+
+mod accelerometer;
+mod gyro;
+#[cfg(feature = "alloc")]
+mod led;
+#[cfg(feature = "alloc")]
+mod mc29;
+mod servo;
+
+#[cfg(feature = "embedded-hal")]
+mod hal;
+#[cfg(feature = "embedded-hal")]
+pub mod i2c;
+
+pub use accelerometer::{AccelerometerRange, AdiAccelerometer};
+pub use gyro::AdiGyro;
+#[cfg(feature = "alloc")]
+pub use gyro::{GyroCalibration, GyroCalibrationError, GyroConfig};
+#[cfg(feature = "alloc")]
+pub use led::{AddrLed, CurrentBudget, Rgb};
+#[cfg(feature = "alloc")]
+pub use mc29::{AdiMotorGroup, Mc29Curve};
+pub use servo::Servo;
+
 pub struct AdiPort(u8);
 
 impl AdiPort {
@@ -45,12 +73,197 @@ impl DerefMut for AdiPort {
     }
 }
 
+/// An analog input on an ADI port, such as a potentiometer or line tracker.
 pub struct AdiAnalogIn {
     port: AdiPort,
 }
 
 impl AdiAnalogIn {
-    pub fn new(port: AdiPort) -> Self {
-        Self { port }
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_ANALOG_IN)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    /// Returns the raw 12-bit reading, from 0 to 4095.
+    pub fn value(&self) -> Result<u16, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_analog_read(*self.port)) } as u16)
+    }
+
+    /// Returns the input voltage, from 0 to 5 volts.
+    pub fn voltage(&self) -> Result<f64, AdiError> {
+        Ok(self.value()? as f64 * 5.0 / 4095.0)
+    }
+}
+
+/// A digital input on an ADI port, such as a limit switch or bumper.
+pub struct AdiDigitalIn {
+    port: AdiPort,
+}
+
+impl AdiDigitalIn {
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_DIGITAL_IN)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    /// Returns true if the input is high (for a limit switch, if it is pressed).
+    pub fn is_high(&self) -> Result<bool, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_digital_read(*self.port)) } == 1)
+    }
+}
+
+/// A limit switch or bumper switch on an ADI port.
+pub struct AdiButton {
+    port: AdiPort,
+}
+
+impl AdiButton {
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_DIGITAL_IN)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    pub fn is_pressed(&self) -> Result<bool, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_digital_read(*self.port)) } == 1)
+    }
+
+    /// Returns true if the button is pressed and was not pressed the last time this was called.
+    ///
+    /// PROS tracks this per port, so only call this from one place for each button.
+    pub fn was_pressed(&self) -> Result<bool, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_digital_get_new_press(*self.port)) } == 1)
+    }
+}
+
+/// A legacy light sensor on an ADI port.
+pub struct AdiLightSensor {
+    port: AdiPort,
+}
+
+impl AdiLightSensor {
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_ANALOG_IN)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    /// Returns the raw 12-bit reading, from 0 to 4095.
+    /// The reading is lower when more light reaches the sensor.
+    pub fn value(&self) -> Result<u16, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_analog_read(*self.port)) } as u16)
+    }
+
+    /// Returns how bright the light reaching the sensor is, from 0.0 (dark) to 1.0 (bright).
+    pub fn brightness(&self) -> Result<f64, AdiError> {
+        Ok(1.0 - self.value()? as f64 / 4095.0)
+    }
+}
+
+/// A digital output on an ADI port, such as a pneumatic solenoid or LED.
+pub struct AdiDigitalOut {
+    port: AdiPort,
+}
+
+impl AdiDigitalOut {
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_DIGITAL_OUT)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    pub fn set(&self, high: bool) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_digital_write(*self.port, high));
+        }
+        Ok(())
+    }
+
+    pub fn set_high(&self) -> Result<(), AdiError> {
+        self.set(true)
+    }
+
+    pub fn set_low(&self) -> Result<(), AdiError> {
+        self.set(false)
+    }
+}
+
+/// A legacy motor or motor controller on an ADI port, driven by PWM.
+pub struct AdiMotor {
+    port: AdiPort,
+}
+
+impl AdiMotor {
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_LEGACY_PWM)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    /// Sets the output from -1.0 to 1.0.
+    pub fn set_output(&self, output: f32) -> Result<(), AdiError> {
+        self.set_raw_output((output.clamp(-1.0, 1.0) * 127.0) as i8)
+    }
+
+    /// Sets the output from -127 to 127.
+    pub fn set_raw_output(&self, output: i8) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_motor_set(*self.port, output));
+        }
+        Ok(())
+    }
+
+    /// Returns the last output that was set, from -127 to 127.
+    pub fn raw_output(&self) -> Result<i8, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_motor_get(*self.port)) } as i8)
+    }
+
+    pub fn stop(&self) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_motor_stop(*self.port));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum AdiError {
+    #[snafu(display("The port is not configured as the type of device being used."))]
+    WrongConfiguration,
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+impl core::error::Error for AdiError {}
+
+map_errno! {
+    AdiError {
+        EADDRINUSE => Self::WrongConfiguration,
     }
+    inherit PortError;
 }
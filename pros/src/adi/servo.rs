@@ -0,0 +1,70 @@
+use core::time::Duration;
+
+use pros_sys::PROS_ERR;
+
+use super::{AdiError, AdiPort};
+use crate::{error::bail_on, position::Position};
+
+/// A legacy 3-wire servo, or any device controlled by servo pulses, on an ADI port.
+pub struct Servo {
+    port: AdiPort,
+}
+
+impl Servo {
+    /// The furthest the legacy VEX servo turns from center in either direction, in degrees.
+    pub const MAX_ANGLE: f64 = 50.0;
+    /// The pulse width that moves the servo to its center.
+    pub const CENTER_PULSE_WIDTH: Duration = Duration::from_micros(1500);
+    /// The pulse width furthest from center that the port can output.
+    pub const PULSE_WIDTH_RANGE: Duration = Duration::from_micros(500);
+
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_port_set_config(*port, pros_sys::E_ADI_LEGACY_SERVO)
+            );
+        }
+        Ok(Self { port })
+    }
+
+    fn set_raw(&self, value: f64) -> Result<(), AdiError> {
+        let value = value.clamp(-127.0, 127.0) as i32;
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_port_set_value(*self.port, value));
+        }
+        Ok(())
+    }
+
+    fn raw(&self) -> Result<f64, AdiError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::adi_port_get_value(*self.port)) } as f64)
+    }
+
+    /// Moves the servo to an angle relative to its center.
+    /// Angles beyond [`Servo::MAX_ANGLE`] are clamped.
+    pub fn set_angle(&self, angle: Position) -> Result<(), AdiError> {
+        self.set_raw(angle.into_degrees() / Self::MAX_ANGLE * 127.0)
+    }
+
+    /// Returns the angle the servo was last told to move to.
+    pub fn target_angle(&self) -> Result<Position, AdiError> {
+        Ok(Position::from_degrees(
+            self.raw()? / 127.0 * Self::MAX_ANGLE,
+        ))
+    }
+
+    /// Sets the width of the pulses sent to the servo.
+    /// Widths further than [`Servo::PULSE_WIDTH_RANGE`] from [`Servo::CENTER_PULSE_WIDTH`] are clamped.
+    pub fn set_pulse_width(&self, width: Duration) -> Result<(), AdiError> {
+        let offset = width.as_micros() as f64 - Self::CENTER_PULSE_WIDTH.as_micros() as f64;
+        self.set_raw(offset / Self::PULSE_WIDTH_RANGE.as_micros() as f64 * 127.0)
+    }
+
+    /// Returns the width of the pulses currently being sent to the servo.
+    pub fn pulse_width(&self) -> Result<Duration, AdiError> {
+        let offset = self.raw()? / 127.0 * Self::PULSE_WIDTH_RANGE.as_micros() as f64;
+        Ok(Duration::from_micros(
+            (Self::CENTER_PULSE_WIDTH.as_micros() as f64 + offset) as u64,
+        ))
+    }
+}
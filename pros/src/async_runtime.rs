@@ -0,0 +1,50 @@
+//! A minimal executor for running futures on the current task.
+//!
+//! Futures are polled again whenever they wake the task blocking on them,
+//! and at least every [`POLL_INTERVAL`] so that futures waiting on device state can simply re-check it when polled.
+
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    time::Duration,
+};
+
+use crate::task::{self, TaskHandle};
+
+/// The longest a blocked task will wait before polling its future again.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs a future to completion on the current task, blocking until it finishes.
+///
+/// Waking the future sends a notification to the blocked task,
+/// so avoid using task notifications for anything else while in this function.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = task_waker(task::current());
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        unsafe {
+            pros_sys::task_notify_take(true, POLL_INTERVAL.as_millis() as u32);
+        }
+    }
+}
+
+fn task_waker(task: TaskHandle) -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |task| RawWaker::new(task, &VTABLE),
+        |task| unsafe {
+            pros_sys::task_notify(task.cast());
+        },
+        |task| unsafe {
+            pros_sys::task_notify(task.cast());
+        },
+        |_| {},
+    );
+
+    unsafe { Waker::from_raw(RawWaker::new(task.raw().cast(), &VTABLE)) }
+}
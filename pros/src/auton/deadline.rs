@@ -0,0 +1,114 @@
+//! Running an autonomous routine so that it always stops before the autonomous period ends.
+
+use alloc::sync::Arc;
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::RunMode;
+use crate::{cancel::CancellationToken, competition, motor::velocity, task};
+
+/// How a routine run with [`with_deadline`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlineOutcome {
+    /// The routine returned before it was cancelled.
+    Finished,
+    /// The routine was cancelled and returned within the margin.
+    Cancelled,
+    /// The routine didn't return within the margin after being cancelled, so its task was deleted.
+    Aborted,
+}
+
+/// How often the supervisor checks on the routine.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs an autonomous routine for a head to head match in a supervised task,
+/// cancelling it `margin` before the 15 second autonomous period ends.
+/// See [`with_deadline_for`].
+pub fn with_deadline(
+    routine: impl FnOnce(CancellationToken) -> crate::Result + Send + 'static,
+    margin: Duration,
+) -> DeadlineOutcome {
+    with_deadline_for(RunMode::Match, routine, margin)
+}
+
+/// Runs an autonomous routine in a supervised task,
+/// cancelling it `margin` before the autonomous period of the given kind of run ends.
+///
+/// The routine is given a [`CancellationToken`] that it should check regularly, returning once it is cancelled.
+/// If it hasn't returned `margin` after being cancelled, its task is deleted.
+/// Either way, every motor is stopped before this returns, so control is handed back cleanly.
+///
+/// The period is timed from when autonomous started (see [`competition::autonomous_elapsed`]),
+/// or from when this is called if autonomous isn't running.
+/// Errors returned by the routine are passed to [`crate::error::report`].
+pub fn with_deadline_for(
+    run_mode: RunMode,
+    routine: impl FnOnce(CancellationToken) -> crate::Result + Send + 'static,
+    margin: Duration,
+) -> DeadlineOutcome {
+    let elapsed = competition::autonomous_elapsed().unwrap_or_default();
+    let time_left = run_mode
+        .autonomous_length()
+        .saturating_sub(elapsed)
+        .saturating_sub(margin);
+
+    let token = CancellationToken::new();
+    let finished = Arc::new(AtomicBool::new(false));
+    let handle = {
+        let token = token.clone();
+        let finished = finished.clone();
+        task::Builder::new()
+            .name("autonomous")
+            .spawn(move || {
+                if let Err(err) = routine(token) {
+                    crate::error::report(&*err);
+                }
+                finished.store(true, Ordering::Release);
+            })
+            .expect("Failed to spawn autonomous task")
+    };
+
+    let outcome = if wait_for(&finished, time_left) {
+        DeadlineOutcome::Finished
+    } else {
+        token.cancel();
+        if wait_for(&finished, margin) {
+            DeadlineOutcome::Cancelled
+        } else {
+            handle.abort();
+            DeadlineOutcome::Aborted
+        }
+    };
+
+    stop_all_motors();
+    outcome
+}
+
+/// Waits until `finished` is set or `timeout` passes, returning whether it was set.
+fn wait_for(finished: &AtomicBool, timeout: Duration) -> bool {
+    let start = unsafe { pros_sys::millis() };
+    loop {
+        if finished.load(Ordering::Acquire) {
+            return true;
+        }
+        let now = unsafe { pros_sys::millis() };
+        if Duration::from_millis((now - start) as u64) >= timeout {
+            return false;
+        }
+        task::sleep(POLL_INTERVAL);
+    }
+}
+
+fn stop_all_motors() {
+    for port in 1..=21 {
+        // Otherwise the background velocity and hold loops would drive the motor again on their next update.
+        velocity::release(port);
+        unsafe {
+            // Ports without a motor just set errno.
+            pros_sys::motor_move(port, 0);
+        }
+        crate::error::take_errno();
+    }
+}
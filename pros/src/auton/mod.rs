@@ -0,0 +1,213 @@
+//! Helpers for writing autonomous routines.
+//!
+//! Routines are usually written once for one alliance and mirrored for the other with [`FieldMirror`].
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::time::Duration;
+
+#[cfg(feature = "alloc")]
+use snafu::Snafu;
+
+use crate::pose::Pose;
+#[cfg(feature = "alloc")]
+use crate::usd::{self, UsdError};
+
+#[cfg(feature = "alloc")]
+pub mod deadline;
+#[cfg(feature = "replan")]
+pub mod replan;
+#[cfg(all(not(feature = "lvgl"), feature = "alloc"))]
+pub mod selector;
+
+#[cfg(feature = "alloc")]
+pub use deadline::{with_deadline, with_deadline_for, DeadlineOutcome};
+
+/// The alliance the robot is playing on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alliance {
+    #[default]
+    Red,
+    Blue,
+}
+
+impl Alliance {
+    /// Returns the other alliance.
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Red => Self::Blue,
+            Self::Blue => Self::Red,
+        }
+    }
+}
+
+/// What kind of run the robot is doing, which changes how long each phase lasts.
+///
+/// It can be chosen with a [`Selector`](selector::Selector),
+/// or stored on the SD card with [`RunMode::save`] and read back with [`RunMode::load`]
+/// so a skills robot can be set up before it gets to the field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RunMode {
+    /// A head to head match with a 15 second autonomous and 1:45 driver control phase.
+    #[default]
+    Match,
+    /// A one minute driver skills run.
+    DriverSkills,
+    /// A one minute programming skills run.
+    ///
+    /// When the program is started without a competition switch or field controller,
+    /// PROS goes straight to opcontrol, so the [`robot!`](crate::robot) entry points run
+    /// autonomous first to make the run work from the brain alone.
+    ProgrammingSkills,
+}
+
+impl RunMode {
+    /// How long the autonomous phase lasts.
+    pub fn autonomous_length(self) -> Duration {
+        match self {
+            Self::Match => Duration::from_secs(15),
+            Self::DriverSkills => Duration::ZERO,
+            Self::ProgrammingSkills => Duration::from_secs(60),
+        }
+    }
+
+    /// How long the driver control phase lasts.
+    /// Programming skills runs started from the brain run their autonomous during this phase.
+    pub fn opcontrol_length(self) -> Duration {
+        match self {
+            Self::Match => Duration::from_secs(105),
+            Self::DriverSkills | Self::ProgrammingSkills => Duration::from_secs(60),
+        }
+    }
+
+    /// The name of the mode as written in a run mode file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Match => "match",
+            Self::DriverSkills => "driver skills",
+            Self::ProgrammingSkills => "programming skills",
+        }
+    }
+
+    /// Parses a mode from its [name](RunMode::name), ignoring case and surrounding whitespace.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        [Self::Match, Self::DriverSkills, Self::ProgrammingSkills]
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(text))
+    }
+
+    /// Reads the mode from a file on the SD card, returning `None` if the file doesn't exist.
+    ///
+    /// The file holds the mode's [name](RunMode::name) on its first line that isn't blank or a `#` comment.
+    #[cfg(feature = "alloc")]
+    pub fn load(path: &str) -> Result<Option<Self>, RunModeError> {
+        let contents = match usd::read(path) {
+            Ok(contents) => contents,
+            Err(UsdError::NotFound) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let contents = String::from_utf8_lossy(&contents);
+        let text = contents
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or("");
+        Self::parse(text)
+            .map(Some)
+            .ok_or_else(|| RunModeError::UnknownMode {
+                text: text.to_string(),
+            })
+    }
+
+    /// Saves the mode to a file on the SD card, replacing it if it exists.
+    #[cfg(feature = "alloc")]
+    pub fn save(self, path: &str) -> Result<(), RunModeError> {
+        usd::write(path, self.name().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, Snafu)]
+pub enum RunModeError {
+    #[snafu(display(
+        "The run mode file says `{text}` instead of `match`, `driver skills`, or `programming skills`."
+    ))]
+    UnknownMode { text: String },
+    #[snafu(display("{source}"), context(false))]
+    Usd { source: UsdError },
+}
+#[cfg(feature = "alloc")]
+impl core::error::Error for RunModeError {}
+
+/// How one alliance's half of the field maps onto the other's.
+/// This changes from game to game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The field is mirrored across the line x = 0.
+    MirrorX,
+    /// The field is mirrored across the line y = 0.
+    MirrorY,
+    /// The field is rotated 180 degrees around its center.
+    Rotational,
+}
+
+/// Converts poses and headings written for one alliance into the equivalent ones for the alliance being played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMirror {
+    /// The alliance the routine was written for.
+    pub authored_for: Alliance,
+    /// The alliance the robot is actually playing on.
+    pub playing_as: Alliance,
+    pub symmetry: Symmetry,
+}
+
+impl FieldMirror {
+    pub fn new(authored_for: Alliance, playing_as: Alliance, symmetry: Symmetry) -> Self {
+        Self {
+            authored_for,
+            playing_as,
+            symmetry,
+        }
+    }
+
+    /// Returns true if poses need to be transformed for the alliance being played.
+    pub fn is_mirrored(&self) -> bool {
+        self.authored_for != self.playing_as
+    }
+
+    /// Transforms a heading target in degrees.
+    pub fn heading(&self, heading: f64) -> f64 {
+        if !self.is_mirrored() {
+            return heading;
+        }
+        match self.symmetry {
+            Symmetry::MirrorX => 180.0 - heading,
+            Symmetry::MirrorY => -heading,
+            Symmetry::Rotational => heading + 180.0,
+        }
+    }
+
+    /// Transforms a pose or waypoint.
+    pub fn pose(&self, pose: Pose) -> Pose {
+        if !self.is_mirrored() {
+            return pose;
+        }
+        let (x, y) = match self.symmetry {
+            Symmetry::MirrorX => (-pose.x, pose.y),
+            Symmetry::MirrorY => (pose.x, -pose.y),
+            Symmetry::Rotational => (-pose.x, -pose.y),
+        };
+        Pose::new(x, y, self.heading(pose.heading))
+    }
+
+    /// Transforms every waypoint in a path.
+    #[cfg(feature = "alloc")]
+    pub fn path(&self, path: &[Pose]) -> Vec<Pose> {
+        path.iter().map(|pose| self.pose(*pose)).collect()
+    }
+}
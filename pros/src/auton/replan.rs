@@ -0,0 +1,291 @@
+//! Re-planning a path of waypoints around an obstacle seen during autonomous.
+//!
+//! When a distance or vision sensor spots something in the way, [`Replanner::avoid`] replaces the stretch
+//! of the remaining path that comes too close to it with a smooth detour that leaves and rejoins the path
+//! heading the same way it would have. Waypoints before the robot's current target are left alone.
+//!
+//! ```rust
+//! let mut replanner = Replanner::new(path, 4.0);
+//! // Later, when the front distance sensor sees something within 24 inches:
+//! let obstacle = Obstacle::from_detection(odometry.pose(), distance, 0.0, 6.0);
+//! if replanner.avoid(obstacle, next_waypoint) == Detour::Blocked {
+//!     log::warn!("the goal is blocked");
+//! }
+//! follow(replanner.path());
+//! ```
+//!
+//! This is only available with the `replan` feature, since building a detour takes a noticeable
+//! amount of CPU time for long paths.
+
+use alloc::vec::Vec;
+
+use crate::pose::Pose;
+
+/// A circular area the robot should stay out of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Obstacle {
+    pub x: f64,
+    pub y: f64,
+    pub radius: f64,
+}
+
+impl Obstacle {
+    pub const fn new(x: f64, y: f64, radius: f64) -> Self {
+        Self { x, y, radius }
+    }
+
+    /// Places an obstacle seen `distance` away from the robot, at `bearing` degrees counterclockwise
+    /// from the way the robot is facing, such as a distance sensor reading (a bearing of 0)
+    /// or a vision object's angle from the center of the camera's view.
+    /// The distance is to the obstacle's near side, so its center is `radius` further away.
+    pub fn from_detection(robot: Pose, distance: f64, bearing: f64, radius: f64) -> Self {
+        let angle = (robot.heading + bearing).to_radians();
+        let range = distance + radius;
+        Self {
+            x: robot.x + range * libm::cos(angle),
+            y: robot.y + range * libm::sin(angle),
+            radius,
+        }
+    }
+
+    /// Returns how far a point is from the obstacle's center.
+    fn distance_to(&self, x: f64, y: f64) -> f64 {
+        libm::hypot(x - self.x, y - self.y)
+    }
+}
+
+/// What [`Replanner::avoid`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detour {
+    /// The remaining path already keeps clear of the obstacle.
+    Clear,
+    /// Part of the remaining path was replaced by a detour.
+    Replanned,
+    /// The obstacle covers the start or end of the remaining path, so there is no way around it.
+    Blocked,
+}
+
+/// A path of waypoints that can be re-planned around obstacles as they are found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replanner {
+    path: Vec<Pose>,
+    clearance: f64,
+    spacing: f64,
+}
+
+impl Replanner {
+    /// Creates a re-planner that keeps the robot's center at least `clearance` from the edge of every obstacle,
+    /// which should be at least half the robot's width.
+    pub fn new(path: Vec<Pose>, clearance: f64) -> Self {
+        Self {
+            path,
+            clearance,
+            spacing: 2.0,
+        }
+    }
+
+    /// Sets how far apart the waypoints of detours are. The default is 2 units.
+    pub fn with_spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// The path, including any detours.
+    pub fn path(&self) -> &[Pose] {
+        &self.path
+    }
+
+    pub fn into_path(self) -> Vec<Pose> {
+        self.path
+    }
+
+    /// Re-plans the path from the waypoint at `from` on so it keeps clear of `obstacle`.
+    ///
+    /// Waypoints are only ever replaced after `from`, so this can be called while the robot
+    /// drives towards that waypoint. Detours are planned around one obstacle at a time,
+    /// so call this again with each new obstacle, including ones already avoided.
+    pub fn avoid(&mut self, obstacle: Obstacle, from: usize) -> Detour {
+        let keep_out = obstacle.radius + self.clearance;
+        let Some(remaining) = self
+            .path
+            .get(from..)
+            .filter(|remaining| remaining.len() >= 2)
+        else {
+            return Detour::Clear;
+        };
+        let (first, last) = (remaining[0], remaining[remaining.len() - 1]);
+        if obstacle.distance_to(first.x, first.y) < keep_out
+            || obstacle.distance_to(last.x, last.y) < keep_out
+        {
+            return Detour::Blocked;
+        }
+
+        // Find where along the path it comes closest to the obstacle.
+        let mut length = 0.0;
+        let mut closest: Option<(f64, f64, f64, f64)> = None;
+        for segment in remaining.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let (dx, dy) = (end.x - start.x, end.y - start.y);
+            let segment_length = libm::hypot(dx, dy);
+            let along = if segment_length > 0.0 {
+                (((obstacle.x - start.x) * dx + (obstacle.y - start.y) * dy) / segment_length)
+                    .clamp(0.0, segment_length)
+            } else {
+                0.0
+            };
+            let fraction = if segment_length > 0.0 {
+                along / segment_length
+            } else {
+                0.0
+            };
+            let (x, y) = (start.x + dx * fraction, start.y + dy * fraction);
+            let distance = obstacle.distance_to(x, y);
+            if distance < closest.map_or(f64::INFINITY, |(_, _, _, best)| best) {
+                closest = Some((length + along, x, y, distance));
+            }
+            length += segment_length;
+        }
+        let Some((closest_at, closest_x, closest_y, closest_distance)) = closest else {
+            return Detour::Clear;
+        };
+        if closest_distance >= keep_out {
+            return Detour::Clear;
+        }
+
+        // Leave and rejoin the path far enough from the obstacle to turn smoothly.
+        let leave_at = (closest_at - 2.0 * keep_out).max(0.0);
+        let rejoin_at = (closest_at + 2.0 * keep_out).min(length);
+        let (leave, leave_index) = point_at(remaining, leave_at);
+        let (rejoin, rejoin_index) = point_at(remaining, rejoin_at);
+
+        // Pass the obstacle on the side the path was already closest to.
+        let (normal_x, normal_y) = if closest_distance < 1e-9 {
+            // The path runs straight through the center, so go around to the left.
+            let heading = libm::atan2(rejoin.y - leave.y, rejoin.x - leave.x);
+            (-libm::sin(heading), libm::cos(heading))
+        } else {
+            (
+                (closest_x - obstacle.x) / closest_distance,
+                (closest_y - obstacle.y) / closest_distance,
+            )
+        };
+        let apex = (
+            obstacle.x + normal_x * keep_out,
+            obstacle.y + normal_y * keep_out,
+        );
+
+        let mut detour = self.detour(
+            remaining, leave, leave_at, apex, rejoin, rejoin_at, &obstacle,
+        );
+        // Leaving or rejoining exactly at a waypoint shouldn't repeat it.
+        if same_place(self.path.get(from + rejoin_index + 1), detour.last()) {
+            detour.pop();
+        }
+        if same_place(self.path.get(from + leave_index), detour.first()) {
+            detour.remove(0);
+        }
+        let replaced = from + leave_index + 1..from + rejoin_index + 1;
+        self.path.splice(replaced, detour);
+        Detour::Replanned
+    }
+
+    /// Builds the waypoints from `leave` to `rejoin` through `apex`,
+    /// as two cubic Hermite curves that start and end tangent to the path.
+    #[allow(clippy::too_many_arguments)]
+    fn detour(
+        &self,
+        remaining: &[Pose],
+        leave: Pose,
+        leave_at: f64,
+        apex: (f64, f64),
+        rejoin: Pose,
+        rejoin_at: f64,
+        obstacle: &Obstacle,
+    ) -> Vec<Pose> {
+        let keep_out = obstacle.radius + self.clearance;
+        let span = (rejoin_at - leave_at).max(1e-9);
+        let leave_direction = direction_at(remaining, leave_at);
+        let rejoin_direction = direction_at(remaining, rejoin_at);
+        let apex_direction = ((rejoin.x - leave.x) / span, (rejoin.y - leave.y) / span);
+
+        let mut points = Vec::new();
+        let halves = [
+            ((leave.x, leave.y), leave_direction, apex, apex_direction),
+            (apex, apex_direction, (rejoin.x, rejoin.y), rejoin_direction),
+        ];
+        for (start, start_direction, end, end_direction) in halves {
+            let half = libm::hypot(end.0 - start.0, end.1 - start.1).max(span / 2.0);
+            let steps = libm::ceil(half / self.spacing.max(1e-3)).max(1.0) as usize;
+            for step in 1..=steps {
+                let t = step as f64 / steps as f64;
+                let (h00, h10, h01, h11) = (
+                    2.0 * t * t * t - 3.0 * t * t + 1.0,
+                    t * t * t - 2.0 * t * t + t,
+                    -2.0 * t * t * t + 3.0 * t * t,
+                    t * t * t - t * t,
+                );
+                let mut x = h00 * start.0
+                    + h10 * half * start_direction.0
+                    + h01 * end.0
+                    + h11 * half * end_direction.0;
+                let mut y = h00 * start.1
+                    + h10 * half * start_direction.1
+                    + h01 * end.1
+                    + h11 * half * end_direction.1;
+                // Curves can cut a little inside the circle, so push those points back out to its edge.
+                let distance = obstacle.distance_to(x, y);
+                if distance < keep_out && distance > 1e-9 {
+                    x = obstacle.x + (x - obstacle.x) / distance * keep_out;
+                    y = obstacle.y + (y - obstacle.y) / distance * keep_out;
+                }
+                points.push((x, y));
+            }
+        }
+        // The last point is the waypoint the path rejoins at, which is kept as it was.
+        points.pop();
+
+        let mut previous = (leave.x, leave.y);
+        let mut detour = Vec::with_capacity(points.len() + 2);
+        detour.push(leave);
+        detour.extend(points.into_iter().map(|(x, y)| {
+            let heading = libm::atan2(y - previous.1, x - previous.0).to_degrees();
+            previous = (x, y);
+            Pose::new(x, y, heading)
+        }));
+        detour.push(rejoin);
+        detour
+    }
+}
+
+/// Returns the point `at` units along a path, and the index of the waypoint at or before it.
+fn point_at(path: &[Pose], at: f64) -> (Pose, usize) {
+    let mut length = 0.0;
+    for (index, segment) in path.windows(2).enumerate() {
+        let (start, end) = (segment[0], segment[1]);
+        let segment_length = libm::hypot(end.x - start.x, end.y - start.y);
+        if length + segment_length >= at && segment_length > 0.0 {
+            let fraction = (at - length) / segment_length;
+            let heading = libm::atan2(end.y - start.y, end.x - start.x).to_degrees();
+            return (
+                Pose::new(
+                    start.x + (end.x - start.x) * fraction,
+                    start.y + (end.y - start.y) * fraction,
+                    heading,
+                ),
+                index,
+            );
+        }
+        length += segment_length;
+    }
+    (path[path.len() - 1], path.len() - 1)
+}
+
+fn same_place(a: Option<&Pose>, b: Option<&Pose>) -> bool {
+    matches!((a, b), (Some(a), Some(b)) if libm::hypot(a.x - b.x, a.y - b.y) < 1e-6)
+}
+
+/// Returns the unit direction of travel `at` units along a path.
+fn direction_at(path: &[Pose], at: f64) -> (f64, f64) {
+    let heading = point_at(path, at).0.heading.to_radians();
+    (libm::cos(heading), libm::sin(heading))
+}
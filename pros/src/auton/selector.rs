@@ -0,0 +1,221 @@
+//! Picking an autonomous routine, alliance, and driver with the LCD buttons.
+
+use alloc::{ffi::CString, format, string::String, string::ToString, sync::Arc, vec::Vec};
+
+use super::{Alliance, RunMode};
+use crate::{
+    lcd::buttons::{self, Button},
+    sync::Mutex,
+};
+
+/// The kinds of run the middle button cycles through.
+const MODES: [(RunMode, Alliance); 4] = [
+    (RunMode::Match, Alliance::Red),
+    (RunMode::Match, Alliance::Blue),
+    (RunMode::DriverSkills, Alliance::Red),
+    (RunMode::ProgrammingSkills, Alliance::Red),
+];
+
+/// The line the left and right buttons change when choosing a driver too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Routine,
+    Mode,
+    Driver,
+}
+
+struct SelectorState {
+    routines: Vec<&'static str>,
+    index: usize,
+    mode: usize,
+    drivers: Vec<String>,
+    driver: usize,
+    row: Row,
+}
+
+impl SelectorState {
+    fn marker(&self, row: Row) -> &'static str {
+        if !self.drivers.is_empty() && self.row == row {
+            "> "
+        } else {
+            ""
+        }
+    }
+
+    fn render(&self) {
+        let (run_mode, alliance) = MODES[self.mode];
+        let mut lines = Vec::from([
+            format!(
+                "{}Auton: {}",
+                self.marker(Row::Routine),
+                self.routines[self.index]
+            ),
+            format!(
+                "{}{}",
+                self.marker(Row::Mode),
+                match run_mode {
+                    RunMode::Match => format!("Mode: Match ({alliance:?})"),
+                    RunMode::DriverSkills => "Mode: Driver Skills".to_string(),
+                    RunMode::ProgrammingSkills => "Mode: Programming Skills".to_string(),
+                }
+            ),
+        ]);
+        if self.drivers.is_empty() {
+            lines.push("<  prev   |   mode   |   next  >".to_string());
+        } else {
+            lines.push(format!(
+                "{}Driver: {}",
+                self.marker(Row::Driver),
+                self.drivers[self.driver]
+            ));
+            lines.push("<  prev   |   line   |   next  >".to_string());
+        }
+        for (line, text) in lines.into_iter().enumerate() {
+            let text = CString::new(text).expect("names should not contain null bytes");
+            unsafe {
+                pros_sys::lcd_set_text(line as _, text.as_ptr());
+            }
+        }
+    }
+
+    /// Moves the selection on the current row forwards or backwards.
+    fn step(&mut self, forward: bool) {
+        let (value, len) = match self.row {
+            Row::Routine => (&mut self.index, self.routines.len()),
+            Row::Mode => (&mut self.mode, MODES.len()),
+            Row::Driver => (&mut self.driver, self.drivers.len()),
+        };
+        *value = if forward {
+            (*value + 1) % len
+        } else {
+            value.checked_sub(1).unwrap_or(len - 1)
+        };
+    }
+
+    fn middle(&mut self) {
+        if self.drivers.is_empty() {
+            self.mode = (self.mode + 1) % MODES.len();
+        } else {
+            self.row = match self.row {
+                Row::Routine => Row::Mode,
+                Row::Mode => Row::Driver,
+                Row::Driver => Row::Routine,
+            };
+        }
+    }
+}
+
+/// Lets the drive team choose an autonomous routine, alliance, and [`RunMode`] before a match,
+/// and optionally which driver's [`DriverProfile`](crate::controller::profile::DriverProfile) to use.
+///
+/// The left and right LCD buttons cycle through the routines and the middle button cycles through
+/// a red alliance match, a blue alliance match, driver skills, and programming skills.
+/// When choosing a driver too, the middle button instead picks which line the left and right buttons change.
+/// Skills runs are always on the red alliance.
+/// The selection is drawn on the top lines of the LCD,
+/// so printing to the LCD while selecting will draw over it.
+pub struct Selector {
+    state: Arc<Mutex<SelectorState>>,
+}
+
+impl Selector {
+    /// Creates a selector for the given routine names and registers its button callbacks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `routines` is empty.
+    pub fn new(routines: Vec<&'static str>) -> Self {
+        Self::with_drivers(routines, Vec::new())
+    }
+
+    /// Creates a selector that also chooses between the given driver names.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `routines` is empty.
+    pub fn with_drivers(routines: Vec<&'static str>, drivers: Vec<String>) -> Self {
+        assert!(
+            !routines.is_empty(),
+            "Selector needs at least one routine to choose from"
+        );
+
+        let state = Arc::new(Mutex::new(SelectorState {
+            routines,
+            index: 0,
+            mode: 0,
+            drivers,
+            driver: 0,
+            row: Row::Routine,
+        }));
+
+        let left = state.clone();
+        buttons::register(
+            move || {
+                let mut state = left.lock();
+                state.step(false);
+                state.render();
+            },
+            Button::Left,
+        );
+
+        let middle = state.clone();
+        buttons::register(
+            move || {
+                let mut state = middle.lock();
+                state.middle();
+                state.render();
+            },
+            Button::Middle,
+        );
+
+        let right = state.clone();
+        buttons::register(
+            move || {
+                let mut state = right.lock();
+                state.step(true);
+                state.render();
+            },
+            Button::Right,
+        );
+
+        state.lock().render();
+        Self { state }
+    }
+
+    /// Returns the index of the selected routine.
+    pub fn routine(&self) -> usize {
+        self.state.lock().index
+    }
+
+    /// Returns the name of the selected routine.
+    pub fn routine_name(&self) -> &'static str {
+        let state = self.state.lock();
+        state.routines[state.index]
+    }
+
+    /// Returns the selected alliance.
+    pub fn alliance(&self) -> Alliance {
+        MODES[self.state.lock().mode].1
+    }
+
+    /// Returns the selected kind of run.
+    pub fn run_mode(&self) -> RunMode {
+        MODES[self.state.lock().mode].0
+    }
+
+    /// Selects a kind of run, such as one read with [`RunMode::load`]. Matches start on the red alliance.
+    pub fn set_run_mode(&self, run_mode: RunMode) {
+        let mut state = self.state.lock();
+        state.mode = MODES
+            .iter()
+            .position(|&(mode, _)| mode == run_mode)
+            .unwrap_or(0);
+        state.render();
+    }
+
+    /// Returns the name of the selected driver, if drivers were given.
+    pub fn driver(&self) -> Option<String> {
+        let state = self.state.lock();
+        state.drivers.get(state.driver).cloned()
+    }
+}
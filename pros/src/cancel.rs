@@ -0,0 +1,132 @@
+//! Cooperative cancellation for tasks, futures, and commands.
+//!
+//! Deleting a task can leave a device half configured or a mutex locked if it happens mid-call.
+//! Instead, long running work checks a [`CancellationToken`] and stops on its own once the token is cancelled,
+//! for example when the autonomous period ends.
+
+use alloc::sync::Arc;
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll},
+};
+
+use snafu::Snafu;
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    parent: Option<Arc<TokenState>>,
+}
+
+impl TokenState {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.is_cancelled())
+    }
+}
+
+/// A flag that work can check to see whether it should stop.
+/// Clones share the same flag, so one can be handed to a task while another is kept to cancel it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that is cancelled when this one is, but can also be cancelled on its own
+    /// without affecting this one.
+    pub fn child(&self) -> Self {
+        Self {
+            state: Arc::new(TokenState {
+                cancelled: AtomicBool::new(false),
+                parent: Some(self.state.clone()),
+            }),
+        }
+    }
+
+    /// Cancels the token and all of its children.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled()
+    }
+
+    /// Returns an error if the token has been cancelled, so that work can stop with `?`.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns a future that resolves once the token is cancelled.
+    ///
+    /// The future checks the token each time it is polled,
+    /// so it relies on being polled regularly, as [`crate::async_runtime::block_on`] does.
+    pub fn cancelled(&self) -> CancelledFuture<'_> {
+        CancelledFuture { token: self }
+    }
+
+    /// Returns a guard that cancels the token when it is dropped,
+    /// so work is cancelled even if the code that started it returns early or panics.
+    pub fn drop_guard(self) -> CancelGuard {
+        CancelGuard { token: Some(self) }
+    }
+}
+
+/// A future that resolves once a [`CancellationToken`] is cancelled.
+/// Created by [`CancellationToken::cancelled`].
+pub struct CancelledFuture<'a> {
+    token: &'a CancellationToken,
+}
+
+impl Future for CancelledFuture<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.token.is_cancelled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Cancels a [`CancellationToken`] when dropped. Created by [`CancellationToken::drop_guard`].
+#[derive(Debug)]
+pub struct CancelGuard {
+    token: Option<CancellationToken>,
+}
+
+impl CancelGuard {
+    /// Returns the token without cancelling it.
+    pub fn disarm(mut self) -> CancellationToken {
+        self.token.take().unwrap()
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        if let Some(token) = &self.token {
+            token.cancel();
+        }
+    }
+}
+
+/// The error returned by [`CancellationToken::check`] once a token has been cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Snafu)]
+#[snafu(display("The operation was cancelled."))]
+pub struct Cancelled;
+impl core::error::Error for Cancelled {}
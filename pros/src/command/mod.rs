@@ -0,0 +1,368 @@
+//! Running named robot actions over several loop iterations.
+//!
+//! A [`Command`] is an action such as "raise the lift" that runs a little every time
+//! [`Scheduler::run`] is called until it finishes.
+//! Commands are registered with the scheduler by name so they can be started from controller bindings,
+//! autonomous routines, or recorded [`Macro`]s.
+//!
+//! Mechanisms can be added to the scheduler as [`Subsystem`]s, usually with the [`subsystem`] attribute.
+//! The scheduler runs their control loops every time it runs, makes sure only one command uses each at a time,
+//! and runs their default command whenever nothing else is using them.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+
+use snafu::Snafu;
+
+use crate::{cancel::CancellationToken, control::exit::ExitCondition, sync::Mutex};
+
+pub mod recording;
+
+pub use recording::Macro;
+use recording::{Playback, Recorder};
+
+/// An action that runs over several updates.
+/// `R` is whatever the command acts on, usually the robot's subsystems.
+pub trait Command<R> {
+    /// Runs one step of the command, returning true once it has finished.
+    fn update(&mut self, robot: &mut R) -> crate::Result<bool>;
+
+    /// Called when the command stops, whether it finished or was cancelled.
+    fn end(&mut self, _robot: &mut R) {}
+}
+
+impl<R, F: FnMut(&mut R) -> crate::Result<bool>> Command<R> for F {
+    fn update(&mut self, robot: &mut R) -> crate::Result<bool> {
+        self(robot)
+    }
+}
+
+/// A command that runs one step of a control loop every update until an [`ExitCondition`] is met.
+///
+/// The step drives the mechanism and returns the loop's error, which is checked against the condition:
+/// ```rust
+/// scheduler.register_requiring("raise lift", &["lift"], || {
+///     Motion::new(
+///         |robot: &mut Robot| Ok(robot.lift.step_towards(30.0)?),
+///         ErrorWithin(0.5).held_for(Duration::from_millis(100)) | Timeout::new(Duration::from_secs(2)),
+///     )
+/// });
+/// ```
+pub struct Motion<S, C> {
+    step: S,
+    exit: C,
+}
+
+impl<S, C: ExitCondition> Motion<S, C> {
+    pub fn new(step: S, exit: C) -> Self {
+        Self { step, exit }
+    }
+}
+
+impl<R, S: FnMut(&mut R) -> crate::Result<f64>, C: ExitCondition> Command<R> for Motion<S, C> {
+    fn update(&mut self, robot: &mut R) -> crate::Result<bool> {
+        let error = (self.step)(robot)?;
+        Ok(self.exit.update(error))
+    }
+
+    fn end(&mut self, _robot: &mut R) {
+        self.exit.reset();
+    }
+}
+
+/// Implements [`Subsystem`] for a struct.
+///
+/// The attribute takes these optional arguments:
+/// - `name = "..."`: the subsystem's [`Subsystem::NAME`], which defaults to the struct's name.
+/// - `periodic = method`: a method run every time the scheduler runs, returning `()` or a `Result`.
+/// - `default_command = "..."`: the registered command to run when nothing else is using the subsystem.
+///
+/// Example:
+/// ```rust
+/// use pros::{command::subsystem, prelude::*};
+///
+/// #[subsystem(periodic = update, default_command = "hold lift")]
+/// pub struct Lift {
+///     motor: Motor,
+///     target: f64,
+/// }
+///
+/// impl Lift {
+///     fn update(&mut self) -> Result<(), MotorError> {
+///         self.motor
+///             .set_position_absolute(Position::from_degrees(self.target), 100)
+///     }
+/// }
+/// ```
+pub use pros_macros::subsystem;
+
+/// A mechanism that commands act on, such as a lift or drivetrain.
+/// This should usually be implemented with the [`subsystem`] attribute.
+pub trait Subsystem: Send + 'static {
+    /// The name commands use to require this subsystem.
+    const NAME: &'static str;
+    /// The registered command to run when no other command requires this subsystem.
+    const DEFAULT_COMMAND: Option<&'static str> = None;
+
+    /// Runs the subsystem's control loops. This is called every time the scheduler runs,
+    /// whether or not a command is using the subsystem.
+    fn periodic(&mut self) -> crate::Result {
+        Ok(())
+    }
+
+    /// Wraps the subsystem so it can be shared between the scheduler and commands.
+    fn shared(self) -> Arc<Mutex<Self>>
+    where
+        Self: Sized,
+    {
+        Arc::new(Mutex::new(self))
+    }
+}
+
+/// Converts what a subsystem's periodic method returns into a result. Used by the [`subsystem`] attribute.
+#[doc(hidden)]
+pub trait PeriodicOutput {
+    fn into_result(self) -> crate::Result;
+}
+
+impl PeriodicOutput for () {
+    fn into_result(self) -> crate::Result {
+        Ok(())
+    }
+}
+
+impl<E: core::error::Error + 'static> PeriodicOutput for Result<(), E> {
+    fn into_result(self) -> crate::Result {
+        Ok(self?)
+    }
+}
+
+type Factory<R> = Box<dyn Fn() -> Box<dyn Command<R>>>;
+
+struct SubsystemEntry {
+    name: &'static str,
+    default_command: Option<&'static str>,
+    periodic: Box<dyn FnMut() -> crate::Result>,
+}
+
+/// Starts, runs, and stops named commands.
+pub struct Scheduler<R> {
+    registry: Vec<(String, Vec<&'static str>, Factory<R>)>,
+    running: Vec<(String, Box<dyn Command<R>>)>,
+    subsystems: Vec<SubsystemEntry>,
+    recorder: Option<Recorder>,
+    macros: Vec<(String, Macro)>,
+    playing: Vec<Playback>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl<R> Default for Scheduler<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> Scheduler<R> {
+    pub fn new() -> Self {
+        Self {
+            registry: Vec::new(),
+            running: Vec::new(),
+            subsystems: Vec::new(),
+            recorder: None,
+            macros: Vec::new(),
+            playing: Vec::new(),
+            cancellation: None,
+        }
+    }
+
+    /// Registers a command under a name, replacing any command already registered with that name.
+    /// `factory` creates a fresh instance of the command each time it is scheduled.
+    pub fn register<C: Command<R> + 'static>(
+        &mut self,
+        name: &str,
+        factory: impl Fn() -> C + 'static,
+    ) {
+        self.register_requiring(name, &[], factory);
+    }
+
+    /// Registers a command that uses the named [`Subsystem`]s.
+    /// Scheduling it cancels any other running command that requires one of the same subsystems.
+    pub fn register_requiring<C: Command<R> + 'static>(
+        &mut self,
+        name: &str,
+        requirements: &[&'static str],
+        factory: impl Fn() -> C + 'static,
+    ) {
+        let factory: Factory<R> = Box::new(move || Box::new(factory()));
+        let requirements = requirements.to_vec();
+        match self.registry.iter_mut().find(|(n, _, _)| n == name) {
+            Some((_, existing_requirements, existing)) => {
+                *existing_requirements = requirements;
+                *existing = factory;
+            }
+            None => self
+                .registry
+                .push((name.to_string(), requirements, factory)),
+        }
+    }
+
+    /// Adds a subsystem whose periodic method and default command are run by the scheduler.
+    /// Adding a subsystem with the same name as one already added replaces it.
+    pub fn add_subsystem<S: Subsystem>(&mut self, subsystem: &Arc<Mutex<S>>) {
+        let subsystem = subsystem.clone();
+        self.subsystems.retain(|entry| entry.name != S::NAME);
+        self.subsystems.push(SubsystemEntry {
+            name: S::NAME,
+            default_command: S::DEFAULT_COMMAND,
+            periodic: Box::new(move || subsystem.lock().periodic()),
+        });
+    }
+
+    fn requirements(&self, name: &str) -> &[&'static str] {
+        self.registry
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map_or(&[], |(_, requirements, _)| requirements)
+    }
+
+    /// Starts a registered command.
+    /// If the command is already running it is restarted from the beginning.
+    pub fn schedule(&mut self, robot: &mut R, name: &str) -> Result<(), SchedulerError> {
+        let (_, requirements, factory) = self
+            .registry
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .ok_or_else(|| SchedulerError::UnknownCommand { name: name.into() })?;
+        let command = factory();
+        let conflicting: Vec<String> = self
+            .running
+            .iter()
+            .map(|(n, _)| n)
+            .filter(|n| {
+                self.requirements(n)
+                    .iter()
+                    .any(|subsystem| requirements.contains(subsystem))
+            })
+            .cloned()
+            .collect();
+
+        self.cancel(robot, name);
+        for conflicting in conflicting {
+            self.cancel(robot, &conflicting);
+        }
+        self.running.push((name.to_string(), command));
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(name);
+        }
+        Ok(())
+    }
+
+    /// Stops a command if it is running.
+    pub fn cancel(&mut self, robot: &mut R, name: &str) {
+        if let Some(index) = self.running.iter().position(|(n, _)| n == name) {
+            let (_, mut command) = self.running.remove(index);
+            command.end(robot);
+        }
+    }
+
+    /// Stops every running command and macro.
+    pub fn cancel_all(&mut self, robot: &mut R) {
+        self.playing.clear();
+        for (_, mut command) in self.running.drain(..) {
+            command.end(robot);
+        }
+    }
+
+    /// Stops every command and macro whenever the token is cancelled,
+    /// such as when the autonomous period ends.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Returns true if the named command is running.
+    pub fn is_running(&self, name: &str) -> bool {
+        self.running.iter().any(|(n, _)| n == name)
+    }
+
+    /// Runs one step of every subsystem and running command,
+    /// and starts any macro steps and default commands that are due.
+    /// This should be called once per loop.
+    /// If the scheduler's cancellation token has been cancelled, every command is stopped instead.
+    ///
+    /// Commands that fail are stopped, and their errors and those from subsystems are passed to [`crate::error::report`].
+    pub fn run(&mut self, robot: &mut R) {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            self.cancel_all(robot);
+            return;
+        }
+
+        for subsystem in self.subsystems.iter_mut() {
+            if let Err(err) = (subsystem.periodic)() {
+                crate::error::report(&*err);
+            }
+        }
+
+        let now = unsafe { pros_sys::millis() };
+        let mut due = Vec::new();
+        self.playing.retain_mut(|playback| {
+            due.extend(playback.due(now));
+            !playback.is_finished()
+        });
+        for name in due {
+            if let Err(err) = self.schedule(robot, &name) {
+                crate::error::report(&err);
+            }
+        }
+
+        // Default commands go last so that anything else started this cycle takes priority.
+        let idle: Vec<&'static str> = self
+            .subsystems
+            .iter()
+            .filter(|subsystem| {
+                !self
+                    .running
+                    .iter()
+                    .any(|(n, _)| self.requirements(n).contains(&subsystem.name))
+            })
+            .filter_map(|subsystem| subsystem.default_command)
+            .filter(|name| !self.is_running(name))
+            .collect();
+        for name in idle {
+            if let Err(err) = self.schedule(robot, name) {
+                crate::error::report(&err);
+            }
+        }
+
+        self.running.retain_mut(|(_, command)| {
+            let finished = command.update(robot).unwrap_or_else(|err| {
+                crate::error::report(&*err);
+                true
+            });
+            if finished {
+                command.end(robot);
+            }
+            !finished
+        });
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SchedulerError {
+    #[snafu(display("No command named {name} is registered."))]
+    UnknownCommand { name: String },
+    #[snafu(display("No macro named {name} has been saved."))]
+    UnknownMacro { name: String },
+    #[snafu(display("A macro is already being recorded."))]
+    AlreadyRecording,
+    #[snafu(display("No macro is being recorded."))]
+    NotRecording,
+}
+impl core::error::Error for SchedulerError {}
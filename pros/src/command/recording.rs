@@ -0,0 +1,150 @@
+//! Recording sequences of commands during driving and replaying them later.
+//!
+//! While a recording is in progress, every command the [`Scheduler`] starts is saved along with when it started.
+//! Playing the resulting [`Macro`] starts the same commands with the same timing,
+//! so a multi-step sequence like scoring a piece can be bound to a single button.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{Scheduler, SchedulerError};
+use crate::encode::{Decode, DecodeError, Decoder, Encode, Encoder};
+
+/// A recorded sequence of commands.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Macro {
+    /// Command names paired with how many milliseconds after the start of the macro they were started.
+    pub steps: Vec<(u32, String)>,
+}
+
+impl Macro {
+    /// Returns how long the macro takes to start all of its commands, in milliseconds.
+    pub fn duration(&self) -> u32 {
+        self.steps.last().map_or(0, |(offset, _)| *offset)
+    }
+}
+
+impl Encode for Macro {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.steps);
+    }
+}
+
+impl Decode for Macro {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            steps: decoder.read()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    name: String,
+    steps: Vec<(u32, String)>,
+}
+
+impl Recorder {
+    pub(crate) fn record(&mut self, command: &str) {
+        let now = unsafe { pros_sys::millis() };
+        self.steps.push((now, command.to_string()));
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Playback {
+    start: u32,
+    steps: Vec<(u32, String)>,
+    next: usize,
+}
+
+impl Playback {
+    /// Returns the commands whose start time has passed since the last call.
+    pub(crate) fn due(&mut self, now: u32) -> Vec<String> {
+        let elapsed = now - self.start;
+        let mut due = Vec::new();
+        while let Some((offset, name)) = self.steps.get(self.next) {
+            if *offset > elapsed {
+                break;
+            }
+            due.push(name.clone());
+            self.next += 1;
+        }
+        due
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.next >= self.steps.len()
+    }
+}
+
+impl<R> Scheduler<R> {
+    /// Starts recording the commands that get scheduled into a macro with the given name.
+    /// The timing of the macro starts with the first recorded command.
+    pub fn start_recording(&mut self, name: &str) -> Result<(), SchedulerError> {
+        if self.recorder.is_some() {
+            return Err(SchedulerError::AlreadyRecording);
+        }
+        self.recorder = Some(Recorder {
+            name: name.to_string(),
+            steps: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Returns true if a macro is being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Finishes the current recording and saves it, replacing any macro with the same name.
+    pub fn stop_recording(&mut self) -> Result<&Macro, SchedulerError> {
+        let recorder = self.recorder.take().ok_or(SchedulerError::NotRecording)?;
+        let start = recorder.steps.first().map_or(0, |(time, _)| *time);
+        let recorded = Macro {
+            steps: recorder
+                .steps
+                .into_iter()
+                .map(|(time, name)| (time - start, name))
+                .collect(),
+        };
+        Ok(self.add_macro(&recorder.name, recorded))
+    }
+
+    /// Saves a macro under a name, replacing any macro with the same name.
+    /// This can be used to restore macros loaded from the SD card.
+    pub fn add_macro(&mut self, name: &str, recorded: Macro) -> &Macro {
+        let index = match self.macros.iter().position(|(n, _)| n == name) {
+            Some(index) => {
+                self.macros[index].1 = recorded;
+                index
+            }
+            None => {
+                self.macros.push((name.to_string(), recorded));
+                self.macros.len() - 1
+            }
+        };
+        &self.macros[index].1
+    }
+
+    /// Returns the macro saved under a name.
+    pub fn get_macro(&self, name: &str) -> Option<&Macro> {
+        self.macros.iter().find(|(n, _)| n == name).map(|(_, m)| m)
+    }
+
+    /// Starts playing a saved macro. Its commands are started by [`Scheduler::run`] as they become due.
+    pub fn play_macro(&mut self, name: &str) -> Result<(), SchedulerError> {
+        let recorded = self
+            .get_macro(name)
+            .ok_or_else(|| SchedulerError::UnknownMacro { name: name.into() })?;
+        let steps = recorded.steps.clone();
+        self.playing.push(Playback {
+            start: unsafe { pros_sys::millis() },
+            steps,
+            next: 0,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+//! Reading the competition state and timing match phases.
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU32, Ordering},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+#[cfg(feature = "alloc")]
+use crate::{auton::RunMode, sync::Mutex, task};
+
+/// The phase of the match the robot is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompetitionMode {
+    Disabled,
+    Autonomous,
+    Opcontrol,
+}
+
+impl CompetitionMode {
+    /// The official length of this phase in a head to head match.
+    pub fn match_length(self) -> Option<Duration> {
+        match self {
+            Self::Disabled => None,
+            Self::Autonomous => Some(Duration::from_secs(15)),
+            Self::Opcontrol => Some(Duration::from_secs(105)),
+        }
+    }
+}
+
+/// Returns the current phase of the match.
+pub fn mode() -> CompetitionMode {
+    let status = unsafe { pros_sys::competition_get_status() } as i32;
+    if status & pros_sys::COMPETITION_DISABLED != 0 {
+        CompetitionMode::Disabled
+    } else if status & pros_sys::COMPETITION_AUTONOMOUS != 0 {
+        CompetitionMode::Autonomous
+    } else {
+        CompetitionMode::Opcontrol
+    }
+}
+
+/// Returns true if the robot is connected to a competition switch or field controller.
+pub fn is_connected() -> bool {
+    unsafe { pros_sys::competition_get_status() as i32 & pros_sys::COMPETITION_CONNECTED != 0 }
+}
+
+/// A change from one phase of the match to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompetitionUpdate {
+    pub previous: CompetitionMode,
+    pub current: CompetitionMode,
+}
+
+/// Returns a stream of competition state transitions,
+/// such as `Disabled -> Autonomous -> Opcontrol`.
+///
+/// Await [`CompetitionUpdates::changed`] in async code, or use it as a blocking [`Iterator`].
+/// Only transitions after this is called are yielded.
+pub fn updates() -> CompetitionUpdates {
+    CompetitionUpdates { last: mode() }
+}
+
+/// A stream of competition state transitions returned by [`updates`].
+#[derive(Debug, Clone)]
+pub struct CompetitionUpdates {
+    last: CompetitionMode,
+}
+
+impl CompetitionUpdates {
+    /// How often the blocking iterator checks the competition state.
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Returns the phase the match was in at the last transition.
+    pub fn mode(&self) -> CompetitionMode {
+        self.last
+    }
+
+    /// Waits for the next transition.
+    pub fn changed(&mut self) -> NextUpdate<'_> {
+        NextUpdate { updates: self }
+    }
+
+    fn poll_update(&mut self) -> Option<CompetitionUpdate> {
+        let current = mode();
+        if current == self.last {
+            return None;
+        }
+        let previous = core::mem::replace(&mut self.last, current);
+        Some(CompetitionUpdate { previous, current })
+    }
+}
+
+impl Iterator for CompetitionUpdates {
+    type Item = CompetitionUpdate;
+
+    /// Blocks until the next transition. This never returns `None`.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(update) = self.poll_update() {
+                return Some(update);
+            }
+            crate::task::sleep(Self::POLL_INTERVAL);
+        }
+    }
+}
+
+/// A future that resolves to the next competition state transition.
+pub struct NextUpdate<'a> {
+    updates: &'a mut CompetitionUpdates,
+}
+
+impl Future for NextUpdate<'_> {
+    type Output = CompetitionUpdate;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut().updates.poll_update() {
+            Some(update) => Poll::Ready(update),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// When the running autonomous routine started, or `u32::MAX` if none is running.
+static AUTONOMOUS_START: AtomicU32 = AtomicU32::new(u32::MAX);
+
+#[doc(hidden)]
+pub fn __set_autonomous_running(running: bool) {
+    let start = if running {
+        unsafe { pros_sys::millis() }
+    } else {
+        u32::MAX
+    };
+    AUTONOMOUS_START.store(start, Ordering::Release);
+}
+
+/// Returns how long the robot's autonomous routine has been running,
+/// or `None` if it isn't running.
+/// This includes autonomous run at the start of programming skills without field control.
+pub fn autonomous_elapsed() -> Option<Duration> {
+    match AUTONOMOUS_START.load(Ordering::Acquire) {
+        u32::MAX => None,
+        start => Some(Duration::from_millis(
+            (unsafe { pros_sys::millis() } - start) as u64,
+        )),
+    }
+}
+
+/// When a scheduled callback should run within its phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchTime {
+    /// After this much time has passed since the phase started.
+    Elapsed(Duration),
+    /// When this much time is left in the phase.
+    Remaining(Duration),
+}
+
+#[cfg(feature = "alloc")]
+struct ScheduledCallback {
+    mode: CompetitionMode,
+    time: MatchTime,
+    fired: bool,
+    callback: Option<Box<dyn FnMut() + Send>>,
+}
+
+#[cfg(feature = "alloc")]
+struct TimerState {
+    mode: CompetitionMode,
+    phase_start: u32,
+    autonomous_length: Duration,
+    opcontrol_length: Duration,
+    callbacks: Vec<ScheduledCallback>,
+}
+
+#[cfg(feature = "alloc")]
+impl TimerState {
+    fn length(&self, mode: CompetitionMode) -> Option<Duration> {
+        match mode {
+            CompetitionMode::Disabled => None,
+            CompetitionMode::Autonomous => Some(self.autonomous_length),
+            CompetitionMode::Opcontrol => Some(self.opcontrol_length),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::from_millis((unsafe { pros_sys::millis() } - self.phase_start) as u64)
+    }
+}
+
+/// Tracks how far into each phase of the match the robot is
+/// and runs callbacks at scheduled times, such as deploying an endgame mechanism with 15 seconds left.
+///
+/// A background task watches for competition state transitions and runs the callbacks.
+/// It stops once the timer is dropped.
+#[cfg(feature = "alloc")]
+pub struct MatchTimer {
+    state: Arc<Mutex<TimerState>>,
+}
+
+#[cfg(feature = "alloc")]
+impl MatchTimer {
+    /// How often the background task checks the competition state.
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Creates a timer using the phase lengths of a head to head match.
+    pub fn new() -> Self {
+        let state = Arc::new(Mutex::new(TimerState {
+            mode: mode(),
+            phase_start: unsafe { pros_sys::millis() },
+            autonomous_length: CompetitionMode::Autonomous.match_length().unwrap(),
+            opcontrol_length: CompetitionMode::Opcontrol.match_length().unwrap(),
+            callbacks: Vec::new(),
+        }));
+
+        let weak = Arc::downgrade(&state);
+        task::spawn(move || {
+            while let Some(state) = weak.upgrade() {
+                Self::tick(&state);
+                drop(state);
+                task::sleep(Self::POLL_INTERVAL);
+            }
+        });
+
+        Self { state }
+    }
+
+    fn tick(state: &Mutex<TimerState>) {
+        let mut due = Vec::new();
+        {
+            let mut state = state.lock();
+            let current = mode();
+            if current != state.mode {
+                state.mode = current;
+                state.phase_start = unsafe { pros_sys::millis() };
+                for scheduled in state.callbacks.iter_mut() {
+                    if scheduled.mode == current {
+                        scheduled.fired = false;
+                    }
+                }
+            }
+
+            let elapsed = state.elapsed();
+            let length = state.length(current);
+            for (index, scheduled) in state.callbacks.iter_mut().enumerate() {
+                if scheduled.fired || scheduled.mode != current {
+                    continue;
+                }
+                let is_due = match scheduled.time {
+                    MatchTime::Elapsed(time) => elapsed >= time,
+                    MatchTime::Remaining(time) => {
+                        length.is_some_and(|length| elapsed + time >= length)
+                    }
+                };
+                if is_due {
+                    scheduled.fired = true;
+                    due.push((index, scheduled.callback.take()));
+                }
+            }
+        }
+
+        // Callbacks are run without holding the lock so that they can use the timer.
+        for (index, mut callback) in due {
+            if let Some(callback) = callback.as_mut() {
+                callback();
+            }
+            state.lock().callbacks[index].callback = callback;
+        }
+    }
+
+    /// Sets how long the autonomous and driver control phases last,
+    /// which determines when [`MatchTime::Remaining`] callbacks run.
+    pub fn set_phase_lengths(&self, autonomous: Duration, opcontrol: Duration) {
+        let mut state = self.state.lock();
+        state.autonomous_length = autonomous;
+        state.opcontrol_length = opcontrol;
+    }
+
+    /// Sets the phase lengths to match the given kind of run.
+    pub fn set_run_mode(&self, run_mode: RunMode) {
+        self.set_phase_lengths(run_mode.autonomous_length(), run_mode.opcontrol_length());
+    }
+
+    /// Returns the phase the match was in when the timer last checked.
+    pub fn mode(&self) -> CompetitionMode {
+        self.state.lock().mode
+    }
+
+    /// Returns how long the current phase has lasted so far.
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().elapsed()
+    }
+
+    /// Returns how much time is left in the current phase, or `None` while disabled.
+    pub fn remaining(&self) -> Option<Duration> {
+        let state = self.state.lock();
+        state
+            .length(state.mode)
+            .map(|length| length.saturating_sub(state.elapsed()))
+    }
+
+    /// Schedules a callback to run once at the given time every time the given phase is entered.
+    pub fn at(
+        &self,
+        mode: CompetitionMode,
+        time: MatchTime,
+        callback: impl FnMut() + Send + 'static,
+    ) {
+        self.state.lock().callbacks.push(ScheduledCallback {
+            mode,
+            time,
+            fired: false,
+            callback: Some(Box::new(callback)),
+        });
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for MatchTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,285 @@
+//! A small LZSS compressor for logs written to the SD card.
+//!
+//! This is in the style of heatshrink: a flag byte says which of the next eight items are literal bytes
+//! and which are references to an earlier run of bytes. A reference is two little endian bytes holding
+//! how far back the run starts (up to [`WINDOW`] bytes) and its length (3 to 18 bytes).
+//! Matches are found with a single-entry hash table, which is fast and uses 8 KiB of memory,
+//! at the cost of missing some matches a slower search would find.
+//!
+//! Compressed files start with [`MAGIC`] and are made of independent chunks,
+//! each the uncompressed and compressed lengths as little endian `u16`s followed by the compressed data.
+//! Data that doesn't get smaller, such as data that is already compressed, is stored as it is instead,
+//! which is marked by both lengths being equal.
+//! A file cut off by power loss can be read up to its last complete chunk.
+//!
+//! This module only depends on `alloc` and `snafu` so that host tools can share it.
+
+use alloc::vec::Vec;
+
+use snafu::Snafu;
+
+/// The bytes compressed files start with.
+pub const MAGIC: &[u8; 4] = b"PLZ1";
+/// How far back a reference can point.
+pub const WINDOW: usize = 4096;
+/// The largest amount of data that can go in one chunk.
+pub const MAX_CHUNK_LEN: usize = u16::MAX as usize;
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = MIN_MATCH + 15;
+const HASH_BITS: u32 = 12;
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+    (value.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+/// Compresses `data` and appends it to `out`.
+pub fn compress(data: &[u8], out: &mut Vec<u8>) {
+    let mut table = alloc::vec![u32::MAX; 1 << HASH_BITS];
+    let mut flag_index = out.len();
+    let mut flag_bit = 8;
+
+    let mut pos = 0;
+    while pos < data.len() {
+        if flag_bit == 8 {
+            flag_index = out.len();
+            out.push(0);
+            flag_bit = 0;
+        }
+
+        let mut match_len = 0;
+        let mut match_offset = 0;
+        if pos + MIN_MATCH <= data.len() {
+            let slot = &mut table[hash(&data[pos..])];
+            let candidate = *slot as usize;
+            *slot = pos as u32;
+            if candidate != u32::MAX as usize && pos - candidate <= WINDOW {
+                let max = (data.len() - pos).min(MAX_MATCH);
+                match_len = data[candidate..]
+                    .iter()
+                    .zip(&data[pos..pos + max])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                match_offset = pos - candidate;
+            }
+        }
+
+        if match_len >= MIN_MATCH {
+            let token = ((match_offset - 1) << 4 | (match_len - MIN_MATCH)) as u16;
+            out.extend_from_slice(&token.to_le_bytes());
+            // Index the skipped positions too so later data can refer back into this run.
+            for skipped in pos + 1..(pos + match_len).min(data.len().saturating_sub(MIN_MATCH - 1))
+            {
+                table[hash(&data[skipped..])] = skipped as u32;
+            }
+            pos += match_len;
+        } else {
+            out[flag_index] |= 1 << flag_bit;
+            out.push(data[pos]);
+            pos += 1;
+        }
+        flag_bit += 1;
+    }
+}
+
+/// Decompresses data produced by [`compress`] that is `len` bytes long uncompressed, appending it to `out`.
+pub fn decompress(mut data: &[u8], len: usize, out: &mut Vec<u8>) -> Result<(), CorruptedError> {
+    let start = out.len();
+    while out.len() - start < len {
+        let (&flags, rest) = data.split_first().ok_or(CorruptedError)?;
+        data = rest;
+        for bit in 0..8 {
+            if out.len() - start >= len {
+                break;
+            }
+            if flags & 1 << bit != 0 {
+                let (&byte, rest) = data.split_first().ok_or(CorruptedError)?;
+                data = rest;
+                out.push(byte);
+            } else {
+                let [low, high, ..] = *data else {
+                    return Err(CorruptedError);
+                };
+                data = &data[2..];
+                let token = u16::from_le_bytes([low, high]) as usize;
+                let offset = (token >> 4) + 1;
+                let match_len = (token & 0xF) + MIN_MATCH;
+                if offset > out.len() - start {
+                    return Err(CorruptedError);
+                }
+                // Runs can overlap what they produce, so copy a byte at a time.
+                for _ in 0..match_len {
+                    out.push(out[out.len() - offset]);
+                }
+            }
+        }
+    }
+    out.truncate(start + len);
+    Ok(())
+}
+
+/// Appends `data` to `out` as one chunk of a compressed file.
+/// If compressing doesn't make `data` smaller, it is stored uncompressed.
+///
+/// # Panics
+///
+/// Panics if `data` is longer than [`MAX_CHUNK_LEN`].
+pub fn write_chunk(data: &[u8], out: &mut Vec<u8>) {
+    assert!(
+        data.len() <= MAX_CHUNK_LEN,
+        "Compressed chunks are too long"
+    );
+    let header = out.len();
+    out.extend_from_slice(&[0; 4]);
+    compress(data, out);
+    let mut compressed_len = out.len() - header - 4;
+    // LZSS can grow incompressible data by an eighth, which could also overflow the length.
+    if compressed_len >= data.len() {
+        out.truncate(header + 4);
+        out.extend_from_slice(data);
+        compressed_len = data.len();
+    }
+    out[header..header + 2].copy_from_slice(&(data.len() as u16).to_le_bytes());
+    out[header + 2..header + 4].copy_from_slice(&(compressed_len as u16).to_le_bytes());
+}
+
+/// Decompresses a file written as [`MAGIC`] followed by chunks from [`write_chunk`].
+/// A chunk cut off at the end of the file is ignored.
+pub fn decompress_file(file: &[u8]) -> Result<Vec<u8>, CorruptedError> {
+    let mut rest = file.strip_prefix(MAGIC).ok_or(CorruptedError)?;
+    let mut out = Vec::new();
+    while let [raw_low, raw_high, low, high, ref chunk @ ..] = *rest {
+        let raw_len = u16::from_le_bytes([raw_low, raw_high]) as usize;
+        let compressed_len = u16::from_le_bytes([low, high]) as usize;
+        if chunk.len() < compressed_len {
+            break;
+        }
+        if compressed_len == raw_len {
+            out.extend_from_slice(&chunk[..raw_len]);
+        } else {
+            decompress(&chunk[..compressed_len], raw_len, &mut out)?;
+        }
+        rest = &chunk[compressed_len..];
+    }
+    Ok(out)
+}
+
+/// Compressed data did not decode to the expected length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Snafu)]
+#[snafu(display("The compressed data is corrupted."))]
+pub struct CorruptedError;
+impl core::error::Error for CorruptedError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    /// Returns bytes that don't compress, from a xorshift generator.
+    fn random(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_F491u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        compress(data, &mut compressed);
+        let mut out = Vec::new();
+        decompress(&compressed, data.len(), &mut out).unwrap();
+        assert_eq!(out, data);
+        compressed
+    }
+
+    fn file(chunks: &[&[u8]]) -> Vec<u8> {
+        let mut file = MAGIC.to_vec();
+        for chunk in chunks {
+            write_chunk(chunk, &mut file);
+        }
+        file
+    }
+
+    #[test]
+    fn empty() {
+        assert!(round_trip(&[]).is_empty());
+        assert_eq!(file(&[&[]]), [b'P', b'L', b'Z', b'1', 0, 0, 0, 0]);
+        assert_eq!(decompress_file(&file(&[&[]])), Ok(Vec::new()));
+        assert_eq!(decompress_file(MAGIC), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn stores_incompressible_data() {
+        let data = random(4096);
+        assert!(round_trip(&data).len() > data.len());
+
+        let file = file(&[&data]);
+        assert_eq!(file.len(), MAGIC.len() + 4 + data.len());
+        assert_eq!(&file[MAGIC.len() + 4..], data);
+        assert_eq!(decompress_file(&file), Ok(data));
+    }
+
+    #[test]
+    fn compresses_long_runs() {
+        let mut data = vec![7u8; 10_000];
+        data.extend(b"abc".iter().cycle().take(10_000));
+        // Each reference covers at most 18 bytes, so this can't shrink below about a ninth.
+        assert!(round_trip(&data).len() < data.len() / 8);
+    }
+
+    #[test]
+    fn decompresses_multiple_chunks() {
+        let text = b"the quick brown fox jumps over the lazy dog. ".repeat(200);
+        let noise = random(1000);
+        let run = vec![0u8; MAX_CHUNK_LEN];
+        let file = file(&[&text, &noise, &run]);
+        assert!(file.len() < text.len() + noise.len() + run.len());
+        assert_eq!(decompress_file(&file), Ok([text, noise, run].concat()));
+    }
+
+    #[test]
+    fn ignores_a_cut_off_chunk() {
+        let text = b"0123456789".repeat(50);
+        let file = file(&[&text, &text]);
+        assert_eq!(decompress_file(&file[..file.len() - 1]), Ok(text));
+    }
+
+    #[test]
+    fn rejects_corrupted_data() {
+        assert_eq!(decompress_file(b"PLZ0"), Err(CorruptedError));
+        // A reference before the start of the output.
+        assert_eq!(
+            decompress(&[0x00, 0x00, 0x00], 3, &mut Vec::new()),
+            Err(CorruptedError)
+        );
+
+        let data = b"abcdefabcdefabcdef, abcdef".repeat(20);
+        let mut compressed = Vec::new();
+        compress(&data, &mut compressed);
+        for len in 0..compressed.len() {
+            assert_eq!(
+                decompress(&compressed[..len], data.len(), &mut Vec::new()),
+                Err(CorruptedError)
+            );
+        }
+        // Corruption is caught when it can be and must never panic.
+        for index in 0..compressed.len() {
+            let mut corrupted = compressed.clone();
+            corrupted[index] ^= 0xFF;
+            _ = decompress(&corrupted, data.len(), &mut Vec::new());
+        }
+        let file = file(&[&data]);
+        for index in MAGIC.len()..file.len() {
+            let mut corrupted = file.clone();
+            corrupted[index] ^= 0xFF;
+            _ = decompress_file(&corrupted);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Tuning [registered](super::registry) configs from the controller, for when a laptop isn't allowed at the field.
+//!
+//! While the menu is open, the controller screen shows one field at a time:
+//! - Up and Down pick the field.
+//! - Left and Right step its value down and up, or toggle it if it is a `bool`.
+//! - X cycles through the step sizes.
+//! - A accepts the changes to the field's config, saving it if it was registered with
+//!   [`register_saved`](super::registry::register_saved).
+//! - B closes the menu, putting back any changes that weren't accepted.
+//!
+//! ```rust
+//! let mut menu = TuningMenu::new(Controller::Master);
+//! loop {
+//!     if !menu.is_open() && Controller::Master.state().buttons.is_pressed(Button::Y) {
+//!         menu.open();
+//!     }
+//!     if menu.update()? {
+//!         // The menu is using the buttons, so don't drive with them.
+//!         continue;
+//!     }
+//!     // ...
+//! }
+//! ```
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{registry, ConfigError, Value};
+use crate::{
+    controller::{Button, Buttons, Controller, ControllerLine},
+    fixed::Fixed,
+};
+
+/// How often the controller screen accepts new text, in milliseconds.
+const PRINT_INTERVAL: u32 = 50;
+
+/// A menu on the controller screen for changing tunable config fields.
+/// See the [module documentation](self) for the controls.
+pub struct TuningMenu {
+    controller: Controller,
+    /// The step sizes X cycles through. Integer fields step by at least 1.
+    pub steps: Vec<f64>,
+    open: bool,
+    fields: Vec<String>,
+    selected: usize,
+    step: usize,
+    /// The value of each changed field from before it was changed.
+    pending: Vec<(String, Value)>,
+    status: Option<String>,
+    last_buttons: Option<[bool; 7]>,
+    shown: [String; 3],
+    last_print: u32,
+}
+
+impl TuningMenu {
+    pub fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            steps: [0.001, 0.01, 0.1, 1.0, 10.0].into(),
+            open: false,
+            fields: Vec::new(),
+            selected: 0,
+            step: 2,
+            pending: Vec::new(),
+            status: None,
+            last_buttons: None,
+            shown: Default::default(),
+            last_print: 0,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the menu, reading the list of fields from the registry again.
+    pub fn open(&mut self) {
+        self.fields = registry::fields()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        self.selected = self.selected.min(self.fields.len().saturating_sub(1));
+        self.open = true;
+        self.status = None;
+        // Buttons held while opening shouldn't count as presses.
+        self.last_buttons = None;
+        self.shown = Default::default();
+    }
+
+    /// Closes the menu, putting back any changes that weren't accepted.
+    pub fn close(&mut self) -> Result<(), ConfigError> {
+        self.open = false;
+        for (path, value) in self.pending.drain(..).rev() {
+            registry::set(&path, value)?;
+        }
+        // The screen is left as it is if it is busy, since there's nothing useful to do about it.
+        _ = self.controller.clear_screen();
+        Ok(())
+    }
+
+    /// Handles button presses and updates the controller screen.
+    /// This should be called every loop, and returns true while the menu is open and using the buttons.
+    pub fn update(&mut self) -> Result<bool, ConfigError> {
+        if !self.open {
+            return Ok(false);
+        }
+
+        let pressed = pressed_buttons(&self.controller.state().buttons);
+        let last = self.last_buttons.replace(pressed).unwrap_or(pressed);
+        let tapped = |index: usize| pressed[index] && !last[index];
+
+        if self.fields.is_empty() {
+            self.status = Some("No tunables".to_string());
+        } else if tapped(UP) {
+            self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+            self.status = None;
+        } else if tapped(DOWN) {
+            self.selected = (self.selected + 1) % self.fields.len();
+            self.status = None;
+        } else if tapped(LEFT) {
+            self.adjust(-1.0)?;
+        } else if tapped(RIGHT) {
+            self.adjust(1.0)?;
+        } else if tapped(X) {
+            self.step = (self.step + 1) % self.steps.len().max(1);
+            self.status = None;
+        } else if tapped(A) {
+            self.accept()?;
+        }
+        if tapped(B) {
+            self.close()?;
+            return Ok(false);
+        }
+
+        self.draw()?;
+        Ok(true)
+    }
+
+    fn step_size(&self) -> f64 {
+        self.steps.get(self.step).copied().unwrap_or(1.0)
+    }
+
+    /// How many decimal places floats are shown with, enough for the finest step.
+    fn decimals(&self) -> u8 {
+        self.steps
+            .iter()
+            .map(|&step| decimals(step))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn adjust(&mut self, direction: f64) -> Result<(), ConfigError> {
+        let path = &self.fields[self.selected];
+        let info = registry::field(path)?;
+        let value = registry::get(path)?;
+        let step = self.step_size();
+        let new = match value {
+            Value::Bool(value) => Value::Bool(!value),
+            Value::Int(value) => {
+                Value::Int(value + direction as i64 * libm::round(step).max(1.0) as i64)
+            }
+            // Rounding to the step, and then to its decimal places, keeps repeated steps
+            // from collecting floating point error such as 0.30000000000000004.
+            Value::Float(value) => {
+                let snapped = libm::round((value + direction * step) / step) * step;
+                let scale = libm::pow(10.0, decimals(step) as f64);
+                Value::Float(libm::round(snapped * scale) / scale)
+            }
+        };
+        let clamped = match new {
+            Value::Bool(_) => new,
+            Value::Int(value) => Value::Int(
+                (value as f64).clamp(info.min.unwrap_or(f64::MIN), info.max.unwrap_or(f64::MAX))
+                    as i64,
+            ),
+            Value::Float(value) => Value::Float(value.clamp(
+                info.min.unwrap_or(f64::NEG_INFINITY),
+                info.max.unwrap_or(f64::INFINITY),
+            )),
+        };
+        registry::set(path, clamped)?;
+        if !self.pending.iter().any(|(pending, _)| pending == path) {
+            self.pending.push((path.clone(), value));
+        }
+        self.status = None;
+        Ok(())
+    }
+
+    fn accept(&mut self) -> Result<(), ConfigError> {
+        let path = &self.fields[self.selected];
+        let name = path.split_once('.').map_or(path.as_str(), |(name, _)| name);
+        let prefix = format!("{name}.");
+        self.pending
+            .retain(|(pending, _)| !pending.starts_with(&prefix));
+        let saved = registry::save(name)?;
+        self.status = Some(if saved { "Saved" } else { "Accepted" }.to_string());
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Result<(), ConfigError> {
+        let lines = match self.fields.get(self.selected) {
+            Some(path) => {
+                let changed = self.pending.iter().any(|(pending, _)| pending == path);
+                let value = match registry::get(path)? {
+                    Value::Float(value) => format!("{}", Fixed::new(value, self.decimals())),
+                    value => format!("{value}"),
+                };
+                let step = self.step_size();
+                [
+                    tail(path),
+                    format!("{value}{}", if changed { " *" } else { "" }),
+                    self.status
+                        .clone()
+                        .unwrap_or_else(|| format!("step {}", Fixed::new(step, decimals(step)))),
+                ]
+            }
+            None => [
+                String::new(),
+                self.status.clone().unwrap_or_default(),
+                String::new(),
+            ],
+        };
+
+        // The controller only takes one line at a time, so only the first changed line is sent.
+        let now = unsafe { pros_sys::millis() };
+        if now.wrapping_sub(self.last_print) < PRINT_INTERVAL {
+            return Ok(());
+        }
+        for (index, text) in lines.into_iter().enumerate() {
+            let text: String = text.chars().take(ControllerLine::MAX_TEXT_LEN).collect();
+            if self.shown[index] == text {
+                continue;
+            }
+            // Padding clears whatever was left on the line from before.
+            let padded = format!("{text:<width$}", width = ControllerLine::MAX_TEXT_LEN);
+            if self.controller.line(index as u8).try_print(padded).is_ok() {
+                self.shown[index] = text;
+                self.last_print = now;
+            }
+            break;
+        }
+        Ok(())
+    }
+}
+
+/// Returns how many decimal places a step size has, such as 2 for 0.05, up to 6.
+fn decimals(step: f64) -> u8 {
+    let mut scaled = libm::fabs(step);
+    for decimals in 0..6 {
+        if libm::fabs(scaled - libm::round(scaled)) < 1e-9 {
+            return decimals;
+        }
+        scaled *= 10.0;
+    }
+    6
+}
+
+const UP: usize = 0;
+const DOWN: usize = 1;
+const LEFT: usize = 2;
+const RIGHT: usize = 3;
+const X: usize = 4;
+const A: usize = 5;
+const B: usize = 6;
+
+fn pressed_buttons(buttons: &Buttons) -> [bool; 7] {
+    [
+        Button::Up,
+        Button::Down,
+        Button::Left,
+        Button::Right,
+        Button::X,
+        Button::A,
+        Button::B,
+    ]
+    .map(|button| buttons.is_pressed(button))
+}
+
+/// Returns the end of `text` that fits on a controller line, since the field name is usually the useful part.
+fn tail(text: &str) -> String {
+    let skip = text
+        .chars()
+        .count()
+        .saturating_sub(ControllerLine::MAX_TEXT_LEN);
+    text.chars().skip(skip).collect()
+}
@@ -0,0 +1,291 @@
+//! Settings structs stored as text on the SD card, with a default and allowed range for each field.
+//!
+//! Deriving [`Config`] on a struct with named numeric and `bool` fields generates everything needed to load,
+//! save, validate, and [tune](registry) it:
+//! ```rust
+//! use pros::config::Config;
+//!
+//! #[derive(Config)]
+//! pub struct DriveConfig {
+//!     #[config(default = 0.5, min = 0.0, max = 2.0)]
+//!     pub kp: f64,
+//!     #[config(default = 12, min = 1)]
+//!     pub max_voltage: i32,
+//!     // Fields without a default use `Default::default()`.
+//!     pub field_centric: bool,
+//! }
+//!
+//! let config = DriveConfig::load("drive.cfg")?;
+//! ```
+//!
+//! Files have one `name = value` pair per line, and `#` starts a comment.
+//! Fields missing from a file keep their defaults,
+//! and lines that can't be used are reported with [`crate::error::report`] and skipped,
+//! so a typo on the SD card doesn't stop the robot from running.
+
+use alloc::string::{String, ToString};
+use core::fmt::{self, Display, Write};
+
+use snafu::Snafu;
+
+use crate::usd::{self, UsdError};
+
+pub mod menu;
+pub mod registry;
+
+/// Derives [`Config`] for a struct with named fields.
+///
+/// Each field can have a `#[config(default = ..., min = ..., max = ...)]` attribute, all parts of which are optional.
+pub use pros_macros::Config;
+
+/// The value of a config field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+impl Value {
+    /// Returns the value as a number, with `true` as 1.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Self::Bool(value) => value as u8 as f64,
+            Self::Int(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+
+    /// Parses a value as written in a config file.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "true" => Some(Self::Bool(true)),
+            "false" => Some(Self::Bool(false)),
+            _ => text
+                .parse()
+                .map(Self::Int)
+                .or_else(|_| text.parse().map(Self::Float))
+                .ok(),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            // Always write a decimal point so the value is read back as a float.
+            Self::Float(value) if *value == libm::trunc(*value) => write!(f, "{value:.1}"),
+            Self::Float(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// A type that can be a field of a [`Config`].
+pub trait ConfigValue: Sized {
+    fn to_value(&self) -> Value;
+    /// Converts a value to this type, returning `None` if it is the wrong type or doesn't fit.
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+impl ConfigValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_config_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl ConfigValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Int(*self as i64)
+                }
+
+                fn from_value(value: Value) -> Option<Self> {
+                    match value {
+                        Value::Int(value) => value.try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_config_value_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+macro_rules! impl_config_value_float {
+    ($($ty:ty),*) => {
+        $(
+            impl ConfigValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Float(*self as f64)
+                }
+
+                fn from_value(value: Value) -> Option<Self> {
+                    match value {
+                        Value::Int(value) => Some(value as $ty),
+                        Value::Float(value) => Some(value as $ty),
+                        Value::Bool(_) => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_config_value_float!(f32, f64);
+
+/// The name and allowed range of a config field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl FieldInfo {
+    /// Returns an error if the value is outside of the field's range.
+    pub fn check(&self, value: Value) -> Result<(), ConfigError> {
+        let number = value.as_f64();
+        let min = self.min.unwrap_or(f64::NEG_INFINITY);
+        let max = self.max.unwrap_or(f64::INFINITY);
+        if number < min || number > max || number.is_nan() {
+            return Err(ConfigError::OutOfRange {
+                name: self.name,
+                value: number,
+                min,
+                max,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Sets a field after checking the value's type and range. Used by the derive macro.
+#[doc(hidden)]
+pub fn set_field<T: ConfigValue>(
+    field: &mut T,
+    info: &FieldInfo,
+    value: Value,
+) -> Result<(), ConfigError> {
+    info.check(value)?;
+    *field = T::from_value(value).ok_or(ConfigError::WrongType {
+        name: info.name,
+        value,
+    })?;
+    Ok(())
+}
+
+/// A settings struct that can be saved to and loaded from the SD card.
+/// This should usually be derived.
+pub trait Config: Sized {
+    /// Every field, in the order they are written to files.
+    const FIELDS: &'static [FieldInfo];
+
+    /// Returns the config with every field set to its default.
+    fn defaults() -> Self;
+
+    /// Returns the value of a field, or `None` if there is no field with that name.
+    fn get(&self, name: &str) -> Option<Value>;
+
+    /// Sets a field, failing if the value is the wrong type or out of range.
+    fn set(&mut self, name: &str, value: Value) -> Result<(), ConfigError>;
+
+    /// Checks that every field is in its range.
+    fn validate(&self) -> Result<(), ConfigError> {
+        for field in Self::FIELDS {
+            if let Some(value) = self.get(field.name) {
+                field.check(value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a config from the text of a file.
+    /// Fields that are missing keep their defaults, and lines that can't be used are reported and skipped.
+    fn parse(text: &str) -> Self {
+        let mut config = Self::defaults();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let result = match line.split_once('=') {
+                Some((name, value)) => match Value::parse(value.trim()) {
+                    Some(value) => config.set(name.trim(), value),
+                    None => Err(ConfigError::Malformed { line: index + 1 }),
+                },
+                None => Err(ConfigError::Malformed { line: index + 1 }),
+            };
+            if let Err(err) = result {
+                crate::error::report(&err);
+            }
+        }
+        config
+    }
+
+    /// Writes the config as it is stored in files.
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        for field in Self::FIELDS {
+            if let Some(value) = self.get(field.name) {
+                // Writing to a string can't fail.
+                _ = writeln!(text, "{} = {value}", field.name);
+            }
+        }
+        text
+    }
+
+    /// Loads a config from a file on the SD card, using the defaults if the file doesn't exist.
+    fn load(path: &str) -> Result<Self, ConfigError> {
+        match usd::read(path) {
+            Ok(contents) => Ok(Self::parse(&String::from_utf8_lossy(&contents))),
+            Err(UsdError::NotFound) => Ok(Self::defaults()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Saves the config to a file on the SD card, replacing it if it exists.
+    fn save(&self, path: &str) -> Result<(), ConfigError> {
+        usd::write(path, self.to_text().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ConfigError {
+    #[snafu(display("There is no config field named {name}."))]
+    UnknownField { name: String },
+    #[snafu(display("{name} must be between {min} and {max}, but was {value}."))]
+    OutOfRange {
+        name: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    #[snafu(display("{name} can't be set to {value}."))]
+    WrongType { name: &'static str, value: Value },
+    #[snafu(display("Line {line} of the config file is not `name = value`."))]
+    Malformed { line: usize },
+    #[snafu(display("{source}"), context(false))]
+    Usd { source: UsdError },
+}
+impl core::error::Error for ConfigError {}
+
+impl ConfigError {
+    /// Creates an [`ConfigError::UnknownField`] error. Used by the derive macro.
+    #[doc(hidden)]
+    pub fn unknown_field(name: &str) -> Self {
+        Self::UnknownField {
+            name: name.to_string(),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Configs that can be read and changed while the program runs, such as from a laptop over a serial bridge.
+//!
+//! Configs are registered under a name, and their fields are addressed as `name.field`.
+//! Configs registered with [`register_saved`] remember the file they were loaded from, so changes can be [saved](save) back to it.
+
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+
+use super::{Config, ConfigError, FieldInfo, Value};
+use crate::sync::Mutex;
+
+trait Tunable: Send + Sync {
+    fn fields(&self) -> &'static [super::FieldInfo];
+    fn get(&self, name: &str) -> Option<Value>;
+    fn set(&self, name: &str, value: Value) -> Result<(), ConfigError>;
+    fn save(&self, path: &str) -> Result<(), ConfigError>;
+}
+
+impl<T: Config + Send> Tunable for Mutex<T> {
+    fn fields(&self) -> &'static [super::FieldInfo] {
+        T::FIELDS
+    }
+
+    fn get(&self, name: &str) -> Option<Value> {
+        self.lock().get(name)
+    }
+
+    fn set(&self, name: &str, value: Value) -> Result<(), ConfigError> {
+        self.lock().set(name, value)
+    }
+
+    fn save(&self, path: &str) -> Result<(), ConfigError> {
+        self.lock().save(path)
+    }
+}
+
+#[derive(Clone)]
+struct Entry {
+    name: String,
+    config: Arc<dyn Tunable>,
+    /// The file the config is saved to, if any.
+    path: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    static ref REGISTRY: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+}
+
+fn insert(entry: Entry) {
+    let mut registry = REGISTRY.lock();
+    registry.retain(|registered| registered.name != entry.name);
+    registry.push(entry);
+}
+
+/// Makes a config tunable under `name`, replacing any config already registered with that name.
+/// The owner keeps reading it through the same mutex, so changes take effect the next time it is locked.
+pub fn register<T: Config + Send + 'static>(name: impl Into<String>, config: Arc<Mutex<T>>) {
+    insert(Entry {
+        name: name.into(),
+        config,
+        path: None,
+    });
+}
+
+/// Makes a config tunable under `name` like [`register`], remembering that it is stored at `path` on the SD card.
+///
+/// ```rust
+/// let drive = Arc::new(Mutex::new(DriveConfig::load("drive.cfg")?));
+/// registry::register_saved("drive", "drive.cfg", drive.clone());
+/// ```
+pub fn register_saved<T: Config + Send + 'static>(
+    name: impl Into<String>,
+    path: impl Into<String>,
+    config: Arc<Mutex<T>>,
+) {
+    insert(Entry {
+        name: name.into(),
+        config,
+        path: Some(path.into()),
+    });
+}
+
+/// Stops a config from being tunable.
+pub fn unregister(name: &str) {
+    REGISTRY.lock().retain(|registered| registered.name != name);
+}
+
+fn find_entry(name: &str) -> Option<Entry> {
+    REGISTRY
+        .lock()
+        .iter()
+        .find(|registered| registered.name == name)
+        .cloned()
+}
+
+fn find(path: &str) -> Result<(Arc<dyn Tunable>, &str), ConfigError> {
+    let unknown = || ConfigError::unknown_field(path);
+    let (name, field) = path.split_once('.').ok_or_else(unknown)?;
+    let entry = find_entry(name).ok_or_else(unknown)?;
+    Ok((entry.config, field))
+}
+
+/// Returns the name and allowed range of the field at `name.field`.
+pub fn field(path: &str) -> Result<FieldInfo, ConfigError> {
+    let (config, field) = find(path)?;
+    config
+        .fields()
+        .iter()
+        .find(|info| info.name == field)
+        .copied()
+        .ok_or_else(|| ConfigError::unknown_field(path))
+}
+
+/// Saves the config registered as `name` to its file.
+/// Returns false without saving if it was registered with [`register`] rather than [`register_saved`].
+pub fn save(name: &str) -> Result<bool, ConfigError> {
+    let entry = find_entry(name).ok_or_else(|| ConfigError::unknown_field(name))?;
+    match entry.path {
+        Some(path) => {
+            entry.config.save(&path)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Returns the value of the field at `name.field`.
+pub fn get(path: &str) -> Result<Value, ConfigError> {
+    let (config, field) = find(path)?;
+    config
+        .get(field)
+        .ok_or_else(|| ConfigError::unknown_field(path))
+}
+
+/// Sets the field at `name.field`, failing if the value is the wrong type or out of range.
+pub fn set(path: &str, value: Value) -> Result<(), ConfigError> {
+    let (config, field) = find(path)?;
+    config.set(field, value)
+}
+
+/// Returns the path and current value of every registered field.
+pub fn fields() -> Vec<(String, Value)> {
+    // The configs are cloned out so that their locks aren't taken while holding the registry's.
+    let configs: Vec<_> = REGISTRY.lock().clone();
+    configs
+        .iter()
+        .flat_map(|Entry { name, config, .. }| {
+            config.fields().iter().filter_map(move |field| {
+                Some((format!("{name}.{}", field.name), config.get(field.name)?))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,258 @@
+//! Suggesting PID gains for any single-input, single-output loop from how it oscillates under relay control.
+//!
+//! This is the Åström–Hägglund relay method: the loop is driven at a fixed output above or below a bias,
+//! switching whenever the measurement crosses the setpoint, until it settles into a steady oscillation.
+//! The size and period of that oscillation give the gain at which a proportional controller
+//! would oscillate forever (the ultimate gain) and how fast it would oscillate (the ultimate period),
+//! and a [`TuningRule`] turns those into gains.
+//!
+//! Tuning a lift around a height, holding it up against gravity with a bias:
+//! ```rust
+//! let relay = RelayAutotune {
+//!     setpoint: Some(20.0),
+//!     bias: 2.5,
+//!     amplitude: 3.0,
+//!     max_excursion: 8.0,
+//!     ..Default::default()
+//! };
+//! let report = relay.run(
+//!     || Ok(lift.position()?.into_degrees()),
+//!     |volts| Ok(lift.set_voltage(volts as f32)?),
+//! )?;
+//! report.gains.save("lift.cfg")?;
+//! ```
+//!
+//! The units of the gains are the output's units per unit of the measurement,
+//! such as volts per degree or volts per RPM.
+
+use core::{f64::consts::PI, time::Duration};
+
+use snafu::Snafu;
+
+use crate::{config::Config, pid::PidController, task};
+
+/// Gains for a PID controller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Config)]
+pub struct PidGains {
+    #[config(min = 0.0)]
+    pub kp: f64,
+    #[config(min = 0.0)]
+    pub ki: f64,
+    #[config(min = 0.0)]
+    pub kd: f64,
+}
+
+impl PidGains {
+    /// Creates a controller with these gains.
+    pub fn controller(&self) -> PidController {
+        PidController::new(self.kp as f32, self.ki as f32, self.kd as f32)
+    }
+}
+
+/// How to turn the ultimate gain and period into PID gains, from most to least aggressive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuningRule {
+    /// The classic Ziegler–Nichols rule, which responds quickly but overshoots noticeably.
+    ZieglerNichols,
+    /// Pessen's integral rule, which reacts harder to error that lingers.
+    PessenIntegral,
+    /// Responds more gently, with a little overshoot.
+    SomeOvershoot,
+    /// Responds slowly enough to avoid overshooting.
+    NoOvershoot,
+}
+
+impl TuningRule {
+    /// Returns the gains for an ultimate gain and an ultimate period in seconds.
+    pub fn gains(self, ultimate_gain: f64, ultimate_period: f64) -> PidGains {
+        let (p, i, d) = match self {
+            Self::ZieglerNichols => (0.6, 1.2, 0.075),
+            Self::PessenIntegral => (0.7, 1.75, 0.105),
+            Self::SomeOvershoot => (0.33, 0.66, 0.11),
+            Self::NoOvershoot => (0.2, 0.4, 0.0667),
+        };
+        PidGains {
+            kp: p * ultimate_gain,
+            ki: i * ultimate_gain / ultimate_period,
+            kd: d * ultimate_gain * ultimate_period,
+        }
+    }
+}
+
+/// What [`RelayAutotune::run`] measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutotuneReport {
+    /// The proportional gain at which the loop would oscillate steadily.
+    pub ultimate_gain: f64,
+    /// How long one of those oscillations takes, in seconds.
+    pub ultimate_period: f64,
+    /// Half the peak to peak swing of the measured oscillations.
+    pub amplitude: f64,
+    pub gains: PidGains,
+}
+
+/// Settings for relay auto-tuning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelayAutotune {
+    /// The measurement to oscillate around. `None` uses the first measurement.
+    pub setpoint: Option<f64>,
+    /// The output midway between the relay's two outputs, such as the voltage that holds a lift in place
+    /// or keeps a flywheel near its target speed.
+    pub bias: f64,
+    /// How far above and below the bias the output switches.
+    pub amplitude: f64,
+    /// How far past the setpoint the measurement must go before the output switches.
+    /// This keeps sensor noise from switching the relay early.
+    pub hysteresis: f64,
+    /// The largest output that will ever be sent, in either direction.
+    pub max_output: f64,
+    /// How far from the setpoint the measurement may go before tuning stops with an error,
+    /// to keep a mechanism from running into its hard stops.
+    pub max_excursion: f64,
+    /// Oscillations to ignore while the loop settles into a steady pattern.
+    pub settle_cycles: usize,
+    /// Oscillations to average over once settled.
+    pub cycles: usize,
+    /// How long to wait for all the oscillations before giving up.
+    pub timeout: Duration,
+    /// How often the loop is measured and driven.
+    pub period: Duration,
+    pub rule: TuningRule,
+}
+
+impl Default for RelayAutotune {
+    fn default() -> Self {
+        Self {
+            setpoint: None,
+            bias: 0.0,
+            amplitude: 4.0,
+            hysteresis: 1.0,
+            max_output: 12.0,
+            max_excursion: f64::INFINITY,
+            settle_cycles: 2,
+            cycles: 4,
+            timeout: Duration::from_secs(20),
+            period: Duration::from_millis(10),
+            rule: TuningRule::SomeOvershoot,
+        }
+    }
+}
+
+impl RelayAutotune {
+    /// Oscillates the loop and returns the suggested gains.
+    ///
+    /// `measure` reads the process and `output` drives it, with a higher output raising the measurement.
+    /// The output is set to zero when tuning finishes or fails.
+    pub fn run(
+        &self,
+        mut measure: impl FnMut() -> crate::Result<f64>,
+        mut output: impl FnMut(f64) -> crate::Result,
+    ) -> crate::Result<AutotuneReport> {
+        if self.max_output < 0.0 || self.max_output.is_nan() {
+            return Err(AutotuneError::InvalidMaxOutput {
+                max_output: self.max_output,
+            }
+            .into());
+        }
+        let result = self.oscillate(&mut measure, &mut output);
+        output(0.0)?;
+        let (amplitude, period) = result?;
+
+        // The first harmonic of a relay's square wave, through a relay with hysteresis.
+        let effective_amplitude =
+            libm::sqrt(amplitude * amplitude - self.hysteresis * self.hysteresis);
+        if !(effective_amplitude > 0.0 && period > 0.0) {
+            return Err(AutotuneError::NoOscillation.into());
+        }
+        // The outputs are clamped, so a bias near the limit makes the relay swing less than its amplitude.
+        let relay_amplitude = (self.clamped_output(true) - self.clamped_output(false)) / 2.0;
+        let ultimate_gain = 4.0 * relay_amplitude / (PI * effective_amplitude);
+        Ok(AutotuneReport {
+            ultimate_gain,
+            ultimate_period: period,
+            amplitude,
+            gains: self.rule.gains(ultimate_gain, period),
+        })
+    }
+
+    /// Returns the relay's high or low output, limited to the largest allowed output.
+    fn clamped_output(&self, high: bool) -> f64 {
+        let offset = if high {
+            self.amplitude
+        } else {
+            -self.amplitude
+        };
+        (self.bias + offset).clamp(-self.max_output, self.max_output)
+    }
+
+    /// Runs the relay and returns the average amplitude and period in seconds of the settled oscillations.
+    fn oscillate(
+        &self,
+        measure: &mut impl FnMut() -> crate::Result<f64>,
+        output: &mut impl FnMut(f64) -> crate::Result,
+    ) -> crate::Result<(f64, f64)> {
+        let first = measure()?;
+        let setpoint = self.setpoint.unwrap_or(first);
+        let start = unsafe { pros_sys::millis() };
+        let mut high = first < setpoint;
+        let mut peak = first;
+        let mut trough = first;
+        // When the relay last switched to its high output, and how many times it has.
+        let mut last_rise: Option<u32> = None;
+        let mut rises = 0;
+        let mut amplitude_sum = 0.0;
+        let mut period_sum = 0.0;
+        let cycles = self.cycles.max(1);
+
+        loop {
+            let now = unsafe { pros_sys::millis() };
+            if now - start > self.timeout.as_millis() as u32 {
+                return Err(AutotuneError::TimedOut.into());
+            }
+            let measurement = measure()?;
+            if libm::fabs(measurement - setpoint) > self.max_excursion {
+                return Err(AutotuneError::Excursion { measurement }.into());
+            }
+            peak = peak.max(measurement);
+            trough = trough.min(measurement);
+
+            if high && measurement > setpoint + self.hysteresis {
+                high = false;
+            } else if !high && measurement < setpoint - self.hysteresis {
+                high = true;
+                // A full oscillation ends each time the relay switches back to its high output.
+                if let Some(last) = last_rise {
+                    rises += 1;
+                    if rises > self.settle_cycles {
+                        amplitude_sum += (peak - trough) / 2.0;
+                        period_sum += (now - last) as f64 / 1000.0;
+                    }
+                    if rises >= self.settle_cycles + cycles {
+                        return Ok((amplitude_sum / cycles as f64, period_sum / cycles as f64));
+                    }
+                }
+                last_rise = Some(now);
+                peak = measurement;
+                trough = measurement;
+            }
+
+            output(self.clamped_output(high))?;
+            task::sleep(self.period);
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum AutotuneError {
+    #[snafu(display("The loop didn't settle into a steady oscillation before timing out."))]
+    TimedOut,
+    #[snafu(display("The loop didn't oscillate, so nothing could be measured."))]
+    NoOscillation,
+    #[snafu(display(
+        "The measurement reached {measurement}, further from the setpoint than allowed."
+    ))]
+    Excursion { measurement: f64 },
+    #[snafu(display("The largest output must not be negative, but it was {max_output}."))]
+    InvalidMaxOutput { max_output: f64 },
+}
+impl core::error::Error for AutotuneError {}
@@ -0,0 +1,370 @@
+//! Building blocks for custom controllers: filtering, differentiating, and integrating signals over time.
+//!
+//! Each block can be updated with the current `millis()` time, which handles uneven loop timing,
+//! or with an explicit time step in seconds, such as for a fixed-rate loop or a simulation.
+//!
+//! Estimating a lift's speed from a potentiometer and smoothing a joystick:
+//! ```rust
+//! let mut speed = Differentiator::new(Duration::from_millis(30));
+//! let mut stick = LowPass::new(Duration::from_millis(80));
+//! loop {
+//!     // A 250 degree potentiometer reads 0 to 4095.
+//!     let degrees = potentiometer.value()? as f64 * 250.0 / 4095.0;
+//!     let degrees_per_second = speed.update(degrees);
+//!     let throttle = stick.update(controller.state().joysticks.left.y as f64);
+//!     task::sleep(Duration::from_millis(10));
+//! }
+//! ```
+
+use core::time::Duration;
+
+/// Time steps longer than this are treated as a restart, since the signal could have done anything in between,
+/// such as while the robot was disabled.
+pub const DEFAULT_MAX_DT: Duration = Duration::from_millis(250);
+
+/// Returns the time step in seconds since the last update at a `millis()` time, and records the new time.
+fn time_step(last: &mut Option<u32>, time: u32) -> Option<f64> {
+    let dt = last.map(|last| time.wrapping_sub(last) as f64 / 1000.0);
+    *last = Some(time);
+    dt
+}
+
+/// A first order low-pass filter, which smooths out changes faster than its time constant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LowPass {
+    time_constant: f64,
+    value: Option<f64>,
+    last_time: Option<u32>,
+}
+
+impl LowPass {
+    /// Creates a filter that takes about `time_constant` to move two thirds of the way to a new input.
+    /// A time constant of zero passes inputs through unchanged.
+    pub fn new(time_constant: Duration) -> Self {
+        Self {
+            time_constant: time_constant.as_secs_f64(),
+            value: None,
+            last_time: None,
+        }
+    }
+
+    /// Adds an input and returns the filtered value.
+    pub fn update(&mut self, input: f64) -> f64 {
+        self.update_at(unsafe { pros_sys::millis() }, input)
+    }
+
+    /// Adds an input measured at a `millis()` time and returns the filtered value.
+    pub fn update_at(&mut self, time: u32, input: f64) -> f64 {
+        let dt = time_step(&mut self.last_time, time);
+        self.update_with_dt(input, dt.unwrap_or(0.0))
+    }
+
+    /// Adds an input measured `dt` seconds after the last one and returns the filtered value.
+    /// The first input is passed through unchanged.
+    pub fn update_with_dt(&mut self, input: f64, dt: f64) -> f64 {
+        let value = match self.value {
+            Some(value) if self.time_constant > 0.0 => {
+                let alpha = dt.max(0.0) / (self.time_constant + dt.max(0.0));
+                value + alpha * (input - value)
+            }
+            _ => input,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    /// The latest filtered value, or `None` before the first input.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    /// Forgets all inputs, so the next one is passed through unchanged.
+    pub fn reset(&mut self) {
+        self.value = None;
+        self.last_time = None;
+    }
+}
+
+/// Estimates how fast a signal changes per second, low-pass filtered to keep sensor noise from being amplified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Differentiator {
+    filter: LowPass,
+    max_dt: f64,
+    last_input: Option<f64>,
+    last_time: Option<u32>,
+    skipped_dt: f64,
+    rate: f64,
+}
+
+impl Differentiator {
+    /// Creates a differentiator whose output is filtered with the given time constant.
+    /// Around 2 to 5 sensor update periods works well for most sensors.
+    pub fn new(time_constant: Duration) -> Self {
+        Self {
+            filter: LowPass::new(time_constant),
+            max_dt: DEFAULT_MAX_DT.as_secs_f64(),
+            last_input: None,
+            last_time: None,
+            skipped_dt: 0.0,
+            rate: 0.0,
+        }
+    }
+
+    /// Sets the longest time step that is differentiated across. Longer gaps restart the estimate.
+    pub fn with_max_dt(mut self, max_dt: Duration) -> Self {
+        self.max_dt = max_dt.as_secs_f64();
+        self
+    }
+
+    /// Adds a sample and returns the rate of change per second.
+    pub fn update(&mut self, input: f64) -> f64 {
+        self.update_at(unsafe { pros_sys::millis() }, input)
+    }
+
+    /// Adds a sample measured at a `millis()` time and returns the rate of change per second.
+    pub fn update_at(&mut self, time: u32, input: f64) -> f64 {
+        match time_step(&mut self.last_time, time) {
+            Some(dt) => self.update_with_dt(input, dt),
+            None => {
+                self.last_input = Some(input);
+                self.rate
+            }
+        }
+    }
+
+    /// Adds a sample measured `dt` seconds after the last one and returns the rate of change per second.
+    ///
+    /// Samples less than a millisecond after the last differentiated one are skipped, since sensors
+    /// that haven't updated yet would otherwise read as a sudden stop. Their time steps still count
+    /// towards the next sample.
+    pub fn update_with_dt(&mut self, input: f64, dt: f64) -> f64 {
+        let dt = dt + self.skipped_dt;
+        if dt > self.max_dt {
+            self.filter.reset();
+            self.rate = 0.0;
+            self.skipped_dt = 0.0;
+            self.last_input = Some(input);
+            return self.rate;
+        }
+        if dt < 0.001 && self.last_input.is_some() {
+            self.skipped_dt = dt;
+            return self.rate;
+        }
+        self.skipped_dt = 0.0;
+        if let Some(last_input) = self.last_input {
+            self.rate = self.filter.update_with_dt((input - last_input) / dt, dt);
+        }
+        self.last_input = Some(input);
+        self.rate
+    }
+
+    /// The latest rate of change per second.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Forgets all samples and resets the rate to zero.
+    pub fn reset(&mut self) {
+        self.filter.reset();
+        self.last_input = None;
+        self.last_time = None;
+        self.skipped_dt = 0.0;
+        self.rate = 0.0;
+    }
+}
+
+/// Accumulates a signal over time, such as the integral term of a controller
+/// or distance from a velocity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Integrator {
+    limits: Option<(f64, f64)>,
+    max_dt: f64,
+    last_input: Option<f64>,
+    last_time: Option<u32>,
+    value: f64,
+}
+
+impl Default for Integrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Integrator {
+    pub fn new() -> Self {
+        Self {
+            limits: None,
+            max_dt: DEFAULT_MAX_DT.as_secs_f64(),
+            last_input: None,
+            last_time: None,
+            value: 0.0,
+        }
+    }
+
+    /// Keeps the total between `min` and `max`, so a controller's integral term can't wind up
+    /// while its output is saturated.
+    pub fn with_limits(mut self, min: f64, max: f64) -> Self {
+        self.limits = Some((min, max));
+        self
+    }
+
+    /// Sets the longest time step that is integrated across. Longer gaps add nothing.
+    pub fn with_max_dt(mut self, max_dt: Duration) -> Self {
+        self.max_dt = max_dt.as_secs_f64();
+        self
+    }
+
+    /// Adds a sample and returns the total.
+    pub fn update(&mut self, input: f64) -> f64 {
+        self.update_at(unsafe { pros_sys::millis() }, input)
+    }
+
+    /// Adds a sample measured at a `millis()` time and returns the total.
+    pub fn update_at(&mut self, time: u32, input: f64) -> f64 {
+        match time_step(&mut self.last_time, time) {
+            Some(dt) => self.update_with_dt(input, dt),
+            None => {
+                self.last_input = Some(input);
+                self.value
+            }
+        }
+    }
+
+    /// Adds a sample measured `dt` seconds after the last one and returns the total.
+    /// Each step adds the average of the two samples around it, times the time step.
+    pub fn update_with_dt(&mut self, input: f64, dt: f64) -> f64 {
+        if dt > 0.0 && dt <= self.max_dt {
+            let average = self
+                .last_input
+                .map_or(input, |last_input| (last_input + input) / 2.0);
+            self.value += average * dt;
+            if let Some((min, max)) = self.limits {
+                self.value = self.value.clamp(min, max);
+            }
+        }
+        self.last_input = Some(input);
+        self.value
+    }
+
+    /// The total so far.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Replaces the total, such as to zero a distance when a mechanism hits a limit switch.
+    pub fn set(&mut self, value: f64) {
+        self.value = value;
+    }
+
+    /// Forgets all samples and resets the total to zero.
+    pub fn reset(&mut self) {
+        self.last_input = None;
+        self.last_time = None;
+        self.value = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        libm::fabs(a - b) < 1e-9
+    }
+
+    #[test]
+    fn low_pass_step_response() {
+        let mut filter = LowPass::new(Duration::from_millis(100));
+        assert_eq!(filter.update_with_dt(0.0, 0.0), 0.0);
+        // Each 100 ms step with a 100 ms time constant moves halfway to the input.
+        assert!(close(filter.update_with_dt(1.0, 0.1), 0.5));
+        assert!(close(filter.update_with_dt(1.0, 0.1), 0.75));
+        assert!(close(filter.update_with_dt(1.0, 0.1), 0.875));
+        for _ in 0..100 {
+            filter.update_with_dt(1.0, 0.1);
+        }
+        assert!(close(filter.value().unwrap(), 1.0));
+    }
+
+    #[test]
+    fn low_pass_passes_first_input_and_zero_time_constant() {
+        let mut filter = LowPass::new(Duration::from_millis(100));
+        assert_eq!(filter.update_with_dt(5.0, 0.1), 5.0);
+        filter.reset();
+        assert_eq!(filter.value(), None);
+
+        let mut unfiltered = LowPass::new(Duration::ZERO);
+        unfiltered.update_with_dt(0.0, 0.0);
+        assert_eq!(unfiltered.update_with_dt(3.0, 0.01), 3.0);
+    }
+
+    #[test]
+    fn differentiator_follows_a_ramp() {
+        let mut speed = Differentiator::new(Duration::ZERO);
+        speed.update_with_dt(0.0, 0.0);
+        for step in 1..=10 {
+            // 2 units every 10 ms is 200 units per second.
+            assert!(close(speed.update_with_dt(step as f64 * 2.0, 0.01), 200.0));
+        }
+    }
+
+    #[test]
+    fn differentiator_restarts_after_max_dt() {
+        let mut speed = Differentiator::new(Duration::ZERO).with_max_dt(Duration::from_millis(50));
+        speed.update_with_dt(0.0, 0.0);
+        assert!(close(speed.update_with_dt(1.0, 0.01), 100.0));
+        assert_eq!(speed.update_with_dt(100.0, 1.0), 0.0);
+        assert!(close(speed.update_with_dt(101.0, 0.01), 100.0));
+    }
+
+    #[test]
+    fn differentiator_accumulates_skipped_time_steps() {
+        let mut speed = Differentiator::new(Duration::ZERO);
+        speed.update_with_dt(0.0, 0.0);
+        assert!(close(speed.update_with_dt(1.0, 0.01), 100.0));
+        // Too soon after the last sample, so the rate is held.
+        assert!(close(speed.update_with_dt(1.5, 0.0006), 100.0));
+        // Differentiated across both steps since the last sample that counted.
+        assert!(close(speed.update_with_dt(1.2, 0.0004), 200.0));
+    }
+
+    #[test]
+    fn differentiator_update_at_uses_millis() {
+        let mut speed = Differentiator::new(Duration::ZERO);
+        speed.update_at(1000, 0.0);
+        assert!(close(speed.update_at(1010, 1.0), 100.0));
+        assert!(close(speed.update_at(1010, 5.0), 100.0));
+        assert!(close(speed.update_at(1020, 2.0), 100.0));
+    }
+
+    #[test]
+    fn integrator_trapezoids() {
+        let mut distance = Integrator::new();
+        distance.update_with_dt(0.0, 0.0);
+        // A ramp from 0 to 10 over a second covers 5.
+        for step in 1..=10 {
+            distance.update_with_dt(step as f64, 0.1);
+        }
+        assert!(close(distance.value(), 5.0));
+        distance.set(1.0);
+        assert!(close(distance.update_with_dt(10.0, 0.1), 2.0));
+        distance.reset();
+        assert_eq!(distance.value(), 0.0);
+    }
+
+    #[test]
+    fn integrator_limits_and_max_dt() {
+        let mut integral = Integrator::new()
+            .with_limits(-1.0, 2.0)
+            .with_max_dt(Duration::from_millis(100));
+        for _ in 0..10 {
+            integral.update_with_dt(10.0, 0.1);
+        }
+        assert_eq!(integral.value(), 2.0);
+        for _ in 0..10 {
+            integral.update_with_dt(-10.0, 0.1);
+        }
+        assert_eq!(integral.value(), -1.0);
+        assert_eq!(integral.update_with_dt(100.0, 0.5), -1.0);
+    }
+}
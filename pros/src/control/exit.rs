@@ -0,0 +1,271 @@
+//! Composable conditions for deciding when a motion is finished.
+//!
+//! An [`ExitCondition`] is updated with the loop's error every time the loop runs,
+//! and conditions combine with `|` (either), `&` (both), and `!` (not):
+//! ```rust
+//! let mut exit = ErrorWithin(1.0).held_for(Duration::from_millis(200))
+//!     | Timeout::new(Duration::from_secs(2))
+//!     | Stalled::new(1.0, 2.0, Duration::from_millis(500));
+//! loop {
+//!     let error = target - lift.position()?.into_degrees();
+//!     if exit.update(error) {
+//!         break;
+//!     }
+//!     lift.set_voltage(pid.update(0.0, -error as f32))?;
+//!     task::sleep(Duration::from_millis(10));
+//! }
+//! ```
+//!
+//! Closures taking the error are conditions too, for completion logic of any kind,
+//! and combine with [`or`](ExitCondition::or) and [`and`](ExitCondition::and),
+//! and [`Motion`](crate::command::Motion) runs a loop as a command until a condition is met.
+
+use core::{
+    ops::{BitAnd, BitOr, Not},
+    time::Duration,
+};
+
+use super::settle::{SettleDetector, StuckDetector};
+
+/// Decides from a loop's error when it should stop.
+pub trait ExitCondition {
+    /// Adds the loop's error at a `millis()` time and returns true if the loop should stop.
+    fn update_at(&mut self, time: u32, error: f64) -> bool;
+
+    /// Forgets everything seen so far, so the condition can be used for another motion.
+    fn reset(&mut self) {}
+
+    /// Adds the loop's error and returns true if the loop should stop.
+    fn update(&mut self, error: f64) -> bool {
+        self.update_at(unsafe { pros_sys::millis() }, error)
+    }
+
+    /// Only stops once this condition has been met for `duration` without interruption.
+    fn held_for(self, duration: Duration) -> HeldFor<Self>
+    where
+        Self: Sized,
+    {
+        HeldFor {
+            condition: self,
+            duration,
+            since: None,
+        }
+    }
+
+    /// Stops when either condition is met. The same as `self | other`.
+    fn or<C: ExitCondition>(self, other: C) -> Or<Self, C>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Stops when both conditions are met. The same as `self & other`.
+    fn and<C: ExitCondition>(self, other: C) -> And<Self, C>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+}
+
+impl<F: FnMut(f64) -> bool> ExitCondition for F {
+    fn update_at(&mut self, _time: u32, error: f64) -> bool {
+        self(error)
+    }
+}
+
+/// Met while the error is no further than this from zero, either way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorWithin(pub f64);
+
+impl ExitCondition for ErrorWithin {
+    fn update_at(&mut self, _time: u32, error: f64) -> bool {
+        libm::fabs(error) <= self.0
+    }
+}
+
+/// Met once this long has passed since the condition was first updated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeout {
+    duration: Duration,
+    started: Option<u32>,
+}
+
+impl Timeout {
+    pub const fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            started: None,
+        }
+    }
+}
+
+impl ExitCondition for Timeout {
+    fn update_at(&mut self, time: u32, _error: f64) -> bool {
+        let started = *self.started.get_or_insert(time);
+        time.wrapping_sub(started) >= self.duration.as_millis() as u32
+    }
+
+    fn reset(&mut self) {
+        self.started = None;
+    }
+}
+
+/// Met once the loop has stopped making progress short of its target. See [`StuckDetector`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stalled(pub StuckDetector);
+
+impl Stalled {
+    /// Stalls when the error stays outside `error_window`, shrinking slower than `derivative_window`
+    /// units per second, for `time`.
+    pub const fn new(error_window: f64, derivative_window: f64, time: Duration) -> Self {
+        Self(StuckDetector::new(error_window, derivative_window, time))
+    }
+}
+
+impl ExitCondition for Stalled {
+    fn update_at(&mut self, time: u32, error: f64) -> bool {
+        self.0.update_at(time, error)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+/// Met once the loop has settled at its target. See [`SettleDetector`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settled(pub SettleDetector);
+
+impl Settled {
+    /// Settles when the error stays within `error_window`, changing slower than `derivative_window`
+    /// units per second, for `time`.
+    pub const fn new(error_window: f64, derivative_window: f64, time: Duration) -> Self {
+        Self(SettleDetector::new(error_window, derivative_window, time))
+    }
+}
+
+impl ExitCondition for Settled {
+    fn update_at(&mut self, time: u32, error: f64) -> bool {
+        self.0.update_at(time, error)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+/// Met once a condition has been met for a duration without interruption.
+/// Created by [`ExitCondition::held_for`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeldFor<C> {
+    condition: C,
+    duration: Duration,
+    since: Option<u32>,
+}
+
+impl<C: ExitCondition> ExitCondition for HeldFor<C> {
+    fn update_at(&mut self, time: u32, error: f64) -> bool {
+        if self.condition.update_at(time, error) {
+            let since = *self.since.get_or_insert(time);
+            time.wrapping_sub(since) >= self.duration.as_millis() as u32
+        } else {
+            self.since = None;
+            false
+        }
+    }
+
+    fn reset(&mut self) {
+        self.condition.reset();
+        self.since = None;
+    }
+}
+
+/// Met when either condition is. Both are always updated, so neither misses any errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<A: ExitCondition, B: ExitCondition> ExitCondition for Or<A, B> {
+    fn update_at(&mut self, time: u32, error: f64) -> bool {
+        let a = self.0.update_at(time, error);
+        let b = self.1.update_at(time, error);
+        a || b
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+        self.1.reset();
+    }
+}
+
+/// Met when both conditions are. Both are always updated, so neither misses any errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A: ExitCondition, B: ExitCondition> ExitCondition for And<A, B> {
+    fn update_at(&mut self, time: u32, error: f64) -> bool {
+        let a = self.0.update_at(time, error);
+        let b = self.1.update_at(time, error);
+        a && b
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+        self.1.reset();
+    }
+}
+
+/// Met when a condition isn't.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inverted<C>(pub C);
+
+impl<C: ExitCondition> ExitCondition for Inverted<C> {
+    fn update_at(&mut self, time: u32, error: f64) -> bool {
+        !self.0.update_at(time, error)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+macro_rules! impl_operators {
+    ($($ty:ident $(<$($param:ident),*>)?),*) => {
+        $(
+            impl<$($($param: ExitCondition,)*)? Rhs: ExitCondition> BitOr<Rhs> for $ty$(<$($param),*>)? {
+                type Output = Or<Self, Rhs>;
+
+                fn bitor(self, rhs: Rhs) -> Self::Output {
+                    Or(self, rhs)
+                }
+            }
+
+            impl<$($($param: ExitCondition,)*)? Rhs: ExitCondition> BitAnd<Rhs> for $ty$(<$($param),*>)? {
+                type Output = And<Self, Rhs>;
+
+                fn bitand(self, rhs: Rhs) -> Self::Output {
+                    And(self, rhs)
+                }
+            }
+
+            impl$(<$($param: ExitCondition),*>)? Not for $ty$(<$($param),*>)? {
+                type Output = Inverted<Self>;
+
+                fn not(self) -> Self::Output {
+                    Inverted(self)
+                }
+            }
+        )*
+    };
+}
+impl_operators!(
+    ErrorWithin,
+    Timeout,
+    Stalled,
+    Settled,
+    HeldFor<C>,
+    Or<A, B>,
+    And<A, B>,
+    Inverted<C>
+);
@@ -0,0 +1,12 @@
+//! Tools for feedback control loops that work with any sensor and actuator.
+//!
+//! Loops are described by a pair of closures, one that measures the process and one that drives it,
+//! so the same code tunes a drivetrain's heading, a lift's height, or a flywheel's speed.
+//! [`settle`] decides when such a loop has finished, or is stuck, and [`exit`] combines that with
+//! timeouts and other conditions. [`blocks`] has pieces for building controllers of your own.
+
+#[cfg(feature = "alloc")]
+pub mod autotune;
+pub mod blocks;
+pub mod exit;
+pub mod settle;
@@ -0,0 +1,200 @@
+//! Deciding when a loop has settled at its target or is stuck short of it.
+//!
+//! Both detectors watch the loop's error, such as the target minus the measurement,
+//! and are configured the same way:
+//! - the error window: how close to zero the error must be to count as at the target,
+//! - the derivative window: how fast the error may change, in units per second, to count as holding still,
+//! - and how long both must hold before the detector trips.
+//!
+//! The error's rate of change is measured across a moving horizon rather than between single updates,
+//! so sensor noise and uneven loop timing don't reset the timer.
+//! ```rust
+//! let mut settle = SettleDetector::new(1.0, 5.0, Duration::from_millis(250));
+//! let mut stuck = StuckDetector::new(1.0, 2.0, Duration::from_millis(500));
+//! loop {
+//!     let error = target - lift.position()?.into_degrees();
+//!     if settle.update(error) || stuck.update(error) {
+//!         break;
+//!     }
+//!     lift.set_voltage(pid.update(0.0, -error as f32))?;
+//!     task::sleep(Duration::from_millis(10));
+//! }
+//! ```
+
+use core::time::Duration;
+
+/// Measures how fast an error changes across a horizon of at least a few updates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rate {
+    horizon: u32,
+    /// The oldest sample in the current horizon.
+    anchor: Option<(u32, f64)>,
+    rate: Option<f64>,
+}
+
+impl Rate {
+    const fn new(horizon: Duration) -> Self {
+        Self {
+            horizon: horizon.as_millis() as u32,
+            anchor: None,
+            rate: None,
+        }
+    }
+
+    /// Adds a sample and returns the latest rate per second, once a full horizon has passed.
+    fn update(&mut self, time: u32, error: f64) -> Option<f64> {
+        match self.anchor {
+            Some((anchor_time, anchor_error)) => {
+                let elapsed = time.wrapping_sub(anchor_time);
+                if elapsed >= self.horizon.max(1) {
+                    self.rate = Some((error - anchor_error) * 1000.0 / elapsed as f64);
+                    self.anchor = Some((time, error));
+                }
+            }
+            None => self.anchor = Some((time, error)),
+        }
+        self.rate
+    }
+}
+
+/// Returns how long a condition has held, starting the timer when it becomes true and clearing it when it doesn't.
+fn held_for(since: &mut Option<u32>, time: u32, holds: bool) -> u32 {
+    if holds {
+        time.wrapping_sub(*since.get_or_insert(time))
+    } else {
+        *since = None;
+        0
+    }
+}
+
+/// Trips once the error has stayed within the error window, changing no faster than the derivative window,
+/// for long enough.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SettleDetector {
+    /// The largest error, either way, that counts as at the target.
+    pub error_window: f64,
+    /// The fastest the error may change, in units per second, while settled.
+    pub derivative_window: f64,
+    /// How long the error must stay within both windows.
+    pub time: Duration,
+    rate: Rate,
+    since: Option<u32>,
+    settled: bool,
+}
+
+impl SettleDetector {
+    /// The default horizon the error's rate of change is measured across.
+    pub const DEFAULT_HORIZON: Duration = Duration::from_millis(50);
+
+    pub const fn new(error_window: f64, derivative_window: f64, time: Duration) -> Self {
+        Self {
+            error_window,
+            derivative_window,
+            time,
+            rate: Rate::new(Self::DEFAULT_HORIZON),
+            since: None,
+            settled: false,
+        }
+    }
+
+    /// Sets how long the error's rate of change is measured across.
+    /// Longer horizons ignore more noise but notice movement later.
+    pub const fn with_horizon(mut self, horizon: Duration) -> Self {
+        self.rate = Rate::new(horizon);
+        self
+    }
+
+    /// Adds a new error and returns true if the loop has settled.
+    pub fn update(&mut self, error: f64) -> bool {
+        self.update_at(unsafe { pros_sys::millis() }, error)
+    }
+
+    /// Like [`update`](Self::update), for an error measured at a `millis()` time.
+    pub fn update_at(&mut self, time: u32, error: f64) -> bool {
+        let rate = self.rate.update(time, error);
+        let within = libm::fabs(error) <= self.error_window
+            && rate.is_some_and(|rate| libm::fabs(rate) <= self.derivative_window);
+        self.settled =
+            within && held_for(&mut self.since, time, within) >= self.time.as_millis() as u32;
+        self.settled
+    }
+
+    /// Whether the last update found the loop settled.
+    pub fn is_settled(&self) -> bool {
+        self.settled
+    }
+
+    /// Forgets the error's history, such as when the target changes.
+    pub fn reset(&mut self) {
+        self.rate = Rate::new(Duration::from_millis(self.rate.horizon as u64));
+        self.since = None;
+        self.settled = false;
+    }
+}
+
+/// Trips once the error has stayed outside the error window while changing no faster than
+/// the derivative window for long enough, meaning the loop stopped making progress short of its target,
+/// such as a mechanism jammed against a game element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StuckDetector {
+    /// The largest error, either way, that counts as at the target, where the loop can't be stuck.
+    pub error_window: f64,
+    /// The slowest the error may shrink, in units per second, and still count as progress.
+    pub derivative_window: f64,
+    /// How long the loop must make no progress.
+    pub time: Duration,
+    rate: Rate,
+    since: Option<u32>,
+    stuck: bool,
+}
+
+impl StuckDetector {
+    /// The default horizon the error's rate of change is measured across.
+    pub const DEFAULT_HORIZON: Duration = Duration::from_millis(100);
+
+    pub const fn new(error_window: f64, derivative_window: f64, time: Duration) -> Self {
+        Self {
+            error_window,
+            derivative_window,
+            time,
+            rate: Rate::new(Self::DEFAULT_HORIZON),
+            since: None,
+            stuck: false,
+        }
+    }
+
+    /// Sets how long the error's rate of change is measured across.
+    pub const fn with_horizon(mut self, horizon: Duration) -> Self {
+        self.rate = Rate::new(horizon);
+        self
+    }
+
+    /// Adds a new error and returns true if the loop is stuck.
+    pub fn update(&mut self, error: f64) -> bool {
+        self.update_at(unsafe { pros_sys::millis() }, error)
+    }
+
+    /// Like [`update`](Self::update), for an error measured at a `millis()` time.
+    pub fn update_at(&mut self, time: u32, error: f64) -> bool {
+        let rate = self.rate.update(time, error);
+        // Progress is the error shrinking towards zero, so an error that grows counts as none.
+        let progress = rate.map(|rate| if error < 0.0 { rate } else { -rate });
+        let stalled = libm::fabs(error) > self.error_window
+            && progress.is_some_and(|progress| progress < self.derivative_window);
+        self.stuck =
+            stalled && held_for(&mut self.since, time, stalled) >= self.time.as_millis() as u32;
+        self.stuck
+    }
+
+    /// Whether the last update found the loop stuck.
+    pub fn is_stuck(&self) -> bool {
+        self.stuck
+    }
+
+    /// Forgets the error's history, such as when the target changes.
+    pub fn reset(&mut self) {
+        self.rate = Rate::new(Duration::from_millis(self.rate.horizon as u64));
+        self.since = None;
+        self.stuck = false;
+    }
+}
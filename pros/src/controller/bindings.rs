@@ -0,0 +1,152 @@
+//! Named actions bound to controller buttons and joystick axes.
+//!
+//! Subsystem code asks whether an action is active instead of reading a specific button,
+//! so the button layout can change, for example with a driver's
+//! [`DriverProfile`](super::profile::DriverProfile), without touching that code.
+
+use alloc::{string::String, vec::Vec};
+
+use super::{
+    gestures::{Gesture, GestureConfig, GestureDetector},
+    profile::DriverProfile,
+    Button, ControllerState,
+};
+
+/// How pressing a button activates an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// The action is active while the button is held.
+    WhileHeld,
+    /// Each press turns the action on or off.
+    Toggle,
+    /// The action is active for one update after a [`Gesture::Tap`].
+    Tap,
+    /// The action is active for one update after a [`Gesture::DoubleTap`].
+    DoubleTap,
+    /// The action is active for one update after a [`Gesture::Hold`].
+    Hold,
+}
+
+/// A joystick axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+}
+
+struct ButtonBinding {
+    action: String,
+    button: Button,
+    trigger: Trigger,
+    active: bool,
+    was_pressed: bool,
+    gestures: GestureDetector,
+}
+
+/// Maps action names to buttons and axes.
+/// [`Bindings::update`] must be called with the controller state once per loop.
+pub struct Bindings {
+    buttons: Vec<ButtonBinding>,
+    axes: Vec<(String, Axis, f32)>,
+    gesture_config: GestureConfig,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self {
+            buttons: Vec::new(),
+            axes: Vec::new(),
+            gesture_config: GestureConfig::default(),
+        }
+    }
+
+    /// Sets the timing used to recognize taps, double taps, and holds.
+    pub fn set_gesture_config(&mut self, config: GestureConfig) {
+        self.gesture_config = config;
+        for binding in &mut self.buttons {
+            binding.gestures.set_config(config);
+        }
+    }
+
+    /// Binds an action to a button, replacing any existing binding for that action.
+    pub fn bind_button(&mut self, action: impl Into<String>, button: Button, trigger: Trigger) {
+        let action = action.into();
+        self.buttons.retain(|binding| binding.action != action);
+        self.buttons.push(ButtonBinding {
+            action,
+            button,
+            trigger,
+            active: false,
+            was_pressed: false,
+            gestures: GestureDetector::new(self.gesture_config),
+        });
+    }
+
+    /// Binds an action to a joystick axis, replacing any existing binding for that action.
+    pub fn bind_axis(&mut self, action: impl Into<String>, axis: Axis) {
+        let action = action.into();
+        self.axes.retain(|(bound, ..)| *bound != action);
+        self.axes.push((action, axis, 0.0));
+    }
+
+    /// Moves actions onto the buttons a driver prefers, keeping their triggers.
+    /// Actions the profile doesn't mention keep their current buttons.
+    pub fn apply_profile(&mut self, profile: &DriverProfile) {
+        for binding in &mut self.buttons {
+            if let Some(button) = profile.button_for(&binding.action) {
+                binding.button = button;
+            }
+        }
+    }
+
+    /// Reads the buttons and axes. This should be called once per loop.
+    pub fn update(&mut self, state: &ControllerState) {
+        for binding in &mut self.buttons {
+            let pressed = state.buttons.is_pressed(binding.button);
+            let just_pressed = pressed && !binding.was_pressed;
+            binding.was_pressed = pressed;
+            let gesture = binding.gestures.update(pressed);
+
+            binding.active = match binding.trigger {
+                Trigger::WhileHeld => pressed,
+                Trigger::Toggle => binding.active ^ just_pressed,
+                Trigger::Tap => gesture == Some(Gesture::Tap),
+                Trigger::DoubleTap => gesture == Some(Gesture::DoubleTap),
+                Trigger::Hold => gesture == Some(Gesture::Hold),
+            };
+        }
+
+        for (_, axis, value) in &mut self.axes {
+            let joysticks = &state.joysticks;
+            *value = match axis {
+                Axis::LeftX => joysticks.left.x,
+                Axis::LeftY => joysticks.left.y,
+                Axis::RightX => joysticks.right.x,
+                Axis::RightY => joysticks.right.y,
+            };
+        }
+    }
+
+    /// Returns true if the action is active. Unbound actions are never active.
+    pub fn is_active(&self, action: &str) -> bool {
+        self.buttons
+            .iter()
+            .any(|binding| binding.action == action && binding.active)
+    }
+
+    /// Returns the value of the axis bound to the action, or zero if it is unbound.
+    pub fn axis(&self, action: &str) -> f32 {
+        self.axes
+            .iter()
+            .find(|(bound, ..)| bound == action)
+            .map_or(0.0, |(.., value)| *value)
+    }
+}
@@ -0,0 +1,144 @@
+//! Recognizing taps, double taps, and holds on controller buttons,
+//! so that one button can do several things.
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use super::{Button, Buttons};
+
+/// A way of pressing a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// A single short press.
+    Tap,
+    /// Two short presses in quick succession.
+    DoubleTap,
+    /// A press held down for a while.
+    Hold,
+}
+
+/// Timing thresholds for recognizing gestures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureConfig {
+    /// How long a button must be held for a [`Gesture::Hold`].
+    pub hold_time: Duration,
+    /// The longest time between the end of one tap and the end of the next for a [`Gesture::DoubleTap`].
+    pub double_tap_window: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            hold_time: Duration::from_millis(500),
+            double_tap_window: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Recognizes gestures on a single button.
+///
+/// A tap is only reported once the double tap window has passed without a second tap,
+/// so it arrives slightly after the button is released.
+/// A hold is reported as soon as the hold time is reached, while the button is still down.
+#[derive(Debug, Clone, Default)]
+pub struct GestureDetector {
+    config: GestureConfig,
+    pressed_at: Option<u32>,
+    held: bool,
+    pending_tap: Option<u32>,
+}
+
+impl GestureDetector {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> GestureConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: GestureConfig) {
+        self.config = config;
+    }
+
+    /// Feeds in whether the button is pressed, returning a gesture if one just finished.
+    /// This should be called once per loop.
+    pub fn update(&mut self, pressed: bool) -> Option<Gesture> {
+        let now = unsafe { pros_sys::millis() };
+        let hold_time = self.config.hold_time.as_millis() as u32;
+        let window = self.config.double_tap_window.as_millis() as u32;
+
+        match (pressed, self.pressed_at) {
+            (true, None) => {
+                self.pressed_at = Some(now);
+                None
+            }
+            (true, Some(pressed_at)) => {
+                if !self.held && now - pressed_at >= hold_time {
+                    self.held = true;
+                    self.pending_tap = None;
+                    Some(Gesture::Hold)
+                } else {
+                    None
+                }
+            }
+            (false, Some(_)) => {
+                self.pressed_at = None;
+                if core::mem::take(&mut self.held) {
+                    return None;
+                }
+                match self.pending_tap.take() {
+                    Some(first) if now - first <= window => Some(Gesture::DoubleTap),
+                    _ => {
+                        self.pending_tap = Some(now);
+                        None
+                    }
+                }
+            }
+            (false, None) => match self.pending_tap {
+                Some(first) if now - first > window => {
+                    self.pending_tap = None;
+                    Some(Gesture::Tap)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Recognizes gestures on every button of a controller.
+#[derive(Debug, Clone)]
+pub struct ControllerGestures {
+    detectors: [GestureDetector; 12],
+}
+
+impl Default for ControllerGestures {
+    fn default() -> Self {
+        Self::new(GestureConfig::default())
+    }
+}
+
+impl ControllerGestures {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            detectors: core::array::from_fn(|_| GestureDetector::new(config)),
+        }
+    }
+
+    /// Feeds in the state of the buttons, returning every gesture that just finished.
+    /// This should be called once per loop.
+    pub fn update(&mut self, buttons: &Buttons) -> Vec<(Button, Gesture)> {
+        Button::ALL
+            .iter()
+            .zip(&mut self.detectors)
+            .filter_map(|(&button, detector)| {
+                detector
+                    .update(buttons.is_pressed(button))
+                    .map(|gesture| (button, gesture))
+            })
+            .collect()
+    }
+}
@@ -1,8 +1,26 @@
+#[cfg(feature = "alloc")]
 use alloc::{ffi::CString, vec::Vec};
-use pros_sys::{controller_id_e_t, PROS_ERR};
+use pros_sys::controller_id_e_t;
+#[cfg(feature = "alloc")]
+use pros_sys::PROS_ERR;
 use snafu::Snafu;
 
-use crate::error::{bail_on, map_errno};
+use crate::error::map_errno;
+#[cfg(feature = "alloc")]
+use crate::{
+    encode::{Decode, DecodeError, Decoder, Encode, Encoder},
+    error::bail_on,
+};
+
+#[cfg(feature = "alloc")]
+pub mod bindings;
+#[cfg(feature = "alloc")]
+pub mod gestures;
+#[cfg(feature = "alloc")]
+pub mod profile;
+
+#[cfg(feature = "alloc")]
+pub use bindings::Bindings;
 
 /// Holds whether or not the buttons on the controller are pressed or not
 pub struct Buttons {
@@ -20,6 +38,79 @@ pub struct Buttons {
     pub right_trigger_2: bool,
 }
 
+impl Buttons {
+    /// Returns true if the given button is pressed.
+    pub fn is_pressed(&self, button: Button) -> bool {
+        match button {
+            Button::A => self.a,
+            Button::B => self.b,
+            Button::X => self.x,
+            Button::Y => self.y,
+            Button::Up => self.up,
+            Button::Down => self.down,
+            Button::Left => self.left,
+            Button::Right => self.right,
+            Button::L1 => self.left_trigger_1,
+            Button::L2 => self.left_trigger_2,
+            Button::R1 => self.right_trigger_1,
+            Button::R2 => self.right_trigger_2,
+        }
+    }
+}
+
+/// A button on the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Button {
+    A,
+    B,
+    X,
+    Y,
+    Up,
+    Down,
+    Left,
+    Right,
+    L1,
+    L2,
+    R1,
+    R2,
+}
+
+impl Button {
+    /// Every button, in declaration order.
+    pub const ALL: [Button; 12] = [
+        Self::A,
+        Self::B,
+        Self::X,
+        Self::Y,
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::L1,
+        Self::L2,
+        Self::R1,
+        Self::R2,
+    ];
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for Button {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(*self as u8));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Decode for Button {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let index: u8 = decoder.read()?;
+        Self::ALL
+            .get(index as usize)
+            .copied()
+            .ok_or(DecodeError::InvalidValue)
+    }
+}
+
 /// Stores how far the joystick is away from the center (at *(0, 0)*) from -1 to 1.
 /// On the x axis left is negative, and right is positive.
 /// On the y axis down is negative, and up is positive.
@@ -40,11 +131,13 @@ pub struct ControllerState {
     pub buttons: Buttons,
 }
 
+#[cfg(feature = "alloc")]
 pub struct ControllerLine {
     controller: Controller,
     line: u8,
 }
 
+#[cfg(feature = "alloc")]
 impl ControllerLine {
     pub const MAX_TEXT_LEN: usize = 14;
     pub const MAX_LINE_NUM: u8 = 2;
@@ -52,7 +145,7 @@ impl ControllerLine {
         let text = text.into();
         let text_len = text.len();
         assert!(
-            text_len > ControllerLine::MAX_TEXT_LEN,
+            text_len <= ControllerLine::MAX_TEXT_LEN,
             "Printed text is too long to fit on controller display ({text_len} > {})",
             Self::MAX_TEXT_LEN
         );
@@ -77,13 +170,17 @@ pub enum Controller {
 }
 
 impl Controller {
+    /// The longest pattern accepted by [`Controller::rumble`].
+    pub const MAX_RUMBLE_LEN: usize = 8;
+
     fn id(&self) -> controller_id_e_t {
         *self as controller_id_e_t
     }
 
+    #[cfg(feature = "alloc")]
     pub fn line(&self, line_num: u8) -> ControllerLine {
         assert!(
-            line_num > ControllerLine::MAX_LINE_NUM,
+            line_num <= ControllerLine::MAX_LINE_NUM,
             "Line number is too large for controller display ({line_num} > {})",
             ControllerLine::MAX_LINE_NUM
         );
@@ -94,6 +191,41 @@ impl Controller {
         }
     }
 
+    /// Clears all of the text on the controller screen.
+    #[cfg(feature = "alloc")]
+    pub fn clear_screen(&self) -> Result<(), ControllerError> {
+        bail_on!(PROS_ERR, unsafe { pros_sys::controller_clear(self.id()) });
+        Ok(())
+    }
+
+    /// Rumbles the controller in a pattern of up to 8 characters,
+    /// where `.` is a short rumble, `-` is a long rumble, and ` ` is a pause.
+    #[cfg(feature = "alloc")]
+    pub fn rumble(&self, pattern: &str) -> Result<(), ControllerError> {
+        assert!(
+            pattern.len() <= Self::MAX_RUMBLE_LEN,
+            "Rumble pattern is too long ({} > {})",
+            pattern.len(),
+            Self::MAX_RUMBLE_LEN
+        );
+        let c_pattern =
+            CString::new(pattern).expect("parameter `pattern` should not contain null bytes");
+        bail_on!(PROS_ERR, unsafe {
+            pros_sys::controller_rumble(self.id(), c_pattern.as_ptr())
+        });
+        Ok(())
+    }
+
+    /// Gets the state of the controller with the joysticks shaped by a driver's profile.
+    #[cfg(feature = "alloc")]
+    pub fn shaped_state(&self, profile: &profile::DriverProfile) -> ControllerState {
+        let state = self.state();
+        ControllerState {
+            joysticks: profile.shape(state.joysticks),
+            buttons: state.buttons,
+        }
+    }
+
     /// Gets the state of the controller; the joysticks and buttons.
     pub fn state(&self) -> ControllerState {
         ControllerState {
@@ -186,6 +318,7 @@ pub enum ControllerError {
     #[snafu(display("Another resource is already using the controller"))]
     ConcurrentAccess,
 }
+impl core::error::Error for ControllerError {}
 
 map_errno! {
     ControllerError {
@@ -0,0 +1,127 @@
+//! Per-driver joystick tuning and button layouts, saved on the SD card.
+
+use alloc::{string::String, vec::Vec};
+
+use super::{Button, Joystick, Joysticks};
+use crate::{
+    encode::{Decode, DecodeError, Decoder, Encode, Encoder},
+    usd::{self, UsdError},
+};
+
+/// Shapes raw joystick values to make fine control easier.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JoystickCurve {
+    /// Inputs are raised to this power, so values above 1 give more precision near the center.
+    pub exponent: f32,
+    /// Inputs smaller than this are ignored, to hide joystick drift.
+    pub deadband: f32,
+}
+
+impl Default for JoystickCurve {
+    fn default() -> Self {
+        Self {
+            exponent: 1.0,
+            deadband: 0.05,
+        }
+    }
+}
+
+impl JoystickCurve {
+    /// Shapes a joystick value from -1.0 to 1.0.
+    /// The output still covers the full range, starting from zero at the edge of the deadband.
+    pub fn apply(&self, value: f32) -> f32 {
+        let magnitude = libm::fabsf(value);
+        if magnitude < self.deadband {
+            return 0.0;
+        }
+        let scaled = ((magnitude - self.deadband) / (1.0 - self.deadband)).min(1.0);
+        libm::copysignf(libm::powf(scaled, self.exponent), value)
+    }
+}
+
+/// One driver's preferences.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriverProfile {
+    pub name: String,
+    pub curve: JoystickCurve,
+    /// The largest forward output, from 0.0 to 1.0, applied to the y axes.
+    pub max_forward: f32,
+    /// The largest turning output, from 0.0 to 1.0, applied to the x axes.
+    pub max_turn: f32,
+    /// Which button each named action is on.
+    pub bindings: Vec<(String, Button)>,
+}
+
+impl DriverProfile {
+    /// Creates a profile with a linear curve, full speed, and no bindings.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            curve: JoystickCurve::default(),
+            max_forward: 1.0,
+            max_turn: 1.0,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Applies the curve and speed limits to both joysticks.
+    pub fn shape(&self, joysticks: Joysticks) -> Joysticks {
+        let shape = |joystick: Joystick| Joystick {
+            x: self.curve.apply(joystick.x) * self.max_turn,
+            y: self.curve.apply(joystick.y) * self.max_forward,
+        };
+        Joysticks {
+            left: shape(joysticks.left),
+            right: shape(joysticks.right),
+        }
+    }
+
+    /// Binds an action to a button, replacing any button it was bound to.
+    pub fn bind(&mut self, action: impl Into<String>, button: Button) {
+        let action = action.into();
+        self.bindings.retain(|(bound, _)| *bound != action);
+        self.bindings.push((action, button));
+    }
+
+    /// Returns the button an action is bound to.
+    pub fn button_for(&self, action: &str) -> Option<Button> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == action)
+            .map(|(_, button)| *button)
+    }
+}
+
+impl Encode for DriverProfile {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.name);
+        encoder.write(&(self.curve.exponent, self.curve.deadband));
+        encoder.write(&(self.max_forward, self.max_turn));
+        encoder.write(&self.bindings);
+    }
+}
+
+impl Decode for DriverProfile {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let name = decoder.read()?;
+        let (exponent, deadband) = decoder.read()?;
+        let (max_forward, max_turn) = decoder.read()?;
+        Ok(Self {
+            name,
+            curve: JoystickCurve { exponent, deadband },
+            max_forward,
+            max_turn,
+            bindings: decoder.read()?,
+        })
+    }
+}
+
+/// Loads the profiles saved at `path`, returning an empty list if there aren't any.
+pub fn load(path: &str) -> Result<Vec<DriverProfile>, UsdError> {
+    Ok(usd::load(path)?.unwrap_or_default())
+}
+
+/// Saves profiles to `path` on the SD card.
+pub fn save(path: &str, profiles: &[DriverProfile]) -> Result<(), UsdError> {
+    usd::save(path, profiles)
+}
@@ -0,0 +1,301 @@
+//! Streaming snapshots of the robot's state over the USB serial connection for live dashboards.
+//!
+//! Nothing is sent until a [`Dashboard`] is spawned. Every period it writes one line of JSON to stdout
+//! with the time in milliseconds since the program started and the value of every selected field:
+//! ```text
+//! {"time":8120,"phase":"autonomous","battery.voltage":12.61,"pose":{"x":24.1,"y":-3.5,"heading":91.2}}
+//! ```
+//! Lines that don't start with `{` can be ignored, so this can share the connection with
+//! [`Shell`](crate::shell::Shell) and printed messages.
+//!
+//! Serial bandwidth is limited, so only send the fields a dashboard is showing, either with
+//! [`Dashboard::select`] or while it runs with [`DashboardHandle::select`].
+//! ```rust
+//! let dashboard = Dashboard::new(Duration::from_millis(100))
+//!     .competition()
+//!     .battery()
+//!     .field("pose", move || odometry.lock().pose())
+//!     .field("temp.left", move || left_motor.temperature().ok())
+//!     .select(["phase", "pose"])
+//!     .spawn();
+//! dashboard.select_all();
+//! ```
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{fmt::Write, time::Duration};
+
+use super::telemetry::Telemetry;
+use crate::{
+    competition::{self, CompetitionMode},
+    fixed::Fixed,
+    pose::Pose,
+    shell::write_stdout,
+    sync::Mutex,
+    task::{self, TaskHandle},
+};
+
+/// A value that can be sent in a dashboard snapshot.
+pub trait DashboardValue {
+    /// Appends the value to `out` as JSON.
+    fn write_json(&self, out: &mut String);
+}
+
+macro_rules! impl_dashboard_value {
+    ($($ty:ty),*) => {
+        $(
+            impl DashboardValue for $ty {
+                fn write_json(&self, out: &mut String) {
+                    _ = write!(out, "{self}");
+                }
+            }
+        )*
+    };
+}
+impl_dashboard_value!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize, bool);
+
+impl DashboardValue for f64 {
+    /// Numbers are sent with three decimal places.
+    /// Infinities and NaN aren't valid JSON, so they are sent as `null`.
+    fn write_json(&self, out: &mut String) {
+        if self.is_finite() {
+            _ = write!(out, "{}", Fixed::new(*self, 3));
+        } else {
+            out.push_str("null");
+        }
+    }
+}
+
+impl DashboardValue for f32 {
+    fn write_json(&self, out: &mut String) {
+        (*self as f64).write_json(out);
+    }
+}
+
+impl DashboardValue for str {
+    fn write_json(&self, out: &mut String) {
+        out.push('"');
+        for character in self.chars() {
+            match character {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                character if (character as u32) < 0x20 => {
+                    _ = write!(out, "\\u{:04x}", character as u32);
+                }
+                character => out.push(character),
+            }
+        }
+        out.push('"');
+    }
+}
+
+impl DashboardValue for String {
+    fn write_json(&self, out: &mut String) {
+        self.as_str().write_json(out);
+    }
+}
+
+/// `None` is sent as `null`.
+impl<T: DashboardValue> DashboardValue for Option<T> {
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Some(value) => value.write_json(out),
+            None => out.push_str("null"),
+        }
+    }
+}
+
+impl<T: DashboardValue> DashboardValue for [T] {
+    fn write_json(&self, out: &mut String) {
+        out.push('[');
+        for (index, value) in self.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            value.write_json(out);
+        }
+        out.push(']');
+    }
+}
+
+impl<T: DashboardValue> DashboardValue for Vec<T> {
+    fn write_json(&self, out: &mut String) {
+        self.as_slice().write_json(out);
+    }
+}
+
+impl DashboardValue for Pose {
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"x\":");
+        self.x.write_json(out);
+        out.push_str(",\"y\":");
+        self.y.write_json(out);
+        out.push_str(",\"heading\":");
+        self.heading.write_json(out);
+        out.push('}');
+    }
+}
+
+impl DashboardValue for CompetitionMode {
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Autonomous => "autonomous",
+            Self::Opcontrol => "opcontrol",
+        }
+        .write_json(out);
+    }
+}
+
+struct Field {
+    name: String,
+    sample: Box<dyn FnMut(&mut String) + Send>,
+}
+
+/// Which fields are sent. `None` sends every field.
+type Selection = Arc<Mutex<Option<Vec<String>>>>;
+
+/// Whether a field is selected by a name, which can be the field's own name or a prefix of it
+/// ending before a `.`, so that `battery` selects `battery.voltage` and `battery.capacity`.
+fn is_selected(field: &str, selection: &[String]) -> bool {
+    selection.iter().any(|name| {
+        field
+            .strip_prefix(name.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Periodically sends the values of registered fields over USB serial as JSON.
+pub struct Dashboard {
+    period: Duration,
+    fields: Vec<Field>,
+    selection: Option<Vec<String>>,
+}
+
+impl Dashboard {
+    /// Creates a dashboard with no fields that sends a snapshot every `period`.
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            fields: Vec::new(),
+            selection: None,
+        }
+    }
+
+    /// Adds a field whose value is sampled every time a snapshot is sent.
+    pub fn field<T: DashboardValue>(
+        mut self,
+        name: impl Into<String>,
+        mut sample: impl FnMut() -> T + Send + 'static,
+    ) -> Self {
+        self.fields.push(Field {
+            name: name.into(),
+            sample: Box::new(move |out| sample().write_json(out)),
+        });
+        self
+    }
+
+    /// Adds a field named `prefix.field` for every field of a [`Telemetry`] struct.
+    /// Each sample briefly locks `source`.
+    pub fn fields_of<T: Telemetry + Send + 'static>(
+        mut self,
+        prefix: &str,
+        source: Arc<Mutex<T>>,
+    ) -> Self {
+        for (index, field) in T::FIELDS.iter().enumerate() {
+            let source = source.clone();
+            self = self.field(format!("{prefix}.{}", field.name), move || {
+                source.lock().field_value(index)
+            });
+        }
+        self
+    }
+
+    /// Adds `battery.voltage` in volts and `battery.capacity` in percent.
+    pub fn battery(self) -> Self {
+        self.field("battery.voltage", || unsafe {
+            pros_sys::battery_get_voltage() as f64 / 1000.0
+        })
+        .field("battery.capacity", || unsafe {
+            pros_sys::battery_get_capacity()
+        })
+    }
+
+    /// Adds `phase`, the current [`CompetitionMode`].
+    pub fn competition(self) -> Self {
+        self.field("phase", competition::mode)
+    }
+
+    /// Sends only the named fields. See [`DashboardHandle::select`].
+    pub fn select<S: ToString>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.selection = Some(names.into_iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Appends a snapshot of the selected fields to `out` as one line of JSON.
+    fn write_snapshot(&mut self, selection: Option<&[String]>, out: &mut String) {
+        _ = write!(out, "{{\"time\":{}", unsafe { pros_sys::millis() });
+        for field in self.fields.iter_mut() {
+            if selection.is_some_and(|selection| !is_selected(&field.name, selection)) {
+                continue;
+            }
+            out.push(',');
+            field.name.write_json(out);
+            out.push(':');
+            (field.sample)(out);
+        }
+        out.push_str("}\n");
+    }
+
+    /// Spawns a task that sends a snapshot every period.
+    pub fn spawn(mut self) -> DashboardHandle {
+        let selection: Selection = Arc::new(Mutex::new(self.selection.take()));
+        let task = task::spawn({
+            let selection = selection.clone();
+            move || {
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    let current = selection.lock().clone();
+                    self.write_snapshot(current.as_deref(), &mut line);
+                    write_stdout(line.as_bytes());
+                    task::sleep(self.period);
+                }
+            }
+        });
+        DashboardHandle { task, selection }
+    }
+}
+
+/// Changes which fields a running [`Dashboard`] sends.
+pub struct DashboardHandle {
+    task: TaskHandle,
+    selection: Selection,
+}
+
+impl DashboardHandle {
+    /// Sends only the named fields from the next snapshot on.
+    /// A name also selects every field it is a prefix of, up to a `.`,
+    /// so `battery` selects both `battery.voltage` and `battery.capacity`.
+    pub fn select<S: ToString>(&self, names: impl IntoIterator<Item = S>) {
+        *self.selection.lock() = Some(names.into_iter().map(|name| name.to_string()).collect());
+    }
+
+    /// Sends every field from the next snapshot on.
+    pub fn select_all(&self) {
+        *self.selection.lock() = None;
+    }
+
+    /// Stops sending snapshots.
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
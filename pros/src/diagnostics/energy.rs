@@ -0,0 +1,302 @@
+//! Measuring how much energy each subsystem uses over a match.
+//!
+//! An [`EnergyMonitor`] samples the battery and every motor of each subsystem in a background task,
+//! adding up the electrical energy drawn and the mechanical energy delivered at the motors' output shafts.
+//! Totals start over when autonomous starts, and a report is logged whenever the robot is disabled:
+//! ```text
+//! energy over 105.2 s: battery 4.21 Wh (lowest 11.62 V, peak 19.8 A)
+//!   drivetrain: 2.87 Wh drawn, 1.52 Wh delivered (53% efficient), peak 72.4 W
+//!   intake: 0.41 Wh drawn, 0.09 Wh delivered (22% efficient), peak 10.8 W
+//!   other: 0.93 Wh
+//! ```
+//! A subsystem that delivers much less than it draws is losing energy to friction or stalling,
+//! which often means its gear ratio is wrong for the load. "Other" is the battery energy
+//! not drawn by any monitored motor, such as by the brain, sensors, and unmonitored motors.
+//!
+//! ```rust
+//! let energy = EnergyMonitor::new(Duration::from_millis(20))
+//!     .subsystem("drivetrain", drivetrain_motors)
+//!     .subsystem("intake", [intake_motor])
+//!     .spawn();
+//! // Later, such as in a shell command:
+//! println!("{}", energy.report());
+//! ```
+
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{fmt, time::Duration};
+
+use crate::{
+    competition::{self, CompetitionMode},
+    control::blocks::{Integrator, DEFAULT_MAX_DT},
+    fixed::Fixed,
+    motor::Motor,
+    sync::Mutex,
+    task::{self, TaskHandle},
+};
+
+/// Joules in a watt-hour.
+const JOULES_PER_WATT_HOUR: f64 = 3600.0;
+
+/// How much energy one subsystem has used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsystemEnergy {
+    pub name: String,
+    /// The electrical energy the motors drew, in watt-hours.
+    pub drawn: f64,
+    /// The mechanical energy the motors delivered at their output shafts, in watt-hours.
+    pub delivered: f64,
+    /// The most electrical power the motors drew at once, in watts.
+    pub peak_power: f64,
+}
+
+impl SubsystemEnergy {
+    /// The fraction of the energy drawn that was delivered, from 0.0 to 1.0, or `None` if nothing was drawn.
+    pub fn efficiency(&self) -> Option<f64> {
+        (self.drawn > 0.0).then(|| self.delivered / self.drawn)
+    }
+}
+
+/// How much energy was used since the monitor started or was last reset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyReport {
+    pub elapsed: Duration,
+    /// The energy drawn from the battery, in watt-hours.
+    pub battery: f64,
+    /// The lowest battery voltage seen, in volts.
+    pub lowest_voltage: f64,
+    /// The highest battery current seen, in amps.
+    pub peak_current: f64,
+    pub subsystems: Vec<SubsystemEnergy>,
+}
+
+impl EnergyReport {
+    /// The battery energy not drawn by any monitored motor, in watt-hours.
+    pub fn other(&self) -> f64 {
+        let motors: f64 = self
+            .subsystems
+            .iter()
+            .map(|subsystem| subsystem.drawn)
+            .sum();
+        (self.battery - motors).max(0.0)
+    }
+}
+
+impl fmt::Display for EnergyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "energy over {} s: battery {} Wh (lowest {} V, peak {} A)",
+            Fixed::new(self.elapsed.as_millis() as f64 / 1000.0, 1),
+            Fixed::new(self.battery, 2),
+            Fixed::new(self.lowest_voltage, 2),
+            Fixed::new(self.peak_current, 1),
+        )?;
+        for subsystem in &self.subsystems {
+            write!(
+                f,
+                "\n  {}: {} Wh drawn, {} Wh delivered",
+                subsystem.name,
+                Fixed::new(subsystem.drawn, 2),
+                Fixed::new(subsystem.delivered, 2),
+            )?;
+            if let Some(efficiency) = subsystem.efficiency() {
+                write!(f, " ({}% efficient)", Fixed::new(efficiency * 100.0, 0))?;
+            }
+            write!(f, ", peak {} W", Fixed::new(subsystem.peak_power, 1))?;
+        }
+        write!(f, "\n  other: {} Wh", Fixed::new(self.other(), 2))
+    }
+}
+
+struct Subsystem {
+    name: String,
+    motors: Vec<Motor>,
+    drawn: Integrator,
+    delivered: Integrator,
+    peak_power: f64,
+}
+
+impl Subsystem {
+    /// Returns the electrical power drawn and mechanical power delivered by the motors, in watts.
+    /// Motors that can't be read, such as unplugged ones, count as using nothing.
+    fn sample(&self) -> (f64, f64) {
+        let mut drawn = 0.0;
+        let mut delivered = 0.0;
+        for motor in &self.motors {
+            drawn += motor.power().unwrap_or(0.0);
+            if let (Ok(torque), Ok(rpm)) = (motor.torque(), motor.velocity()) {
+                // Power is torque times angular velocity in radians per second.
+                delivered += libm::fabs(torque * rpm * core::f64::consts::TAU / 60.0);
+            }
+        }
+        (drawn, delivered)
+    }
+}
+
+struct State {
+    started: u32,
+    battery: Integrator,
+    lowest_voltage: f64,
+    peak_current: f64,
+    subsystems: Vec<Subsystem>,
+}
+
+impl State {
+    fn update(&mut self, time: u32) {
+        let (millivolts, milliamps) = unsafe {
+            (
+                pros_sys::battery_get_voltage(),
+                pros_sys::battery_get_current(),
+            )
+        };
+        let volts = millivolts as f64 / 1000.0;
+        let amps = milliamps as f64 / 1000.0;
+        self.battery.update_at(time, volts * amps);
+        // The battery reads 0 V while it can't be read.
+        if volts > 0.0 {
+            self.lowest_voltage = self.lowest_voltage.min(volts);
+        }
+        self.peak_current = self.peak_current.max(amps);
+
+        for subsystem in self.subsystems.iter_mut() {
+            let (drawn, delivered) = subsystem.sample();
+            subsystem.drawn.update_at(time, drawn);
+            subsystem.delivered.update_at(time, delivered);
+            subsystem.peak_power = subsystem.peak_power.max(drawn);
+        }
+    }
+
+    fn reset(&mut self, time: u32) {
+        self.started = time;
+        self.battery.reset();
+        self.lowest_voltage = f64::INFINITY;
+        self.peak_current = 0.0;
+        for subsystem in self.subsystems.iter_mut() {
+            subsystem.drawn.reset();
+            subsystem.delivered.reset();
+            subsystem.peak_power = 0.0;
+        }
+    }
+
+    fn report(&self, time: u32) -> EnergyReport {
+        EnergyReport {
+            elapsed: Duration::from_millis(time.wrapping_sub(self.started) as u64),
+            battery: self.battery.value() / JOULES_PER_WATT_HOUR,
+            lowest_voltage: if self.lowest_voltage.is_finite() {
+                self.lowest_voltage
+            } else {
+                0.0
+            },
+            peak_current: self.peak_current,
+            subsystems: self
+                .subsystems
+                .iter()
+                .map(|subsystem| SubsystemEnergy {
+                    name: subsystem.name.clone(),
+                    drawn: subsystem.drawn.value() / JOULES_PER_WATT_HOUR,
+                    delivered: subsystem.delivered.value() / JOULES_PER_WATT_HOUR,
+                    peak_power: subsystem.peak_power,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Adds up the energy used by the battery and by groups of motors.
+pub struct EnergyMonitor {
+    period: Duration,
+    subsystems: Vec<Subsystem>,
+}
+
+impl EnergyMonitor {
+    /// Creates a monitor that samples every `period`. Power changes quickly, so 20 ms or faster works best.
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            subsystems: Vec::new(),
+        }
+    }
+
+    /// Adds a subsystem made of the given motors.
+    pub fn subsystem(mut self, name: &str, motors: impl IntoIterator<Item = Motor>) -> Self {
+        self.subsystems.push(Subsystem {
+            name: name.to_string(),
+            motors: motors.into_iter().collect(),
+            drawn: self.integrator(),
+            delivered: self.integrator(),
+            peak_power: 0.0,
+        });
+        self
+    }
+
+    /// Creates an integrator that keeps adding across a few late samples, however long the period is.
+    fn integrator(&self) -> Integrator {
+        Integrator::new().with_max_dt((self.period * 4).max(DEFAULT_MAX_DT))
+    }
+
+    /// Spawns a task that samples every period, starts the totals over when autonomous starts,
+    /// and logs a report whenever the robot is disabled.
+    pub fn spawn(self) -> EnergyHandle {
+        let now = unsafe { pros_sys::millis() };
+        let mut state = State {
+            started: now,
+            battery: self.integrator(),
+            lowest_voltage: f64::INFINITY,
+            peak_current: 0.0,
+            subsystems: self.subsystems,
+        };
+        state.reset(now);
+        let state = Arc::new(Mutex::new(state));
+
+        let period = self.period;
+        let task = task::spawn({
+            let state = state.clone();
+            move || {
+                let mut mode = competition::mode();
+                loop {
+                    let now = unsafe { pros_sys::millis() };
+                    let mut state = state.lock();
+                    let previous = core::mem::replace(&mut mode, competition::mode());
+                    if previous != mode {
+                        match mode {
+                            CompetitionMode::Autonomous => state.reset(now),
+                            CompetitionMode::Disabled => log::info!("{}", state.report(now)),
+                            CompetitionMode::Opcontrol => {}
+                        }
+                    }
+                    state.update(now);
+                    drop(state);
+                    task::sleep(period);
+                }
+            }
+        });
+        EnergyHandle { task, state }
+    }
+}
+
+/// Reads the totals of a running [`EnergyMonitor`].
+pub struct EnergyHandle {
+    task: TaskHandle,
+    state: Arc<Mutex<State>>,
+}
+
+impl EnergyHandle {
+    /// Returns how much energy was used since the monitor started or was last reset.
+    pub fn report(&self) -> EnergyReport {
+        self.state.lock().report(unsafe { pros_sys::millis() })
+    }
+
+    /// Starts every total over from zero.
+    pub fn reset(&self) {
+        self.state.lock().reset(unsafe { pros_sys::millis() });
+    }
+
+    /// Stops sampling. The totals so far are lost.
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,6 @@
+//! Collecting information about the robot for reviewing problems, during a match or after the fact.
+
+pub mod dashboard;
+pub mod energy;
+pub mod postmortem;
+pub mod telemetry;
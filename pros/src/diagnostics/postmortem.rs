@@ -0,0 +1,174 @@
+//! Writing a snapshot of the robot's state to the SD card at the end of each match.
+//!
+//! A bundle is a text file named `postmortem_<match>_<time>.txt`,
+//! where `<match>` counts bundles written since the program started and `<time>` is in milliseconds since then.
+//! It contains the battery's state, the number of running tasks, the temperature of every motor,
+//! recently reported errors (see [`crate::error::report`]), and recent log messages (see [`crate::logger`]).
+//!
+//! After [`write_on_panic`], a bundle is also written if the program panics, along with a BMP screenshot
+//! of the [`Framebuffer`] given to it, named the same as the bundle but ending in `.bmp`.
+
+use alloc::{format, string::String, sync::Arc};
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    competition::{self, CompetitionMode},
+    fixed::Fixed,
+    logger,
+    screen::framebuffer::Framebuffer,
+    sync::Mutex,
+    task::{self, TaskHandle},
+    usd::{self, UsdError},
+};
+
+/// How many bundles have been written since the program started.
+static BUNDLES_WRITTEN: AtomicU32 = AtomicU32::new(0);
+
+/// How often [`write_on_disable`] checks whether the robot was disabled.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Builds the contents of a bundle, including up to `log_lines` of the most recent log messages.
+pub fn collect(log_lines: usize) -> String {
+    let mut bundle = String::new();
+    // Writing to a string can't fail.
+    _ = write_bundle(&mut bundle, log_lines);
+    bundle
+}
+
+fn write_bundle(out: &mut String, log_lines: usize) -> core::fmt::Result {
+    writeln!(out, "time: {} ms", unsafe { pros_sys::millis() })?;
+
+    writeln!(out, "\n[battery]")?;
+    unsafe {
+        writeln!(out, "voltage: {} mV", pros_sys::battery_get_voltage())?;
+        writeln!(out, "current: {} mA", pros_sys::battery_get_current())?;
+        writeln!(
+            out,
+            "temperature: {} C",
+            Fixed::new(pros_sys::battery_get_temperature(), 1)
+        )?;
+        writeln!(
+            out,
+            "capacity: {}%",
+            Fixed::new(pros_sys::battery_get_capacity(), 1)
+        )?;
+    }
+
+    writeln!(out, "\n[tasks]")?;
+    writeln!(out, "running: {}", unsafe { pros_sys::task_get_count() })?;
+
+    writeln!(out, "\n[motors]")?;
+    for port in 1..=21 {
+        let temperature = unsafe { pros_sys::motor_get_temperature(port) };
+        if temperature == pros_sys::PROS_ERR_F {
+            // Nothing is plugged in or the device isn't a motor.
+            crate::error::take_errno();
+            continue;
+        }
+        let over_temp = unsafe { pros_sys::motor_is_over_temp(port) } == 1;
+        writeln!(
+            out,
+            "port {port}: {} C{}",
+            Fixed::new(temperature, 1),
+            if over_temp { " (over temperature)" } else { "" }
+        )?;
+    }
+
+    writeln!(out, "\n[errors]")?;
+    for (time, message) in crate::error::recent_reports() {
+        writeln!(out, "{time} ms: {message}")?;
+    }
+
+    writeln!(out, "\n[log]")?;
+    let lines = logger::recent();
+    for line in &lines[lines.len().saturating_sub(log_lines)..] {
+        writeln!(out, "{} ms {}: {}", line.time, line.level, line.message)?;
+    }
+    Ok(())
+}
+
+/// Writes a bundle to the SD card, returning the name of the file it was written to.
+pub fn write(log_lines: usize) -> Result<String, UsdError> {
+    write_with_screenshot(log_lines, None)
+}
+
+/// Writes a bundle to the SD card like [`write`], and a screenshot next to it if a framebuffer is given.
+pub fn write_with_screenshot(
+    log_lines: usize,
+    screenshot: Option<&Framebuffer>,
+) -> Result<String, UsdError> {
+    let bundle = collect(log_lines);
+    let number = BUNDLES_WRITTEN.fetch_add(1, Ordering::Relaxed) + 1;
+    let name = format!("postmortem_{number}_{}", unsafe { pros_sys::millis() });
+    if let Some(framebuffer) = screenshot {
+        usd::write(&format!("{name}.bmp"), &framebuffer.to_bmp())?;
+    }
+    let path = format!("{name}.txt");
+    usd::write(&path, bundle.as_bytes())?;
+    Ok(path)
+}
+
+struct PanicCapture {
+    log_lines: usize,
+    screenshot: Option<Arc<Mutex<Framebuffer>>>,
+}
+
+lazy_static::lazy_static! {
+    static ref PANIC_CAPTURE: Mutex<Option<PanicCapture>> = Mutex::new(None);
+}
+
+/// Writes a bundle if the program panics, including when a [`crate::Robot`] method returns an error.
+///
+/// If `screenshot` is given, whatever was last drawn into it is saved as well,
+/// so the diagnostics that were on screen aren't lost.
+///
+/// ```rust
+/// let screen = Arc::new(Mutex::new(Framebuffer::full_screen()));
+/// postmortem::write_on_panic(50, Some(screen.clone()));
+/// ```
+pub fn write_on_panic(log_lines: usize, screenshot: Option<Arc<Mutex<Framebuffer>>>) {
+    *PANIC_CAPTURE.lock() = Some(PanicCapture {
+        log_lines,
+        screenshot,
+    });
+}
+
+/// Called by the panic handler.
+#[doc(hidden)]
+pub fn __write_panic_bundle() {
+    // The panicking task may be holding either lock, and waiting on it would never finish.
+    let Some(capture) = PANIC_CAPTURE.try_lock() else {
+        return;
+    };
+    let Some(capture) = capture.as_ref() else {
+        return;
+    };
+    let framebuffer = capture
+        .screenshot
+        .as_ref()
+        .and_then(|framebuffer| framebuffer.try_lock());
+    _ = write_with_screenshot(capture.log_lines, framebuffer.as_deref());
+}
+
+/// Starts a background task that writes a bundle every time the robot goes from enabled to disabled,
+/// such as at the end of each match.
+/// Errors writing bundles are passed to [`crate::error::report`].
+pub fn write_on_disable(log_lines: usize) -> TaskHandle {
+    task::spawn(move || {
+        let mut was_enabled = competition::mode() != CompetitionMode::Disabled;
+        loop {
+            let enabled = competition::mode() != CompetitionMode::Disabled;
+            if was_enabled && !enabled {
+                if let Err(err) = write(log_lines) {
+                    crate::error::report(&err);
+                }
+            }
+            was_enabled = enabled;
+            task::sleep(POLL_INTERVAL);
+        }
+    })
+}
@@ -0,0 +1,358 @@
+//! The binary telemetry log format.
+//!
+//! A log starts with the magic bytes `PTLM`, a format version byte, and a schema:
+//! the recording period in milliseconds, then each channel's name and resolution.
+//! Every row after that is the time since the previous row in milliseconds,
+//! a bit mask of which channels were recorded, and for each recorded channel
+//! the change in its value since it was last recorded, in multiples of its resolution.
+//! Integers are LEB128 varints, and signed ones are zigzag encoded first.
+//!
+//! Most values change by small amounts between rows, so this takes a small fraction of the space of CSV.
+//! A log cut off by power loss can be read up to the last complete row.
+//!
+//! This module only depends on `alloc`, `libm`, `snafu`, and [`varint`](crate::encode::varint)
+//! so that the host converter in `tools/log-convert` can share it.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use snafu::Snafu;
+
+use crate::encode::varint::{read_varint, take, unzigzag, write_varint, zigzag, ReadError};
+
+/// The bytes every log starts with.
+pub const MAGIC: &[u8; 4] = b"PTLM";
+/// The version of the format written by this module.
+pub const VERSION: u8 = 1;
+/// The most channels a log can have, since a row's mask of recorded channels is 64 bits.
+pub const MAX_CHANNELS: usize = 64;
+
+/// A channel's name and how precisely its values are recorded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelSchema {
+    pub name: String,
+    /// Values are rounded to a multiple of this.
+    pub resolution: f64,
+}
+
+/// What a log contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    /// The period rows were recorded at, in milliseconds. Rows may be further apart if the recorder backed off.
+    pub period_ms: u32,
+    pub channels: Vec<ChannelSchema>,
+}
+
+impl Schema {
+    /// Appends the magic bytes, version, and schema to `out`.
+    pub fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        write_varint(out, self.period_ms as u64);
+        write_varint(out, self.channels.len() as u64);
+        for channel in &self.channels {
+            write_varint(out, channel.name.len() as u64);
+            out.extend_from_slice(channel.name.as_bytes());
+            out.extend_from_slice(&channel.resolution.to_le_bytes());
+        }
+    }
+
+    /// Reads the header at the start of a log, returning the schema and the rest of the log.
+    pub fn read(log: &[u8]) -> Result<(Self, &[u8]), FormatError> {
+        let rest = log.strip_prefix(MAGIC).ok_or(FormatError::NotALog)?;
+        let (&version, mut rest) = rest.split_first().ok_or(FormatError::Truncated)?;
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+
+        let period_ms = read_varint(&mut rest)? as u32;
+        let count = read_varint(&mut rest)? as usize;
+        if count > MAX_CHANNELS {
+            return Err(FormatError::Corrupted);
+        }
+        let mut channels = Vec::with_capacity(count);
+        for _ in 0..count {
+            let len = read_varint(&mut rest)? as usize;
+            let name = take(&mut rest, len)?;
+            let name = String::from_utf8(name.to_vec()).map_err(|_| FormatError::Corrupted)?;
+            let resolution = f64::from_le_bytes(take(&mut rest, 8)?.try_into().unwrap());
+            channels.push(ChannelSchema { name, resolution });
+        }
+        Ok((
+            Self {
+                period_ms,
+                channels,
+            },
+            rest,
+        ))
+    }
+}
+
+/// Encodes rows as changes from the previous row.
+#[derive(Debug, Clone)]
+pub struct RowEncoder {
+    resolutions: Vec<f64>,
+    last_time: u32,
+    last: Vec<i64>,
+}
+
+impl RowEncoder {
+    pub fn new(schema: &Schema) -> Self {
+        Self {
+            resolutions: schema.channels.iter().map(|c| c.resolution).collect(),
+            last_time: 0,
+            last: alloc::vec![0; schema.channels.len()],
+        }
+    }
+
+    /// Appends a row to `out`. Channels that weren't recorded are `None`.
+    /// Rows must be written in increasing order of time.
+    pub fn write(&mut self, out: &mut Vec<u8>, time_ms: u32, values: &[Option<f64>]) {
+        write_varint(out, time_ms.wrapping_sub(self.last_time) as u64);
+        self.last_time = time_ms;
+
+        let mask = values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_some_and(|value| !value.is_nan()))
+            .fold(0u64, |mask, (index, _)| mask | 1 << index);
+        write_varint(out, mask);
+
+        for (index, value) in values.iter().enumerate() {
+            if mask & 1 << index == 0 {
+                continue;
+            }
+            let quantized = libm::round(value.unwrap() / self.resolutions[index]) as i64;
+            write_varint(out, zigzag(quantized.wrapping_sub(self.last[index])));
+            self.last[index] = quantized;
+        }
+    }
+}
+
+/// A decoded row of a log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub time_ms: u32,
+    /// The value of each channel, or `None` if it wasn't recorded in this row.
+    pub values: Vec<Option<f64>>,
+}
+
+/// Reads the rows of a log in order.
+#[derive(Debug, Clone)]
+pub struct LogReader<'a> {
+    schema: Schema,
+    rest: &'a [u8],
+    time: u32,
+    last: Vec<i64>,
+}
+
+impl<'a> LogReader<'a> {
+    pub fn new(log: &'a [u8]) -> Result<Self, FormatError> {
+        let (schema, rest) = Schema::read(log)?;
+        let last = alloc::vec![0; schema.channels.len()];
+        Ok(Self {
+            schema,
+            rest,
+            time: 0,
+            last,
+        })
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn read_row(&mut self) -> Result<Row, FormatError> {
+        let mut rest = self.rest;
+        let time = self.time.wrapping_add(read_varint(&mut rest)? as u32);
+        let mask = read_varint(&mut rest)?;
+
+        let mut last = self.last.clone();
+        let mut values = Vec::with_capacity(last.len());
+        for (index, last) in last.iter_mut().enumerate() {
+            if mask & 1 << index == 0 {
+                values.push(None);
+                continue;
+            }
+            *last = last.wrapping_add(unzigzag(read_varint(&mut rest)?));
+            values.push(Some(*last as f64 * self.schema.channels[index].resolution));
+        }
+
+        self.rest = rest;
+        self.time = time;
+        self.last = last;
+        Ok(Row {
+            time_ms: time,
+            values,
+        })
+    }
+}
+
+impl Iterator for LogReader<'_> {
+    type Item = Row;
+
+    /// Returns the next row, or `None` at the end of the log or a row that was cut off.
+    fn next(&mut self) -> Option<Row> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        self.read_row().ok()
+    }
+}
+
+/// Converts a log to CSV in the format read by [`Replay`](crate::testing::replay::Replay).
+/// Channels that weren't recorded in a row are written as `NaN`.
+pub fn to_csv(log: &[u8]) -> Result<String, FormatError> {
+    let reader = LogReader::new(log)?;
+    let mut csv = String::from("time_ms");
+    for channel in &reader.schema().channels {
+        csv.push(',');
+        csv.push_str(&channel.name);
+    }
+    csv.push('\n');
+
+    for row in reader {
+        // Writing to a string can't fail.
+        _ = write!(csv, "{}", row.time_ms);
+        for value in row.values {
+            match value {
+                Some(value) => _ = write!(csv, ",{value}"),
+                None => csv.push_str(",NaN"),
+            }
+        }
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+#[derive(Debug, Snafu)]
+pub enum FormatError {
+    #[snafu(display("The file is not a telemetry log."))]
+    NotALog,
+    #[snafu(display(
+        "The log was written in version {version} of the format, which isn't supported."
+    ))]
+    UnsupportedVersion { version: u8 },
+    #[snafu(display("The log ended partway through its header."))]
+    Truncated,
+    #[snafu(display("The log's header is corrupted."))]
+    Corrupted,
+}
+impl core::error::Error for FormatError {}
+
+impl From<ReadError> for FormatError {
+    fn from(err: ReadError) -> Self {
+        match err {
+            ReadError::UnexpectedEnd => Self::Truncated,
+            ReadError::Overlong => Self::Corrupted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use super::*;
+
+    fn schema(resolutions: &[f64]) -> Schema {
+        Schema {
+            period_ms: 10,
+            channels: resolutions
+                .iter()
+                .enumerate()
+                .map(|(index, &resolution)| ChannelSchema {
+                    name: format!("c{index}"),
+                    resolution,
+                })
+                .collect(),
+        }
+    }
+
+    fn log(schema: &Schema, rows: &[(u32, Vec<Option<f64>>)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        schema.write(&mut out);
+        let mut encoder = RowEncoder::new(schema);
+        for (time_ms, values) in rows {
+            encoder.write(&mut out, *time_ms, values);
+        }
+        out
+    }
+
+    #[test]
+    fn round_trips_missing_and_nan_channels() {
+        let schema = schema(&[0.25, 1.0, 0.5]);
+        let mut log = log(
+            &schema,
+            &[
+                (0, vec![Some(1.1), None, Some(f64::NAN)]),
+                (10, vec![Some(1.25), Some(-3.0), Some(2.0)]),
+                (25, vec![None, Some(-3.0), None]),
+            ],
+        );
+        // A row cut off by power loss is skipped.
+        log.push(0x80);
+
+        let reader = LogReader::new(&log).unwrap();
+        assert_eq!(reader.schema(), &schema);
+        let rows: Vec<Row> = reader.collect();
+        assert_eq!(
+            rows,
+            [
+                Row {
+                    time_ms: 0,
+                    values: vec![Some(1.0), None, None],
+                },
+                Row {
+                    time_ms: 10,
+                    values: vec![Some(1.25), Some(-3.0), Some(2.0)],
+                },
+                Row {
+                    time_ms: 25,
+                    values: vec![None, Some(-3.0), None],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn records_all_64_channels() {
+        let schema = schema(&[1.0; MAX_CHANNELS]);
+        let all: Vec<Option<f64>> = (0..MAX_CHANNELS).map(|index| Some(index as f64)).collect();
+        let mut last_only = vec![None; MAX_CHANNELS];
+        last_only[MAX_CHANNELS - 1] = Some(-1.0);
+        let log = log(&schema, &[(0, all.clone()), (10, last_only.clone())]);
+
+        let values: Vec<Vec<Option<f64>>> = LogReader::new(&log)
+            .unwrap()
+            .map(|row| row.values)
+            .collect();
+        assert_eq!(values, [all, last_only]);
+    }
+
+    #[test]
+    fn converts_to_csv() {
+        let schema = schema(&[0.5, 1.0]);
+        let log = log(
+            &schema,
+            &[(0, vec![Some(1.0), None]), (10, vec![Some(1.5), Some(2.0)])],
+        );
+        assert_eq!(
+            to_csv(&log).unwrap(),
+            "time_ms,c0,c1\n0,1,NaN\n10,1.5,2\n".to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(matches!(LogReader::new(b"nope"), Err(FormatError::NotALog)));
+        assert!(matches!(
+            LogReader::new(b"PTLM\x02"),
+            Err(FormatError::UnsupportedVersion { version: 2 })
+        ));
+        assert!(matches!(
+            LogReader::new(b"PTLM\x01\x0A"),
+            Err(FormatError::Truncated)
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+//! Recording sensor and controller values to the SD card without starving control loops.
+//!
+//! A [`TelemetryRecorder`] samples its channels every period and appends them to a compact binary log
+//! (see [`format`]), which [`Replay::from_log`](crate::testing::replay::Replay::from_log) reads directly,
+//! and which can be converted to CSV or MCAP with the `log-convert` tool in the repository's `tools` directory.
+//! It times itself every cycle, and when it runs over its budget or the CPU is reported to be busy
+//! it first records less often and then stops recording its lowest priority channels.
+//! It recovers gradually once there is time to spare again.
+//! Channels that are not being recorded are left out of the row.
+//!
+//! A struct's numeric fields can all be recorded at once by deriving [`Telemetry`] for it
+//! and adding it with [`TelemetryRecorder::channels_of`].
+//!
+//! With the `compression` feature, logs can be made smaller still by recording to a
+//! [`BufferedFile::compressed`](crate::usd::BufferedFile::compressed) with [`TelemetryRecorder::with_file`].
+
+pub mod format;
+
+use alloc::{boxed::Box, format, string::String, sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use self::format::{ChannelSchema, RowEncoder, Schema, MAX_CHANNELS};
+use crate::{
+    sync::Mutex,
+    usd::{BufferedFile, Flush, UsdError},
+};
+
+/// Derives [`Telemetry`] for a struct with named fields, making each field a channel.
+///
+/// Fields can have a `#[telemetry(resolution = ..., priority = ...)]` attribute to set how their channel is recorded,
+/// or `#[telemetry(skip)]` to leave them out. Every other field must implement [`TelemetryValue`].
+pub use pros_macros::Telemetry;
+
+/// A value that can be recorded in a telemetry channel.
+pub trait TelemetryValue {
+    fn to_telemetry(&self) -> f64;
+}
+
+macro_rules! impl_telemetry_value {
+    ($($ty:ty),*) => {
+        $(
+            impl TelemetryValue for $ty {
+                fn to_telemetry(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+impl_telemetry_value!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize, f32, f64);
+
+impl TelemetryValue for bool {
+    fn to_telemetry(&self) -> f64 {
+        *self as u8 as f64
+    }
+}
+
+/// `None` is left out of the row.
+impl<T: TelemetryValue> TelemetryValue for Option<T> {
+    fn to_telemetry(&self) -> f64 {
+        self.as_ref().map_or(f64::NAN, T::to_telemetry)
+    }
+}
+
+/// How one field of a [`Telemetry`] struct is recorded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TelemetryField {
+    pub name: &'static str,
+    pub resolution: f64,
+    pub priority: u8,
+}
+
+/// A struct whose fields can be recorded as telemetry channels. This should usually be derived.
+pub trait Telemetry {
+    /// Every recorded field, in order.
+    const FIELDS: &'static [TelemetryField];
+
+    /// Returns the current value of the field at `index` in [`Telemetry::FIELDS`].
+    fn field_value(&self, index: usize) -> f64;
+}
+
+/// Limits on how much time telemetry may use.
+#[derive(Debug, Clone, Copy)]
+pub struct TelemetryBudget {
+    /// The most time one cycle of sampling and writing may take.
+    pub max_cycle_time: Duration,
+    /// CPU load, from 0.0 to 1.0, above which telemetry backs off.
+    pub high_load: f32,
+    /// CPU load below which telemetry may speed back up.
+    pub low_load: f32,
+    /// The most the sample rate will be divided by before channels are dropped.
+    pub max_decimation: u32,
+    /// How many cycles in a row must be under budget before recording more.
+    pub recovery_cycles: u32,
+}
+
+impl Default for TelemetryBudget {
+    fn default() -> Self {
+        Self {
+            max_cycle_time: Duration::from_micros(500),
+            high_load: 0.8,
+            low_load: 0.6,
+            max_decimation: 8,
+            recovery_cycles: 50,
+        }
+    }
+}
+
+/// Counters describing how the recorder has adapted, for diagnostics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TelemetryStats {
+    /// Rows written to the file.
+    pub rows: u32,
+    /// Cycles that took longer than [`TelemetryBudget::max_cycle_time`].
+    pub overruns: u32,
+    /// How many periods pass between rows.
+    pub decimation: u32,
+    /// How many channels are not being recorded.
+    pub dropped_channels: u32,
+    /// How long the last cycle took.
+    pub last_cycle_time: Duration,
+}
+
+struct Channel {
+    name: String,
+    resolution: f64,
+    priority: u8,
+    sample: Box<dyn FnMut() -> f64 + Send>,
+}
+
+/// Samples named channels periodically and appends them to a binary log on the SD card (see [`format`]).
+pub struct TelemetryRecorder {
+    file: BufferedFile,
+    channels: Vec<Channel>,
+    period: Duration,
+    budget: TelemetryBudget,
+    load: f32,
+    min_priority: u8,
+    encoder: Option<RowEncoder>,
+    values: Vec<Option<f64>>,
+    last_row: Option<u32>,
+    calm_cycles: u32,
+    buf: Vec<u8>,
+    stats: TelemetryStats,
+}
+
+impl TelemetryRecorder {
+    /// Creates a recorder that writes a row to `path` every `period`, replacing any existing file.
+    pub fn new(path: &str, period: Duration) -> Result<Self, UsdError> {
+        Ok(Self::with_file(BufferedFile::create(path)?, period))
+    }
+
+    /// Creates a recorder that writes to an already open file, to control how it is buffered.
+    ///
+    /// Rows are only written to the card when the buffer fills or its flush interval passes,
+    /// so pass the recorder to [`usd::flush_on_disable`](crate::usd::flush_on_disable)
+    /// to save everything at the end of each match.
+    pub fn with_file(file: BufferedFile, period: Duration) -> Self {
+        Self {
+            file,
+            channels: Vec::new(),
+            period,
+            budget: TelemetryBudget::default(),
+            load: 0.0,
+            min_priority: 0,
+            encoder: None,
+            values: Vec::new(),
+            last_row: None,
+            calm_cycles: 0,
+            buf: Vec::new(),
+            stats: TelemetryStats {
+                decimation: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_budget(mut self, budget: TelemetryBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// The resolution of channels added with [`TelemetryRecorder::channel`].
+    pub const DEFAULT_RESOLUTION: f64 = 0.001;
+
+    /// Adds a channel. Channels with a lower priority are dropped first when telemetry has to back off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row has already been written, since the channels can't change after that,
+    /// or if there are already [`MAX_CHANNELS`] channels.
+    pub fn channel(
+        self,
+        name: impl Into<String>,
+        priority: u8,
+        sample: impl FnMut() -> f64 + Send + 'static,
+    ) -> Self {
+        self.channel_with_resolution(name, priority, Self::DEFAULT_RESOLUTION, sample)
+    }
+
+    /// Adds a channel whose values are rounded to a multiple of `resolution`.
+    /// Coarser resolutions take less space.
+    pub fn channel_with_resolution(
+        mut self,
+        name: impl Into<String>,
+        priority: u8,
+        resolution: f64,
+        sample: impl FnMut() -> f64 + Send + 'static,
+    ) -> Self {
+        assert!(
+            self.encoder.is_none(),
+            "Telemetry channels must be added before recording starts"
+        );
+        assert!(
+            self.channels.len() < MAX_CHANNELS,
+            "Telemetry logs can have at most {MAX_CHANNELS} channels"
+        );
+        self.channels.push(Channel {
+            name: name.into(),
+            resolution,
+            priority,
+            sample: Box::new(sample),
+        });
+        self
+    }
+
+    /// Adds a channel named `prefix.field` for every field of a [`Telemetry`] struct.
+    /// Each sample briefly locks `source`.
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same reasons as [`TelemetryRecorder::channel`].
+    pub fn channels_of<T: Telemetry + Send + 'static>(
+        mut self,
+        prefix: &str,
+        source: Arc<Mutex<T>>,
+    ) -> Self {
+        for (index, field) in T::FIELDS.iter().enumerate() {
+            let source = source.clone();
+            self = self.channel_with_resolution(
+                format!("{prefix}.{}", field.name),
+                field.priority,
+                field.resolution,
+                move || source.lock().field_value(index),
+            );
+        }
+        self
+    }
+
+    /// Tells the recorder how busy the CPU is, from 0.0 (idle) to 1.0 (fully loaded),
+    /// for example from a measurement of how much time the idle task gets.
+    pub fn report_load(&mut self, load: f32) {
+        self.load = load.clamp(0.0, 1.0);
+    }
+
+    pub fn stats(&self) -> TelemetryStats {
+        self.stats
+    }
+
+    /// Records a row if one is due. This should be called at least once every period.
+    pub fn update(&mut self) -> Result<(), UsdError> {
+        let now = unsafe { pros_sys::millis() };
+        let interval = self.period.as_millis() as u32 * self.stats.decimation;
+        if self
+            .last_row
+            .is_some_and(|last| now.wrapping_sub(last) < interval)
+        {
+            return Ok(());
+        }
+        self.last_row = Some(now);
+
+        let start = unsafe { pros_sys::micros() };
+        self.buf.clear();
+        let encoder = match &mut self.encoder {
+            Some(encoder) => encoder,
+            None => {
+                let schema = Schema {
+                    period_ms: self.period.as_millis() as u32,
+                    channels: self
+                        .channels
+                        .iter()
+                        .map(|channel| ChannelSchema {
+                            name: channel.name.clone(),
+                            resolution: channel.resolution,
+                        })
+                        .collect(),
+                };
+                schema.write(&mut self.buf);
+                self.encoder.insert(RowEncoder::new(&schema))
+            }
+        };
+
+        self.values.clear();
+        for channel in self.channels.iter_mut() {
+            self.values
+                .push((channel.priority >= self.min_priority).then(|| (channel.sample)()));
+        }
+        encoder.write(&mut self.buf, now, &self.values);
+        self.file.write_all(&self.buf)?;
+        self.stats.rows += 1;
+
+        let elapsed = Duration::from_micros(unsafe { pros_sys::micros() } - start);
+        self.stats.last_cycle_time = elapsed;
+        self.adapt(elapsed);
+        Ok(())
+    }
+
+    /// Backs off when over budget, and recovers one step at a time after enough calm cycles.
+    fn adapt(&mut self, elapsed: Duration) {
+        let over_budget = elapsed > self.budget.max_cycle_time;
+        if over_budget {
+            self.stats.overruns += 1;
+        }
+
+        if over_budget || self.load > self.budget.high_load {
+            self.calm_cycles = 0;
+            if self.stats.decimation < self.budget.max_decimation {
+                self.stats.decimation = (self.stats.decimation * 2).min(self.budget.max_decimation);
+            } else {
+                self.drop_lowest_priority();
+            }
+            return;
+        }
+
+        if elapsed * 2 > self.budget.max_cycle_time || self.load > self.budget.low_load {
+            return;
+        }
+        self.calm_cycles += 1;
+        if self.calm_cycles < self.budget.recovery_cycles {
+            return;
+        }
+        self.calm_cycles = 0;
+        if self.stats.dropped_channels > 0 {
+            self.restore_highest_dropped();
+        } else if self.stats.decimation > 1 {
+            self.stats.decimation /= 2;
+        }
+    }
+
+    fn drop_lowest_priority(&mut self) {
+        // Always keep the highest priority channels.
+        let Some(next) = self
+            .channels
+            .iter()
+            .map(|channel| channel.priority)
+            .filter(|&priority| priority > self.min_priority)
+            .min()
+        else {
+            return;
+        };
+        self.min_priority = next;
+        self.count_dropped();
+    }
+
+    fn restore_highest_dropped(&mut self) {
+        self.min_priority = self
+            .channels
+            .iter()
+            .map(|channel| channel.priority)
+            .filter(|&priority| priority < self.min_priority)
+            .max()
+            .unwrap_or(0);
+        self.count_dropped();
+    }
+
+    fn count_dropped(&mut self) {
+        self.stats.dropped_channels = self
+            .channels
+            .iter()
+            .filter(|channel| channel.priority < self.min_priority)
+            .count() as u32;
+    }
+
+    /// Writes any buffered rows to the SD card.
+    pub fn flush(&mut self) -> Result<(), UsdError> {
+        self.file.flush()
+    }
+}
+
+impl Flush for TelemetryRecorder {
+    fn flush(&mut self) -> Result<(), UsdError> {
+        TelemetryRecorder::flush(self)
+    }
+}
@@ -0,0 +1,63 @@
+//! Driver assists for teleop driving.
+
+use super::{Drivetrain, DrivetrainError};
+use crate::sensors::imu::Gyro;
+
+/// Arcade drive that uses the IMU to keep the robot driving straight
+/// whenever the driver isn't turning.
+///
+/// While the turn input is outside the deadband, the driver has full control.
+/// Once it is released, the heading is held with a proportional correction
+/// until the driver turns again or stops driving.
+pub struct HeadingAssist {
+    /// Turn output per degree of heading error.
+    pub gain: f32,
+    /// Turn inputs smaller than this count as not turning.
+    pub turn_deadband: f32,
+    /// Forward inputs smaller than this count as not driving, which also turns the assist off.
+    pub forward_deadband: f32,
+    /// The IMU rotation to hold, if the assist is active.
+    target: Option<f64>,
+}
+
+impl HeadingAssist {
+    pub fn new(gain: f32) -> Self {
+        Self {
+            gain,
+            turn_deadband: 0.05,
+            forward_deadband: 0.05,
+            target: None,
+        }
+    }
+
+    /// Returns true if the assist is currently correcting the heading.
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Drives with a forward and a turning input, each from -1.0 to 1.0,
+    /// adding a heading correction when the driver isn't turning.
+    pub fn arcade(
+        &mut self,
+        drivetrain: &Drivetrain,
+        imu: &impl Gyro,
+        forward: f32,
+        turn: f32,
+    ) -> Result<(), DrivetrainError> {
+        let driving_straight =
+            libm::fabsf(turn) < self.turn_deadband && libm::fabsf(forward) >= self.forward_deadband;
+
+        let turn = if driving_straight {
+            let rotation = imu.rotation()?;
+            let target = *self.target.get_or_insert(rotation);
+            // The IMU measures clockwise, and positive turns are counterclockwise.
+            (self.gain * (rotation - target) as f32).clamp(-1.0, 1.0)
+        } else {
+            self.target = None;
+            turn
+        };
+
+        drivetrain.arcade(forward, turn)?;
+        Ok(())
+    }
+}
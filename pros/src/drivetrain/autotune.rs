@@ -0,0 +1,63 @@
+//! Suggesting heading PID gains by oscillating the robot in place.
+//!
+//! This runs [`control::autotune`](crate::control::autotune) on the drivetrain's turn axis,
+//! measured by an IMU in degrees and driven by turning in place.
+//!
+//! ```rust
+//! let gains = autotune::tune_heading(&drivetrain, &imu, Some("heading.cfg"))?;
+//! let mut pid = gains.controller();
+//! ```
+
+use super::Drivetrain;
+use crate::{
+    config::Config,
+    control::autotune::{AutotuneReport, PidGains, RelayAutotune},
+    fixed::Fixed,
+    sensors::imu::InertialSensor,
+};
+
+/// Oscillates the robot around its current heading with `relay`, where the output is the voltage
+/// each side turns clockwise at, and returns the suggested gains in volts per degree.
+///
+/// The robot turns back and forth by a few degrees, so it needs room to turn. The drivetrain is braked afterwards.
+pub fn run(
+    relay: &RelayAutotune,
+    drivetrain: &Drivetrain,
+    imu: &InertialSensor,
+) -> crate::Result<AutotuneReport> {
+    // Turning clockwise increases the IMU's rotation.
+    let result = relay.run(
+        || Ok(imu.rotation()?),
+        |volts| Ok(drivetrain.set_voltage(volts as f32, -volts as f32)?),
+    );
+    drivetrain.brake()?;
+    result
+}
+
+/// Runs a [`RelayAutotune`] at 4 volts and no more than 45 degrees from the starting heading,
+/// logs the results, and saves the gains to `path` if one is given.
+pub fn tune_heading(
+    drivetrain: &Drivetrain,
+    imu: &InertialSensor,
+    path: Option<&str>,
+) -> crate::Result<PidGains> {
+    let relay = RelayAutotune {
+        max_excursion: 45.0,
+        ..Default::default()
+    };
+    let report = run(&relay, drivetrain, imu)?;
+    let gains = report.gains;
+    log::info!(
+        "heading autotune: Ku = {} V/deg, Tu = {} s, amplitude = {} deg, kP = {}, kI = {}, kD = {}",
+        Fixed::new(report.ultimate_gain, 4),
+        Fixed::new(report.ultimate_period, 3),
+        Fixed::new(report.amplitude, 2),
+        Fixed::new(gains.kp, 4),
+        Fixed::new(gains.ki, 4),
+        Fixed::new(gains.kd, 4),
+    );
+    if let Some(path) = path {
+        gains.save(path)?;
+    }
+    Ok(gains)
+}
@@ -0,0 +1,274 @@
+//! Measuring a drivetrain's effective track width, wheel diameter, and friction.
+//!
+//! Wheels sink into foam tiles and scrub while turning,
+//! so the numbers that make odometry accurate rarely match a tape measure.
+//! These routines measure them from how the robot actually moves.
+
+use alloc::vec::Vec;
+use core::{f64::consts::PI, time::Duration};
+
+use super::{DriveFeedforward, Drivetrain, DrivetrainConfig, DrivetrainError};
+use crate::{
+    config::Config,
+    controller::Controller,
+    fixed::Fixed,
+    motor::{BrakeMode, MotorError},
+    sensors::imu::InertialSensor,
+    task,
+};
+
+/// Spins the robot in place at `voltage` for `turns` full turns, measured by the IMU,
+/// and returns the track width that explains how far the wheels moved.
+///
+/// The robot should have room to spin. The drivetrain is braked afterwards.
+/// If the robot takes more than 5 seconds per turn, such as when it is stuck against a wall,
+/// it stops and returns [`DrivetrainError::NoMovement`].
+pub fn measure_track_width(
+    drivetrain: &Drivetrain,
+    imu: &InertialSensor,
+    turns: f64,
+    voltage: f32,
+) -> Result<f64, DrivetrainError> {
+    const TIMEOUT_PER_TURN: f64 = 5000.0;
+
+    let target = turns * 360.0;
+    let timeout = (turns.max(1.0) * TIMEOUT_PER_TURN) as u32;
+    drivetrain.zero()?;
+    let start = imu.rotation()?;
+    let started = unsafe { pros_sys::millis() };
+
+    drivetrain.set_voltage(-voltage, voltage)?;
+    while libm::fabs(imu.rotation()? - start) < target {
+        if unsafe { pros_sys::millis() } - started > timeout {
+            drivetrain.brake()?;
+            return Err(DrivetrainError::NoMovement);
+        }
+        task::sleep(Duration::from_millis(10));
+    }
+    drivetrain.brake()?;
+    // Let the robot settle so the final readings include any coasting.
+    task::sleep(Duration::from_millis(500));
+
+    let radians = libm::fabs(imu.rotation()? - start).to_radians();
+    let wheel_travel =
+        libm::fabs(drivetrain.left_distance()?) + libm::fabs(drivetrain.right_distance()?);
+    if radians == 0.0 || wheel_travel == 0.0 {
+        return Err(DrivetrainError::NoMovement);
+    }
+    // Each side travels along a circle whose radius is half the track width.
+    Ok(wheel_travel / radians)
+}
+
+/// Measures the wheel diameter while the robot is driven or pushed exactly `distance` in a straight line.
+///
+/// `done` is polled periodically and should return true once the robot has covered the distance,
+/// for example when a controller button is pressed.
+pub fn measure_wheel_diameter(
+    drivetrain: &Drivetrain,
+    distance: f64,
+    mut done: impl FnMut() -> bool,
+) -> Result<f64, DrivetrainError> {
+    let config = drivetrain.config();
+    drivetrain.zero()?;
+
+    while !done() {
+        task::sleep(Duration::from_millis(10));
+    }
+
+    let rotations = (libm::fabs(drivetrain.left().position()?.into_rotations())
+        + libm::fabs(drivetrain.right().position()?.into_rotations()))
+        / 2.0;
+    if rotations == 0.0 {
+        return Err(DrivetrainError::NoMovement);
+    }
+    Ok(distance / (rotations * config.gear_ratio * PI))
+}
+
+/// Runs both measurements using the master controller, then saves the results to `path`.
+///
+/// First the robot spins `turns` times to measure the track width.
+/// Then the driver drives it `distance` in a straight line with the left joystick and presses A.
+/// Returns the new config, which is also applied to `drivetrain`.
+pub fn calibrate(
+    drivetrain: &mut Drivetrain,
+    imu: &InertialSensor,
+    turns: f64,
+    distance: f64,
+    path: &str,
+) -> Result<DrivetrainConfig, DrivetrainError> {
+    let track_width = measure_track_width(drivetrain, imu, turns, 4.0)?;
+
+    let wheel_diameter = measure_wheel_diameter(drivetrain, distance, || {
+        let state = Controller::Master.state();
+        let _ = drivetrain.arcade(state.joysticks.left.y, 0.0);
+        state.buttons.a
+    })?;
+    drivetrain.brake()?;
+
+    let config = DrivetrainConfig {
+        track_width,
+        wheel_diameter,
+        ..*drivetrain.config()
+    };
+    config.save(path)?;
+    drivetrain.set_config(config);
+    Ok(config)
+}
+
+/// What [`measure_friction`] measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoastDown {
+    pub feedforward: DriveFeedforward,
+    /// How quickly friction slows the robot near a stop, in units per second squared.
+    pub deceleration: f64,
+    /// The fastest the robot went, in units per second.
+    pub top_speed: f64,
+}
+
+/// Drives straight at `voltage` for `duration`, then lets the robot coast to a stop,
+/// and fits a feedforward model to how it sped up and slowed down.
+///
+/// Friction is what slows the robot while coasting,
+/// so comparing the two phases separates the voltage lost to friction (kS) from the voltage
+/// that accelerates the robot (kA) and the voltage that holds its speed (kV).
+/// The robot needs room to drive forward, and its motors are left in [`BrakeMode::None`].
+pub fn measure_friction(
+    drivetrain: &Drivetrain,
+    voltage: f32,
+    duration: Duration,
+) -> Result<CoastDown, DrivetrainError> {
+    const SAMPLE_PERIOD: Duration = Duration::from_millis(10);
+    const COAST_TIMEOUT: u32 = 3000;
+
+    drivetrain.zero()?;
+    let start = unsafe { pros_sys::millis() };
+    let distance = || -> Result<f64, MotorError> {
+        Ok((drivetrain.left_distance()? + drivetrain.right_distance()?) / 2.0)
+    };
+
+    let mut powered = Vec::new();
+    while unsafe { pros_sys::millis() } - start < duration.as_millis() as u32 {
+        drivetrain.set_voltage(voltage, voltage)?;
+        powered.push((unsafe { pros_sys::millis() } - start, distance()?));
+        task::sleep(SAMPLE_PERIOD);
+    }
+
+    for motor in drivetrain
+        .left()
+        .motors()
+        .iter()
+        .chain(drivetrain.right().motors())
+    {
+        motor.set_brake_mode(BrakeMode::None)?;
+    }
+    drivetrain.brake()?;
+    let coast_start = unsafe { pros_sys::millis() };
+    let mut coasting = Vec::new();
+    loop {
+        let now = unsafe { pros_sys::millis() };
+        coasting.push((now - start, distance()?));
+        let stopped = coasting.len() > 10
+            && libm::fabs(coasting[coasting.len() - 1].1 - coasting[coasting.len() - 11].1) < 1e-3;
+        if stopped || now - coast_start > COAST_TIMEOUT {
+            break;
+        }
+        task::sleep(SAMPLE_PERIOD);
+    }
+
+    let powered = motion(&powered);
+    let top_speed = powered.iter().map(|&(v, _)| v).fold(0.0, f64::max);
+    if top_speed <= 0.0 {
+        return Err(DrivetrainError::NoMovement);
+    }
+    // Readings near a stop are mostly encoder noise.
+    let coasting: Vec<_> = motion(&coasting)
+        .into_iter()
+        .filter(|&(v, _)| v > top_speed * 0.05)
+        .collect();
+
+    // While powered, acceleration = (V - kS) / kA - kV / kA * velocity.
+    // While coasting, acceleration = -kS / kA - (friction that grows with speed) * velocity.
+    let (powered_intercept, powered_slope) =
+        fit_line(&powered).ok_or(DrivetrainError::NoMovement)?;
+    let (coasting_intercept, _) = fit_line(&coasting).ok_or(DrivetrainError::NoMovement)?;
+    let deceleration = -coasting_intercept;
+    if powered_intercept + deceleration <= 0.0 {
+        return Err(DrivetrainError::NoMovement);
+    }
+    let ka = voltage as f64 / (powered_intercept + deceleration);
+    Ok(CoastDown {
+        feedforward: DriveFeedforward {
+            ks: deceleration * ka,
+            kv: -powered_slope * ka,
+            ka,
+        },
+        deceleration,
+        top_speed,
+    })
+}
+
+/// Runs [`measure_friction`] at 6 volts for 1.5 seconds, logs the results, and saves the feedforward model to `path`.
+pub fn calibrate_feedforward(
+    drivetrain: &Drivetrain,
+    path: &str,
+) -> Result<DriveFeedforward, DrivetrainError> {
+    let coast_down = measure_friction(drivetrain, 6.0, Duration::from_millis(1500))?;
+    let feedforward = coast_down.feedforward;
+    log::info!(
+        "drivetrain feedforward: kS = {} V, kV = {} V/(unit/s), kA = {} V/(unit/s^2), \
+         coasting deceleration = {} unit/s^2, top speed = {} unit/s",
+        Fixed::new(feedforward.ks, 3),
+        Fixed::new(feedforward.kv, 4),
+        Fixed::new(feedforward.ka, 4),
+        Fixed::new(coast_down.deceleration, 2),
+        Fixed::new(coast_down.top_speed, 2),
+    );
+    feedforward.save(path)?;
+    Ok(feedforward)
+}
+
+/// Turns `(milliseconds, distance)` samples into `(velocity, acceleration)` pairs,
+/// differentiating over several samples to smooth out encoder noise.
+fn motion(samples: &[(u32, f64)]) -> Vec<(f64, f64)> {
+    const SPAN: usize = 5;
+    let velocities: Vec<(f64, f64)> = samples
+        .windows(SPAN + 1)
+        .map(|window| {
+            let (t0, d0) = window[0];
+            let (t1, d1) = window[SPAN];
+            let dt = (t1 - t0) as f64 / 1000.0;
+            ((t0 + t1) as f64 / 2000.0, (d1 - d0) / dt)
+        })
+        .collect();
+    velocities
+        .windows(SPAN + 1)
+        .map(|window| {
+            let (t0, v0) = window[0];
+            let (t1, v1) = window[SPAN];
+            ((v0 + v1) / 2.0, (v1 - v0) / (t1 - t0))
+        })
+        .collect()
+}
+
+/// Fits `y = intercept + slope * x` by least squares, returning `(intercept, slope)`.
+fn fit_line(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|&(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points
+        .iter()
+        .map(|&(x, _)| (x - mean_x) * (x - mean_x))
+        .sum();
+    if variance == 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+    Some((mean_y - slope * mean_x, slope))
+}
@@ -0,0 +1,130 @@
+//! Detecting when the robot runs into something.
+//!
+//! Collisions show up in two ways: a sudden spike in the acceleration measured by the IMU when the robot hits something,
+//! and the drive motors drawing high current without the wheels turning while the robot pushes against something.
+//! Autonomous code can use these events to recover, for example by re-localizing against a wall it drove into.
+
+use core::time::Duration;
+
+use super::{Drivetrain, DrivetrainError};
+use crate::sensors::imu::InertialSensor;
+
+/// Thresholds for detecting collisions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionConfig {
+    /// Horizontal acceleration in g above which the robot is considered to have hit something.
+    pub impact_acceleration: f64,
+    /// Combined current draw of the drivetrain in milliamps that indicates it may be pushing against something.
+    pub stall_current: i32,
+    /// How long the current must stay above `stall_current` for a stall to be reported.
+    pub stall_time: Duration,
+    /// A stall is only reported if each side moved less than this distance while the current was high.
+    pub stall_distance: f64,
+    /// How long to wait after reporting a collision before reporting another.
+    pub cooldown: Duration,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            impact_acceleration: 1.0,
+            stall_current: 8000,
+            stall_time: Duration::from_millis(250),
+            stall_distance: 0.25,
+            cooldown: Duration::from_millis(500),
+        }
+    }
+}
+
+/// How a collision was detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionKind {
+    /// The IMU measured a sudden jolt.
+    Impact,
+    /// The drivetrain drew high current without moving.
+    Stall,
+}
+
+/// A detected collision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Collision {
+    pub kind: CollisionKind,
+    /// When the collision was detected, in milliseconds since the program started.
+    pub time: u32,
+    /// The horizontal acceleration in g when the collision was detected.
+    pub acceleration: f64,
+    /// The combined current draw of the drivetrain in milliamps when the collision was detected.
+    pub current: i32,
+}
+
+/// Watches a drivetrain and IMU for collisions.
+pub struct CollisionDetector {
+    config: CollisionConfig,
+    /// When the current went above the stall threshold and how far each side had traveled then.
+    high_current_since: Option<(u32, f64, f64)>,
+    last_collision: Option<u32>,
+}
+
+impl CollisionDetector {
+    pub fn new(config: CollisionConfig) -> Self {
+        Self {
+            config,
+            high_current_since: None,
+            last_collision: None,
+        }
+    }
+
+    pub fn config(&self) -> &CollisionConfig {
+        &self.config
+    }
+
+    /// Reads the sensors, returning a collision if one was just detected.
+    /// This should be called once per loop.
+    pub fn update(
+        &mut self,
+        drivetrain: &Drivetrain,
+        imu: &InertialSensor,
+    ) -> Result<Option<Collision>, DrivetrainError> {
+        let now = unsafe { pros_sys::millis() };
+        let accel = imu.acceleration()?;
+        let acceleration = libm::sqrt(accel.x * accel.x + accel.y * accel.y);
+        let current = drivetrain.left().current_draw()? + drivetrain.right().current_draw()?;
+        let (left, right) = (drivetrain.left_distance()?, drivetrain.right_distance()?);
+
+        let mut kind = None;
+        if acceleration > self.config.impact_acceleration {
+            kind = Some(CollisionKind::Impact);
+        }
+
+        if current > self.config.stall_current {
+            let (since, start_left, start_right) =
+                *self.high_current_since.get_or_insert((now, left, right));
+            let moved = libm::fabs(left - start_left).max(libm::fabs(right - start_right));
+            if now - since >= self.config.stall_time.as_millis() as u32 {
+                if moved < self.config.stall_distance {
+                    kind = kind.or(Some(CollisionKind::Stall));
+                }
+                // Start a new window so a robot that is slowly moving isn't checked against where it started.
+                self.high_current_since = Some((now, left, right));
+            }
+        } else {
+            self.high_current_since = None;
+        }
+
+        let cooling_down = self
+            .last_collision
+            .is_some_and(|last| now - last < self.config.cooldown.as_millis() as u32);
+        match kind {
+            Some(kind) if !cooling_down => {
+                self.last_collision = Some(now);
+                Ok(Some(Collision {
+                    kind,
+                    time: now,
+                    acceleration,
+                    current,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
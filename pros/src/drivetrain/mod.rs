@@ -0,0 +1,270 @@
+//! Differential (tank style) drivetrains.
+//!
+//! Distances are in whatever unit the wheel diameter and track width are given in,
+//! which should match the unit used for [`Pose`](crate::pose::Pose)s.
+
+use core::{
+    f64::consts::PI,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use snafu::Snafu;
+
+use crate::{
+    config::{Config, ConfigError},
+    encode::{Decode, DecodeError, Decoder, Encode, Encoder},
+    motor::{MotorError, MotorGroup, MAX_SLEW_STEP},
+    sensors::imu::ImuError,
+    usd::{self, UsdError},
+};
+
+pub mod assist;
+pub mod autotune;
+pub mod characterize;
+pub mod collision;
+pub mod tilt;
+
+/// The physical measurements of a drivetrain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrivetrainConfig {
+    /// The effective diameter of the drive wheels.
+    pub wheel_diameter: f64,
+    /// The effective distance between the left and right wheels.
+    pub track_width: f64,
+    /// Wheel rotations per motor rotation.
+    pub gear_ratio: f64,
+}
+
+impl DrivetrainConfig {
+    /// Loads a config saved with [`DrivetrainConfig::save`], returning `None` if there isn't one.
+    pub fn load(path: &str) -> Result<Option<Self>, UsdError> {
+        usd::load(path)
+    }
+
+    /// Saves the config to the SD card.
+    pub fn save(&self, path: &str) -> Result<(), UsdError> {
+        usd::save(path, self)
+    }
+
+    /// Returns how far a wheel travels per rotation of its motor.
+    pub fn distance_per_rotation(&self) -> f64 {
+        self.gear_ratio * PI * self.wheel_diameter
+    }
+}
+
+impl Encode for DrivetrainConfig {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(self.wheel_diameter, self.track_width, self.gear_ratio));
+    }
+}
+
+impl Decode for DrivetrainConfig {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let (wheel_diameter, track_width, gear_ratio) = decoder.read()?;
+        Ok(Self {
+            wheel_diameter,
+            track_width,
+            gear_ratio,
+        })
+    }
+}
+
+/// The voltage the drivetrain needs to move at a velocity and acceleration,
+/// as measured by [`characterize::measure_friction`].
+///
+/// ```rust
+/// let feedforward = DriveFeedforward::load("feedforward.cfg")?;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Config)]
+pub struct DriveFeedforward {
+    /// The voltage needed to overcome friction and start moving.
+    #[config(min = 0.0, max = 12.0)]
+    pub ks: f64,
+    /// Volts per unit per second of velocity.
+    #[config(min = 0.0)]
+    pub kv: f64,
+    /// Volts per unit per second squared of acceleration.
+    #[config(min = 0.0)]
+    pub ka: f64,
+}
+
+impl DriveFeedforward {
+    /// Returns the voltage for one side of the drivetrain to move at `velocity` while accelerating at `acceleration`.
+    pub fn voltage(&self, velocity: f64, acceleration: f64) -> f32 {
+        let friction = if velocity > 0.0 {
+            self.ks
+        } else if velocity < 0.0 {
+            -self.ks
+        } else {
+            0.0
+        };
+        (friction + self.kv * velocity + self.ka * acceleration) as f32
+    }
+}
+
+impl Encode for DriveFeedforward {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(self.ks, self.kv, self.ka));
+    }
+}
+
+impl Decode for DriveFeedforward {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let (ks, kv, ka) = decoder.read()?;
+        Ok(Self { ks, kv, ka })
+    }
+}
+
+/// Limits how quickly the drivetrain speeds up at the start of autonomous,
+/// so the robot doesn't wheelie or spin its wheels off the line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoftStart {
+    /// How long after autonomous starts the limit applies.
+    pub duration: Duration,
+    /// The fastest each side's voltage may rise, in volts per second.
+    pub max_ramp: f32,
+}
+
+/// A drivetrain with one group of motors on each side.
+pub struct Drivetrain {
+    left: MotorGroup,
+    right: MotorGroup,
+    config: DrivetrainConfig,
+    soft_start: Option<SoftStart>,
+    last_voltages: [AtomicU32; 2],
+    last_update: AtomicU32,
+}
+
+impl Drivetrain {
+    pub fn new(left: MotorGroup, right: MotorGroup, config: DrivetrainConfig) -> Self {
+        Self {
+            left,
+            right,
+            config,
+            soft_start: None,
+            last_voltages: [AtomicU32::new(0), AtomicU32::new(0)],
+            last_update: AtomicU32::new(0),
+        }
+    }
+
+    /// Ramps up the drivetrain's output at the start of autonomous.
+    /// See [`crate::competition::autonomous_elapsed`] for when autonomous is considered to start.
+    pub fn with_soft_start(mut self, soft_start: SoftStart) -> Self {
+        self.soft_start = Some(soft_start);
+        self
+    }
+
+    pub fn set_soft_start(&mut self, soft_start: Option<SoftStart>) {
+        self.soft_start = soft_start;
+    }
+
+    /// Returns the soft start settings if soft start is active right now.
+    fn active_soft_start(&self) -> Option<SoftStart> {
+        let soft_start = self.soft_start?;
+        let active = crate::competition::autonomous_elapsed()
+            .is_some_and(|elapsed| elapsed < soft_start.duration);
+        if !active {
+            // Start ramping from a standstill the next time autonomous starts.
+            for last in &self.last_voltages {
+                last.store(0, Ordering::Relaxed);
+            }
+        }
+        active.then_some(soft_start)
+    }
+
+    /// Limits how far each side's voltage rises since the last command while soft start is active.
+    fn ramp(&self, soft_start: SoftStart, left: f32, right: f32) -> (f32, f32) {
+        let now = unsafe { pros_sys::millis() };
+        let dt = now.wrapping_sub(self.last_update.swap(now, Ordering::Relaxed)) as f32 / 1000.0;
+        let max_change = soft_start.max_ramp * dt.min(MAX_SLEW_STEP);
+
+        let mut ramped = [left, right];
+        for (voltage, last) in ramped.iter_mut().zip(&self.last_voltages) {
+            let last_voltage = f32::from_bits(last.load(Ordering::Relaxed));
+            // Only increases in magnitude are limited so the robot can always stop.
+            if libm::fabsf(*voltage) > libm::fabsf(last_voltage) {
+                *voltage = voltage.clamp(last_voltage - max_change, last_voltage + max_change);
+            }
+            last.store(voltage.to_bits(), Ordering::Relaxed);
+        }
+        (ramped[0], ramped[1])
+    }
+
+    pub fn left(&self) -> &MotorGroup {
+        &self.left
+    }
+
+    pub fn right(&self) -> &MotorGroup {
+        &self.right
+    }
+
+    pub fn config(&self) -> &DrivetrainConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: DrivetrainConfig) {
+        self.config = config;
+    }
+
+    /// Sets the output of each side from -1.0 to 1.0.
+    pub fn tank(&self, left: f32, right: f32) -> Result<(), MotorError> {
+        if self.active_soft_start().is_some() {
+            return self.set_voltage(left.clamp(-1.0, 1.0) * 12.0, right.clamp(-1.0, 1.0) * 12.0);
+        }
+        self.left.set_output(left)?;
+        self.right.set_output(right)
+    }
+
+    /// Drives with a forward and a turning output, each from -1.0 to 1.0.
+    /// Positive turning turns counterclockwise.
+    pub fn arcade(&self, forward: f32, turn: f32) -> Result<(), MotorError> {
+        self.tank(forward - turn, forward + turn)
+    }
+
+    /// Sets the voltage of each side, from -12 to 12 volts.
+    pub fn set_voltage(&self, left: f32, right: f32) -> Result<(), MotorError> {
+        let (left, right) = match self.active_soft_start() {
+            Some(soft_start) => self.ramp(soft_start, left, right),
+            None => (left, right),
+        };
+        self.left.set_voltage(left)?;
+        self.right.set_voltage(right)
+    }
+
+    pub fn brake(&self) -> Result<(), MotorError> {
+        self.left.brake()?;
+        self.right.brake()
+    }
+
+    /// Sets the distance each side has traveled to zero.
+    pub fn zero(&self) -> Result<(), MotorError> {
+        self.left.zero()?;
+        self.right.zero()
+    }
+
+    /// Returns how far the left wheels have traveled since they were last zeroed.
+    pub fn left_distance(&self) -> Result<f64, MotorError> {
+        Ok(self.left.position()?.into_rotations() * self.config.distance_per_rotation())
+    }
+
+    /// Returns how far the right wheels have traveled since they were last zeroed.
+    pub fn right_distance(&self) -> Result<f64, MotorError> {
+        Ok(self.right.position()?.into_rotations() * self.config.distance_per_rotation())
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum DrivetrainError {
+    #[snafu(display("The robot did not move, so nothing could be measured."))]
+    NoMovement,
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+    #[snafu(display("{source}"), context(false))]
+    Imu { source: ImuError },
+    #[snafu(display("{source}"), context(false))]
+    Usd { source: UsdError },
+    #[snafu(display("{source}"), context(false))]
+    Config { source: ConfigError },
+}
+impl core::error::Error for DrivetrainError {}
@@ -0,0 +1,134 @@
+//! Protecting the robot from tipping over.
+
+use alloc::boxed::Box;
+
+use super::{Drivetrain, DrivetrainError};
+use crate::sensors::imu::{Euler, InertialSensor};
+
+/// Settings for a [`TiltGuard`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TiltConfig {
+    /// Pitch or roll in degrees above which the robot is considered to be tipping.
+    pub threshold: f64,
+    /// Pitch and roll must both fall below this many degrees before the robot is considered level again.
+    /// This should be less than `threshold` so the guard doesn't flicker on and off.
+    pub recovery_threshold: f64,
+    /// While tipping, the fastest each side's output may change, in output per second.
+    /// `None` leaves the output alone and only runs the callback.
+    pub max_ramp: Option<f32>,
+}
+
+impl Default for TiltConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 15.0,
+            recovery_threshold: 10.0,
+            max_ramp: Some(2.0),
+        }
+    }
+}
+
+/// Watches the IMU's pitch and roll and eases off the drivetrain when the robot starts to tip.
+///
+/// The guard can be disabled, for example while a climbing mechanism is deliberately tilting the robot.
+pub struct TiltGuard {
+    config: TiltConfig,
+    enabled: bool,
+    tilted: bool,
+    on_tilt: Option<Box<dyn FnMut(Euler) + Send>>,
+    last_output: (f32, f32),
+    last_update: Option<u32>,
+}
+
+impl TiltGuard {
+    pub fn new(config: TiltConfig) -> Self {
+        Self {
+            config,
+            enabled: true,
+            tilted: false,
+            on_tilt: None,
+            last_output: (0.0, 0.0),
+            last_update: None,
+        }
+    }
+
+    /// Sets a callback that runs with the IMU's orientation each time the robot starts tipping.
+    pub fn on_tilt(&mut self, callback: impl FnMut(Euler) + Send + 'static) {
+        self.on_tilt = Some(Box::new(callback));
+    }
+
+    /// Turns the guard on or off. While off, outputs are passed through and the callback never runs.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.tilted = false;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns true if the robot was tipping at the last update.
+    pub fn is_tilted(&self) -> bool {
+        self.tilted
+    }
+
+    /// Reads the IMU, running the callback if the robot just started tipping,
+    /// and returns whether it is tipping.
+    pub fn update(&mut self, imu: &InertialSensor) -> Result<bool, DrivetrainError> {
+        if !self.enabled {
+            return Ok(false);
+        }
+
+        let euler = imu.euler()?;
+        let tilt = libm::fabs(euler.pitch).max(libm::fabs(euler.roll));
+        if !self.tilted && tilt > self.config.threshold {
+            self.tilted = true;
+            if let Some(callback) = &mut self.on_tilt {
+                callback(euler);
+            }
+        } else if self.tilted && tilt < self.config.recovery_threshold {
+            self.tilted = false;
+        }
+        Ok(self.tilted)
+    }
+
+    /// Updates the guard and sets the output of each side from -1.0 to 1.0,
+    /// limiting how fast the output changes while the robot is tipping.
+    pub fn tank(
+        &mut self,
+        drivetrain: &Drivetrain,
+        imu: &InertialSensor,
+        left: f32,
+        right: f32,
+    ) -> Result<(), DrivetrainError> {
+        let now = unsafe { pros_sys::millis() };
+        let dt = self
+            .last_update
+            .map_or(0.0, |last| (now - last) as f32 / 1000.0);
+        self.last_update = Some(now);
+
+        let (mut left, mut right) = (left, right);
+        if let (true, Some(ramp)) = (self.update(imu)?, self.config.max_ramp) {
+            let max_change = ramp * dt;
+            let (last_left, last_right) = self.last_output;
+            left = left.clamp(last_left - max_change, last_left + max_change);
+            right = right.clamp(last_right - max_change, last_right + max_change);
+        }
+        self.last_output = (left, right);
+        Ok(drivetrain.tank(left, right)?)
+    }
+
+    /// Like [`TiltGuard::tank`], but with a forward and a turning output.
+    /// Positive turning turns counterclockwise.
+    pub fn arcade(
+        &mut self,
+        drivetrain: &Drivetrain,
+        imu: &InertialSensor,
+        forward: f32,
+        turn: f32,
+    ) -> Result<(), DrivetrainError> {
+        self.tank(drivetrain, imu, forward - turn, forward + turn)
+    }
+}
@@ -0,0 +1,52 @@
+//! Cyclic redundancy checks for detecting corrupted data.
+
+/// Computes the CRC-16/CCITT-FALSE checksum of `data`.
+/// This is used for short messages where two bytes of overhead matter.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `data`, the same one used by zip and PNG.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The standard check values for these variants.
+    #[test]
+    fn check_values() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(crc16(&[]), 0xFFFF);
+        assert_eq!(crc32(&[]), 0);
+    }
+}
@@ -0,0 +1,168 @@
+//! Splitting a byte stream into checksummed messages.
+//!
+//! Each frame is a start byte, the payload length as a little endian `u16`, the payload,
+//! and a CRC16 of the length and payload.
+//! Readers that lose track of where a frame starts resynchronize by scanning for the next start byte.
+
+use alloc::vec::Vec;
+
+use super::crc::crc16;
+
+/// The byte every frame starts with.
+pub const START_BYTE: u8 = 0xA5;
+/// The largest payload a frame can hold.
+pub const MAX_PAYLOAD_LEN: usize = u16::MAX as usize;
+
+/// Wraps a payload in a frame.
+///
+/// # Panics
+///
+/// Panics if the payload is longer than [`MAX_PAYLOAD_LEN`].
+pub fn frame(payload: &[u8]) -> Vec<u8> {
+    assert!(
+        payload.len() <= MAX_PAYLOAD_LEN,
+        "Frame payload is too long ({} > {MAX_PAYLOAD_LEN})",
+        payload.len()
+    );
+
+    let mut frame = Vec::with_capacity(payload.len() + 5);
+    frame.push(START_BYTE);
+    frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    frame.extend_from_slice(payload);
+    let checksum = crc16(&frame[1..]);
+    frame.extend_from_slice(&checksum.to_le_bytes());
+    frame
+}
+
+/// Collects bytes from a stream and returns the complete, valid frames in them.
+#[derive(Debug)]
+pub struct FrameReader {
+    buf: Vec<u8>,
+    max_payload_len: usize,
+    dropped: u32,
+}
+
+impl FrameReader {
+    /// The largest payload accepted by [`FrameReader::new`].
+    pub const DEFAULT_MAX_PAYLOAD_LEN: usize = 1024;
+
+    pub fn new() -> Self {
+        Self::with_max_payload_len(Self::DEFAULT_MAX_PAYLOAD_LEN)
+    }
+
+    /// Creates a reader that treats frames longer than `max_payload_len` as corrupted.
+    /// Keeping this small lets the reader recover quickly when noise looks like the start of a huge frame.
+    pub fn with_max_payload_len(max_payload_len: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            max_payload_len: max_payload_len.min(MAX_PAYLOAD_LEN),
+            dropped: 0,
+        }
+    }
+
+    /// Adds bytes received from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Returns how many corrupted frames have been skipped.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Returns the payload of the next complete frame, or `None` if more bytes are needed.
+    pub fn next_frame(&mut self) -> Option<Vec<u8>> {
+        let mut payload = Vec::new();
+        self.next_frame_into(&mut payload).then_some(payload)
+    }
+
+    /// Replaces the contents of `payload` with the next complete frame's payload,
+    /// returning false if more bytes are needed.
+    /// Reusing the same buffer avoids allocating for every frame.
+    pub fn next_frame_into(&mut self, payload: &mut Vec<u8>) -> bool {
+        loop {
+            match self.buf.iter().position(|byte| *byte == START_BYTE) {
+                Some(start) => {
+                    self.buf.drain(..start);
+                }
+                None => {
+                    self.buf.clear();
+                    return false;
+                }
+            }
+
+            if self.buf.len() < 3 {
+                return false;
+            }
+            let len = u16::from_le_bytes([self.buf[1], self.buf[2]]) as usize;
+            if len <= self.max_payload_len {
+                if self.buf.len() < len + 5 {
+                    return false;
+                }
+
+                let checksum = u16::from_le_bytes([self.buf[len + 3], self.buf[len + 4]]);
+                if crc16(&self.buf[1..len + 3]) == checksum {
+                    payload.clear();
+                    payload.extend_from_slice(&self.buf[3..len + 3]);
+                    self.buf.drain(..len + 5);
+                    return true;
+                }
+            }
+
+            // Not a real frame, so skip this start byte and look for the next one.
+            self.dropped += 1;
+            self.buf.remove(0);
+        }
+    }
+}
+
+impl Default for FrameReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn reads_split_frames() {
+        let mut reader = FrameReader::new();
+        let bytes = frame(b"hello");
+        reader.push(&bytes[..4]);
+        assert_eq!(reader.next_frame(), None);
+        reader.push(&bytes[4..]);
+        assert_eq!(reader.next_frame(), Some(b"hello".to_vec()));
+        assert_eq!(reader.next_frame(), None);
+        assert_eq!(reader.dropped(), 0);
+    }
+
+    #[test]
+    fn resyncs_after_garbage() {
+        let mut reader = FrameReader::new();
+        // Noise including a stray start byte, then a frame with a corrupted payload.
+        reader.push(&[0x00, START_BYTE, 0x02, 0x00, 0xFF, 0x13]);
+        let mut corrupted = frame(b"bad");
+        corrupted[3] ^= 0xFF;
+        reader.push(&corrupted);
+        reader.push(&frame(b"first"));
+        reader.push(&frame(&[]));
+
+        assert_eq!(reader.next_frame(), Some(b"first".to_vec()));
+        assert_eq!(reader.next_frame(), Some(vec![]));
+        assert_eq!(reader.next_frame(), None);
+        assert!(reader.dropped() >= 2);
+    }
+
+    #[test]
+    fn rejects_oversized_payloads() {
+        let mut reader = FrameReader::with_max_payload_len(4);
+        reader.push(&frame(b"too long"));
+        reader.push(&frame(b"ok"));
+        assert_eq!(reader.next_frame(), Some(b"ok".to_vec()));
+        assert_eq!(reader.dropped(), 1);
+    }
+}
@@ -0,0 +1,412 @@
+//! A compact binary format shared by everything that persists or transmits data.
+//!
+//! Values are encoded in the spirit of [postcard](https://docs.rs/postcard):
+//! unsigned integers as LEB128 varints, signed integers zigzag encoded and then as varints,
+//! floats as little endian bytes, and sequences prefixed by their length.
+//! [`to_checked_vec`] appends a CRC32 for data at rest (such as files on the SD card),
+//! and [`frame`] wraps messages for byte streams like serial and VEXLink.
+
+use alloc::{string::String, vec::Vec};
+
+use snafu::Snafu;
+
+pub mod crc;
+pub mod frame;
+pub mod varint;
+
+pub use frame::{frame, FrameReader};
+
+/// A type that can be written in the binary format.
+pub trait Encode {
+    fn encode(&self, encoder: &mut Encoder);
+}
+
+/// A type that can be read from the binary format.
+pub trait Decode: Sized {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError>;
+}
+
+/// A type that is sent as a message over a link or serial connection.
+pub trait Message: Encode + Decode {
+    /// Identifies this type of message. Each message type on a connection should have a different topic.
+    const TOPIC: u16;
+}
+
+/// Writes values into a byte buffer.
+#[derive(Debug, Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes a value.
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) {
+        value.encode(self);
+    }
+
+    /// Writes raw bytes without a length prefix.
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Writes an unsigned LEB128 varint.
+    pub fn write_varint(&mut self, value: u64) {
+        varint::write_varint(&mut self.buf, value);
+    }
+
+    /// Returns the encoded bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Reads values out of a byte buffer.
+#[derive(Debug)]
+pub struct Decoder<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    /// Decodes a value.
+    pub fn read<T: Decode>(&mut self) -> Result<T, DecodeError> {
+        T::decode(self)
+    }
+
+    /// Reads exactly `len` raw bytes.
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        Ok(varint::take(&mut self.buf, len)?)
+    }
+
+    /// Reads an unsigned LEB128 varint.
+    pub fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        Ok(varint::read_varint(&mut self.buf)?)
+    }
+
+    /// Returns the bytes that have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+/// Encodes a value into a new buffer.
+pub fn to_vec<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder.write(value);
+    encoder.into_inner()
+}
+
+/// Decodes a value, failing if there are bytes left over.
+pub fn from_slice<T: Decode>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let mut decoder = Decoder::new(bytes);
+    let value = decoder.read()?;
+    if !decoder.remaining().is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(value)
+}
+
+/// Encodes a value followed by a CRC32 of its encoding, for data that may be corrupted at rest.
+pub fn to_checked_vec<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut bytes = to_vec(value);
+    let checksum = crc::crc32(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    bytes
+}
+
+/// Verifies the CRC32 written by [`to_checked_vec`] and decodes the value.
+pub fn from_checked_slice<T: Decode>(bytes: &[u8]) -> Result<T, DecodeError> {
+    if bytes.len() < 4 {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (payload, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc::crc32(payload).to_le_bytes() != checksum {
+        return Err(DecodeError::ChecksumMismatch);
+    }
+    from_slice(payload)
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, encoder: &mut Encoder) {
+                encoder.write_varint(*self as u64);
+            }
+        }
+        impl Decode for $ty {
+            fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                decoder.read_varint()?.try_into().map_err(|_| DecodeError::InvalidValue)
+            }
+        }
+    )*};
+}
+impl_unsigned!(u16, u32, u64, usize);
+
+macro_rules! impl_signed {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, encoder: &mut Encoder) {
+                encoder.write_varint(varint::zigzag(*self as i64));
+            }
+        }
+        impl Decode for $ty {
+            fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                varint::unzigzag(decoder.read_varint()?)
+                    .try_into()
+                    .map_err(|_| DecodeError::InvalidValue)
+            }
+        }
+    )*};
+}
+impl_signed!(i8, i16, i32, i64, isize);
+
+macro_rules! impl_float {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, encoder: &mut Encoder) {
+                encoder.write_raw(&self.to_le_bytes());
+            }
+        }
+        impl Decode for $ty {
+            fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                let bytes = decoder.read_raw(core::mem::size_of::<$ty>())?;
+                Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+impl_float!(f32, f64);
+
+impl Encode for u8 {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_raw(&[*self]);
+    }
+}
+impl Decode for u8 {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Ok(decoder.read_raw(1)?[0])
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_raw(&[*self as u8]);
+    }
+}
+impl Decode for bool {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        match decoder.read_raw(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidValue),
+        }
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_varint(self.len() as u64);
+        encoder.write_raw(self.as_bytes());
+    }
+}
+impl Encode for String {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_str().encode(encoder);
+    }
+}
+impl Decode for String {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let len = decoder.read()?;
+        let bytes = decoder.read_raw(len)?;
+        core::str::from_utf8(bytes)
+            .map(Into::into)
+            .map_err(|_| DecodeError::InvalidValue)
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_varint(self.len() as u64);
+        for item in self {
+            item.encode(encoder);
+        }
+    }
+}
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_slice().encode(encoder);
+    }
+}
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let len: usize = decoder.read()?;
+        // Every item takes at least one byte, so this stops corrupt lengths from allocating huge buffers.
+        if len > decoder.remaining().len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        (0..len).map(|_| decoder.read()).collect()
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, encoder: &mut Encoder) {
+        for item in self {
+            item.encode(encoder);
+        }
+    }
+}
+impl<T: Decode + Default + Copy, const N: usize> Decode for [T; N] {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let mut array = [T::default(); N];
+        for item in array.iter_mut() {
+            *item = decoder.read()?;
+        }
+        Ok(array)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            Some(value) => {
+                encoder.write(&true);
+                value.encode(encoder);
+            }
+            None => encoder.write(&false),
+        }
+    }
+}
+impl<T: Decode> Decode for Option<T> {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Ok(if decoder.read()? {
+            Some(decoder.read()?)
+        } else {
+            None
+        })
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: Encode),*> Encode for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn encode(&self, encoder: &mut Encoder) {
+                let ($($name,)*) = self;
+                $($name.encode(encoder);)*
+            }
+        }
+        impl<$($name: Decode),*> Decode for ($($name,)*) {
+            fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                Ok(($(decoder.read::<$name>()?,)*))
+            }
+        }
+    };
+}
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+
+#[derive(Debug, Snafu, PartialEq, Eq)]
+pub enum DecodeError {
+    #[snafu(display("The data ended before the value was fully decoded."))]
+    UnexpectedEnd,
+    #[snafu(display("The data contained a value that is invalid for its type."))]
+    InvalidValue,
+    #[snafu(display("There was data left over after decoding the value."))]
+    TrailingBytes,
+    #[snafu(display("The checksum did not match the data, so it is probably corrupted."))]
+    ChecksumMismatch,
+}
+impl core::error::Error for DecodeError {}
+
+impl From<varint::ReadError> for DecodeError {
+    fn from(err: varint::ReadError) -> Self {
+        match err {
+            varint::ReadError::UnexpectedEnd => Self::UnexpectedEnd,
+            varint::ReadError::Overlong => Self::InvalidValue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    #[test]
+    fn varint_edges() {
+        assert_eq!(to_vec(&0u64), [0x00]);
+        assert_eq!(to_vec(&127u64), [0x7F]);
+        assert_eq!(to_vec(&128u64), [0x80, 0x01]);
+
+        let max = to_vec(&u64::MAX);
+        assert_eq!(max.len(), 10);
+        assert_eq!(max[9], 0x01);
+        assert_eq!(from_slice::<u64>(&max), Ok(u64::MAX));
+
+        // An 11th continuation byte can't fit in a u64.
+        assert_eq!(
+            from_slice::<u64>(&[0xFF; 11]),
+            Err(DecodeError::InvalidValue)
+        );
+        assert_eq!(from_slice::<u64>(&[0x80]), Err(DecodeError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn zigzag_edges() {
+        assert_eq!(to_vec(&0i64), [0x00]);
+        assert_eq!(to_vec(&-1i64), [0x01]);
+        assert_eq!(to_vec(&1i64), [0x02]);
+        // The most negative value maps to the largest varint.
+        assert_eq!(to_vec(&i64::MIN), to_vec(&u64::MAX));
+
+        for value in [0, -1, 1, i64::MIN, i64::MAX] {
+            assert_eq!(from_slice::<i64>(&to_vec(&value)), Ok(value));
+        }
+        assert_eq!(
+            from_slice::<i8>(&to_vec(&(i8::MIN as i64 - 1))),
+            Err(DecodeError::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let tuple = (3u8, -7i32, 1.5f64, "pros".to_string());
+        assert_eq!(from_slice(&to_vec(&tuple)), Ok(tuple));
+
+        let options = vec![Some(4u16), None, Some(u16::MAX)];
+        assert_eq!(from_slice(&to_vec(&options)), Ok(options.clone()));
+        // Slices encode the same way as vectors, so they decode as one.
+        assert_eq!(from_slice(&to_vec(options.as_slice())), Ok(options));
+
+        assert_eq!(from_slice(&to_vec(&[1.0f32, -2.0])), Ok([1.0f32, -2.0]));
+        assert_eq!(from_slice::<bool>(&[2]), Err(DecodeError::InvalidValue));
+        assert_eq!(from_slice::<u8>(&[1, 2]), Err(DecodeError::TrailingBytes));
+    }
+
+    #[test]
+    fn checked_round_trip() {
+        let value = (42u32, Some(-1.0f32));
+        let mut bytes = to_checked_vec(&value);
+        assert_eq!(from_checked_slice(&bytes), Ok(value));
+
+        bytes[0] ^= 1;
+        assert_eq!(
+            from_checked_slice::<(u32, Option<f32>)>(&bytes),
+            Err(DecodeError::ChecksumMismatch)
+        );
+        assert_eq!(
+            from_checked_slice::<u32>(&[0, 0]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! LEB128 varints and zigzag encoding, the integer primitives shared by every binary format in this crate.
+//!
+//! This file only depends on `alloc` so that the host tools can include it with `#[path]`.
+//! Each format maps [`ReadError`] to its own error type.
+
+use alloc::vec::Vec;
+
+/// Maps signed integers to unsigned ones so that values near zero have short varints.
+pub fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Reverses [`zigzag`].
+pub fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// Appends an unsigned LEB128 varint to `out`.
+pub fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Reads an unsigned LEB128 varint from the start of `buf`, advancing past it.
+pub fn read_varint(buf: &mut &[u8]) -> Result<u64, ReadError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take(buf, 1)?[0];
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(ReadError::Overlong)
+}
+
+/// Splits `len` bytes off the start of `buf`.
+pub fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], ReadError> {
+    if buf.len() < len {
+        return Err(ReadError::UnexpectedEnd);
+    }
+    let (bytes, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(bytes)
+}
+
+/// Why reading from a buffer failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError {
+    /// The buffer ended partway through the value.
+    UnexpectedEnd,
+    /// A varint had more bytes than fit in a `u64`.
+    Overlong,
+}
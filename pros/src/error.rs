@@ -70,9 +70,15 @@ macro_rules! bail_on {
         val
     }};
 }
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::VecDeque, format, string::String, vec::Vec};
 pub(crate) use bail_on;
+
 use snafu::Snafu;
 
+#[cfg(feature = "alloc")]
+use crate::sync::Mutex;
+
 pub trait FromErrno {
     /// Consume the current `errno` and, if it contains a known error, returns Self.
     fn from_errno(num: i32) -> Option<Self>
@@ -96,3 +102,61 @@ map_errno!(PortError {
     ENXIO => Self::PortOutOfRange,
     ENODEV => Self::PortCannotBeConfigured,
 });
+
+#[cfg(feature = "alloc")]
+type ErrorSink = Box<dyn FnMut(&dyn core::error::Error) + Send>;
+
+/// How many reported errors [`recent_reports`] keeps.
+#[cfg(feature = "alloc")]
+const REPORT_HISTORY: usize = 16;
+
+#[cfg(feature = "alloc")]
+lazy_static::lazy_static! {
+    static ref ERROR_SINK: Mutex<Option<ErrorSink>> = Mutex::new(None);
+    static ref RECENT_REPORTS: Mutex<VecDeque<(u32, String)>> = Mutex::new(VecDeque::new());
+}
+
+/// Sets where errors passed to [`report`] go, replacing any previous sink.
+#[cfg(feature = "alloc")]
+pub fn set_error_sink(sink: impl FnMut(&dyn core::error::Error) + Send + 'static) {
+    *ERROR_SINK.lock() = Some(Box::new(sink));
+}
+
+/// Reports an error that can't be returned to anyone, such as one from a background task
+/// or a problem that was worked around.
+/// Errors go to the sink set with [`set_error_sink`].
+/// Without a sink, they are logged if a logger is installed and printed otherwise.
+#[cfg(feature = "alloc")]
+pub fn report(error: &dyn core::error::Error) {
+    {
+        let mut recent = RECENT_REPORTS.lock();
+        if recent.len() == REPORT_HISTORY {
+            recent.pop_front();
+        }
+        recent.push_back((unsafe { pros_sys::millis() }, format!("{error}")));
+    }
+
+    match ERROR_SINK.lock().as_mut() {
+        Some(sink) => sink(error),
+        None if log::max_level() != log::LevelFilter::Off => log::error!("{error}"),
+        #[cfg(not(feature = "lvgl"))]
+        None => {
+            crate::println!("Error: {error}");
+        }
+        #[cfg(feature = "lvgl")]
+        None => {}
+    }
+}
+
+/// Returns the most recently reported errors, oldest first,
+/// along with the time in milliseconds since the program started that each was reported.
+#[cfg(feature = "alloc")]
+pub fn recent_reports() -> Vec<(u32, String)> {
+    RECENT_REPORTS.lock().iter().cloned().collect()
+}
+
+/// Returns when the most recent error was reported, in milliseconds since the program started.
+#[cfg(feature = "alloc")]
+pub fn last_report_time() -> Option<u32> {
+    RECENT_REPORTS.lock().back().map(|(time, _)| *time)
+}
@@ -0,0 +1,62 @@
+//! Displaying floating point numbers with a fixed number of decimal places.
+//!
+//! With the `minimal-fmt` feature, [`Fixed`] formats numbers using only integer arithmetic,
+//! so `core`'s floating point formatting code can be left out of the binary
+//! as long as nothing else formats floats.
+
+use core::fmt;
+
+/// Displays a number rounded to a fixed number of decimal places.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fixed {
+    value: f64,
+    decimals: u8,
+}
+
+impl Fixed {
+    /// The most decimal places that can be displayed.
+    pub const MAX_DECIMALS: u8 = 9;
+
+    /// Displays `value` with `decimals` decimal places, up to [`Fixed::MAX_DECIMALS`].
+    pub fn new(value: impl Into<f64>, decimals: u8) -> Self {
+        Self {
+            value: value.into(),
+            decimals: decimals.min(Self::MAX_DECIMALS),
+        }
+    }
+}
+
+#[cfg(not(feature = "minimal-fmt"))]
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.*}", self.decimals as usize, self.value)
+    }
+}
+
+#[cfg(feature = "minimal-fmt")]
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.value.is_nan() {
+            return f.write_str("NaN");
+        }
+        if self.value.is_sign_negative() {
+            f.write_str("-")?;
+        }
+        if self.value.is_infinite() {
+            return f.write_str("inf");
+        }
+
+        let scale = 10u64.pow(self.decimals as u32);
+        let scaled = libm::round(libm::fabs(self.value) * scale as f64) as u64;
+        write!(f, "{}", scaled / scale)?;
+        if self.decimals > 0 {
+            write!(
+                f,
+                ".{:0width$}",
+                scaled % scale,
+                width = self.decimals as usize
+            )?;
+        }
+        Ok(())
+    }
+}
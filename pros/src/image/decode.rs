@@ -0,0 +1,500 @@
+//! Decoding BMP and PNG files into 32-bit ARGB pixels.
+//!
+//! Only the common cases are supported:
+//! - BMP files with uncompressed 24 or 32 bit pixels.
+//! - PNG files with 8 bit channels that aren't interlaced, in any color type.
+//!
+//! Pixels are `0xAARRGGBB`, where an alpha of `0xFF` is opaque,
+//! and are stored row by row from the top left.
+//!
+//! This module only depends on `alloc` and `snafu` so that `pros-macros` can share it to decode images at build time.
+
+use alloc::{vec, vec::Vec};
+
+use snafu::Snafu;
+
+/// A decoded image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedImage {
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Vec<u32>,
+}
+
+/// Decodes a BMP or PNG file, picking the format from its first bytes.
+pub fn decode(data: &[u8]) -> Result<DecodedImage, DecodeImageError> {
+    if data.starts_with(PNG_SIGNATURE) {
+        decode_png(data)
+    } else if data.starts_with(b"BM") {
+        decode_bmp(data)
+    } else {
+        Err(DecodeImageError::UnknownFormat)
+    }
+}
+
+/// Adds two lengths read from the file, which could overflow if the file is corrupted.
+fn add(a: usize, b: usize) -> Result<usize, DecodeImageError> {
+    a.checked_add(b).ok_or(DecodeImageError::Corrupted)
+}
+
+/// Multiplies two lengths read from the file, which could overflow if the file is corrupted.
+fn mul(a: usize, b: usize) -> Result<usize, DecodeImageError> {
+    a.checked_mul(b).ok_or(DecodeImageError::Corrupted)
+}
+
+fn u16_le(data: &[u8], at: usize) -> Result<u16, DecodeImageError> {
+    let bytes = data
+        .get(at..add(at, 2)?)
+        .ok_or(DecodeImageError::Corrupted)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_le(data: &[u8], at: usize) -> Result<u32, DecodeImageError> {
+    let bytes = data
+        .get(at..add(at, 4)?)
+        .ok_or(DecodeImageError::Corrupted)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn u32_be(data: &[u8], at: usize) -> Result<u32, DecodeImageError> {
+    let bytes = data
+        .get(at..add(at, 4)?)
+        .ok_or(DecodeImageError::Corrupted)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn dimension(value: i64) -> Result<u16, DecodeImageError> {
+    u16::try_from(value).map_err(|_| DecodeImageError::Unsupported {
+        feature: "Images wider or taller than 65535 pixels",
+    })
+}
+
+fn argb(alpha: u8, red: u8, green: u8, blue: u8) -> u32 {
+    u32::from_be_bytes([alpha, red, green, blue])
+}
+
+/// Decodes a BMP file.
+pub fn decode_bmp(data: &[u8]) -> Result<DecodedImage, DecodeImageError> {
+    if !data.starts_with(b"BM") {
+        return Err(DecodeImageError::UnknownFormat);
+    }
+    let offset = u32_le(data, 10)? as usize;
+    let width = u32_le(data, 18)? as i32;
+    // A negative height means the rows are stored from the top down instead of the bottom up.
+    let height = u32_le(data, 22)? as i32;
+    let bits = u16_le(data, 28)?;
+    let compression = u32_le(data, 30)?;
+
+    // Compression 3 is bit fields, which 32 bit files use to say where the alpha channel is.
+    // The usual layout of blue, green, red, alpha is assumed.
+    let has_alpha = match (bits, compression) {
+        (24, 0) => false,
+        (32, 0) => false,
+        (32, 3) => true,
+        _ => {
+            return Err(DecodeImageError::Unsupported {
+                feature: "BMP files that aren't uncompressed 24 or 32 bit",
+            })
+        }
+    };
+
+    let width = dimension(width as i64)?;
+    let top_down = height < 0;
+    let height = dimension((height as i64).abs())?;
+    let bytes_per_pixel = bits as usize / 8;
+    let row_len = width as usize * bytes_per_pixel;
+    let row_size = (row_len + 3) & !3;
+    // Every row ends before the last one does, so checking it covers the others.
+    if height > 0 {
+        let end = add(add(offset, mul(height as usize - 1, row_size)?)?, row_len)?;
+        if data.len() < end {
+            return Err(DecodeImageError::Corrupted);
+        }
+    }
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let row = if top_down { y } else { height as usize - 1 - y };
+        let start = offset + row * row_size;
+        let row = data
+            .get(start..start + row_len)
+            .ok_or(DecodeImageError::Corrupted)?;
+        for pixel in row.chunks_exact(bytes_per_pixel) {
+            let alpha = if has_alpha { pixel[3] } else { 0xFF };
+            pixels.push(argb(alpha, pixel[2], pixel[1], pixel[0]));
+        }
+    }
+    Ok(DecodedImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Decodes a PNG file.
+pub fn decode_png(data: &[u8]) -> Result<DecodedImage, DecodeImageError> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return Err(DecodeImageError::UnknownFormat);
+    }
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+    let mut at = PNG_SIGNATURE.len();
+    loop {
+        let len = u32_be(data, at)? as usize;
+        let kind = data
+            .get(add(at, 4)?..add(at, 8)?)
+            .ok_or(DecodeImageError::Corrupted)?;
+        let chunk = data
+            .get(add(at, 8)?..add(add(at, 8)?, len)?)
+            .ok_or(DecodeImageError::Corrupted)?;
+        // Chunks end with a CRC, which isn't checked.
+        at = add(add(at, 12)?, len)?;
+        match kind {
+            b"IHDR" => header = Some(chunk),
+            b"PLTE" => palette = chunk,
+            b"tRNS" => transparency = chunk,
+            b"IDAT" => compressed.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header
+        .filter(|header| header.len() == 13)
+        .ok_or(DecodeImageError::Corrupted)?;
+    let width = dimension(u32_be(header, 0)? as i64)?;
+    let height = dimension(u32_be(header, 4)? as i64)?;
+    let (depth, color_type, interlaced) = (header[8], header[9], header[12] != 0);
+    if depth != 8 || interlaced {
+        return Err(DecodeImageError::Unsupported {
+            feature: "Interlaced PNG files and PNG files without 8 bit channels",
+        });
+    }
+    let channels = match color_type {
+        0 => 1,
+        2 => 3,
+        3 => 1,
+        4 => 2,
+        6 => 4,
+        _ => return Err(DecodeImageError::Corrupted),
+    };
+
+    // The image data is a zlib stream: a two byte header, deflate data, and a checksum that isn't checked.
+    let deflated = compressed.get(2..).ok_or(DecodeImageError::Corrupted)?;
+    if compressed[0] & 0x0F != 8 || compressed[1] & 0x20 != 0 {
+        return Err(DecodeImageError::Corrupted);
+    }
+    let stride = width as usize * channels;
+    let raw_len = mul(stride + 1, height as usize)?;
+    let mut raw = inflate(deflated, raw_len)?;
+    if raw.len() < raw_len {
+        return Err(DecodeImageError::Corrupted);
+    }
+    unfilter(&mut raw, stride, channels, height as usize)?;
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let row = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        for pixel in row.chunks_exact(channels) {
+            pixels.push(match color_type {
+                0 => argb(0xFF, pixel[0], pixel[0], pixel[0]),
+                2 => argb(0xFF, pixel[0], pixel[1], pixel[2]),
+                3 => {
+                    let index = pixel[0] as usize;
+                    let color = palette
+                        .get(index * 3..index * 3 + 3)
+                        .ok_or(DecodeImageError::Corrupted)?;
+                    let alpha = transparency.get(index).copied().unwrap_or(0xFF);
+                    argb(alpha, color[0], color[1], color[2])
+                }
+                4 => argb(pixel[1], pixel[0], pixel[0], pixel[0]),
+                _ => argb(pixel[3], pixel[0], pixel[1], pixel[2]),
+            });
+        }
+    }
+    Ok(DecodedImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Undoes PNG's per-row filters in place. Each row starts with a byte saying which filter it used.
+fn unfilter(
+    raw: &mut [u8],
+    stride: usize,
+    channels: usize,
+    height: usize,
+) -> Result<(), DecodeImageError> {
+    for y in 0..height {
+        let start = y * (stride + 1);
+        let filter = raw[start];
+        for x in 0..stride {
+            let at = start + 1 + x;
+            let left = if x >= channels { raw[at - channels] } else { 0 };
+            let up = if y > 0 { raw[at - stride - 1] } else { 0 };
+            let up_left = if y > 0 && x >= channels {
+                raw[at - stride - 1 - channels]
+            } else {
+                0
+            };
+            let predicted = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => return Err(DecodeImageError::Corrupted),
+            };
+            raw[at] = raw[at].wrapping_add(predicted);
+        }
+    }
+    Ok(())
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance = |value: u8| (estimate - value as i16).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Reads a deflate stream one bit at a time, least significant bit first.
+struct Bits<'a> {
+    data: &'a [u8],
+    at: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl Bits<'_> {
+    fn read(&mut self, count: u32) -> Result<u32, DecodeImageError> {
+        while self.count < count {
+            let byte = *self.data.get(self.at).ok_or(DecodeImageError::Corrupted)?;
+            self.at += 1;
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1 << count) - 1);
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    /// Skips to the start of the next byte.
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+const MAX_CODE_LEN: usize = 15;
+
+/// A canonical Huffman code, stored as how many codes there are of each length
+/// and the symbols in code order.
+struct Huffman {
+    counts: [u16; MAX_CODE_LEN + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_CODE_LEN + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; MAX_CODE_LEN + 2];
+        for len in 1..=MAX_CODE_LEN {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut Bits<'_>) -> Result<u16, DecodeImageError> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..=MAX_CODE_LEN {
+            code |= bits.read(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - count < first {
+                return self
+                    .symbols
+                    .get((index + code - first) as usize)
+                    .copied()
+                    .ok_or(DecodeImageError::Corrupted);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(DecodeImageError::Corrupted)
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order code length code lengths are stored in.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompresses a raw deflate stream, failing if it holds more than `limit` bytes.
+fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>, DecodeImageError> {
+    let mut bits = Bits {
+        data,
+        at: 0,
+        buffer: 0,
+        count: 0,
+    };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => {
+                bits.align();
+                let len = u16_le(data, bits.at)? as usize;
+                let start = add(bits.at, 4)?;
+                let end = add(start, len)?;
+                let stored = data.get(start..end).ok_or(DecodeImageError::Corrupted)?;
+                if out.len() + len > limit {
+                    return Err(DecodeImageError::Corrupted);
+                }
+                out.extend_from_slice(stored);
+                bits.at = end;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let literals = Huffman::new(&lengths);
+                let distances = Huffman::new(&[5; 30]);
+                inflate_block(&mut bits, &mut out, limit, &literals, &distances)?;
+            }
+            2 => {
+                let literal_count = bits.read(5)? as usize + 257;
+                let distance_count = bits.read(5)? as usize + 1;
+                let code_length_count = bits.read(4)? as usize + 4;
+
+                let mut code_lengths = [0u8; 19];
+                for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+                    code_lengths[index] = bits.read(3)? as u8;
+                }
+                let code_lengths = Huffman::new(&code_lengths);
+
+                let mut lengths = vec![0u8; literal_count + distance_count];
+                let mut index = 0;
+                while index < lengths.len() {
+                    let (value, repeat) = match code_lengths.decode(&mut bits)? {
+                        symbol @ 0..=15 => (symbol as u8, 1),
+                        16 => {
+                            let previous = *lengths
+                                .get(index.wrapping_sub(1))
+                                .ok_or(DecodeImageError::Corrupted)?;
+                            (previous, 3 + bits.read(2)? as usize)
+                        }
+                        17 => (0, 3 + bits.read(3)? as usize),
+                        _ => (0, 11 + bits.read(7)? as usize),
+                    };
+                    let end = index + repeat;
+                    lengths
+                        .get_mut(index..end)
+                        .ok_or(DecodeImageError::Corrupted)?
+                        .fill(value);
+                    index = end;
+                }
+                let literals = Huffman::new(&lengths[..literal_count]);
+                let distances = Huffman::new(&lengths[literal_count..]);
+                inflate_block(&mut bits, &mut out, limit, &literals, &distances)?;
+            }
+            _ => return Err(DecodeImageError::Corrupted),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn inflate_block(
+    bits: &mut Bits<'_>,
+    out: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), DecodeImageError> {
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+        if symbol < 256 {
+            if out.len() >= limit {
+                return Err(DecodeImageError::Corrupted);
+            }
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let symbol = symbol - 257;
+        if symbol >= LENGTH_BASE.len() {
+            return Err(DecodeImageError::Corrupted);
+        }
+        let len = LENGTH_BASE[symbol] as usize + bits.read(LENGTH_EXTRA[symbol] as u32)? as usize;
+        let symbol = distances.decode(bits)? as usize;
+        if symbol >= DISTANCE_BASE.len() {
+            return Err(DecodeImageError::Corrupted);
+        }
+        let distance =
+            DISTANCE_BASE[symbol] as usize + bits.read(DISTANCE_EXTRA[symbol] as u32)? as usize;
+        if distance > out.len() || out.len() + len > limit {
+            return Err(DecodeImageError::Corrupted);
+        }
+        // The run can overlap the bytes it is copying, so it is copied one byte at a time.
+        let start = out.len() - distance;
+        for index in start..start + len {
+            out.push(out[index]);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Snafu)]
+pub enum DecodeImageError {
+    #[snafu(display("The file isn't a BMP or PNG image."))]
+    UnknownFormat,
+    #[snafu(display("The image is corrupted."))]
+    Corrupted,
+    #[snafu(display("{feature} aren't supported."))]
+    Unsupported { feature: &'static str },
+}
+impl core::error::Error for DecodeImageError {}
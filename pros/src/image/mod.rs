@@ -0,0 +1,121 @@
+//! Images for drawing on the brain's screen, such as team logos and field diagrams.
+//!
+//! Images can be decoded from BMP and PNG files on the SD card while the program runs,
+//! or converted when the program is built with [`include_image!`] so they are stored in flash
+//! and cost no time to load:
+//! ```rust
+//! static LOGO: Image<'static> = include_image!("assets/logo.png");
+//!
+//! let mut screen = Framebuffer::full_screen();
+//! screen.draw_image(0, 0, &LOGO);
+//! screen.draw_image(240, 0, &Image::load("field.bmp")?);
+//! screen.flush(0, 0)?;
+//! ```
+//!
+//! See [`decode`] for which kinds of files are supported.
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use snafu::Snafu;
+
+use crate::usd::{self, UsdError};
+
+pub mod decode;
+
+pub use decode::DecodeImageError;
+/// Converts a BMP or PNG file to an [`Image`] when the program is built.
+///
+/// The path is relative to the directory containing the crate's `Cargo.toml`.
+pub use pros_macros::include_image;
+
+/// An image made of 32-bit `0xAARRGGBB` pixels, stored row by row from the top left.
+/// An alpha of `0xFF` is opaque and `0x00` is fully transparent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image<'a> {
+    width: u16,
+    height: u16,
+    pixels: Cow<'a, [u32]>,
+}
+
+impl<'a> Image<'a> {
+    /// Creates an image from pixels that live for as long as the image, such as those in a `static`.
+    /// This is what [`include_image!`] expands to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there aren't exactly `width * height` pixels.
+    pub const fn from_static(width: u16, height: u16, pixels: &'a [u32]) -> Self {
+        assert!(
+            pixels.len() == width as usize * height as usize,
+            "the number of pixels should be the image's width times its height"
+        );
+        Self {
+            width,
+            height,
+            pixels: Cow::Borrowed(pixels),
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns every pixel, row by row from the top left.
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    /// Returns the pixel at (x, y), or `None` if it is outside of the image.
+    pub fn pixel(&self, x: u16, y: u16) -> Option<u32> {
+        (x < self.width && y < self.height)
+            .then(|| self.pixels[y as usize * self.width as usize + x as usize])
+    }
+}
+
+impl Image<'static> {
+    /// Creates an image that owns its pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there aren't exactly `width * height` pixels.
+    pub fn from_pixels(width: u16, height: u16, pixels: Vec<u32>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize,
+            "the number of pixels should be the image's width times its height"
+        );
+        Self {
+            width,
+            height,
+            pixels: Cow::Owned(pixels),
+        }
+    }
+
+    /// Decodes a BMP or PNG file.
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeImageError> {
+        let decoded = decode::decode(data)?;
+        Ok(Self::from_pixels(
+            decoded.width,
+            decoded.height,
+            decoded.pixels,
+        ))
+    }
+
+    /// Loads a BMP or PNG file from the SD card.
+    pub fn load(path: &str) -> Result<Self, ImageError> {
+        Ok(Self::decode(&usd::read(path)?)?)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ImageError {
+    #[snafu(display("{source}"), context(false))]
+    Decode { source: DecodeImageError },
+    #[snafu(display("{source}"), context(false))]
+    Usd { source: UsdError },
+}
+impl core::error::Error for ImageError {}
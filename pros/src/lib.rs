@@ -1,26 +1,82 @@
 #![feature(error_in_core, stdsimd)]
 #![cfg_attr(not(target_arch = "wasm32"), no_std)]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+// Lets the derive macros' `::pros::...` paths resolve when they are used inside this crate.
+extern crate self as pros;
 
+pub mod actuator;
+#[cfg(feature = "alloc")]
+pub mod async_runtime;
+pub mod auton;
+#[cfg(feature = "alloc")]
+pub mod cancel;
+#[cfg(feature = "alloc")]
+pub mod command;
+pub mod competition;
+#[cfg(feature = "compression")]
+pub mod compress;
+#[cfg(feature = "alloc")]
+pub mod config;
+pub mod control;
 pub mod controller;
+#[cfg(feature = "alloc")]
+pub mod diagnostics;
+#[cfg(feature = "alloc")]
+pub mod drivetrain;
+#[cfg(feature = "alloc")]
+pub mod encode;
 pub mod error;
+pub mod fixed;
+#[cfg(feature = "alloc")]
+pub mod image;
+#[cfg(feature = "alloc")]
+pub mod lights;
+#[cfg(feature = "alloc")]
+pub mod logger;
+pub mod math;
 pub mod motor;
+#[cfg(feature = "alloc")]
+pub mod odometry;
+#[cfg(feature = "alloc")]
+pub mod path;
 pub mod pid;
+pub mod poll;
+#[cfg(feature = "alloc")]
+pub mod port_map;
+pub mod pose;
 pub mod position;
+pub mod profile;
+pub mod rand;
+#[cfg(feature = "alloc")]
+pub mod routines;
+#[cfg(feature = "alloc")]
+pub mod screen;
 pub mod sensors;
+pub mod serial;
+#[cfg(feature = "alloc")]
+pub mod shell;
+#[cfg(feature = "alloc")]
+pub mod sound;
+#[cfg(feature = "alloc")]
+pub mod subsystems;
 pub mod sync;
 pub mod task;
+#[cfg(feature = "alloc")]
+pub mod testing;
+#[cfg(feature = "alloc")]
+pub mod usd;
 
 #[doc(hidden)]
 pub use pros_sys as __pros_sys;
 
 #[cfg(target_os = "vexos")]
 mod vexos_env;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "alloc"))]
 mod wasm_env;
 
-#[cfg(not(feature = "lvgl"))]
+#[cfg(all(not(feature = "lvgl"), feature = "alloc"))]
 #[macro_use]
 pub mod lcd;
 
@@ -29,9 +85,14 @@ pub mod lcd;
 pub mod lvgl;
 
 pub mod adi;
+#[cfg(feature = "alloc")]
 pub mod link;
 
+#[cfg(feature = "alloc")]
 pub type Result<T = ()> = core::result::Result<T, alloc::boxed::Box<dyn core::error::Error>>;
+/// Without `alloc`, errors can't be boxed, so robot code returns references to errors stored in statics.
+#[cfg(not(feature = "alloc"))]
+pub type Result<T = ()> = core::result::Result<T, &'static (dyn core::error::Error + Sync)>;
 
 pub trait Robot {
     fn opcontrol(&mut self) -> Result {
@@ -46,6 +107,12 @@ pub trait Robot {
     fn comp_init(&mut self) -> Result {
         Ok(())
     }
+    /// The kind of run the robot is doing, usually chosen with an [`auton::selector::Selector`]
+    /// or read from the SD card with [`auton::RunMode::load`].
+    /// Programming skills runs started without field control run autonomous before opcontrol.
+    fn run_mode(&self) -> auton::RunMode {
+        auton::RunMode::Match
+    }
 }
 
 #[doc(hidden)]
@@ -57,23 +124,32 @@ macro_rules! __gen_exports {
         #[doc(hidden)]
         #[no_mangle]
         extern "C" fn opcontrol() {
-            <$rbt as $crate::Robot>::opcontrol(unsafe {
+            let robot = unsafe {
                 ROBOT
                     .as_mut()
                     .expect("Expected initialize to run before opcontrol")
-            })
-            .unwrap();
+            };
+            if <$rbt as $crate::Robot>::run_mode(robot) == $crate::auton::RunMode::ProgrammingSkills
+                && !$crate::competition::is_connected()
+            {
+                $crate::competition::__set_autonomous_running(true);
+                <$rbt as $crate::Robot>::auto(robot).unwrap();
+                $crate::competition::__set_autonomous_running(false);
+            }
+            <$rbt as $crate::Robot>::opcontrol(robot).unwrap();
         }
 
         #[doc(hidden)]
         #[no_mangle]
         extern "C" fn autonomous() {
+            $crate::competition::__set_autonomous_running(true);
             <$rbt as $crate::Robot>::auto(unsafe {
                 ROBOT
                     .as_mut()
                     .expect("Expected initialize to run before auto")
             })
             .unwrap();
+            $crate::competition::__set_autonomous_running(false);
         }
 
         #[doc(hidden)]
@@ -169,14 +245,18 @@ macro_rules! robot {
 pub mod prelude {
     pub use crate::robot;
     pub use crate::Robot;
+    #[cfg(feature = "alloc")]
     pub use crate::{print, println};
 
     pub use crate::controller::*;
     pub use crate::error::PortError;
+    #[cfg(all(not(feature = "lvgl"), feature = "alloc"))]
     pub use crate::lcd::{buttons::Button, LcdError};
+    #[cfg(feature = "alloc")]
     pub use crate::link::*;
     pub use crate::motor::*;
     pub use crate::pid::*;
+    pub use crate::pose::*;
     pub use crate::position::*;
     pub use crate::sensors::distance::*;
     pub use crate::sensors::gps::*;
@@ -0,0 +1,180 @@
+//! Built-in animations and ways of combining them.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{ops::Range, time::Duration};
+
+use super::Animation;
+use crate::adi::Rgb;
+
+/// How far through a repeating cycle of `period` the time is, from 0.0 to 1.0.
+fn phase(time: Duration, period: Duration) -> f32 {
+    let period = period.as_millis().max(1) as u64;
+    (time.as_millis() as u64 % period) as f32 / period as f32
+}
+
+/// Every LED the same color.
+#[derive(Debug, Clone, Copy)]
+pub struct Solid(pub Rgb);
+
+impl Animation for Solid {
+    fn render(&mut self, _time: Duration, frame: &mut [Rgb]) {
+        frame.fill(self.0);
+    }
+}
+
+/// A rainbow that scrolls along the strip.
+#[derive(Debug, Clone, Copy)]
+pub struct Rainbow {
+    /// How long the rainbow takes to scroll through every hue.
+    pub period: Duration,
+    /// How many degrees of hue the strip shows at once.
+    pub spread: f32,
+}
+
+impl Default for Rainbow {
+    fn default() -> Self {
+        Self {
+            period: Duration::from_secs(3),
+            spread: 360.0,
+        }
+    }
+}
+
+impl Animation for Rainbow {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        let offset = phase(time, self.period) * 360.0;
+        let step = self.spread / frame.len().max(1) as f32;
+        for (index, pixel) in frame.iter_mut().enumerate() {
+            *pixel = Rgb::from_hsv(offset + index as f32 * step, 1.0, 1.0);
+        }
+    }
+}
+
+/// A block of color that runs along the strip and wraps around.
+#[derive(Debug, Clone, Copy)]
+pub struct Chase {
+    pub color: Rgb,
+    pub background: Rgb,
+    /// How many LEDs the block covers.
+    pub length: usize,
+    /// How long the block takes to go around the strip once.
+    pub period: Duration,
+}
+
+impl Animation for Chase {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        let len = frame.len();
+        let head = (phase(time, self.period) * len as f32) as usize;
+        for (index, pixel) in frame.iter_mut().enumerate() {
+            let behind = (head + len - index) % len.max(1);
+            *pixel = if behind < self.length {
+                self.color
+            } else {
+                self.background
+            };
+        }
+    }
+}
+
+/// A color that slowly fades in and out.
+#[derive(Debug, Clone, Copy)]
+pub struct Breathing {
+    pub color: Rgb,
+    /// How long one breath takes.
+    pub period: Duration,
+    /// The dimmest the color gets, from 0.0 to 1.0.
+    pub min_brightness: f32,
+}
+
+impl Animation for Breathing {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        let wave = (1.0 - libm::cosf(phase(time, self.period) * 2.0 * core::f32::consts::PI)) / 2.0;
+        let brightness = self.min_brightness + (1.0 - self.min_brightness) * wave;
+        frame.fill(self.color.scale(brightness));
+    }
+}
+
+/// Fills the strip in proportion to a value from 0.0 to 1.0, such as a flywheel's speed or match time.
+pub struct ProgressBar<F> {
+    pub color: Rgb,
+    pub background: Rgb,
+    pub progress: F,
+}
+
+impl<F: FnMut() -> f32 + Send> Animation for ProgressBar<F> {
+    fn render(&mut self, _time: Duration, frame: &mut [Rgb]) {
+        let lit = (self.progress)().clamp(0.0, 1.0) * frame.len() as f32;
+        for (index, pixel) in frame.iter_mut().enumerate() {
+            let fill = (lit - index as f32).clamp(0.0, 1.0);
+            *pixel = self.background.lerp(self.color, fill);
+        }
+    }
+}
+
+/// Shows one animation while a condition holds and another otherwise,
+/// for binding lights to robot state such as whether the intake is holding a game piece.
+pub struct When<F, A, B> {
+    pub condition: F,
+    pub then: A,
+    pub otherwise: B,
+}
+
+impl<F, A, B> Animation for When<F, A, B>
+where
+    F: FnMut() -> bool + Send,
+    A: Animation,
+    B: Animation,
+{
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        if (self.condition)() {
+            self.then.render(time, frame);
+        } else {
+            self.otherwise.render(time, frame);
+        }
+    }
+}
+
+/// Dims an animation by a factor from 0.0 to 1.0.
+pub struct Dimmed<A> {
+    pub animation: A,
+    pub brightness: f32,
+}
+
+impl<A: Animation> Animation for Dimmed<A> {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        self.animation.render(time, frame);
+        for pixel in frame.iter_mut() {
+            *pixel = pixel.scale(self.brightness);
+        }
+    }
+}
+
+/// Runs different animations on different parts of the strip.
+/// LEDs not covered by any segment are turned off, and segments past the end of the strip are cut off.
+#[derive(Default)]
+pub struct Segments {
+    segments: Vec<(Range<usize>, Box<dyn Animation>)>,
+}
+
+impl Segments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an animation for the LEDs in `range`.
+    pub fn with(mut self, range: Range<usize>, animation: impl Animation + 'static) -> Self {
+        self.segments.push((range, Box::new(animation)));
+        self
+    }
+}
+
+impl Animation for Segments {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        frame.fill(Rgb::BLACK);
+        let len = frame.len();
+        for (range, animation) in self.segments.iter_mut() {
+            let range = range.start.min(len)..range.end.min(len);
+            animation.render(time, &mut frame[range]);
+        }
+    }
+}
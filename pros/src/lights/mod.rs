@@ -0,0 +1,112 @@
+//! Animating addressable LED strips.
+//!
+//! An [`Animation`] draws a frame of colors for a point in time. Animations can be combined,
+//! for example with [`Segments`](animation::Segments) to run different ones on parts of a strip
+//! or [`When`](animation::When) to switch between them based on the robot's state.
+//!
+//! A [`LedAnimator`] renders an animation to an [`AddrLed`] strip, either when ticked by the caller
+//! or from a background task started with [`LedAnimator::spawn`].
+//! [`StatusLights`](status::StatusLights) is a ready-made animation showing the alliance and match state.
+
+pub mod animation;
+pub mod status;
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::time::Duration;
+
+use crate::{
+    adi::{AddrLed, AdiError, Rgb},
+    sync::Mutex,
+    task,
+};
+
+/// Something that can be shown on an LED strip.
+pub trait Animation: Send {
+    /// Draws the frame `time` after the animation started, with one color per LED.
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]);
+}
+
+impl<A: Animation + ?Sized> Animation for Box<A> {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        (**self).render(time, frame);
+    }
+}
+
+/// Plays an [`Animation`] on an LED strip.
+pub struct LedAnimator {
+    led: AddrLed,
+    animation: Box<dyn Animation>,
+    started: u32,
+    frame: Vec<Rgb>,
+}
+
+impl LedAnimator {
+    /// How often the background task started by [`LedAnimator::spawn`] draws a frame.
+    pub const FRAME_INTERVAL: Duration = Duration::from_millis(20);
+
+    pub fn new(led: AddrLed, animation: impl Animation + 'static) -> Self {
+        let frame = vec![Rgb::BLACK; led.len()];
+        Self {
+            led,
+            animation: Box::new(animation),
+            started: unsafe { pros_sys::millis() },
+            frame,
+        }
+    }
+
+    /// Switches to a different animation, starting it from the beginning.
+    pub fn set_animation(&mut self, animation: impl Animation + 'static) {
+        self.animation = Box::new(animation);
+        self.started = unsafe { pros_sys::millis() };
+    }
+
+    pub fn led(&self) -> &AddrLed {
+        &self.led
+    }
+
+    pub fn led_mut(&mut self) -> &mut AddrLed {
+        &mut self.led
+    }
+
+    /// Draws the current frame and sends it to the strip.
+    pub fn tick(&mut self) -> Result<(), AdiError> {
+        let time = Duration::from_millis((unsafe { pros_sys::millis() } - self.started) as u64);
+        self.animation.render(time, &mut self.frame);
+        self.led.pixels_mut().copy_from_slice(&self.frame);
+        self.led.show()
+    }
+
+    /// Ticks the animator from a background task every [`LedAnimator::FRAME_INTERVAL`].
+    /// The task stops once the returned handle is dropped.
+    pub fn spawn(self) -> AnimatorHandle {
+        let animator = Arc::new(Mutex::new(self));
+        let weak = Arc::downgrade(&animator);
+        task::spawn(move || {
+            while let Some(animator) = weak.upgrade() {
+                if let Err(err) = animator.lock().tick() {
+                    crate::error::report(&err);
+                }
+                drop(animator);
+                task::sleep(Self::FRAME_INTERVAL);
+            }
+        });
+        AnimatorHandle { animator }
+    }
+}
+
+/// Controls an animator running in a background task.
+pub struct AnimatorHandle {
+    animator: Arc<Mutex<LedAnimator>>,
+}
+
+impl AnimatorHandle {
+    /// Switches to a different animation, starting it from the beginning.
+    pub fn set_animation(&self, animation: impl Animation + 'static) {
+        self.animator.lock().set_animation(animation);
+    }
+
+    /// Runs `f` with the animator locked, for example to change the strip's current limit.
+    pub fn with<T>(&self, f: impl FnOnce(&mut LedAnimator) -> T) -> T {
+        f(&mut self.animator.lock())
+    }
+}
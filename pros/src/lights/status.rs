@@ -0,0 +1,102 @@
+//! Showing the robot's alliance and match state on an LED strip.
+
+use core::time::Duration;
+
+use super::{
+    animation::{Breathing, ProgressBar, Solid},
+    Animation, AnimatorHandle, LedAnimator,
+};
+use crate::{
+    adi::{AddrLed, Rgb},
+    auton::{Alliance, RunMode},
+    competition::{self, CompetitionMode},
+    error,
+};
+
+/// An [`Animation`] that shows the robot's state at a glance:
+///
+/// - While disabled, the alliance color breathes slowly.
+/// - During autonomous, the strip counts down the time left in the alliance color.
+/// - During driver control, the alliance color is solid.
+/// - For a few seconds after an error is reported with [`error::report`], the strip flashes orange.
+pub struct StatusLights {
+    alliance: Alliance,
+    autonomous_length: Duration,
+    error_duration: Duration,
+    disabled: Breathing,
+}
+
+impl StatusLights {
+    /// How long the strip flashes after an error is reported, by default.
+    pub const ERROR_DURATION: Duration = Duration::from_secs(3);
+    /// The color of error flashes.
+    pub const ERROR_COLOR: Rgb = Rgb::ORANGE;
+
+    pub fn new(alliance: Alliance) -> Self {
+        Self {
+            alliance,
+            autonomous_length: RunMode::Match.autonomous_length(),
+            error_duration: Self::ERROR_DURATION,
+            disabled: Breathing {
+                color: Self::alliance_color(alliance),
+                period: Duration::from_secs(3),
+                min_brightness: 0.1,
+            },
+        }
+    }
+
+    /// Counts down autonomous over the length for the given kind of run.
+    pub fn with_run_mode(mut self, run_mode: RunMode) -> Self {
+        self.autonomous_length = run_mode.autonomous_length();
+        self
+    }
+
+    /// Sets how long the strip flashes after an error is reported.
+    pub fn with_error_duration(mut self, duration: Duration) -> Self {
+        self.error_duration = duration;
+        self
+    }
+
+    pub fn alliance_color(alliance: Alliance) -> Rgb {
+        match alliance {
+            Alliance::Red => Rgb::RED,
+            Alliance::Blue => Rgb::BLUE,
+        }
+    }
+
+    /// Shows the status on a strip from a background task.
+    pub fn spawn(self, led: AddrLed) -> AnimatorHandle {
+        LedAnimator::new(led, self).spawn()
+    }
+}
+
+impl Animation for StatusLights {
+    fn render(&mut self, time: Duration, frame: &mut [Rgb]) {
+        let now = unsafe { pros_sys::millis() };
+        let error_shown = error::last_report_time()
+            .is_some_and(|reported| now - reported < self.error_duration.as_millis() as u32);
+        if error_shown {
+            let on = (now / 250) % 2 == 0;
+            frame.fill(if on { Self::ERROR_COLOR } else { Rgb::BLACK });
+            return;
+        }
+
+        let color = Self::alliance_color(self.alliance);
+        match competition::mode() {
+            CompetitionMode::Disabled => self.disabled.render(time, frame),
+            CompetitionMode::Autonomous => {
+                let length = self.autonomous_length.as_secs_f32();
+                let elapsed = competition::autonomous_elapsed()
+                    .unwrap_or_default()
+                    .as_secs_f32();
+                ProgressBar {
+                    color,
+                    background: Rgb::BLACK,
+                    progress: || 1.0 - elapsed / length,
+                }
+                .render(time, frame);
+            }
+            CompetitionMode::Opcontrol => Solid(color).render(time, frame),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Typed, acknowledged messages between robots.
+//!
+//! Every message type has a topic ID so that several kinds of messages can share one link.
+//! Messages can be sent best effort with [`Bus::publish`], or with [`Bus::send_reliable`],
+//! which retransmits them until the other robot acknowledges them.
+//! Both robots send periodic heartbeats so each can tell whether the other is still connected.
+//!
+//! ```rust
+//! struct SharedPose(Pose);
+//! impl Message for SharedPose {
+//!     const TOPIC: u16 = 1;
+//! }
+//! ```
+
+use alloc::{collections::VecDeque, vec::Vec};
+use core::time::Duration;
+
+use no_std_io::io;
+use snafu::Snafu;
+
+pub use crate::encode::Message;
+use crate::encode::{self, DecodeError, FrameReader};
+
+/// Timing settings for a [`Bus`].
+#[derive(Debug, Clone, Copy)]
+pub struct BusConfig {
+    /// How long to wait for an acknowledgement before resending a reliable message.
+    pub retry_interval: Duration,
+    /// How many times a reliable message is resent before it is given up on.
+    pub max_retries: u32,
+    /// How often to send a heartbeat.
+    pub heartbeat_interval: Duration,
+    /// How long without hearing anything before the other robot is considered disconnected.
+    pub peer_timeout: Duration,
+}
+
+impl Default for BusConfig {
+    fn default() -> Self {
+        Self {
+            retry_interval: Duration::from_millis(100),
+            max_retries: 5,
+            heartbeat_interval: Duration::from_millis(250),
+            peer_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+const KIND_DATA: u8 = 0;
+const KIND_RELIABLE: u8 = 1;
+const KIND_ACK: u8 = 2;
+const KIND_HEARTBEAT: u8 = 3;
+
+/// How many recently received reliable sequence numbers are remembered to drop duplicates.
+const RECENT_SEQUENCES: usize = 32;
+
+struct Pending {
+    sequence: u16,
+    frame: Vec<u8>,
+    last_sent: u32,
+    retries: u32,
+}
+
+/// A message bus over any byte stream, usually a VEXLink.
+/// [`Bus::poll`] must be called periodically to receive messages, resend them, and send heartbeats.
+pub struct Bus<T> {
+    transport: T,
+    config: BusConfig,
+    reader: FrameReader,
+    next_sequence: u16,
+    pending: Vec<Pending>,
+    inbox: VecDeque<(u16, Vec<u8>)>,
+    recent: VecDeque<u16>,
+    last_heard: Option<u32>,
+    last_heartbeat: Option<u32>,
+    failed: u32,
+}
+
+impl<T: io::Read + io::Write> Bus<T> {
+    pub fn new(transport: T) -> Self {
+        Self::with_config(transport, BusConfig::default())
+    }
+
+    pub fn with_config(transport: T, config: BusConfig) -> Self {
+        Self {
+            transport,
+            config,
+            reader: FrameReader::new(),
+            next_sequence: 0,
+            pending: Vec::new(),
+            inbox: VecDeque::new(),
+            recent: VecDeque::with_capacity(RECENT_SEQUENCES),
+            last_heard: None,
+            last_heartbeat: None,
+            failed: 0,
+        }
+    }
+
+    fn send_packet(
+        &mut self,
+        kind: u8,
+        sequence: u16,
+        topic: u16,
+        data: &[u8],
+    ) -> Result<Vec<u8>, BusError> {
+        let mut encoder = encode::Encoder::new();
+        encoder.write(&(kind, sequence, topic));
+        encoder.write(data);
+        let frame = encode::frame(&encoder.into_inner());
+        self.transport
+            .write_all(&frame)
+            .map_err(|_| BusError::Transmit)?;
+        Ok(frame)
+    }
+
+    /// Sends a message once, without checking that it arrived.
+    /// This suits messages that are sent constantly, like poses, where a newer one is always on the way.
+    pub fn publish<M: Message>(&mut self, message: &M) -> Result<(), BusError> {
+        self.send_packet(KIND_DATA, 0, M::TOPIC, &encode::to_vec(message))?;
+        Ok(())
+    }
+
+    /// Sends a message and keeps resending it during [`Bus::poll`] until the other robot acknowledges it.
+    pub fn send_reliable<M: Message>(&mut self, message: &M) -> Result<(), BusError> {
+        let sequence = self.next_sequence;
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+
+        let frame =
+            self.send_packet(KIND_RELIABLE, sequence, M::TOPIC, &encode::to_vec(message))?;
+        self.pending.push(Pending {
+            sequence,
+            frame,
+            last_sent: unsafe { pros_sys::millis() },
+            retries: 0,
+        });
+        Ok(())
+    }
+
+    /// Reads incoming messages, resends unacknowledged messages, and sends heartbeats.
+    pub fn poll(&mut self) -> Result<(), BusError> {
+        let now = unsafe { pros_sys::millis() };
+
+        // Read errors just mean there is nothing to read right now;
+        // a link that has actually gone down shows up as a missing peer.
+        let mut buf = [0; 64];
+        while let Ok(read @ 1..) = self.transport.read(&mut buf) {
+            self.reader.push(&buf[..read]);
+        }
+
+        while let Some(payload) = self.reader.next_frame() {
+            let mut decoder = encode::Decoder::new(&payload);
+            let Ok((kind, sequence, topic)) = decoder.read::<(u8, u16, u16)>() else {
+                continue;
+            };
+            let Ok(data) = decoder.read::<Vec<u8>>() else {
+                continue;
+            };
+            self.last_heard = Some(now);
+
+            match kind {
+                KIND_DATA => self.inbox.push_back((topic, data)),
+                KIND_RELIABLE => {
+                    self.send_packet(KIND_ACK, sequence, topic, &[])?;
+                    if !self.recent.contains(&sequence) {
+                        if self.recent.len() == RECENT_SEQUENCES {
+                            self.recent.pop_front();
+                        }
+                        self.recent.push_back(sequence);
+                        self.inbox.push_back((topic, data));
+                    }
+                }
+                KIND_ACK => self.pending.retain(|pending| pending.sequence != sequence),
+                _ => {}
+            }
+        }
+
+        let retry_interval = self.config.retry_interval.as_millis() as u32;
+        let mut index = 0;
+        while index < self.pending.len() {
+            let pending = &mut self.pending[index];
+            if now - pending.last_sent < retry_interval {
+                index += 1;
+            } else if pending.retries >= self.config.max_retries {
+                self.pending.remove(index);
+                self.failed += 1;
+            } else {
+                pending.retries += 1;
+                pending.last_sent = now;
+                self.transport
+                    .write_all(&pending.frame)
+                    .map_err(|_| BusError::Transmit)?;
+                index += 1;
+            }
+        }
+
+        let heartbeat_due = match self.last_heartbeat {
+            Some(last) => now - last >= self.config.heartbeat_interval.as_millis() as u32,
+            None => true,
+        };
+        if heartbeat_due {
+            self.send_packet(KIND_HEARTBEAT, 0, 0, &[])?;
+            self.last_heartbeat = Some(now);
+        }
+
+        Ok(())
+    }
+
+    /// Takes the oldest received message of type `M`, if there is one.
+    pub fn receive<M: Message>(&mut self) -> Option<Result<M, DecodeError>> {
+        let index = self
+            .inbox
+            .iter()
+            .position(|(topic, _)| *topic == M::TOPIC)?;
+        let (_, data) = self.inbox.remove(index)?;
+        Some(encode::from_slice(&data))
+    }
+
+    /// Returns true if anything has been heard from the other robot recently.
+    pub fn is_peer_alive(&self) -> bool {
+        let now = unsafe { pros_sys::millis() };
+        self.last_heard
+            .is_some_and(|last| now - last < self.config.peer_timeout.as_millis() as u32)
+    }
+
+    /// Returns how many reliable messages are still waiting to be acknowledged.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns how many reliable messages were given up on after running out of retries.
+    pub fn failed(&self) -> u32 {
+        self.failed
+    }
+
+    /// Returns the underlying transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum BusError {
+    #[snafu(display("A message could not be transmitted."))]
+    Transmit,
+}
+impl core::error::Error for BusError {}
@@ -1,4 +1,8 @@
 //! Connect to VEXLink for robot-to-robot communication.
+//!
+//! The radio's role only decides which robot hosts the connection;
+//! both [`RxLink`] and [`TxLink`] can send and receive once connected.
+//! Use [`bus::Bus`] for typed, acknowledged messages on top of a link.
 
 use core::ffi::CStr;
 
@@ -9,6 +13,35 @@ use snafu::Snafu;
 
 use crate::error::{bail_errno, bail_on, map_errno, FromErrno, PortError};
 
+pub mod bus;
+
+fn receive_raw(port: u8, buf: &mut [u8]) -> Result<u32, LinkError> {
+    const PROS_ERR_U32: u32 = pros_sys::PROS_ERR as _;
+
+    match unsafe { link_receive(port, buf.as_mut_ptr().cast(), buf.len() as _) } {
+        PROS_ERR_U32 => {
+            bail_errno!();
+            unreachable!("Expected errno to be set");
+        }
+        0 => Err(LinkError::Busy),
+        n => Ok(n),
+    }
+}
+
+fn transmit_raw(port: u8, buf: &[u8]) -> Result<u32, LinkError> {
+    const PROS_ERR_U32: u32 = pros_sys::PROS_ERR as _;
+
+    match unsafe { link_transmit(port, buf.as_ptr().cast(), buf.len() as _) } {
+        PROS_ERR_U32 => {
+            let errno = crate::error::take_errno();
+            Err(FromErrno::from_errno(errno)
+                .unwrap_or_else(|| panic!("Unknown errno code {errno}")))
+        }
+        0 => Err(LinkError::Busy),
+        n => Ok(n),
+    }
+}
+
 pub trait Link {
     fn port(&self) -> u8;
     fn id(&self) -> &CStr;
@@ -49,16 +82,12 @@ impl RxLink {
     }
 
     pub fn receive(&self, buf: &mut [u8]) -> Result<u32, LinkError> {
-        const PROS_ERR_U32: u32 = pros_sys::PROS_ERR as _;
-
-        match unsafe { link_receive(self.port, buf.as_mut_ptr().cast(), buf.len() as _) } {
-            PROS_ERR_U32 => {
-                bail_errno!();
-                unreachable!("Expected errno to be set");
-            }
-            0 => Err(LinkError::Busy),
-            n => Ok(n),
-        }
+        receive_raw(self.port, buf)
+    }
+
+    /// Sends data back to the transmitting robot.
+    pub fn transmit(&self, buf: &[u8]) -> Result<u32, LinkError> {
+        transmit_raw(self.port, buf)
     }
 }
 
@@ -94,6 +123,18 @@ impl io::Read for RxLink {
     }
 }
 
+impl io::Write for RxLink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes_written = self
+            .transmit(buf)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to write to link"))?;
+        Ok(bytes_written as _)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 pub struct TxLink {
     port: u8,
     id: CString,
@@ -114,17 +155,12 @@ impl TxLink {
     }
 
     pub fn transmit(&self, buf: &[u8]) -> Result<u32, LinkError> {
-        const PROS_ERR_U32: u32 = pros_sys::PROS_ERR as _;
-
-        match unsafe { link_transmit(self.port, buf.as_ptr().cast(), buf.len() as _) } {
-            PROS_ERR_U32 => {
-                let errno = crate::error::take_errno();
-                Err(FromErrno::from_errno(errno)
-                    .unwrap_or_else(|| panic!("Unknown errno code {errno}")))
-            }
-            0 => Err(LinkError::Busy),
-            n => Ok(n),
-        }
+        transmit_raw(self.port, buf)
+    }
+
+    /// Receives data sent back by the receiving robot.
+    pub fn receive(&self, buf: &mut [u8]) -> Result<u32, LinkError> {
+        receive_raw(self.port, buf)
     }
 }
 
@@ -140,6 +176,15 @@ impl io::Write for TxLink {
     }
 }
 
+impl io::Read for TxLink {
+    fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self
+            .receive(dst)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to read from link"))?;
+        Ok(bytes_read as _)
+    }
+}
+
 impl Link for TxLink {
     fn id(&self) -> &CStr {
         &self.id
@@ -0,0 +1,91 @@
+//! A [`log`] logger that keeps recent messages in memory.
+//!
+//! Messages logged with the [`log`] macros anywhere in the program, including background tasks,
+//! are kept so that they can be shown on the screen with [`LogView`](crate::screen::log_view::LogView).
+
+use alloc::{collections::VecDeque, format, string::String, vec::Vec};
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::sync::Mutex;
+
+/// How many messages are kept before the oldest are dropped.
+pub const CAPACITY: usize = 128;
+
+/// A logged message.
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    /// Counts up by one for every message logged, so viewers can tell which messages are new.
+    pub sequence: u64,
+    /// Milliseconds since the program started.
+    pub time: u32,
+    pub level: Level,
+    pub message: String,
+}
+
+struct Buffer {
+    lines: VecDeque<LogLine>,
+    next_sequence: u64,
+}
+
+lazy_static::lazy_static! {
+    static ref BUFFER: Mutex<Buffer> = Mutex::new(Buffer {
+        lines: VecDeque::with_capacity(CAPACITY),
+        next_sequence: 0,
+    });
+}
+
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut buffer = BUFFER.lock();
+        if buffer.lines.len() == CAPACITY {
+            buffer.lines.pop_front();
+        }
+        let sequence = buffer.next_sequence;
+        buffer.next_sequence += 1;
+        buffer.lines.push_back(LogLine {
+            sequence,
+            time: unsafe { pros_sys::millis() },
+            level: record.level(),
+            message: format!("{}", record.args()),
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+
+/// Installs the logger, keeping messages at `level` and above.
+/// Fails if another logger has already been installed.
+pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Returns the kept messages, oldest first.
+pub fn recent() -> Vec<LogLine> {
+    BUFFER.lock().lines.iter().cloned().collect()
+}
+
+/// Returns the kept messages logged after the one with the given sequence number.
+pub fn since(sequence: u64) -> Vec<LogLine> {
+    BUFFER
+        .lock()
+        .lines
+        .iter()
+        .filter(|line| line.sequence > sequence)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,154 @@
+//! Wrapping and comparing angles without wraparound bugs.
+//!
+//! Headings and rotations are usually continuous: an IMU's rotation or an odometry heading keeps counting
+//! past 360 degrees, while a GPS or a vision target reports headings within a single turn.
+//! Subtracting one from the other directly can give an error of almost a full turn, sending a
+//! controller the long way around. [`difference`] and [`nearest`] give the shortest way instead:
+//! ```rust
+//! let heading = odometry.pose().heading;
+//! let target = angle::nearest_degrees(170.0, heading);
+//! let turn = heading_pid.update(target as f32, heading as f32);
+//! ```
+//!
+//! Every function takes radians, and has a `_degrees` equivalent, since most of this crate works in degrees.
+//! Use [`f64::to_radians`] and [`f64::to_degrees`] to convert between them.
+
+use core::f64::consts::{PI, TAU};
+
+/// Wraps an angle in radians to the range (-π, π].
+pub fn normalize(angle: f64) -> f64 {
+    wrap(angle, PI)
+}
+
+/// Wraps an angle in radians to the range [0, 2π).
+pub fn normalize_positive(angle: f64) -> f64 {
+    wrap_positive(angle, TAU)
+}
+
+/// Returns the shortest signed angle in radians that turns `from` to `to`, in the range (-π, π].
+/// Positive results are counterclockwise.
+pub fn difference(from: f64, to: f64) -> f64 {
+    normalize(to - from)
+}
+
+/// Interpolates between two angles in radians the short way around, where `t` is 0.0 at `from` and 1.0 at `to`.
+///
+/// The result is not wrapped, so it stays within half a turn of `from`,
+/// which keeps continuous angles such as an odometry heading continuous.
+pub fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + difference(from, to) * t
+}
+
+/// Returns the equivalent of `angle` in radians (any whole number of turns away) closest to `reference`,
+/// such as a target for a controller whose measurement keeps counting past a full turn.
+pub fn nearest(angle: f64, reference: f64) -> f64 {
+    reference + difference(reference, angle)
+}
+
+/// Wraps an angle in degrees to the range (-180, 180].
+pub fn normalize_degrees(angle: f64) -> f64 {
+    wrap(angle, 180.0)
+}
+
+/// Wraps an angle in degrees to the range [0, 360).
+pub fn normalize_positive_degrees(angle: f64) -> f64 {
+    wrap_positive(angle, 360.0)
+}
+
+/// Returns the shortest signed angle in degrees that turns `from` to `to`, in the range (-180, 180].
+/// Positive results are counterclockwise.
+pub fn difference_degrees(from: f64, to: f64) -> f64 {
+    normalize_degrees(to - from)
+}
+
+/// Interpolates between two angles in degrees the short way around. See [`lerp`].
+pub fn lerp_degrees(from: f64, to: f64, t: f64) -> f64 {
+    from + difference_degrees(from, to) * t
+}
+
+/// Returns the equivalent of `angle` in degrees closest to `reference`. See [`nearest`].
+pub fn nearest_degrees(angle: f64, reference: f64) -> f64 {
+    reference + difference_degrees(reference, angle)
+}
+
+/// Wraps an angle to the range (-half, half], where `half` is half a turn.
+fn wrap(angle: f64, half: f64) -> f64 {
+    // `remainder` rounds to the nearest turn, which leaves exactly half a turn as either sign.
+    let wrapped = libm::remainder(angle, 2.0 * half);
+    if wrapped <= -half {
+        wrapped + 2.0 * half
+    } else {
+        wrapped
+    }
+}
+
+/// Wraps an angle to the range [0, turn).
+fn wrap_positive(angle: f64, turn: f64) -> f64 {
+    let wrapped = libm::fmod(angle, turn);
+    if wrapped < 0.0 {
+        // Tiny negative angles round up to a full turn, which is outside the range.
+        let shifted = wrapped + turn;
+        if shifted >= turn {
+            0.0
+        } else {
+            shifted
+        }
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_turns_wrap_to_positive() {
+        for angle in [180.0, -180.0, 540.0, -540.0, 900.0, -900.0] {
+            assert_eq!(normalize_degrees(angle), 180.0, "{angle}");
+        }
+        assert_eq!(normalize(-PI), PI);
+        assert_eq!(normalize(3.0 * PI), PI);
+    }
+
+    #[test]
+    fn normalize_rounds_to_the_nearest_turn() {
+        assert_eq!(normalize_degrees(190.0), -170.0);
+        assert_eq!(normalize_degrees(-190.0), 170.0);
+        assert_eq!(normalize_degrees(719.0), -1.0);
+        assert_eq!(normalize_degrees(-45.0), -45.0);
+        assert_eq!(normalize_degrees(360.0), 0.0);
+    }
+
+    #[test]
+    fn normalize_positive_stays_below_a_turn() {
+        assert_eq!(normalize_positive_degrees(360.0), 0.0);
+        assert_eq!(normalize_positive_degrees(-90.0), 270.0);
+        assert_eq!(normalize_positive_degrees(-720.0), 0.0);
+        assert_eq!(normalize_positive(-TAU), 0.0);
+    }
+
+    #[test]
+    fn tiny_negative_angles() {
+        assert_eq!(normalize_positive_degrees(-1e-20), 0.0);
+        assert_eq!(normalize_positive(-1e-20), 0.0);
+        assert_eq!(normalize_degrees(-1e-20), -1e-20);
+        assert!(normalize_positive_degrees(-1e-9) < 360.0);
+    }
+
+    #[test]
+    fn difference_takes_the_short_way() {
+        assert_eq!(difference_degrees(170.0, -170.0), 20.0);
+        assert_eq!(difference_degrees(-170.0, 170.0), -20.0);
+        assert_eq!(difference_degrees(0.0, 180.0), 180.0);
+        assert_eq!(difference_degrees(720.0, 10.0), 10.0);
+    }
+
+    #[test]
+    fn nearest_and_lerp_stay_continuous() {
+        assert_eq!(nearest_degrees(170.0, 720.0), 890.0);
+        assert_eq!(nearest_degrees(-10.0, 355.0), 350.0);
+        assert_eq!(lerp_degrees(350.0, 10.0, 0.5), 360.0);
+        assert_eq!(lerp_degrees(10.0, 350.0, 0.25), 5.0);
+    }
+}
@@ -0,0 +1,3 @@
+//! Math helpers shared by controllers, odometry, and subsystems.
+
+pub mod angle;
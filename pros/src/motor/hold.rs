@@ -0,0 +1,110 @@
+//! Holding a motor at a position with this crate's own position loop.
+//!
+//! [`BrakeMode::Hold`](super::BrakeMode::Hold) uses the firmware's holding loop, which can't be tuned and
+//! gives way under heavy loads, such as an arm holding a game piece out in front of the robot.
+//! [`Motor::hold_at_current_position`] instead runs a PID loop on the same background task as
+//! [external velocity control](super::velocity), with a [`HoldGains::stiffness`] that can be raised
+//! until the mechanism resists being pushed.
+
+use super::{
+    velocity::{release, with_tracked},
+    Motor, MotorError,
+};
+use crate::position::Position;
+
+/// How firmly [`Motor::hold_at_current_position`] holds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HoldGains {
+    /// Volts applied per degree the motor is pushed away from the held position.
+    pub stiffness: f32,
+    /// Volts applied against each RPM the motor is moving at, to keep it from oscillating.
+    pub damping: f32,
+    /// Volts added per degree second of error, to overcome a steady load like gravity.
+    pub integral: f32,
+    /// The most voltage the hold will apply in either direction.
+    pub max_voltage: f32,
+}
+
+impl Default for HoldGains {
+    fn default() -> Self {
+        Self {
+            stiffness: 0.15,
+            damping: 0.01,
+            integral: 0.0,
+            max_voltage: 12.0,
+        }
+    }
+}
+
+impl HoldGains {
+    /// The default gains with a different stiffness, in volts per degree.
+    pub fn with_stiffness(stiffness: f32) -> Self {
+        Self {
+            stiffness,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Hold {
+    target: f32,
+    integral: f32,
+}
+
+impl Hold {
+    /// Returns the voltage to apply given the position in degrees and velocity in RPM.
+    pub(super) fn update(
+        &mut self,
+        gains: &HoldGains,
+        position: f32,
+        velocity: f32,
+        dt: f32,
+    ) -> f32 {
+        let error = self.target - position;
+        let max = gains.max_voltage.clamp(0.0, 12.0);
+        if gains.integral != 0.0 {
+            // Limiting the integral to what it could ever apply keeps it from winding up.
+            let limit = max / libm::fabsf(gains.integral);
+            self.integral = (self.integral + error * dt).clamp(-limit, limit);
+        }
+        let voltage =
+            gains.stiffness * error + gains.integral * self.integral - gains.damping * velocity;
+        voltage.clamp(-max, max)
+    }
+}
+
+impl Motor {
+    /// Holds the motor where it is with a background position loop, using [`HoldGains::default`]
+    /// unless [`Motor::set_hold_gains`] was called. See the [module documentation](super::hold) for details.
+    ///
+    /// The motor holds until it is given another command, such as [`Motor::set_voltage`] or [`Motor::brake`].
+    /// Errors reading or driving the motor from the loop are passed to `crate::error::report` and end the hold.
+    pub fn hold_at_current_position(&self) -> Result<(), MotorError> {
+        let position = self.position()?;
+        self.hold_at(position);
+        Ok(())
+    }
+
+    /// Holds the motor at a position with a background position loop, like [`Motor::hold_at_current_position`].
+    pub fn hold_at(&self, position: Position) {
+        release(self.port);
+        with_tracked(self, |tracked| {
+            tracked.hold = Some(Hold {
+                target: position.into_degrees() as f32,
+                integral: 0.0,
+            });
+        });
+    }
+
+    /// Sets the gains used by [`Motor::hold_at_current_position`] and [`Motor::hold_at`],
+    /// including for a hold that is already running.
+    pub fn set_hold_gains(&self, gains: HoldGains) {
+        with_tracked(self, |tracked| tracked.hold_gains = gains);
+    }
+
+    /// Returns true if the motor is being held by [`Motor::hold_at_current_position`] or [`Motor::hold_at`].
+    pub fn is_holding(&self) -> bool {
+        with_tracked(self, |tracked| tracked.hold.is_some())
+    }
+}
@@ -0,0 +1,788 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use pros_sys::{PROS_ERR, PROS_ERR_F};
+use snafu::Snafu;
+
+use crate::{
+    control::exit::{ErrorWithin, ExitCondition, Stalled, Timeout},
+    error::{bail_on, map_errno, PortError},
+    position::Position,
+};
+
+pub mod hold;
+pub mod replay;
+pub mod velocity;
+
+pub use hold::HoldGains;
+pub use velocity::{VelocityEstimator, VelocityFilter, VelocityGains};
+
+/// The basic motor struct.
+#[derive(Debug, Clone, Copy)]
+pub struct Motor {
+    port: u8,
+    encoder_units: EncoderUnits,
+}
+
+//TODO: Implement good set_velocity and get_velocity functions.
+impl Motor {
+    pub fn new(port: u8, brake_mode: BrakeMode) -> Result<Self, MotorError> {
+        let mut motor = Self {
+            port,
+            encoder_units: EncoderUnits::Degrees,
+        };
+        motor.set_encoder_units(EncoderUnits::Degrees)?;
+        motor.set_brake_mode(brake_mode)?;
+        Ok(motor)
+    }
+
+    /// Sets which kind of [`Position`] this motor reports.
+    ///
+    /// Positions are converted using the units the motor is actually configured with
+    /// each time they are read or written, so they stay correct even if
+    /// the motor's units are changed elsewhere.
+    pub fn set_encoder_units(&mut self, units: EncoderUnits) -> Result<(), MotorError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::motor_set_encoder_units(self.port, units.into())
+            );
+        }
+        self.encoder_units = units;
+        Ok(())
+    }
+
+    pub fn encoder_units(&self) -> EncoderUnits {
+        self.encoder_units
+    }
+
+    /// Returns how many of the motor's configured encoder units make up one degree.
+    fn device_units_per_degree(&self) -> Result<f64, MotorError> {
+        let units = bail_on!(pros_sys::E_MOTOR_ENCODER_INVALID, unsafe {
+            pros_sys::motor_get_encoder_units(self.port)
+        });
+        units_per_degree(units, || self.gearset())
+    }
+
+    /// Converts a position into the motor's configured encoder units.
+    fn in_device_units(&self, position: Position) -> Result<f64, MotorError> {
+        Ok(position.into_degrees() * self.device_units_per_degree()?)
+    }
+
+    pub fn set_gearset(&self, gearset: Gearset) -> Result<(), MotorError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::motor_set_gearing(self.port, gearset as i32)
+            );
+        }
+        Ok(())
+    }
+
+    pub fn gearset(&self) -> Result<Gearset, MotorError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::motor_get_gearing(self.port)) }.into())
+    }
+
+    /// Takes in a f32 from -1 to 1 that is scaled to -12 to 12 volts.
+    /// Useful for driving motors with controllers.
+    pub fn set_output(&self, output: f32) -> Result<(), MotorError> {
+        velocity::release(self.port);
+        replay::note(self.port, output * 12.0);
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::motor_move(self.port, (output * 127.0) as i32)
+            );
+        }
+        Ok(())
+    }
+
+    /// Takes in and i8 between -127 and 127 which is scaled to -12 to 12 Volts.
+    pub fn set_raw_output(&self, raw_output: i8) -> Result<(), MotorError> {
+        velocity::release(self.port);
+        replay::note(self.port, raw_output as f32 / 127.0 * 12.0);
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::motor_move(self.port, raw_output as i32));
+        }
+        Ok(())
+    }
+
+    /// Takes in a voltage that must be between -12 and 12 Volts.
+    pub fn set_voltage(&self, voltage: f32) -> Result<(), MotorError> {
+        if !(-12.0..=12.0).contains(&voltage) || voltage.is_nan() {
+            return Err(MotorError::VoltageOutOfRange);
+        }
+        velocity::release(self.port);
+        self.set_voltage_raw(voltage)
+    }
+
+    /// Sets the voltage without validating it or ending external velocity control.
+    fn set_voltage_raw(&self, voltage: f32) -> Result<(), MotorError> {
+        replay::note(self.port, voltage);
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::motor_move_voltage(self.port, (voltage * 1000.0) as i32)
+            );
+        }
+        Ok(())
+    }
+
+    /// Moves the motor to an absolute position, based off of the last motor zeroing.
+    /// units for the velocity is RPM.
+    pub fn set_position_absolute(
+        &self,
+        position: Position,
+        velocity: i32,
+    ) -> Result<(), MotorError> {
+        velocity::release(self.port);
+        replay::note(self.port, f32::NAN);
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::motor_move_absolute(self.port, self.in_device_units(position)?, velocity)
+            );
+        };
+        Ok(())
+    }
+
+    /// Moves the motor to an absolute position like [`Motor::set_position_absolute`],
+    /// returning a future that resolves once the motor reaches it.
+    ///
+    /// The move starts when the future is first polled.
+    /// After that, the future checks the motor each time it is polled,
+    /// so it relies on being polled regularly, as [`crate::async_runtime::block_on`] does.
+    pub fn move_absolute_async(&self, position: Position, velocity: i32) -> MoveFuture {
+        MoveFuture {
+            motor: *self,
+            target: position,
+            velocity,
+            started: false,
+            done: ErrorWithin(5.0),
+            timeout: None,
+            // Sitting still within the tolerance isn't a stall, such as while waiting on a condition from `until`.
+            stalled: Stalled::new(5.0, MoveFuture::STALL_SPEED, MoveFuture::STALL_TIME),
+        }
+    }
+
+    /// Moves the motor to a position relative to the current position.
+    /// units for velocity is RPM.
+    pub fn set_position_relative(
+        &self,
+        position: Position,
+        velocity: i32,
+    ) -> Result<(), MotorError> {
+        velocity::release(self.port);
+        replay::note(self.port, f32::NAN);
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::motor_move_relative(self.port, self.in_device_units(position)?, velocity)
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the measured velocity of the motor in RPM.
+    pub fn velocity(&self) -> Result<f64, MotorError> {
+        unsafe {
+            Ok(bail_on!(
+                PROS_ERR_F,
+                pros_sys::motor_get_actual_velocity(self.port)
+            ))
+        }
+    }
+
+    /// Returns the power drawn by the motor in Watts.
+    pub fn power(&self) -> Result<f64, MotorError> {
+        unsafe { Ok(bail_on!(PROS_ERR_F, pros_sys::motor_get_power(self.port))) }
+    }
+
+    /// Returns the torque output of the motor in Nm.
+    pub fn torque(&self) -> Result<f64, MotorError> {
+        unsafe { Ok(bail_on!(PROS_ERR_F, pros_sys::motor_get_torque(self.port))) }
+    }
+
+    /// Returns the temperature of the motor in degrees Celsius.
+    pub fn temperature(&self) -> Result<f64, MotorError> {
+        unsafe {
+            Ok(bail_on!(
+                PROS_ERR_F,
+                pros_sys::motor_get_temperature(self.port)
+            ))
+        }
+    }
+
+    /// Returns the voltage the motor is drawing in volts.
+    pub fn voltage(&self) -> Result<f64, MotorError> {
+        // docs say this function returns PROS_ERR_F but it actually returns PROS_ERR
+        let millivolts = unsafe { bail_on!(PROS_ERR, pros_sys::motor_get_voltage(self.port)) };
+        Ok(millivolts as f64 / 1000.0)
+    }
+
+    /// Returns the current position of the motor in its [`EncoderUnits`].
+    pub fn position(&self) -> Result<Position, MotorError> {
+        let raw = unsafe { bail_on!(PROS_ERR_F, pros_sys::motor_get_position(self.port)) };
+        let degrees = raw / self.device_units_per_degree()?;
+        Ok(self.encoder_units.position(degrees))
+    }
+
+    /// Returns the current draw of the motor.
+    pub fn current_draw(&self) -> Result<i32, MotorError> {
+        Ok(bail_on!(PROS_ERR, unsafe {
+            pros_sys::motor_get_current_draw(self.port)
+        }))
+    }
+
+    /// Sets the current position to zero.
+    pub fn tare(&self) -> Result<(), MotorError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::motor_tare_position(self.port));
+        }
+        Ok(())
+    }
+
+    /// Sets the current position to zero. Same as [`Motor::tare`].
+    pub fn zero(&self) -> Result<(), MotorError> {
+        self.tare()
+    }
+
+    /// Stops the motor based on the current [`BrakeMode`]
+    pub fn brake(&self) -> Result<(), MotorError> {
+        velocity::release(self.port);
+        replay::note(self.port, f32::NAN);
+        bail_on!(PROS_ERR, unsafe { pros_sys::motor_brake(self.port) });
+        Ok(())
+    }
+
+    /// Makes the given position the new zero, so that it reads as zero from now on.
+    /// For example, if the motor is at 90 degrees, setting the zero position to 90 degrees
+    /// has the same effect as [`Motor::tare`].
+    pub fn set_zero_position(&self, position: Position) -> Result<(), MotorError> {
+        let position = self.in_device_units(position)?;
+        bail_on!(PROS_ERR, unsafe {
+            pros_sys::motor_set_zero_position(self.port, position)
+        });
+        Ok(())
+    }
+
+    /// Sets how the motor should act when stopping.
+    pub fn set_brake_mode(&self, brake_mode: BrakeMode) -> Result<(), MotorError> {
+        bail_on!(PROS_ERR, unsafe {
+            pros_sys::motor_set_brake_mode(self.port, brake_mode.into())
+        });
+        Ok(())
+    }
+
+    //TODO: Test this, as im not entirely sure of the actual implementation
+    /// Get the current state of the motor.
+    pub fn get_state(&self) -> Result<MotorState, MotorError> {
+        let bit_flags = bail_on!(PROS_ERR as _, unsafe {
+            pros_sys::motor_get_flags(self.port)
+        });
+        Ok(bit_flags.into())
+    }
+
+    /// Reverse this motor by multiplying all input by -1.
+    pub fn set_reversed(&self, reversed: bool) -> Result<(), MotorError> {
+        bail_on!(PROS_ERR, unsafe {
+            pros_sys::motor_set_reversed(self.port, reversed)
+        });
+        Ok(())
+    }
+
+    /// Check if this motor has been reversed.
+    pub fn reversed(&self) -> bool {
+        unsafe { pros_sys::motor_is_reversed(self.port) == 1 }
+    }
+}
+
+/// The longest time step in seconds that slew limiting and ramping allow for,
+/// so outputs that haven't been commanded for a while can't jump straight to full power.
+#[cfg(feature = "alloc")]
+pub(crate) const MAX_SLEW_STEP: f32 = 0.02;
+
+/// Shaping applied to every voltage sent to a [`MotorGroup`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputStage {
+    /// The fastest the voltage may change, in volts per second. `None` disables slew limiting.
+    pub slew_rate: Option<f32>,
+    /// Commands smaller than this many volts are treated as zero.
+    pub deadband: f32,
+    /// The voltage needed to overcome friction.
+    /// Commands outside the deadband are rescaled to start at this voltage so small commands still move the mechanism.
+    pub min_voltage: f32,
+}
+
+#[cfg(feature = "alloc")]
+impl OutputStage {
+    fn apply(&self, voltage: f32, last_voltage: f32, dt: f32) -> f32 {
+        let magnitude = libm::fabsf(voltage);
+        let target = if magnitude == 0.0 || magnitude < self.deadband {
+            0.0
+        } else {
+            libm::copysignf(
+                self.min_voltage + magnitude * (12.0 - self.min_voltage) / 12.0,
+                voltage,
+            )
+        };
+
+        match self.slew_rate {
+            Some(rate) => {
+                let max_change = rate * dt.min(MAX_SLEW_STEP);
+                target.clamp(last_voltage - max_change, last_voltage + max_change)
+            }
+            None => target,
+        }
+    }
+}
+
+/// A group of motors that are commanded together, such as both sides of a lift.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct MotorGroup {
+    motors: Vec<Motor>,
+    output_stage: Option<OutputStage>,
+    last_voltage: AtomicU32,
+    last_update: AtomicU32,
+}
+
+#[cfg(feature = "alloc")]
+impl Clone for MotorGroup {
+    fn clone(&self) -> Self {
+        Self {
+            motors: self.motors.clone(),
+            output_stage: self.output_stage,
+            last_voltage: AtomicU32::new(self.last_voltage.load(Ordering::Relaxed)),
+            last_update: AtomicU32::new(self.last_update.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl MotorGroup {
+    pub fn new(motors: Vec<Motor>) -> Self {
+        Self {
+            motors,
+            output_stage: None,
+            last_voltage: AtomicU32::new(0.0f32.to_bits()),
+            last_update: AtomicU32::new(0),
+        }
+    }
+
+    /// Shapes every voltage sent to this group with an [`OutputStage`].
+    pub fn with_output_stage(mut self, stage: OutputStage) -> Self {
+        self.output_stage = Some(stage);
+        self
+    }
+
+    /// Sets or removes the [`OutputStage`] used by this group.
+    pub fn set_output_stage(&mut self, stage: Option<OutputStage>) {
+        self.output_stage = stage;
+    }
+
+    pub fn output_stage(&self) -> Option<OutputStage> {
+        self.output_stage
+    }
+
+    /// Returns the motors in this group.
+    pub fn motors(&self) -> &[Motor] {
+        &self.motors
+    }
+
+    /// Takes in a f32 from -1 to 1 that is scaled to -12 to 12 volts for every motor.
+    pub fn set_output(&self, output: f32) -> Result<(), MotorError> {
+        if self.output_stage.is_some() {
+            return self.set_voltage(output.clamp(-1.0, 1.0) * 12.0);
+        }
+        for motor in &self.motors {
+            motor.set_output(output)?;
+        }
+        Ok(())
+    }
+
+    /// Takes in a voltage that must be between -12 and 12 Volts for every motor.
+    /// The voltage is shaped by the group's [`OutputStage`] if it has one.
+    pub fn set_voltage(&self, voltage: f32) -> Result<(), MotorError> {
+        if !(-12.0..=12.0).contains(&voltage) || voltage.is_nan() {
+            return Err(MotorError::VoltageOutOfRange);
+        }
+        let voltage = match &self.output_stage {
+            Some(stage) => {
+                let now = unsafe { pros_sys::millis() };
+                let dt =
+                    now.wrapping_sub(self.last_update.swap(now, Ordering::Relaxed)) as f32 / 1000.0;
+                let last_voltage = f32::from_bits(self.last_voltage.load(Ordering::Relaxed));
+                let shaped = stage.apply(voltage, last_voltage, dt);
+                self.last_voltage.store(shaped.to_bits(), Ordering::Relaxed);
+                shaped
+            }
+            None => voltage,
+        };
+        for motor in &self.motors {
+            motor.set_voltage(voltage)?;
+        }
+        Ok(())
+    }
+
+    /// Stops every motor based on its current [`BrakeMode`]
+    pub fn brake(&self) -> Result<(), MotorError> {
+        self.last_voltage.store(0.0f32.to_bits(), Ordering::Relaxed);
+        for motor in &self.motors {
+            motor.brake()?;
+        }
+        Ok(())
+    }
+
+    /// Sets the current position of every motor to zero.
+    pub fn zero(&self) -> Result<(), MotorError> {
+        for motor in &self.motors {
+            motor.zero()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the average position of the motors in the group.
+    pub fn position(&self) -> Result<Position, MotorError> {
+        let mut total = 0.0;
+        for motor in &self.motors {
+            total += motor.position()?.into_degrees();
+        }
+        Ok(Position::from_degrees(
+            total / self.motors.len().max(1) as f64,
+        ))
+    }
+
+    /// Returns the combined current draw of the group in milliamps.
+    pub fn current_draw(&self) -> Result<i32, MotorError> {
+        let mut total = 0;
+        for motor in &self.motors {
+            total += motor.current_draw()?;
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Vec<Motor>> for MotorGroup {
+    fn from(motors: Vec<Motor>) -> Self {
+        Self::new(motors)
+    }
+}
+
+/// A future that resolves once a motor reaches a target position.
+/// Created by [`Motor::move_absolute_async`].
+///
+/// The move finishes once the [`ExitCondition`] `C` is met, which is updated with the error in degrees.
+/// By default that is being within a tolerance of the target, and [`MoveFuture::until`] replaces it:
+/// ```rust
+/// motor
+///     .move_absolute_async(Position::from_degrees(90.0), 100)
+///     .until(ErrorWithin(2.0).held_for(Duration::from_millis(200)))
+///     .with_timeout(Duration::from_secs(2))
+///     .await?;
+/// ```
+pub struct MoveFuture<C = ErrorWithin> {
+    motor: Motor,
+    target: Position,
+    velocity: i32,
+    started: bool,
+    done: C,
+    timeout: Option<Timeout>,
+    stalled: Stalled,
+}
+
+impl MoveFuture {
+    /// How long the motor can sit still short of its target before it is considered stalled.
+    pub const STALL_TIME: Duration = Duration::from_millis(500);
+    /// The slowest the motor can approach its target, in degrees per second, without counting as still.
+    pub const STALL_SPEED: f64 = 6.0;
+
+    /// Sets how close the motor must get to the target. Defaults to 5 degrees.
+    pub fn with_tolerance(mut self, tolerance: Position) -> Self {
+        let tolerance = libm::fabs(tolerance.into_degrees());
+        self.done = ErrorWithin(tolerance);
+        self.stalled = Stalled::new(tolerance, Self::STALL_SPEED, Self::STALL_TIME);
+        self
+    }
+}
+
+impl<C: ExitCondition> MoveFuture<C> {
+    /// Fails with [`MoveError::TimedOut`] if the move doesn't finish within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(Timeout::new(timeout));
+        self
+    }
+
+    /// Finishes the move once `condition` is met instead of once the motor is within the tolerance.
+    /// Timeouts and stalls outside the tolerance still fail the move.
+    pub fn until<U: ExitCondition>(self, condition: U) -> MoveFuture<U> {
+        MoveFuture {
+            motor: self.motor,
+            target: self.target,
+            velocity: self.velocity,
+            started: self.started,
+            done: condition,
+            timeout: self.timeout,
+            stalled: self.stalled,
+        }
+    }
+}
+
+impl<C: ExitCondition + Unpin> Future for MoveFuture<C> {
+    type Output = Result<(), MoveError>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let now = unsafe { pros_sys::millis() };
+        if !this.started {
+            this.motor
+                .set_position_absolute(this.target, this.velocity)?;
+            this.started = true;
+        }
+
+        let error = (this.motor.position()? - this.target).into_degrees();
+        if this.done.update_at(now, error) {
+            return Poll::Ready(Ok(()));
+        }
+
+        if let Some(timeout) = &mut this.timeout {
+            if timeout.update_at(now, error) {
+                return Poll::Ready(Err(MoveError::TimedOut));
+            }
+        }
+
+        if this.stalled.update_at(now, error) {
+            return Poll::Ready(Err(MoveError::Stalled));
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Which kind of [`Position`] a [`Motor`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderUnits {
+    Degrees,
+    Rotations,
+    /// [`Position::Counts`], which are independent of the motor's gearset.
+    Counts,
+}
+
+impl EncoderUnits {
+    /// Expresses an angle in degrees as a position in these units.
+    pub fn position(&self, degrees: f64) -> Position {
+        match self {
+            Self::Degrees => Position::from_degrees(degrees),
+            Self::Rotations => Position::from_rotations(degrees / 360.0),
+            Self::Counts => Position::from_counts(Position::from_degrees(degrees).into_counts()),
+        }
+    }
+}
+
+/// Returns how many of a motor's encoder units make up one degree,
+/// only reading the gearset for counts, which depend on it.
+fn units_per_degree(
+    units: pros_sys::motor_encoder_units_e_t,
+    gearset: impl FnOnce() -> Result<Gearset, MotorError>,
+) -> Result<f64, MotorError> {
+    Ok(match units {
+        pros_sys::E_MOTOR_ENCODER_ROTATIONS => 1.0 / 360.0,
+        pros_sys::E_MOTOR_ENCODER_COUNTS => gearset()?.ticks_per_rotation() / 360.0,
+        _ => 1.0,
+    })
+}
+
+impl From<EncoderUnits> for pros_sys::motor_encoder_units_e_t {
+    fn from(units: EncoderUnits) -> Self {
+        match units {
+            EncoderUnits::Degrees => pros_sys::E_MOTOR_ENCODER_DEGREES,
+            EncoderUnits::Rotations => pros_sys::E_MOTOR_ENCODER_ROTATIONS,
+            EncoderUnits::Counts => pros_sys::E_MOTOR_ENCODER_COUNTS,
+        }
+    }
+}
+
+/// Determines how a motor should act when braking.
+pub enum BrakeMode {
+    /// Motor never brakes.
+    None,
+    /// Motor uses regenerative braking to slow down faster.
+    Brake,
+    /// Motor exerts force to hold the same position.
+    Hold,
+}
+
+impl From<BrakeMode> for pros_sys::motor_brake_mode_e_t {
+    fn from(other: BrakeMode) -> pros_sys::motor_brake_mode_e_t {
+        match other {
+            BrakeMode::Brake => pros_sys::E_MOTOR_BRAKE_BRAKE,
+            BrakeMode::Hold => pros_sys::E_MOTOR_BRAKE_HOLD,
+            BrakeMode::None => pros_sys::E_MOTOR_BRAKE_COAST,
+        }
+    }
+}
+
+/// Represents what the physical motor is currently doing.
+#[derive(Debug, Clone, Default)]
+pub struct MotorState {
+    pub busy: bool,
+    pub stopped: bool,
+    /// the motor is at zero encoder units of rotation.
+    pub zeroed: bool,
+}
+
+//TODO: Test this, like mentioned above
+impl From<u32> for MotorState {
+    fn from(value: u32) -> Self {
+        Self {
+            busy: (value & pros_sys::E_MOTOR_FLAGS_BUSY) == 0b001,
+            stopped: (value & pros_sys::E_MOTOR_FLAGS_ZERO_VELOCITY) == 0b010,
+            zeroed: (value & pros_sys::E_MOTOR_FLAGS_ZERO_POSITION) == 0b100,
+        }
+    }
+}
+
+/// Internal gearset used by VEX smart motors.
+#[derive(Debug)]
+#[repr(i32)]
+pub enum Gearset {
+    Red = pros_sys::E_MOTOR_GEAR_RED,
+    Green = pros_sys::E_MOTOR_GEAR_GREEN,
+    Blue = pros_sys::E_MOTOR_GEAR_BLUE,
+}
+
+impl Gearset {
+    /// 36:1 gear ratio
+    pub const RATIO_36: Gearset = Gearset::Red;
+    /// 18:1 gear ratio
+    pub const RATIO_18: Gearset = Gearset::Green;
+    /// 6:1 gear ratio
+    pub const RATIO_6: Gearset = Gearset::Blue;
+
+    /// 100 rpm
+    pub const RPM_100: Gearset = Gearset::Red;
+    /// 200 rpm
+    pub const RPM_200: Gearset = Gearset::Green;
+    /// 600 rpm
+    pub const RPM_600: Gearset = Gearset::Blue;
+
+    /// Returns how many encoder ticks the motor reports per rotation of its output shaft.
+    pub fn ticks_per_rotation(&self) -> f64 {
+        match self {
+            Self::Red => 1800.0,
+            Self::Green => 900.0,
+            Self::Blue => 300.0,
+        }
+    }
+
+    /// Returns the free speed of the motor's output shaft in RPM.
+    pub fn max_rpm(&self) -> f32 {
+        match self {
+            Self::Red => 100.0,
+            Self::Green => 200.0,
+            Self::Blue => 600.0,
+        }
+    }
+}
+
+impl From<i32> for Gearset {
+    fn from(value: i32) -> Self {
+        match value {
+            pros_sys::E_MOTOR_GEAR_RED => Gearset::Red,
+            pros_sys::E_MOTOR_GEAR_GREEN => Gearset::Green,
+            pros_sys::E_MOTOR_GEAR_BLUE => Gearset::Blue,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum MotorError {
+    #[snafu(display("The voltage supplied was outside of the allowed range (-12 to 12)."))]
+    VoltageOutOfRange,
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+impl core::error::Error for MotorError {}
+
+map_errno! {
+    MotorError {}
+    inherit PortError;
+}
+
+#[derive(Debug, Snafu)]
+pub enum MoveError {
+    #[snafu(display("The motor stopped before reaching its target."))]
+    Stalled,
+    #[snafu(display("The motor did not reach its target in time."))]
+    TimedOut,
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+}
+impl core::error::Error for MoveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        libm::fabs(a - b) < 1e-9
+    }
+
+    #[test]
+    fn gearset_ticks() {
+        assert_eq!(Gearset::Red.ticks_per_rotation(), 1800.0);
+        assert_eq!(Gearset::Green.ticks_per_rotation(), 900.0);
+        assert_eq!(Gearset::Blue.ticks_per_rotation(), 300.0);
+    }
+
+    #[test]
+    fn device_units_per_degree() {
+        let unused =
+            || -> Result<Gearset, MotorError> { panic!("gearset read for non-count units") };
+        assert_eq!(
+            units_per_degree(pros_sys::E_MOTOR_ENCODER_DEGREES, unused).unwrap(),
+            1.0
+        );
+        assert!(close(
+            units_per_degree(pros_sys::E_MOTOR_ENCODER_ROTATIONS, unused).unwrap(),
+            1.0 / 360.0
+        ));
+        for (gearset, ticks) in [
+            (Gearset::Red, 1800.0),
+            (Gearset::Green, 900.0),
+            (Gearset::Blue, 300.0),
+        ] {
+            let per_degree =
+                units_per_degree(pros_sys::E_MOTOR_ENCODER_COUNTS, || Ok(gearset)).unwrap();
+            assert!(close(per_degree * 360.0, ticks));
+        }
+    }
+
+    #[test]
+    fn encoder_units_positions() {
+        assert!(matches!(EncoderUnits::Degrees.position(90.0), Position::Degrees(d) if d == 90.0));
+        assert!(
+            matches!(EncoderUnits::Rotations.position(720.0), Position::Rotations(r) if r == 2.0)
+        );
+        // Counts are independent of the gearset: 4096 per rotation.
+        assert!(matches!(
+            EncoderUnits::Counts.position(90.0),
+            Position::Counts(1024)
+        ));
+        assert!(matches!(
+            EncoderUnits::Counts.position(-360.0),
+            Position::Counts(-4096)
+        ));
+    }
+}
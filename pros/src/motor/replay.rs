@@ -0,0 +1,243 @@
+//! Recording the voltage commands sent to motors and playing them back.
+//!
+//! A [`MotorRecorder`] notes the last voltage commanded to each of its motors, from any code that commands them,
+//! and takes a snapshot every time it is ticked. The resulting [`MotorLog`] can be saved to the SD card
+//! and played back open loop with [`MotorPlayback`], for example to compare how a mechanism behaves
+//! before and after a change, or to drive a "ghost" of a driver's run as an autonomous routine.
+//!
+//! Position targets (such as [`Motor::set_position_absolute`](super::Motor::set_position_absolute))
+//! aren't voltages, so they are recorded as gaps like calls to [`Motor::brake`](super::Motor::brake),
+//! and playback brakes the motor during them.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "alloc")]
+use core::time::Duration;
+
+#[cfg(feature = "alloc")]
+use pros_sys::PROS_ERR;
+
+#[cfg(feature = "alloc")]
+use super::{velocity, MotorError};
+#[cfg(feature = "alloc")]
+use crate::{
+    encode::{Decode, DecodeError, Decoder, Encode, Encoder},
+    error::bail_on,
+};
+
+/// A bit for each port whose commands are being recorded.
+static RECORDED_PORTS: AtomicU32 = AtomicU32::new(0);
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NOT_COMMANDED: AtomicU32 = AtomicU32::new(f32::NAN.to_bits());
+/// The bits of the last voltage commanded to each port.
+static COMMANDED: [AtomicU32; 21] = [NOT_COMMANDED; 21];
+
+/// Notes a command sent to a motor, if its port is being recorded.
+/// Commands that aren't voltages are noted as NaN.
+pub(crate) fn note(port: u8, voltage: f32) {
+    if RECORDED_PORTS.load(Ordering::Relaxed) & 1 << port != 0 {
+        COMMANDED[port as usize - 1].store(voltage.to_bits(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn commanded(port: u8) -> f32 {
+    f32::from_bits(COMMANDED[port as usize - 1].load(Ordering::Relaxed))
+}
+
+/// A snapshot of the voltages commanded to the recorded motors.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotorLogSample {
+    /// Milliseconds since recording started.
+    pub time: u32,
+    /// The voltage for each port in [`MotorLog::ports`], or NaN if the motor wasn't commanded with a voltage.
+    pub voltages: Vec<f32>,
+}
+
+/// Voltage commands recorded by a [`MotorRecorder`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MotorLog {
+    pub ports: Vec<u8>,
+    pub samples: Vec<MotorLogSample>,
+}
+
+#[cfg(feature = "alloc")]
+impl MotorLog {
+    /// How long the log lasts.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.samples.last().map_or(0, |sample| sample.time) as u64)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for MotorLog {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.ports);
+        encoder.write(&self.samples.len());
+        for sample in &self.samples {
+            encoder.write(&sample.time);
+            encoder.write(&sample.voltages);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Decode for MotorLog {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let ports: Vec<u8> = decoder.read()?;
+        let len: usize = decoder.read()?;
+        if len > decoder.remaining().len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let mut samples = Vec::with_capacity(len);
+        for _ in 0..len {
+            let time = decoder.read()?;
+            let voltages: Vec<f32> = decoder.read()?;
+            if voltages.len() != ports.len() {
+                return Err(DecodeError::InvalidValue);
+            }
+            samples.push(MotorLogSample { time, voltages });
+        }
+        Ok(Self { ports, samples })
+    }
+}
+
+/// Records the voltages commanded to a set of motors.
+/// Recording stops when this is dropped.
+#[cfg(feature = "alloc")]
+pub struct MotorRecorder {
+    log: MotorLog,
+    start: Option<u32>,
+}
+
+#[cfg(feature = "alloc")]
+impl MotorRecorder {
+    /// Starts noting commands sent to the motors on `ports`.
+    pub fn new(ports: &[u8]) -> Self {
+        let mask = ports.iter().fold(0, |mask, port| mask | 1 << port);
+        for &port in ports {
+            COMMANDED[port as usize - 1].store(f32::NAN.to_bits(), Ordering::Relaxed);
+        }
+        RECORDED_PORTS.fetch_or(mask, Ordering::Relaxed);
+        Self {
+            log: MotorLog {
+                ports: ports.to_vec(),
+                samples: Vec::new(),
+            },
+            start: None,
+        }
+    }
+
+    /// Snapshots the last voltage commanded to each motor. This should be called once per control loop tick,
+    /// after the motors have been commanded.
+    pub fn tick(&mut self) {
+        let now = unsafe { pros_sys::millis() };
+        let start = *self.start.get_or_insert(now);
+        self.log.samples.push(MotorLogSample {
+            time: now - start,
+            voltages: self.log.ports.iter().map(|&port| commanded(port)).collect(),
+        });
+    }
+
+    pub fn log(&self) -> &MotorLog {
+        &self.log
+    }
+
+    /// Stops recording and returns what was recorded.
+    pub fn finish(mut self) -> MotorLog {
+        core::mem::take(&mut self.log)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for MotorRecorder {
+    fn drop(&mut self) {
+        let mask = self.log.ports.iter().fold(0, |mask, port| mask | 1 << port);
+        RECORDED_PORTS.fetch_and(!mask, Ordering::Relaxed);
+    }
+}
+
+/// Plays back a [`MotorLog`] by sending each recorded voltage at the time it was recorded.
+#[cfg(feature = "alloc")]
+pub struct MotorPlayback {
+    log: MotorLog,
+    start: Option<u32>,
+    next: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl MotorPlayback {
+    /// How often [`MotorPlayback::run`] updates the motors.
+    pub const INTERVAL: Duration = Duration::from_millis(5);
+
+    pub fn new(log: MotorLog) -> Self {
+        Self {
+            log,
+            start: None,
+            next: 0,
+        }
+    }
+
+    fn apply(&self, index: usize) -> Result<(), MotorError> {
+        let sample = &self.log.samples[index];
+        for (&port, &voltage) in self.log.ports.iter().zip(&sample.voltages) {
+            velocity::release(port);
+            note(port, voltage);
+            unsafe {
+                if voltage.is_nan() {
+                    bail_on!(PROS_ERR, pros_sys::motor_brake(port));
+                } else {
+                    bail_on!(
+                        PROS_ERR,
+                        pros_sys::motor_move_voltage(port, (voltage * 1000.0) as i32)
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the voltages for the current time. The first call starts playback.
+    /// Returns true once the whole log has been played, after which the motors are stopped.
+    pub fn update(&mut self) -> Result<bool, MotorError> {
+        let now = unsafe { pros_sys::millis() };
+        let elapsed = now - *self.start.get_or_insert(now);
+
+        let mut latest = None;
+        while self
+            .log
+            .samples
+            .get(self.next)
+            .is_some_and(|sample| sample.time <= elapsed)
+        {
+            latest = Some(self.next);
+            self.next += 1;
+        }
+        if let Some(index) = latest {
+            self.apply(index)?;
+        }
+
+        if self.next < self.log.samples.len() {
+            return Ok(false);
+        }
+        for &port in &self.log.ports {
+            note(port, 0.0);
+            unsafe {
+                bail_on!(PROS_ERR, pros_sys::motor_move_voltage(port, 0));
+            }
+        }
+        Ok(true)
+    }
+
+    /// Plays the whole log, blocking until it is finished.
+    pub fn run(&mut self) -> Result<(), MotorError> {
+        while !self.update()? {
+            crate::task::sleep(Self::INTERVAL);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+//! Velocity estimation and control that run in this crate instead of in the motor firmware.
+//!
+//! The firmware reports velocity from a 10 millisecond loop and the reading is noisy.
+//! Motors passed to [`Motor::velocity_filtered`] or [`Motor::set_velocity_external`] are instead sampled by a background task
+//! every 5 milliseconds, and their velocity is estimated by differentiating the raw position through a [`VelocityFilter`].
+//!
+//! Motors controlled with [`Motor::set_velocity_external`] are driven by voltage from a PIDF loop on that estimate,
+//! which tracks setpoints more closely for flywheels and drivetrains than the firmware's velocity loop.
+//! The same task runs the position loop for [`Motor::hold_at_current_position`](super::hold).
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::{
+    hold::{Hold, HoldGains},
+    Motor, MotorError,
+};
+use crate::sync::Mutex;
+
+/// How often motors are sampled and controlled.
+const LOOP_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How a [`VelocityEstimator`] smooths the derivative of position.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VelocityFilter {
+    /// An exponential moving average of the difference between consecutive samples.
+    /// The value is how much of each new difference goes into the estimate, from 0 to 1.
+    /// Lower values filter out more noise but respond more slowly.
+    Ema(f32),
+    /// The slope of a line fit through the last five samples.
+    /// This rejects noise well with a fixed lag of two samples.
+    #[default]
+    SavitzkyGolay,
+}
+
+/// Estimates velocity from position samples taken at a fixed interval.
+#[derive(Debug, Clone)]
+pub struct VelocityEstimator {
+    filter: VelocityFilter,
+    samples: [f32; 5],
+    count: usize,
+    estimate: f32,
+}
+
+impl VelocityEstimator {
+    pub fn new(filter: VelocityFilter) -> Self {
+        Self {
+            filter,
+            samples: [0.0; 5],
+            count: 0,
+            estimate: 0.0,
+        }
+    }
+
+    /// Adds a position sample in degrees taken `dt` seconds after the last one,
+    /// returning the new estimate in RPM.
+    pub fn update(&mut self, position: f32, dt: f32) -> f32 {
+        self.samples.rotate_left(1);
+        self.samples[4] = position;
+        self.count = (self.count + 1).min(5);
+
+        // degrees per second to RPM
+        let scale = 1.0 / (dt * 6.0);
+        let s = &self.samples;
+        match self.filter {
+            VelocityFilter::Ema(alpha) if self.count >= 2 => {
+                let measured = (s[4] - s[3]) * scale;
+                self.estimate += alpha * (measured - self.estimate);
+            }
+            VelocityFilter::SavitzkyGolay if self.count == 5 => {
+                self.estimate = (2.0 * (s[4] - s[0]) + (s[3] - s[1])) / 10.0 * scale;
+            }
+            _ => {}
+        }
+        self.estimate
+    }
+
+    /// Returns the latest estimate in RPM.
+    pub fn estimate(&self) -> f32 {
+        self.estimate
+    }
+
+    /// Forgets all samples and resets the estimate to zero.
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.estimate = 0.0;
+    }
+}
+
+/// Gains for an externally controlled motor.
+/// Outputs are in volts and errors are in RPM.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VelocityGains {
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+    /// Volts applied per RPM of setpoint, before any feedback.
+    pub kf: f32,
+}
+
+impl VelocityGains {
+    /// Gains with pure feedforward sized for the given gearset and light proportional feedback.
+    pub fn for_gearset(gearset: super::Gearset) -> Self {
+        Self {
+            kp: 0.02,
+            ki: 0.0,
+            kd: 0.0,
+            kf: 12.0 / gearset.max_rpm(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Control {
+    setpoint: f32,
+    last_error: f32,
+    integral: f32,
+}
+
+#[derive(Debug)]
+pub(super) struct Tracked {
+    pub(super) motor: Motor,
+    estimator: VelocityEstimator,
+    gains: Option<VelocityGains>,
+    control: Option<Control>,
+    pub(super) hold: Option<Hold>,
+    pub(super) hold_gains: HoldGains,
+}
+
+impl Tracked {
+    fn update(&mut self, dt: f32) -> Result<(), MotorError> {
+        let position = self.motor.position()?.into_degrees() as f32;
+        let estimate = self.estimator.update(position, dt);
+
+        if let Some(hold) = &mut self.hold {
+            let voltage = hold.update(&self.hold_gains, position, estimate, dt);
+            return self.motor.set_voltage_raw(voltage);
+        }
+
+        let Some(control) = &mut self.control else {
+            return Ok(());
+        };
+        let gains = match self.gains {
+            Some(gains) => gains,
+            None => *self
+                .gains
+                .insert(VelocityGains::for_gearset(self.motor.gearset()?)),
+        };
+
+        let error = control.setpoint - estimate;
+        control.integral += error * dt;
+        let derivative = (error - control.last_error) / dt;
+        control.last_error = error;
+
+        let voltage = gains.kf * control.setpoint
+            + gains.kp * error
+            + gains.ki * control.integral
+            + gains.kd * derivative;
+        self.motor.set_voltage_raw(voltage.clamp(-12.0, 12.0))
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Motors being sampled, indexed by port number minus one.
+    static ref TRACKED: Mutex<[Option<Tracked>; 21]> = Mutex::new(core::array::from_fn(|_| None));
+}
+static TASK_STARTED: AtomicBool = AtomicBool::new(false);
+
+pub(super) fn with_tracked<T>(motor: &Motor, f: impl FnOnce(&mut Tracked) -> T) -> T {
+    if !TASK_STARTED.swap(true, Ordering::AcqRel) {
+        crate::task::spawn(sample_loop);
+    }
+
+    let mut tracked = TRACKED.lock();
+    let tracked = tracked[motor.port as usize - 1].get_or_insert_with(|| Tracked {
+        motor: *motor,
+        estimator: VelocityEstimator::new(VelocityFilter::default()),
+        gains: None,
+        control: None,
+        hold: None,
+        hold_gains: HoldGains::default(),
+    });
+    f(tracked)
+}
+
+fn sample_loop() {
+    let dt = LOOP_INTERVAL.as_secs_f32();
+    loop {
+        for slot in TRACKED.lock().iter_mut() {
+            if let Some(Err(err)) = slot.as_mut().map(|tracked| tracked.update(dt)) {
+                #[cfg(feature = "alloc")]
+                crate::error::report(&err);
+                #[cfg(not(feature = "alloc"))]
+                let _ = err;
+                *slot = None;
+            }
+        }
+        crate::task::sleep(LOOP_INTERVAL);
+    }
+}
+
+/// Stops external velocity or position control of the motor on the given port, if it is running.
+/// The motor's velocity keeps being estimated.
+pub(crate) fn release(port: u8) {
+    if TASK_STARTED.load(Ordering::Acquire) {
+        if let Some(tracked) = &mut TRACKED.lock()[port as usize - 1] {
+            tracked.control = None;
+            tracked.hold = None;
+        }
+    }
+}
+
+impl Motor {
+    /// Returns the motor's velocity in RPM, estimated from its raw position.
+    /// See the [module documentation](self) for details.
+    ///
+    /// The first call starts sampling the motor and returns zero;
+    /// the estimate is ready once a few samples have been taken.
+    /// Errors reading the motor are passed to `crate::error::report` and stop sampling until this is called again.
+    pub fn velocity_filtered(&self) -> f32 {
+        with_tracked(self, |tracked| tracked.estimator.estimate())
+    }
+
+    /// Sets how [`Motor::velocity_filtered`] smooths its estimate.
+    pub fn set_velocity_filter(&self, filter: VelocityFilter) {
+        with_tracked(self, |tracked| {
+            tracked.estimator = VelocityEstimator::new(filter);
+        });
+    }
+
+    /// Spins the motor at a velocity in RPM using this crate's own control loop
+    /// rather than the firmware's. See the [module documentation](self) for details.
+    ///
+    /// The motor stays under external control until it is given another command,
+    /// such as [`Motor::set_voltage`] or [`Motor::brake`].
+    /// Errors reading or driving the motor from the control loop are passed to `crate::error::report`
+    /// and end external control.
+    pub fn set_velocity_external(&self, rpm: f32) {
+        with_tracked(self, |tracked| {
+            tracked.hold = None;
+            tracked
+                .control
+                .get_or_insert_with(Control::default)
+                .setpoint = rpm;
+        });
+    }
+
+    /// Sets the gains used by [`Motor::set_velocity_external`].
+    /// Until this is called, gains from [`VelocityGains::for_gearset`] are used.
+    pub fn set_velocity_gains(&self, gains: VelocityGains) {
+        with_tracked(self, |tracked| tracked.gains = Some(gains));
+    }
+}
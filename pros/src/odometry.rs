@@ -0,0 +1,541 @@
+//! Tracking the robot's pose from wheel travel and an IMU.
+//!
+//! [`Odometry`] prefers dedicated tracking wheels, which don't slip when the drive wheels do,
+//! but keeps checking them against the drive motors and the IMU.
+//! If a tracking wheel is unplugged, stops turning, or disagrees with the IMU,
+//! odometry falls back to the drive motor encoders and reports the problem through [`error::report`].
+//!
+//! Odometry also estimates how far off its pose may have drifted. The estimate grows as the robot drives and turns,
+//! and shrinks with each absolute correction, such as from a GPS sensor or squaring up against a wall,
+//! so autonomous routines can check [`Odometry::pose_confidence`] to decide when it's worth stopping to re-localize.
+
+use alloc::sync::Arc;
+use core::{f64::consts::PI, time::Duration};
+
+use snafu::Snafu;
+
+use crate::{
+    competition::{self, CompetitionMode},
+    drivetrain::Drivetrain,
+    error::{self, PortError},
+    math::angle,
+    motor::MotorError,
+    pose::{Pose, PoseHistory},
+    sensors::{
+        gps::GpsSensor,
+        imu::{Gyro, ImuError},
+        rotation::{ContinuousRotation, RotationSensor},
+    },
+    sync::Watch,
+};
+
+/// An unpowered wheel on a rotation sensor that measures how far the robot travels.
+///
+/// Travel is built from the sensor's angle with a [`ContinuousRotation`],
+/// so the distance doesn't jump if the sensor briefly loses power.
+pub struct TrackingWheel {
+    sensor: ContinuousRotation,
+    diameter: f64,
+}
+
+impl TrackingWheel {
+    pub fn new(sensor: RotationSensor, diameter: f64) -> Result<Self, PortError> {
+        Ok(Self {
+            sensor: ContinuousRotation::new(sensor)?,
+            diameter,
+        })
+    }
+
+    /// Returns how far the wheel has traveled since it was created.
+    pub fn distance(&self) -> Result<f64, PortError> {
+        Ok(self.sensor.position()?.into_rotations() * PI * self.diameter)
+    }
+}
+
+/// A pair of parallel tracking wheels on either side of the robot.
+pub struct TrackingWheels {
+    pub left: TrackingWheel,
+    pub right: TrackingWheel,
+    /// The distance between the two wheels.
+    pub track_width: f64,
+}
+
+impl TrackingWheels {
+    /// Sets up tracking wheels on the given ports,
+    /// returning `None` if either rotation sensor isn't plugged in.
+    pub fn detect(left_port: u8, right_port: u8, diameter: f64, track_width: f64) -> Option<Self> {
+        let left = RotationSensor::new(left_port, false).ok()?;
+        let right = RotationSensor::new(right_port, false).ok()?;
+        Some(Self {
+            left: TrackingWheel::new(left, diameter).ok()?,
+            right: TrackingWheel::new(right, diameter).ok()?,
+            track_width,
+        })
+    }
+
+    fn distances(&self) -> Result<(f64, f64), PortError> {
+        Ok((self.left.distance()?, self.right.distance()?))
+    }
+}
+
+/// Where [`Odometry`] is getting wheel travel from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdometrySource {
+    TrackingWheels,
+    /// The drive motors' encoders, used when there are no working tracking wheels.
+    MotorEncoders,
+}
+
+/// Thresholds for deciding that the tracking wheels can't be trusted.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthConfig {
+    /// How many updates to gather before comparing sensors.
+    pub window: u32,
+    /// The least the drive motors must travel during a window for the check to run.
+    pub min_distance: f64,
+    /// The fraction of the drive motors' travel the tracking wheels may fall short by.
+    pub slip_tolerance: f64,
+    /// How many degrees the tracking wheels' heading change may differ from the IMU's during a window.
+    pub heading_tolerance: f64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            window: 50,
+            min_distance: 2.0,
+            slip_tolerance: 0.5,
+            heading_tolerance: 10.0,
+        }
+    }
+}
+
+/// How quickly [`Odometry`]'s estimate of its own error grows.
+///
+/// Errors are standard deviations, and each one's variance grows in proportion to how far the robot moves,
+/// like a random walk.
+#[derive(Debug, Clone, Copy)]
+pub struct UncertaintyConfig {
+    /// The variance in position, in units squared, added for each unit the tracking wheels travel.
+    pub position_drift: f64,
+    /// The variance in heading, in degrees squared, added for each degree the robot turns.
+    pub heading_drift: f64,
+    /// How many times faster position error grows when using the drive motor encoders,
+    /// since drive wheels slip much more than tracking wheels.
+    pub motor_encoder_factor: f64,
+    /// The position error at which [`Odometry::pose_confidence`] is 0.5.
+    pub tolerance: f64,
+}
+
+impl Default for UncertaintyConfig {
+    fn default() -> Self {
+        Self {
+            position_drift: 0.01,
+            heading_drift: 0.005,
+            motor_encoder_factor: 4.0,
+            tolerance: 2.0,
+        }
+    }
+}
+
+/// How far off [`Odometry`]'s pose may be, as standard deviations.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PoseUncertainty {
+    /// The position error, in pose units.
+    pub position: f64,
+    /// The heading error, in degrees.
+    pub heading: f64,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    updates: u32,
+    wheel_travel: f64,
+    motor_travel: f64,
+    wheel_turn: f64,
+    imu_turn: f64,
+}
+
+/// Tracks the robot's pose.
+pub struct Odometry {
+    pose: Pose,
+    tracking_wheels: Option<TrackingWheels>,
+    source: OdometrySource,
+    health: HealthConfig,
+    window: Window,
+    last_wheels: Option<(f64, f64)>,
+    last_motors: Option<(f64, f64)>,
+    last_rotation: Option<f64>,
+    published: Arc<Watch<Pose>>,
+    history: PoseHistory,
+    uncertainty: UncertaintyConfig,
+    position_variance: f64,
+    heading_variance: f64,
+}
+
+impl Odometry {
+    /// Starts tracking from `pose`, using the tracking wheels if there are any.
+    pub fn new(pose: Pose, tracking_wheels: Option<TrackingWheels>) -> Self {
+        Self::with_health_config(pose, tracking_wheels, HealthConfig::default())
+    }
+
+    pub fn with_health_config(
+        pose: Pose,
+        tracking_wheels: Option<TrackingWheels>,
+        health: HealthConfig,
+    ) -> Self {
+        let source = match tracking_wheels {
+            Some(_) => OdometrySource::TrackingWheels,
+            None => OdometrySource::MotorEncoders,
+        };
+        Self {
+            pose,
+            tracking_wheels,
+            source,
+            health,
+            window: Window::default(),
+            last_wheels: None,
+            last_motors: None,
+            last_rotation: None,
+            published: Arc::new(Watch::new(pose)),
+            history: PoseHistory::new(),
+            uncertainty: UncertaintyConfig::default(),
+            position_variance: 0.0,
+            heading_variance: 0.0,
+        }
+    }
+
+    pub fn with_uncertainty_config(mut self, uncertainty: UncertaintyConfig) -> Self {
+        self.uncertainty = uncertainty;
+        self
+    }
+
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    /// Moves the tracked pose without touching any sensors.
+    /// The pose is assumed to be exact, so the uncertainty is reset to zero.
+    pub fn set_pose(&mut self, pose: Pose) {
+        self.pose = pose;
+        self.position_variance = 0.0;
+        self.heading_variance = 0.0;
+        self.history.clear();
+        self.published.send(pose);
+    }
+
+    /// Returns how far off the pose may be.
+    pub fn pose_uncertainty(&self) -> PoseUncertainty {
+        PoseUncertainty {
+            position: libm::sqrt(self.position_variance),
+            heading: libm::sqrt(self.heading_variance),
+        }
+    }
+
+    /// Returns how much to trust the pose, from 1.0 when it was just set to 0.0 as it drifts without bound.
+    /// This is 0.5 when the position error reaches [`UncertaintyConfig::tolerance`].
+    pub fn pose_confidence(&self) -> f64 {
+        let tolerance = self.uncertainty.tolerance;
+        if tolerance <= 0.0 {
+            return if self.position_variance == 0.0 {
+                1.0
+            } else {
+                0.0
+            };
+        }
+        1.0 / (1.0 + self.position_variance / (tolerance * tolerance))
+    }
+
+    /// Returns the recent poses recorded by [`Odometry::update`].
+    pub fn history(&self) -> &PoseHistory {
+        &self.history
+    }
+
+    /// Returns where the robot was at a `millis()` time, if it is recent enough to still be in the history.
+    pub fn pose_at(&self, time: u32) -> Option<Pose> {
+        self.history.at(time)
+    }
+
+    /// Corrects the pose with an absolute measurement, such as from vision, that was captured at a `millis()` time.
+    ///
+    /// The measurement is compared against the pose at the time it was captured, and the difference
+    /// is applied to the current pose, so any movement since then is kept.
+    /// `weight` is how much to trust the measurement, from 0.0 (ignore it) to 1.0 (fully trust it),
+    /// and the uncertainty shrinks by the same fraction.
+    pub fn correct_at(&mut self, time: u32, measured: Pose, weight: f64) {
+        let then = self.history.at(time).unwrap_or(self.pose);
+        let weight = weight.clamp(0.0, 1.0);
+        self.position_variance *= 1.0 - weight;
+        self.heading_variance *= 1.0 - weight;
+        let dx = (measured.x - then.x) * weight;
+        let dy = (measured.y - then.y) * weight;
+        let dheading = angle::difference_degrees(then.heading, measured.heading) * weight;
+
+        self.pose.x += dx;
+        self.pose.y += dy;
+        self.pose.heading += dheading;
+        self.history.shift(dx, dy, dheading);
+        self.published.send(self.pose);
+    }
+
+    /// Corrects the pose with an absolute measurement whose own error is known, such as a GPS reading,
+    /// that was captured at a `millis()` time.
+    ///
+    /// The measurement is weighed against the pose's uncertainty, so it moves the pose more when
+    /// the pose has drifted further, like one step of a Kalman filter. `error` is the measurement's
+    /// position error as a standard deviation in pose units.
+    pub fn correct_with_error_at(&mut self, time: u32, measured: Pose, error: f64) {
+        let measured_variance = error * error;
+        let total = self.position_variance + measured_variance;
+        let weight = if total > 0.0 {
+            self.position_variance / total
+        } else {
+            1.0
+        };
+        self.correct_at(time, measured, weight);
+    }
+
+    /// Returns a [`Watch`] that is sent the pose every time it changes,
+    /// so other tasks can follow it without access to the odometry itself.
+    pub fn pose_watch(&self) -> Arc<Watch<Pose>> {
+        self.published.clone()
+    }
+
+    pub fn source(&self) -> OdometrySource {
+        self.source
+    }
+
+    /// Stops using the tracking wheels and reports why.
+    fn degrade(&mut self, reason: OdometryError) {
+        self.source = OdometrySource::MotorEncoders;
+        error::report(&reason);
+    }
+
+    /// Reads the sensors and updates the pose. This should be called every 10 ms or so.
+    pub fn update(
+        &mut self,
+        drivetrain: &Drivetrain,
+        imu: &impl Gyro,
+    ) -> Result<Pose, OdometryError> {
+        let motors = (drivetrain.left_distance()?, drivetrain.right_distance()?);
+        let rotation = imu.rotation()?;
+
+        let wheels = match (&self.tracking_wheels, self.source) {
+            (Some(tracking_wheels), OdometrySource::TrackingWheels) => {
+                match tracking_wheels.distances() {
+                    Ok(wheels) => Some(wheels),
+                    Err(_) => {
+                        self.degrade(OdometryError::TrackingWheelDisconnected);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let (Some(last_motors), Some(last_rotation)) = (self.last_motors, self.last_rotation)
+        else {
+            self.last_motors = Some(motors);
+            self.last_rotation = Some(rotation);
+            self.last_wheels = wheels;
+            return Ok(self.pose);
+        };
+
+        let motor_delta = (motors.0 - last_motors.0, motors.1 - last_motors.1);
+        // The IMU measures clockwise, but poses are counterclockwise.
+        let turn = -(rotation - last_rotation);
+
+        let travel = match (wheels, self.last_wheels) {
+            (Some(wheels), Some(last_wheels)) => {
+                let wheel_delta = (wheels.0 - last_wheels.0, wheels.1 - last_wheels.1);
+                self.check_health(wheel_delta, motor_delta, turn);
+                (wheel_delta.0 + wheel_delta.1) / 2.0
+            }
+            _ => (motor_delta.0 + motor_delta.1) / 2.0,
+        };
+
+        let heading = (self.pose.heading + turn / 2.0).to_radians();
+        self.pose.x += travel * libm::cos(heading);
+        self.pose.y += travel * libm::sin(heading);
+        self.pose.heading += turn;
+        self.grow_uncertainty(travel, turn);
+
+        self.last_motors = Some(motors);
+        self.last_rotation = Some(rotation);
+        self.last_wheels = wheels;
+        self.history.push(unsafe { pros_sys::millis() }, self.pose);
+        self.published.send(self.pose);
+        Ok(self.pose)
+    }
+
+    fn grow_uncertainty(&mut self, travel: f64, turn: f64) {
+        let drift = match self.source {
+            OdometrySource::TrackingWheels => self.uncertainty.position_drift,
+            OdometrySource::MotorEncoders => {
+                self.uncertainty.position_drift * self.uncertainty.motor_encoder_factor
+            }
+        };
+        self.heading_variance += self.uncertainty.heading_drift * libm::fabs(turn);
+        // An error in heading also moves the robot sideways from where odometry thinks it went.
+        let sideways = travel * libm::sqrt(self.heading_variance).to_radians();
+        self.position_variance += drift * libm::fabs(travel) + sideways * sideways;
+    }
+
+    fn check_health(&mut self, wheel_delta: (f64, f64), motor_delta: (f64, f64), imu_turn: f64) {
+        let Some(tracking_wheels) = &self.tracking_wheels else {
+            return;
+        };
+        let window = &mut self.window;
+        window.updates += 1;
+        window.wheel_travel += libm::fabs(wheel_delta.0) + libm::fabs(wheel_delta.1);
+        window.motor_travel += libm::fabs(motor_delta.0) + libm::fabs(motor_delta.1);
+        window.wheel_turn +=
+            ((wheel_delta.1 - wheel_delta.0) / tracking_wheels.track_width).to_degrees();
+        window.imu_turn += imu_turn;
+
+        if window.updates < self.health.window {
+            return;
+        }
+        let window = core::mem::take(&mut self.window);
+
+        if window.motor_travel < self.health.min_distance {
+            return;
+        }
+        if window.wheel_travel < window.motor_travel * (1.0 - self.health.slip_tolerance) {
+            self.degrade(OdometryError::TrackingWheelStalled);
+        } else if libm::fabs(window.wheel_turn - window.imu_turn) > self.health.heading_tolerance {
+            self.degrade(OdometryError::TrackingWheelSlipping);
+        }
+    }
+}
+
+/// How to find the starting pose with a GPS sensor before the match starts.
+///
+/// ```rust
+/// let start = GpsStart {
+///     fallback: Pose::new(-60.0, -36.0, 0.0),
+///     ..Default::default()
+/// };
+/// // The offset moves readings from the sensor to the center of the robot.
+/// let gps = GpsSensor::new(12)?;
+/// gps.set_offset(-0.1, 0.05);
+/// let mut odometry = Odometry::new(start.fallback, None);
+/// odometry.start_from_gps(&start, &gps);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsStart {
+    /// The pose to start from if there is no GPS sensor or it can't see the field strip well enough.
+    pub fallback: Pose,
+    /// The most RMS error a reading can have to be used, in meters.
+    pub max_error: f64,
+    /// How many good readings to average.
+    pub samples: usize,
+    /// How long to try for before falling back.
+    pub timeout: Duration,
+    /// How many pose units are in a meter, such as 39.37 for inches.
+    pub units_per_meter: f64,
+}
+
+impl Default for GpsStart {
+    fn default() -> Self {
+        Self {
+            fallback: Pose::default(),
+            max_error: 0.03,
+            samples: 20,
+            timeout: Duration::from_secs(2),
+            units_per_meter: 39.37,
+        }
+    }
+}
+
+/// Where a starting pose found by [`GpsStart::locate`] came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StartSource {
+    /// The average of good GPS readings, with the worst RMS error among them in meters.
+    Gps { max_error: f64 },
+    /// The configured fallback pose.
+    Fallback,
+}
+
+impl GpsStart {
+    /// Samples a GPS sensor while the robot is disabled and returns the average pose.
+    /// The sensor's offset should already be set so that it reports the center of the robot.
+    ///
+    /// Falls back to [`GpsStart::fallback`] if the sensor can't be read, such as when it is unplugged,
+    /// or if there weren't enough readings under [`GpsStart::max_error`]
+    /// before the timeout or the match starting.
+    pub fn locate(&self, gps: &GpsSensor) -> (Pose, StartSource) {
+        let start = unsafe { pros_sys::millis() };
+        let (mut x, mut y, mut sin, mut cos) = (0.0, 0.0, 0.0, 0.0);
+        let mut worst_error: f64 = 0.0;
+        let mut count = 0;
+        while count < self.samples.max(1) {
+            let match_started =
+                competition::is_connected() && competition::mode() != CompetitionMode::Disabled;
+            let timed_out = unsafe { pros_sys::millis() } - start > self.timeout.as_millis() as u32;
+            if match_started || timed_out {
+                return (self.fallback, StartSource::Fallback);
+            }
+
+            if let (Ok(error), Ok(pose)) = (gps.rms_error(), gps.pose(self.units_per_meter)) {
+                if error <= self.max_error {
+                    x += pose.x;
+                    y += pose.y;
+                    // Headings are averaged as vectors so that 359 and 1 average to 0.
+                    sin += libm::sin(pose.heading.to_radians());
+                    cos += libm::cos(pose.heading.to_radians());
+                    worst_error = worst_error.max(error);
+                    count += 1;
+                }
+            }
+            crate::task::sleep(Duration::from_millis(20));
+        }
+
+        let pose = Pose::new(
+            x / count as f64,
+            y / count as f64,
+            libm::atan2(sin, cos).to_degrees(),
+        );
+        (
+            pose,
+            StartSource::Gps {
+                max_error: worst_error,
+            },
+        )
+    }
+}
+
+impl Odometry {
+    /// Sets the pose from a GPS sensor, or the fallback pose if it can't be used.
+    /// This should be called from `disabled` or early in `init`, while the robot is still.
+    ///
+    /// The uncertainty starts at the worst GPS error among the readings, or zero for the fallback pose.
+    pub fn start_from_gps(&mut self, start: &GpsStart, gps: &GpsSensor) -> StartSource {
+        let (pose, source) = start.locate(gps);
+        self.set_pose(pose);
+        if let StartSource::Gps { max_error } = source {
+            let error = max_error * start.units_per_meter;
+            self.position_variance = error * error;
+        }
+        source
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum OdometryError {
+    #[snafu(display("A tracking wheel stopped responding; falling back to motor encoders."))]
+    TrackingWheelDisconnected,
+    #[snafu(display(
+        "A tracking wheel isn't turning while the robot drives; falling back to motor encoders."
+    ))]
+    TrackingWheelStalled,
+    #[snafu(display(
+        "The tracking wheels disagree with the IMU; falling back to motor encoders."
+    ))]
+    TrackingWheelSlipping,
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+    #[snafu(display("{source}"), context(false))]
+    Imu { source: ImuError },
+}
+impl core::error::Error for OdometryError {}
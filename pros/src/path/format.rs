@@ -0,0 +1,197 @@
+//! The binary format for sets of named paths.
+//!
+//! A file starts with the magic bytes `PPTH`, a format version byte, and the number of paths.
+//! Each path is its name, its number of waypoints, and for each waypoint its x, y, and heading
+//! and its speed limit, with `NaN` meaning there is none.
+//! Counts and lengths are LEB128 varints, and numbers are little endian `f64`s.
+//! A CRC32 of everything before it ends the file, so a file damaged on the SD card is rejected
+//! rather than driven.
+//!
+//! This module only depends on `alloc`, `snafu`, [`crc`](crate::encode::crc), and [`varint`](crate::encode::varint)
+//! so that the host crate in `tools/pros-paths` can share it.
+
+use alloc::{string::String, vec::Vec};
+
+use snafu::Snafu;
+
+use crate::encode::{
+    crc::crc32,
+    varint::{read_varint, take, write_varint, ReadError},
+};
+
+/// The bytes every file starts with.
+pub const MAGIC: &[u8; 4] = b"PPTH";
+/// The version of the format written by this module.
+pub const VERSION: u8 = 1;
+
+/// A point a path passes through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Waypoint {
+    pub x: f64,
+    pub y: f64,
+    /// The heading in degrees counterclockwise from the positive x axis.
+    pub heading: f64,
+    /// The fastest the robot should go on the way to this waypoint, if it should go slower than usual.
+    pub max_speed: Option<f64>,
+}
+
+/// A named list of waypoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub name: String,
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// Encodes paths in the format.
+pub fn write(paths: &[Path]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    write_varint(&mut out, paths.len() as u64);
+    for path in paths {
+        write_varint(&mut out, path.name.len() as u64);
+        out.extend_from_slice(path.name.as_bytes());
+        write_varint(&mut out, path.waypoints.len() as u64);
+        for waypoint in &path.waypoints {
+            for value in [
+                waypoint.x,
+                waypoint.y,
+                waypoint.heading,
+                waypoint.max_speed.unwrap_or(f64::NAN),
+            ] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    let crc = crc32(&out);
+    out.extend_from_slice(&crc.to_le_bytes());
+    out
+}
+
+/// Decodes paths written by [`write`].
+pub fn read(data: &[u8]) -> Result<Vec<Path>, FormatError> {
+    let rest = data.strip_prefix(MAGIC).ok_or(FormatError::NotAPathFile)?;
+    let (&version, _) = rest.split_first().ok_or(FormatError::Truncated)?;
+    if version != VERSION {
+        return Err(FormatError::UnsupportedVersion { version });
+    }
+    if data.len() < MAGIC.len() + 1 + 4 {
+        return Err(FormatError::Truncated);
+    }
+    let (body, crc) = data.split_at(data.len() - 4);
+    if crc32(body).to_le_bytes() != crc {
+        return Err(FormatError::Corrupted);
+    }
+
+    let mut rest = &body[MAGIC.len() + 1..];
+    let count = read_varint(&mut rest)? as usize;
+    // Every path takes at least two bytes, which bounds how much a corrupted count can allocate.
+    let mut paths = Vec::with_capacity(count.min(rest.len() / 2));
+    for _ in 0..count {
+        let len = read_varint(&mut rest)? as usize;
+        let name = String::from_utf8(take(&mut rest, len)?.to_vec())
+            .map_err(|_| FormatError::Corrupted)?;
+        let len = read_varint(&mut rest)? as usize;
+        let mut waypoints = Vec::with_capacity(len.min(rest.len() / 32));
+        for _ in 0..len {
+            let mut next = || -> Result<f64, FormatError> {
+                Ok(f64::from_le_bytes(take(&mut rest, 8)?.try_into().unwrap()))
+            };
+            let (x, y, heading, max_speed) = (next()?, next()?, next()?, next()?);
+            waypoints.push(Waypoint {
+                x,
+                y,
+                heading,
+                max_speed: (!max_speed.is_nan()).then_some(max_speed),
+            });
+        }
+        paths.push(Path { name, waypoints });
+    }
+    if !rest.is_empty() {
+        return Err(FormatError::Corrupted);
+    }
+    Ok(paths)
+}
+
+#[derive(Debug, Snafu)]
+pub enum FormatError {
+    #[snafu(display("The file is not a path file."))]
+    NotAPathFile,
+    #[snafu(display(
+        "The paths were written in version {version} of the format, which isn't supported."
+    ))]
+    UnsupportedVersion { version: u8 },
+    #[snafu(display("The path file ended early."))]
+    Truncated,
+    #[snafu(display("The path file is corrupted."))]
+    Corrupted,
+}
+impl core::error::Error for FormatError {}
+
+impl From<ReadError> for FormatError {
+    fn from(err: ReadError) -> Self {
+        match err {
+            ReadError::UnexpectedEnd => Self::Truncated,
+            ReadError::Overlong => Self::Corrupted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    fn paths() -> Vec<Path> {
+        vec![
+            Path {
+                name: "left".to_string(),
+                waypoints: vec![
+                    Waypoint {
+                        x: 0.0,
+                        y: 0.0,
+                        heading: 90.0,
+                        max_speed: None,
+                    },
+                    Waypoint {
+                        x: 24.0,
+                        y: -12.5,
+                        heading: 45.0,
+                        max_speed: Some(0.5),
+                    },
+                ],
+            },
+            Path {
+                name: "empty".to_string(),
+                waypoints: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(read(&write(&paths())).unwrap(), paths());
+        assert_eq!(read(&write(&[])).unwrap(), []);
+    }
+
+    #[test]
+    fn rejects_corruption() {
+        let data = write(&paths());
+        for index in [MAGIC.len() + 1, data.len() / 2, data.len() - 1] {
+            let mut corrupted = data.clone();
+            corrupted[index] ^= 0x10;
+            assert!(matches!(read(&corrupted), Err(FormatError::Corrupted)));
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(matches!(read(b"nope"), Err(FormatError::NotAPathFile)));
+        assert!(matches!(
+            read(b"PPTH\x02"),
+            Err(FormatError::UnsupportedVersion { version: 2 })
+        ));
+        assert!(matches!(read(b"PPTH\x01"), Err(FormatError::Truncated)));
+    }
+}
@@ -0,0 +1,83 @@
+//! Named paths planned ahead of time on a computer.
+//!
+//! Paths are built with the `pros-paths` crate in `tools/pros-paths`, typically from a build script,
+//! and saved in a compact binary format (see [`format`]). The file can be built into the program
+//! or copied to the SD card so paths can be changed without re-uploading:
+//! ```rust
+//! static PATHS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/paths.bin"));
+//!
+//! let paths = PathSet::parse(PATHS)?;
+//! // Or, from the SD card:
+//! let paths = PathSet::load("paths.bin")?;
+//! let poses = paths.get("left_side").unwrap().poses();
+//! ```
+
+use alloc::vec::Vec;
+
+use snafu::Snafu;
+
+use crate::{
+    pose::Pose,
+    usd::{self, UsdError},
+};
+
+pub mod format;
+
+pub use format::{FormatError, Path, Waypoint};
+
+impl Waypoint {
+    /// The waypoint's position and heading.
+    pub const fn pose(&self) -> Pose {
+        Pose::new(self.x, self.y, self.heading)
+    }
+}
+
+impl Path {
+    /// The position and heading of every waypoint, in order.
+    pub fn poses(&self) -> Vec<Pose> {
+        self.waypoints.iter().map(Waypoint::pose).collect()
+    }
+}
+
+/// A set of paths looked up by name.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PathSet {
+    paths: Vec<Path>,
+}
+
+impl PathSet {
+    /// Reads paths written by `pros-paths`.
+    pub fn parse(data: &[u8]) -> Result<Self, FormatError> {
+        Ok(Self {
+            paths: format::read(data)?,
+        })
+    }
+
+    /// Loads paths written by `pros-paths` from the SD card.
+    pub fn load(path: &str) -> Result<Self, PathError> {
+        Ok(Self::parse(&usd::read(path)?)?)
+    }
+
+    /// Returns the path with the given name.
+    pub fn get(&self, name: &str) -> Option<&Path> {
+        self.paths.iter().find(|path| path.name == name)
+    }
+
+    /// The name of every path, in the order they were defined.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(|path| path.name.as_str())
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Path> {
+        self.paths.iter()
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum PathError {
+    #[snafu(display("{source}"), context(false))]
+    Format { source: FormatError },
+    #[snafu(display("{source}"), context(false))]
+    Usd { source: UsdError },
+}
+impl core::error::Error for PathError {}
@@ -1,3 +1,5 @@
+use crate::actuator::VoltageOutput;
+
 /// A proportional–integral–derivative controller.
 ///
 /// This controller is used to smoothly move motors to a certain point,
@@ -12,10 +14,13 @@ pub struct PidController {
     pub ki: f32,
     /// Derivative constant. This allows you to change the motor behavior
     /// based on the rate of change of the error (predicting future values).
+    /// The rate is taken from the position rather than the error, so setpoint changes don't kick the output.
     pub kd: f32,
+    /// Slack in the gear train to compensate for, if any.
+    pub backlash: Option<Backlash>,
 
     last_time: i32,
-    last_position: f32,
+    last_position: Option<f32>,
     i: f32,
 }
 
@@ -25,13 +30,24 @@ impl PidController {
             kp,
             ki,
             kd,
+            backlash: None,
             last_time: 0,
-            last_position: 0.0,
+            last_position: None,
             i: 0.0,
         }
     }
 
+    /// Compensates for `width` sensor units of slack in the gear train. See [`Backlash`].
+    pub fn with_backlash(mut self, width: f32) -> Self {
+        self.backlash = Some(Backlash::new(width));
+        self
+    }
+
     pub fn update(&mut self, setpoint: f32, position: f32) -> f32 {
+        let setpoint = match &mut self.backlash {
+            Some(backlash) => backlash.apply(setpoint, position),
+            None => setpoint,
+        };
         let time = unsafe { pros_sys::clock() };
         let mut delta_time = (time - self.last_time) as f32 / pros_sys::CLOCKS_PER_SEC as f32;
         if delta_time == 0.0 {
@@ -39,21 +55,151 @@ impl PidController {
         }
         let error = setpoint - position;
 
-        self.i += error * delta_time;
+        // There's no time step before the first update, so it only sets the starting point.
+        if self.last_position.is_some() {
+            self.i += error * delta_time;
+        }
 
         let p = self.kp * error;
         let i = self.ki * self.i;
 
-        let mut d = (position - self.last_position) / delta_time;
+        // Moving towards the setpoint shrinks the error, so the position's rate is subtracted to damp it.
+        let mut d = match self.last_position {
+            Some(last_position) => -self.kd * (position - last_position) / delta_time,
+            None => 0.0,
+        };
         if d.is_nan() {
             d = 0.0
         }
 
         let output = p + i + d;
 
-        self.last_position = position;
+        self.last_position = Some(position);
         self.last_time = time;
 
         output
     }
+
+    /// Runs the controller and sends its output to `output` as a voltage.
+    pub fn drive<O: VoltageOutput>(
+        &mut self,
+        output: &mut O,
+        setpoint: f32,
+        position: f32,
+    ) -> Result<(), O::Error> {
+        let voltage = self.update(setpoint, position);
+        output.set_voltage(voltage.clamp(-12.0, 12.0))
+    }
+}
+
+/// Compensates for slack between a sensor and the mechanism it measures, such as loose gears between a motor and an arm.
+///
+/// When the mechanism reverses, the sensor has to travel across the slack before the mechanism moves,
+/// so it stops short of its target from whichever direction it approaches.
+/// This shifts the setpoint half the slack further in the direction the setpoint last moved,
+/// so the mechanism ends up where it was asked to be either way.
+/// Elevators, turrets, and other mechanisms built on a [`PidController`] get this through [`PidController::with_backlash`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backlash {
+    /// How far the sensor moves across the slack before the mechanism moves, in sensor units.
+    pub width: f32,
+    /// How far the setpoint has to move back before the direction is considered to have changed,
+    /// so a setpoint that jitters, like a turret tracking a target, doesn't flip the offset back and forth.
+    pub hysteresis: f32,
+    direction: f32,
+    extreme: Option<f32>,
+}
+
+impl Backlash {
+    pub const fn new(width: f32) -> Self {
+        Self {
+            width,
+            hysteresis: 0.0,
+            direction: 0.0,
+            extreme: None,
+        }
+    }
+
+    pub const fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Returns the direction the setpoint last moved: 1.0, -1.0, or 0.0 before it has moved.
+    pub fn direction(&self) -> f32 {
+        self.direction
+    }
+
+    /// Returns the setpoint shifted to make up for the slack.
+    /// `position` is used to tell which direction the first setpoint is in.
+    pub fn apply(&mut self, setpoint: f32, position: f32) -> f32 {
+        let extreme = *self.extreme.get_or_insert(position);
+        let change = setpoint - extreme;
+        if self.direction == 0.0 {
+            if change != 0.0 {
+                self.direction = libm::copysignf(1.0, change);
+                self.extreme = Some(setpoint);
+            }
+        } else if change * self.direction > 0.0 {
+            // Still moving the same way, so the slack is already taken up.
+            self.extreme = Some(setpoint);
+        } else if libm::fabsf(change) > self.hysteresis {
+            self.direction = -self.direction;
+            self.extreme = Some(setpoint);
+        }
+        setpoint + self.direction * self.width / 2.0
+    }
+
+    /// Forgets which direction the setpoint was moving, for when the mechanism is moved by something else.
+    pub fn reset(&mut self) {
+        self.direction = 0.0;
+        self.extreme = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_move_sets_direction() {
+        let mut backlash = Backlash::new(2.0);
+        assert_eq!(backlash.direction(), 0.0);
+        // A setpoint at the current position doesn't pick a direction yet.
+        assert_eq!(backlash.apply(10.0, 10.0), 10.0);
+        assert_eq!(backlash.apply(20.0, 10.0), 21.0);
+        assert_eq!(backlash.direction(), 1.0);
+
+        let mut backlash = Backlash::new(2.0);
+        assert_eq!(backlash.apply(0.0, 10.0), -1.0);
+        assert_eq!(backlash.direction(), -1.0);
+    }
+
+    #[test]
+    fn reverses_past_hysteresis() {
+        let mut backlash = Backlash::new(2.0).with_hysteresis(1.0);
+        backlash.apply(20.0, 0.0);
+        assert_eq!(backlash.apply(30.0, 0.0), 31.0);
+        assert_eq!(backlash.apply(28.0, 0.0), 27.0);
+        assert_eq!(backlash.direction(), -1.0);
+    }
+
+    #[test]
+    fn ignores_jitter_inside_hysteresis() {
+        let mut backlash = Backlash::new(2.0).with_hysteresis(1.0);
+        backlash.apply(20.0, 0.0);
+        for setpoint in [19.5, 20.0, 19.2, 19.9] {
+            assert_eq!(backlash.apply(setpoint, 0.0), setpoint + 1.0);
+            assert_eq!(backlash.direction(), 1.0);
+        }
+    }
+
+    #[test]
+    fn reset_forgets_direction() {
+        let mut backlash = Backlash::new(2.0);
+        backlash.apply(20.0, 0.0);
+        backlash.reset();
+        assert_eq!(backlash.direction(), 0.0);
+        assert_eq!(backlash.apply(-5.0, 0.0), -6.0);
+    }
 }
@@ -0,0 +1,249 @@
+//! Debouncing, timers, and rate limiting for programs that run everything from one loop.
+//!
+//! Some programs are easier to follow as a single loop than as several tasks:
+//! nothing runs in between two lines of the loop, so there is no shared state to lock.
+//! The helpers here only look at the time they are given, so they never block the loop
+//! and can be driven from virtual time in tests.
+//!
+//! A [`Poller`] runs every registered [`Pollable`] once per iteration:
+//! ```rust
+//! let mut poller = Poller::new();
+//! let mut blink = Timer::every(Duration::from_millis(500));
+//! poller.add(move |now| {
+//!     if blink.fired(now) {
+//!         println!("blink");
+//!     }
+//! });
+//! poller.run(Duration::from_millis(10));
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+
+/// Something that does a small amount of work each time the loop comes around.
+pub trait Pollable {
+    /// Does any work that is due at `now`, in milliseconds since the program started.
+    fn poll(&mut self, now: u32);
+}
+
+impl<F: FnMut(u32)> Pollable for F {
+    fn poll(&mut self, now: u32) {
+        self(now)
+    }
+}
+
+/// Runs registered [`Pollable`]s in the order they were added, once per iteration of a loop.
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct Poller {
+    pollables: Vec<Box<dyn Pollable>>,
+}
+
+#[cfg(feature = "alloc")]
+impl Poller {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pollable to the end of the loop.
+    pub fn add(&mut self, pollable: impl Pollable + 'static) {
+        self.pollables.push(Box::new(pollable));
+    }
+
+    /// Returns how many pollables have been added.
+    pub fn len(&self) -> usize {
+        self.pollables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pollables.is_empty()
+    }
+
+    /// Polls everything once at the current time.
+    pub fn poll(&mut self) {
+        self.poll_at(unsafe { pros_sys::millis() });
+    }
+
+    /// Polls everything once at the given time.
+    pub fn poll_at(&mut self, now: u32) {
+        for pollable in &mut self.pollables {
+            pollable.poll(now);
+        }
+    }
+
+    /// Polls everything every `period` forever.
+    /// Iterations start a fixed period apart, however long the last one took.
+    pub fn run(&mut self, period: Duration) -> ! {
+        let period = (period.as_millis() as u32).max(1);
+        let mut last = unsafe { pros_sys::millis() };
+        loop {
+            self.poll_at(last);
+            // `task_delay_until` moves `last` forward by the period.
+            unsafe { pros_sys::task_delay_until(core::ptr::addr_of_mut!(last), period) };
+        }
+    }
+}
+
+/// Ignores changes in a signal until they have held for a while, such as a limit switch that bounces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Debouncer {
+    hold: u32,
+    state: bool,
+    pending: Option<(bool, u32)>,
+    changed: bool,
+}
+
+impl Debouncer {
+    /// Creates a debouncer that starts off and only changes once the input has held for `hold`.
+    pub const fn new(hold: Duration) -> Self {
+        Self {
+            hold: hold.as_millis() as u32,
+            state: false,
+            pending: None,
+            changed: false,
+        }
+    }
+
+    /// Feeds in a raw reading taken at `now` and returns the debounced state.
+    pub fn update(&mut self, now: u32, raw: bool) -> bool {
+        self.changed = false;
+        if raw == self.state {
+            self.pending = None;
+            return self.state;
+        }
+        match self.pending {
+            Some((value, since)) if value == raw => {
+                if now.wrapping_sub(since) >= self.hold {
+                    self.state = raw;
+                    self.pending = None;
+                    self.changed = true;
+                }
+            }
+            _ => {
+                self.pending = Some((raw, now));
+                if self.hold == 0 {
+                    self.state = raw;
+                    self.pending = None;
+                    self.changed = true;
+                }
+            }
+        }
+        self.state
+    }
+
+    /// Returns the debounced state.
+    pub fn state(&self) -> bool {
+        self.state
+    }
+
+    /// Returns true if the last update turned the state on.
+    pub fn rose(&self) -> bool {
+        self.changed && self.state
+    }
+
+    /// Returns true if the last update turned the state off.
+    pub fn fell(&self) -> bool {
+        self.changed && !self.state
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimerState {
+    Unstarted,
+    Running { deadline: u32 },
+    Stopped,
+}
+
+/// Fires once after a delay, or repeatedly with a fixed period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timer {
+    period: u32,
+    repeat: bool,
+    state: TimerState,
+}
+
+impl Timer {
+    /// Creates a timer that fires once, `delay` after the first time it is checked.
+    pub const fn after(delay: Duration) -> Self {
+        Self {
+            period: delay.as_millis() as u32,
+            repeat: false,
+            state: TimerState::Unstarted,
+        }
+    }
+
+    /// Creates a timer that fires every `period`, starting one period after the first time it is checked.
+    pub const fn every(period: Duration) -> Self {
+        let period = period.as_millis() as u32;
+        Self {
+            period: if period == 0 { 1 } else { period },
+            repeat: true,
+            state: TimerState::Unstarted,
+        }
+    }
+
+    /// Starts the timer over from `now`.
+    pub fn restart(&mut self, now: u32) {
+        self.state = TimerState::Running {
+            deadline: now.wrapping_add(self.period),
+        };
+    }
+
+    /// Stops the timer until it is restarted. A one shot timer stops itself after firing.
+    pub fn stop(&mut self) {
+        self.state = TimerState::Stopped;
+    }
+
+    /// Returns true if the timer is due at `now`.
+    /// A repeating timer that has missed several periods fires once and then keeps to its original schedule.
+    pub fn fired(&mut self, now: u32) -> bool {
+        let deadline = match self.state {
+            TimerState::Running { deadline } => deadline,
+            TimerState::Stopped => return false,
+            TimerState::Unstarted => {
+                self.restart(now);
+                return false;
+            }
+        };
+        let late = now.wrapping_sub(deadline);
+        if (late as i32) < 0 {
+            return false;
+        }
+        self.state = if self.repeat {
+            TimerState::Running {
+                deadline: deadline.wrapping_add((late / self.period + 1) * self.period),
+            }
+        } else {
+            TimerState::Stopped
+        };
+        true
+    }
+}
+
+/// Lets an action happen at most once per interval, such as printing a warning or sending telemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimiter {
+    interval: u32,
+    last: Option<u32>,
+}
+
+impl RateLimiter {
+    pub const fn new(interval: Duration) -> Self {
+        Self {
+            interval: interval.as_millis() as u32,
+            last: None,
+        }
+    }
+
+    /// Returns true, and starts a new interval, if the last interval has passed by `now`.
+    pub fn ready(&mut self, now: u32) -> bool {
+        match self.last {
+            Some(last) if now.wrapping_sub(last) < self.interval => false,
+            _ => {
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Declaring all of a robot's devices and their ports in one place.
+//!
+//! See [`port_map!`](crate::port_map!).
+
+/// Which set of ports a device is plugged into.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    Smart,
+    Adi,
+}
+
+/// Panics if a port is out of range or used twice. Called in a const context by [`port_map!`](crate::port_map!)
+/// so that mistakes are compile errors.
+#[doc(hidden)]
+pub const fn check_ports(ports: &[(PortKind, u8)]) {
+    let mut i = 0;
+    while i < ports.len() {
+        let (kind, port) = ports[i];
+        match kind {
+            PortKind::Smart => assert!(
+                port >= 1 && port as usize <= pros_sys::NUM_V5_PORTS,
+                "Smart ports must be between 1 and 21"
+            ),
+            PortKind::Adi => assert!(
+                (port as i32) < pros_sys::NUM_ADI_PORTS,
+                "ADI port is out of range"
+            ),
+        }
+
+        let mut j = i + 1;
+        while j < ports.len() {
+            let (other_kind, other_port) = ports[j];
+            assert!(
+                !(kind as u8 == other_kind as u8 && port == other_port),
+                "Two devices in the port map use the same port"
+            );
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Declares a struct holding all of the robot's devices, with each device's port written next to it.
+///
+/// Every field is either a smart device, constructed with `Type::new(port, args...)`,
+/// or an ADI device, constructed with `Type::new(AdiPort::new(port), args...)`.
+/// Ports that are out of range or used by two devices are compile errors.
+/// The generated `new` function constructs every device, failing on the first one that can't be set up,
+/// and is usually called when the robot is initialized.
+///
+/// Example:
+/// ```rust
+/// use pros::prelude::*;
+/// use pros::{adi::AdiDigitalOut, sensors::imu::InertialSensor};
+///
+/// pros::port_map! {
+///     pub struct Devices {
+///         pub left_drive: Motor => smart(1, BrakeMode::Brake),
+///         pub right_drive: Motor => smart(2, BrakeMode::Brake),
+///         pub imu: InertialSensor => smart(3),
+///         pub clamp: AdiDigitalOut => adi(0),
+///     }
+/// }
+///
+/// struct ExampleRobot {
+///     devices: Devices,
+/// }
+/// impl Robot for ExampleRobot {}
+/// robot!(ExampleRobot, ExampleRobot {
+///     devices: Devices::new().unwrap(),
+/// });
+/// ```
+#[macro_export]
+macro_rules! port_map {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident: $ty:ty => $kind:ident($port:literal $(, $arg:expr)* $(,)?)
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: $ty,
+            )*
+        }
+
+        const _: () = $crate::port_map::check_ports(&[
+            $(($crate::port_map!(@kind $kind), $port)),*
+        ]);
+
+        impl $name {
+            /// Constructs every device in the port map.
+            $vis fn new() -> $crate::Result<Self> {
+                Ok(Self {
+                    $(
+                        $field: $crate::port_map!(@new $kind $ty, $port $(, $arg)*)?,
+                    )*
+                })
+            }
+        }
+    };
+    (@kind smart) => { $crate::port_map::PortKind::Smart };
+    (@kind adi) => { $crate::port_map::PortKind::Adi };
+    (@new smart $ty:ty, $port:literal $(, $arg:expr)*) => {
+        <$ty>::new($port $(, $arg)*)
+    };
+    (@new adi $ty:ty, $port:literal $(, $arg:expr)*) => {
+        <$ty>::new($crate::adi::AdiPort::new($port) $(, $arg)*)
+    };
+}
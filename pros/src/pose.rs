@@ -0,0 +1,211 @@
+//! The position and orientation of the robot on the field.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use core::time::Duration;
+
+use crate::math::angle;
+#[cfg(feature = "alloc")]
+use crate::{
+    encode::{Decode, DecodeError, Decoder, Encode, Encoder},
+    usd::{self, UsdError},
+};
+
+/// A position and heading on the field.
+///
+/// Coordinates are measured from the center of the field in whatever unit the user prefers,
+/// with the heading in degrees counterclockwise from the positive x axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+impl Pose {
+    pub const fn new(x: f64, y: f64, heading: f64) -> Self {
+        Self { x, y, heading }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for Pose {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(self.x, self.y, self.heading));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Decode for Pose {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let (x, y, heading) = decoder.read()?;
+        Ok(Self { x, y, heading })
+    }
+}
+
+/// The robot's recent poses and when they were measured.
+///
+/// Measurements like vision detections arrive late, so they should be compared
+/// against where the robot was when they were captured rather than where it is now.
+#[derive(Debug, Clone)]
+pub struct PoseHistory<const N: usize = 64> {
+    entries: [(u32, Pose); N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> PoseHistory<N> {
+    pub const fn new() -> Self {
+        Self {
+            entries: [(0, Pose::new(0.0, 0.0, 0.0)); N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Records the pose at a `millis()` time, replacing the oldest entry if the history is full.
+    /// Times should be pushed in increasing order.
+    pub fn push(&mut self, time: u32, pose: Pose) {
+        self.entries[self.next] = (time, pose);
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Returns the entries from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &(u32, Pose)> + '_ {
+        let start = (self.next + N - self.len) % N;
+        (0..self.len).map(move |i| &self.entries[(start + i) % N])
+    }
+
+    /// Returns the newest entry.
+    pub fn latest(&self) -> Option<(u32, Pose)> {
+        (self.len > 0).then(|| self.entries[(self.next + N - 1) % N])
+    }
+
+    /// Returns the pose at a `millis()` time, interpolating between the entries around it.
+    /// Times outside the history use the oldest or newest entry.
+    pub fn at(&self, time: u32) -> Option<Pose> {
+        let mut before: Option<(u32, Pose)> = None;
+        for &(recorded, pose) in self.iter() {
+            if recorded >= time {
+                let Some((before_time, before_pose)) = before else {
+                    return Some(pose);
+                };
+                let t = (time - before_time) as f64 / (recorded - before_time) as f64;
+                return Some(Pose::new(
+                    before_pose.x + (pose.x - before_pose.x) * t,
+                    before_pose.y + (pose.y - before_pose.y) * t,
+                    angle::lerp_degrees(before_pose.heading, pose.heading, t),
+                ));
+            }
+            before = Some((recorded, pose));
+        }
+        before.map(|(_, pose)| pose)
+    }
+
+    /// Moves every entry by the same amount, for when the current pose is corrected.
+    pub fn shift(&mut self, dx: f64, dy: f64, dheading: f64) {
+        for (_, pose) in self.entries.iter_mut() {
+            pose.x += dx;
+            pose.y += dy;
+            pose.heading += dheading;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+impl<const N: usize> Default for PoseHistory<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A pose saved by a [`PoseCheckpoint`], along with the offset between the IMU's heading and the pose's heading.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SavedPose {
+    pub pose: Pose,
+    pub heading_offset: f64,
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for SavedPose {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.pose);
+        encoder.write(&self.heading_offset);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Decode for SavedPose {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            pose: decoder.read()?,
+            heading_offset: decoder.read()?,
+        })
+    }
+}
+
+/// Periodically saves the robot's pose to the SD card so that it can be restored after the program restarts,
+/// such as between the driver and programming portions of a skills run.
+#[cfg(feature = "alloc")]
+pub struct PoseCheckpoint {
+    path: String,
+    interval: Duration,
+    last_save: Option<u32>,
+}
+
+#[cfg(feature = "alloc")]
+impl PoseCheckpoint {
+    /// Creates a checkpoint that saves to the given file at most once every `interval`.
+    pub fn new(path: impl Into<String>, interval: Duration) -> Self {
+        Self {
+            path: path.into(),
+            interval,
+            last_save: None,
+        }
+    }
+
+    /// Saves the pose if at least `interval` has passed since it was last saved.
+    /// This should be called periodically, usually right after odometry is updated.
+    pub fn update(&mut self, pose: Pose, heading_offset: f64) -> Result<(), UsdError> {
+        let now = unsafe { pros_sys::millis() };
+        let due = match self.last_save {
+            Some(last) => now - last >= self.interval.as_millis() as u32,
+            None => true,
+        };
+        if due {
+            self.save(pose, heading_offset)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the pose immediately.
+    pub fn save(&mut self, pose: Pose, heading_offset: f64) -> Result<(), UsdError> {
+        let saved = SavedPose {
+            pose,
+            heading_offset,
+        };
+        usd::save(&self.path, &saved)?;
+        self.last_save = Some(unsafe { pros_sys::millis() });
+        Ok(())
+    }
+
+    /// Loads the last saved pose, returning `None` if nothing was saved or the file is corrupted.
+    pub fn restore(&self) -> Result<Option<SavedPose>, UsdError> {
+        usd::load(&self.path)
+    }
+
+    /// Deletes the saved pose so that the next program start begins fresh.
+    pub fn clear(&mut self) -> Result<(), UsdError> {
+        self.last_save = None;
+        match usd::remove(&self.path) {
+            Ok(()) | Err(UsdError::NotFound) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -41,7 +41,7 @@ impl Position {
         match self {
             Self::Degrees(num) => num / 360.0,
             Self::Rotations(num) => num,
-            Self::Counts(num) => num as f64 * 4096.0,
+            Self::Counts(num) => num as f64 / 4096.0,
         }
     }
 
@@ -144,3 +144,38 @@ impl PartialOrd for Position {
         self.into_degrees().partial_cmp(&other.into_degrees())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degrees() {
+        let position = Position::from_degrees(90.0);
+        assert_eq!(position.into_degrees(), 90.0);
+        assert_eq!(position.into_rotations(), 0.25);
+        assert_eq!(position.into_counts(), 1024);
+    }
+
+    #[test]
+    fn rotations() {
+        let position = Position::from_rotations(-1.5);
+        assert_eq!(position.into_degrees(), -540.0);
+        assert_eq!(position.into_rotations(), -1.5);
+        assert_eq!(position.into_counts(), -6144);
+    }
+
+    #[test]
+    fn counts() {
+        let position = Position::from_counts(2048);
+        assert_eq!(position.into_degrees(), 180.0);
+        assert_eq!(position.into_rotations(), 0.5);
+        assert_eq!(position.into_counts(), 2048);
+    }
+
+    #[test]
+    fn mixed_units_compare_as_degrees() {
+        assert_eq!(Position::from_rotations(1.0), Position::from_degrees(360.0));
+        assert_eq!(Position::from_counts(4096), Position::from_rotations(1.0));
+    }
+}
@@ -0,0 +1,105 @@
+//! Motion profiles for smoothly moving mechanisms between positions.
+
+/// The desired position and velocity at a point in time along a profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileState {
+    pub position: f64,
+    pub velocity: f64,
+}
+
+/// A trapezoidal velocity profile.
+///
+/// The profile accelerates at a constant rate up to a maximum velocity,
+/// cruises, and then decelerates so that it arrives at the goal with zero velocity.
+/// If the move is too short to reach the maximum velocity the profile is triangular instead.
+/// Units are up to the user, as long as velocity and acceleration are per second.
+#[derive(Debug, Clone, Copy)]
+pub struct TrapezoidProfile {
+    start: f64,
+    direction: f64,
+    distance: f64,
+    acceleration: f64,
+    peak_velocity: f64,
+    accel_time: f64,
+    cruise_time: f64,
+}
+
+impl TrapezoidProfile {
+    pub fn new(start: f64, goal: f64, max_velocity: f64, max_acceleration: f64) -> Self {
+        let distance = libm::fabs(goal - start);
+        let direction = if goal < start { -1.0 } else { 1.0 };
+
+        if distance == 0.0 || max_velocity <= 0.0 || max_acceleration <= 0.0 {
+            return Self {
+                start: goal,
+                direction,
+                distance: 0.0,
+                acceleration: 0.0,
+                peak_velocity: 0.0,
+                accel_time: 0.0,
+                cruise_time: 0.0,
+            };
+        }
+
+        // Triangular profile when we can't reach max velocity before having to slow down.
+        let peak_velocity = max_velocity.min(libm::sqrt(distance * max_acceleration));
+        let accel_time = peak_velocity / max_acceleration;
+        let accel_distance = 0.5 * max_acceleration * accel_time * accel_time;
+        let cruise_time = (distance - 2.0 * accel_distance) / peak_velocity;
+
+        Self {
+            start,
+            direction,
+            distance,
+            acceleration: max_acceleration,
+            peak_velocity,
+            accel_time,
+            cruise_time: cruise_time.max(0.0),
+        }
+    }
+
+    /// Returns how long the profile takes to complete in seconds.
+    pub fn duration(&self) -> f64 {
+        2.0 * self.accel_time + self.cruise_time
+    }
+
+    /// Returns true if the profile has reached its goal at the given time.
+    pub fn is_finished(&self, time: f64) -> bool {
+        time >= self.duration()
+    }
+
+    /// Returns the goal position of the profile.
+    pub fn goal(&self) -> f64 {
+        self.start + self.direction * self.distance
+    }
+
+    /// Returns the desired state of the profile `time` seconds after it started.
+    pub fn sample(&self, time: f64) -> ProfileState {
+        let time = time.clamp(0.0, self.duration());
+        let decel_start = self.accel_time + self.cruise_time;
+
+        let (traveled, speed) = if time < self.accel_time {
+            (
+                0.5 * self.acceleration * time * time,
+                self.acceleration * time,
+            )
+        } else if time < decel_start {
+            let accel_distance = 0.5 * self.acceleration * self.accel_time * self.accel_time;
+            (
+                accel_distance + self.peak_velocity * (time - self.accel_time),
+                self.peak_velocity,
+            )
+        } else {
+            let remaining = self.duration() - time;
+            (
+                self.distance - 0.5 * self.acceleration * remaining * remaining,
+                self.acceleration * remaining,
+            )
+        };
+
+        ProfileState {
+            position: self.start + self.direction * traveled,
+            velocity: self.direction * speed,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Fast, small pseudorandom numbers.
+//!
+//! [`Rng`] is a xoshiro128++ generator, which only needs 32-bit arithmetic and 16 bytes of state.
+//! It is good enough for jittering retry delays, resampling particle filters, and LED effects,
+//! but is not cryptographically secure.
+//!
+//! The brain has no hardware random number generator, so [`Rng::from_entropy`] seeds from the noise in
+//! the battery's voltage and current readings and the time in microseconds since startup.
+//! [`Rng::from_analog`] additionally mixes in readings from an unconnected analog port, which are mostly noise.
+
+use core::{ops::Range, time::Duration};
+
+use crate::{
+    adi::{AdiAnalogIn, AdiError},
+    sync::Mutex,
+};
+
+/// A xoshiro128++ pseudorandom number generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: [u32; 4],
+}
+
+/// Mixes a 64-bit value; used to spread out seeds so similar seeds give unrelated sequences.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl Rng {
+    /// Creates a generator that always produces the same sequence for the same seed.
+    pub fn new(seed: u64) -> Self {
+        let mut seed = seed;
+        let a = splitmix64(&mut seed);
+        let b = splitmix64(&mut seed);
+        Self {
+            state: [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32],
+        }
+    }
+
+    /// Creates a generator seeded from battery sensor noise and the time since startup.
+    pub fn from_entropy() -> Self {
+        Self::new(entropy())
+    }
+
+    /// Creates a generator seeded from an analog port as well as [`Rng::from_entropy`]'s sources.
+    /// The port should have nothing plugged into it, so that its readings are just noise.
+    pub fn from_analog(port: &AdiAnalogIn) -> Result<Self, AdiError> {
+        let mut seed = entropy();
+        for _ in 0..32 {
+            seed = seed.rotate_left(5) ^ port.value()? as u64;
+            seed ^= splitmix64(&mut seed);
+        }
+        Ok(Self::new(seed))
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let [s0, s1, s2, s3] = self.state;
+        let result = s0.wrapping_add(s3).rotate_left(7).wrapping_add(s0);
+        let t = s1 << 9;
+        let mut s = [s0, s1, s2 ^ s0, s3 ^ s1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(11);
+        self.state = s;
+        result
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        (self.next_u32() as u64) << 32 | self.next_u32() as u64
+    }
+
+    /// Returns a number in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns a number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number in the range, without bias toward any part of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    pub fn range_u32(&mut self, range: Range<u32>) -> u32 {
+        assert!(!range.is_empty(), "Cannot pick from an empty range");
+        let len = range.end - range.start;
+        // Lemire's method: the high half of a 64-bit product is uniform once the biased low values are rejected.
+        let threshold = len.wrapping_neg() % len;
+        loop {
+            let product = self.next_u32() as u64 * len as u64;
+            if product as u32 >= threshold {
+                return range.start + (product >> 32) as u32;
+            }
+        }
+    }
+
+    /// Returns a number in the range.
+    pub fn range_f32(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.next_f32()
+    }
+
+    /// Returns true with the given probability, from 0.0 to 1.0.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    /// Returns a random element, or `None` if the slice is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        Some(&items[self.range_u32(0..items.len() as u32) as usize])
+    }
+
+    /// Puts the elements in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range_u32(0..i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Randomly lengthens or shortens a duration by up to `fraction` of it,
+    /// so that retries from several tasks or robots don't all happen at once.
+    pub fn jitter(&mut self, duration: Duration, fraction: f32) -> Duration {
+        let scale = 1.0 + fraction * (self.next_f32() * 2.0 - 1.0);
+        duration.mul_f32(scale.max(0.0))
+    }
+}
+
+/// Gathers a seed from sources that vary between runs.
+fn entropy() -> u64 {
+    let mut seed = unsafe { pros_sys::micros() };
+    for _ in 0..16 {
+        let voltage = unsafe { pros_sys::battery_get_voltage() } as u64;
+        let current = unsafe { pros_sys::battery_get_current() } as u64;
+        seed = seed.rotate_left(7) ^ voltage ^ current << 16 ^ unsafe { pros_sys::micros() } << 32;
+        seed ^= splitmix64(&mut seed);
+    }
+    seed
+}
+
+lazy_static::lazy_static! {
+    static ref GLOBAL: Mutex<Rng> = Mutex::new(Rng::from_entropy());
+}
+
+/// Calls `f` with a generator shared by the whole program, seeded with [`Rng::from_entropy`] when first used.
+pub fn with_rng<T>(f: impl FnOnce(&mut Rng) -> T) -> T {
+    f(&mut GLOBAL.lock())
+}
+
+/// Returns a random number in `[0, 1)` from the shared generator.
+pub fn random() -> f32 {
+    with_rng(Rng::next_f32)
+}
+
+/// Randomly lengthens or shortens a duration by up to `fraction` of it using the shared generator.
+/// See [`Rng::jitter`].
+pub fn jitter(duration: Duration, fraction: f32) -> Duration {
+    with_rng(|rng| rng.jitter(duration, fraction))
+}
@@ -0,0 +1,246 @@
+//! Finding a mechanism's zero by driving it into a limit switch or hard stop.
+//!
+//! ```rust
+//! let home = Home {
+//!     voltage: -3.0,
+//!     current_limit: Some(2000),
+//!     back_off: 5.0,
+//!     ..Default::default()
+//! };
+//! let report = home.run(&mut lift_motors, Some(&limit_switch))?;
+//! ```
+
+use alloc::boxed::Box;
+use core::time::Duration;
+
+use snafu::Snafu;
+
+use crate::{
+    actuator::VoltageOutput,
+    adi::{AdiButton, AdiDigitalIn},
+    motor::{Motor, MotorGroup},
+    sensors::rotation::RotationSensor,
+    task::sleep,
+};
+
+/// Says when a mechanism has reached its home position.
+pub trait HomeSwitch {
+    /// Returns true once the mechanism is at its stop.
+    fn tripped(&self) -> crate::Result<bool>;
+}
+
+/// A limit switch that reads high when pressed.
+impl HomeSwitch for AdiDigitalIn {
+    fn tripped(&self) -> crate::Result<bool> {
+        Ok(self.is_high()?)
+    }
+}
+
+impl HomeSwitch for AdiButton {
+    fn tripped(&self) -> crate::Result<bool> {
+        Ok(self.is_pressed()?)
+    }
+}
+
+/// A mechanism that can be driven, measured, and zeroed by [`Home`].
+pub trait Homeable {
+    /// Sets the voltage driving the mechanism, from -12 to 12 volts.
+    fn set_voltage(&mut self, voltage: f32) -> crate::Result;
+    /// Returns the mechanism's position in degrees.
+    fn position(&self) -> crate::Result<f64>;
+    /// Sets the current position to zero.
+    fn zero(&mut self) -> crate::Result;
+    /// Returns the current draw in milliamps, or `None` if it can't be measured.
+    fn current_draw(&self) -> crate::Result<Option<i32>> {
+        Ok(None)
+    }
+}
+
+impl Homeable for Motor {
+    fn set_voltage(&mut self, voltage: f32) -> crate::Result {
+        Ok(Motor::set_voltage(self, voltage.clamp(-12.0, 12.0))?)
+    }
+
+    fn position(&self) -> crate::Result<f64> {
+        Ok(Motor::position(self)?.into_degrees())
+    }
+
+    fn zero(&mut self) -> crate::Result {
+        Ok(Motor::zero(self)?)
+    }
+
+    fn current_draw(&self) -> crate::Result<Option<i32>> {
+        Ok(Some(Motor::current_draw(self)?))
+    }
+}
+
+impl Homeable for MotorGroup {
+    fn set_voltage(&mut self, voltage: f32) -> crate::Result {
+        Ok(MotorGroup::set_voltage(self, voltage.clamp(-12.0, 12.0))?)
+    }
+
+    fn position(&self) -> crate::Result<f64> {
+        Ok(MotorGroup::position(self)?.into_degrees())
+    }
+
+    fn zero(&mut self) -> crate::Result {
+        Ok(MotorGroup::zero(self)?)
+    }
+
+    fn current_draw(&self) -> crate::Result<Option<i32>> {
+        Ok(Some(MotorGroup::current_draw(self)?))
+    }
+}
+
+/// Any output measured by a rotation sensor, such as a turret whose motors slip relative to it.
+/// Current spikes can't be detected this way, so a [`HomeSwitch`] is needed.
+impl<O> Homeable for (O, RotationSensor)
+where
+    O: VoltageOutput,
+    O::Error: core::error::Error + 'static,
+{
+    fn set_voltage(&mut self, voltage: f32) -> crate::Result {
+        Ok(self.0.set_voltage(voltage)?)
+    }
+
+    fn position(&self) -> crate::Result<f64> {
+        Ok(self.1.position()?.into_degrees())
+    }
+
+    fn zero(&mut self) -> crate::Result {
+        Ok(self.1.zero()?)
+    }
+}
+
+/// How to home a mechanism.
+///
+/// The mechanism is driven at [`Home::voltage`] until the switch trips or its current stays above
+/// [`Home::current_limit`], it is zeroed there, and then it is driven back [`Home::back_off`] degrees
+/// so it isn't left pressing on the stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Home {
+    /// The voltage to drive toward the stop with. Its sign sets the direction.
+    pub voltage: f32,
+    /// The current draw in milliamps that means the mechanism has stalled against a hard stop.
+    pub current_limit: Option<i32>,
+    /// How long to ignore the current after starting, so the spin-up spike isn't mistaken for the stop.
+    pub grace_period: Duration,
+    /// How far to move away from the stop after zeroing, in degrees.
+    pub back_off: f64,
+    /// The voltage to back off with, which is applied away from the stop.
+    pub back_off_voltage: f32,
+    /// How long homing may take, including backing off.
+    pub timeout: Duration,
+}
+
+impl Default for Home {
+    fn default() -> Self {
+        Self {
+            voltage: -3.0,
+            current_limit: None,
+            grace_period: Duration::from_millis(250),
+            back_off: 0.0,
+            back_off_voltage: 3.0,
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+/// How many high current readings in a row mean the mechanism has stalled.
+const STALL_SAMPLES: u32 = 3;
+
+/// How homing went.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HomeReport {
+    /// How long it took to reach the stop.
+    pub time_to_stop: Duration,
+    /// What found the stop.
+    pub trigger: HomeTrigger,
+    /// The position after backing off, in degrees from the stop.
+    pub position: f64,
+}
+
+/// What told [`Home`] the mechanism had reached its stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HomeTrigger {
+    Switch,
+    CurrentSpike,
+}
+
+impl Home {
+    /// Homes the mechanism, blocking the current task until it is done or times out.
+    /// The mechanism is stopped afterwards either way.
+    pub fn run(
+        &self,
+        mechanism: &mut impl Homeable,
+        switch: Option<&dyn HomeSwitch>,
+    ) -> crate::Result<HomeReport> {
+        let result = self.drive(mechanism, switch);
+        let stopped = mechanism.set_voltage(0.0);
+        let report = result?;
+        stopped?;
+        Ok(report)
+    }
+
+    fn drive(
+        &self,
+        mechanism: &mut impl Homeable,
+        switch: Option<&dyn HomeSwitch>,
+    ) -> crate::Result<HomeReport> {
+        let start = unsafe { pros_sys::millis() };
+        let elapsed = || unsafe { pros_sys::millis() } - start;
+        let timeout = self.timeout.as_millis() as u32;
+
+        let mut high_samples = 0;
+        let trigger = loop {
+            if let Some(switch) = switch {
+                if switch.tripped()? {
+                    break HomeTrigger::Switch;
+                }
+            }
+            if let Some(limit) = self.current_limit {
+                let high = elapsed() > self.grace_period.as_millis() as u32
+                    && mechanism
+                        .current_draw()?
+                        .is_some_and(|current| current >= limit);
+                high_samples = if high { high_samples + 1 } else { 0 };
+                if high_samples >= STALL_SAMPLES {
+                    break HomeTrigger::CurrentSpike;
+                }
+            }
+            if elapsed() > timeout {
+                return Err(Box::new(HomeError::TimedOut));
+            }
+            mechanism.set_voltage(self.voltage)?;
+            sleep(Duration::from_millis(10));
+        };
+        let time_to_stop = Duration::from_millis(elapsed() as u64);
+        mechanism.set_voltage(0.0)?;
+        mechanism.zero()?;
+
+        // Back off in the opposite direction to the one the stop was found in.
+        let away = if self.voltage < 0.0 { 1.0 } else { -1.0 };
+        while mechanism.position()? * away < self.back_off {
+            if elapsed() > timeout {
+                return Err(Box::new(HomeError::BackOffTimedOut));
+            }
+            mechanism.set_voltage(libm::fabsf(self.back_off_voltage) * away as f32)?;
+            sleep(Duration::from_millis(10));
+        }
+
+        Ok(HomeReport {
+            time_to_stop,
+            trigger,
+            position: mechanism.position()?,
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum HomeError {
+    #[snafu(display("The mechanism didn't reach its home switch or hard stop in time."))]
+    TimedOut,
+    #[snafu(display("The mechanism didn't back off from its stop in time."))]
+    BackOffTimedOut,
+}
+impl core::error::Error for HomeError {}
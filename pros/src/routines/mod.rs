@@ -0,0 +1,8 @@
+//! Reusable routines that run a mechanism through a sequence of steps, such as homing.
+//!
+//! Routines are written against [`crate::actuator`] traits and small sensor traits
+//! so the same routine works for lifts, elevators, turrets, and anything else built from the device wrappers.
+
+pub mod home;
+
+pub use home::Home;
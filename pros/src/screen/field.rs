@@ -0,0 +1,94 @@
+//! A top-down view of the field showing the robot's pose and its path.
+
+use super::ScreenError;
+use crate::pose::Pose;
+
+/// Draws a square field with the robot and the part of its path it hasn't driven yet,
+/// for tuning autonomous routines without a laptop.
+///
+/// Field coordinates follow [`Pose`]: the origin is the center of the field,
+/// positive x is to the right and positive y is up on the screen.
+pub struct FieldView {
+    /// The left edge of the view on the screen.
+    pub x: i16,
+    /// The top edge of the view on the screen.
+    pub y: i16,
+    /// The width and height of the view in pixels.
+    pub size: i16,
+    /// The width of the field in the same unit as poses, such as 144 inches.
+    pub field_size: f64,
+    /// The width of the robot in the same unit as poses.
+    pub robot_size: f64,
+    /// How many tiles the field is split into along each side.
+    pub tiles: u8,
+    pub background_color: u32,
+    pub tile_color: u32,
+    pub path_color: u32,
+    pub robot_color: u32,
+}
+
+impl FieldView {
+    /// Creates a view of a standard 12 foot, 6 by 6 tile field measured in inches.
+    pub fn new(x: i16, y: i16, size: i16) -> Self {
+        Self {
+            x,
+            y,
+            size,
+            field_size: 144.0,
+            robot_size: 18.0,
+            tiles: 6,
+            background_color: pros_sys::COLOR_BLACK,
+            tile_color: pros_sys::COLOR_DARK_GRAY,
+            path_color: pros_sys::COLOR_YELLOW,
+            robot_color: pros_sys::COLOR_RED,
+        }
+    }
+
+    /// Converts a field position into screen coordinates.
+    pub fn to_screen(&self, x: f64, y: f64) -> (i16, i16) {
+        let scale = self.size as f64 / self.field_size;
+        let center = self.size as f64 / 2.0;
+        (
+            self.x + (center + x * scale) as i16,
+            self.y + (center - y * scale) as i16,
+        )
+    }
+
+    /// Redraws the view with the robot at `pose` and the remaining `path`.
+    pub fn draw(&self, pose: Pose, path: &[Pose]) -> Result<(), ScreenError> {
+        let right = self.x + self.size - 1;
+        let bottom = self.y + self.size - 1;
+
+        super::set_pen(self.background_color)?;
+        super::fill_rect(self.x, self.y, right, bottom)?;
+
+        super::set_pen(self.tile_color)?;
+        for tile in 1..self.tiles as i16 {
+            let offset = self.size * tile / self.tiles as i16;
+            super::draw_line(self.x + offset, self.y, self.x + offset, bottom)?;
+            super::draw_line(self.x, self.y + offset, right, self.y + offset)?;
+        }
+        super::draw_rect(self.x, self.y, right, bottom)?;
+
+        super::set_pen(self.path_color)?;
+        let mut last = self.to_screen(pose.x, pose.y);
+        for point in path {
+            let next = self.to_screen(point.x, point.y);
+            super::draw_line(last.0, last.1, next.0, next.1)?;
+            last = next;
+        }
+
+        super::set_pen(self.robot_color)?;
+        let (x, y) = self.to_screen(pose.x, pose.y);
+        let radius = (self.robot_size / 2.0 * self.size as f64 / self.field_size) as i16;
+        super::draw_circle(x, y, radius.max(2))?;
+        let heading = pose.heading.to_radians();
+        let (nose_x, nose_y) = self.to_screen(
+            pose.x + libm::cos(heading) * self.robot_size / 2.0,
+            pose.y + libm::sin(heading) * self.robot_size / 2.0,
+        );
+        super::draw_line(x, y, nose_x, nose_y)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+//! Drawing text in bitmap fonts anywhere on the screen and at any whole-number scale,
+//! rather than only in the rows used by [`print_at`](super::print_at).
+//!
+//! Two fonts are bundled, [`SMALL`] and [`MEDIUM`], and others can be converted from BDF files
+//! when the program is built with [`include_font!`]:
+//! ```rust
+//! static TITLE: Font<'static> = include_font!("assets/terminus-16.bdf");
+//!
+//! let mut screen = Framebuffer::full_screen();
+//! screen.draw_text(10, 10, "Auton: left", &TITLE, 2, pros_sys::COLOR_WHITE);
+//! screen.flush(0, 0)?;
+//! font::draw_text(10, 200, "12.4 V", &font::SMALL, 3, pros_sys::COLOR_YELLOW)?;
+//! ```
+
+use super::ScreenError;
+
+/// Converts a BDF font file to a [`Font`] when the program is built.
+///
+/// The printable ASCII characters are included, and the path is relative to the directory containing the crate's `Cargo.toml`.
+pub use pros_macros::include_font;
+
+/// Where one character's pixels are in a [`Font`].
+/// A glyph with no width or advance means the font doesn't have the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    /// The width of the glyph's bitmap in pixels.
+    pub width: u8,
+    /// How far to move right after drawing the glyph, in pixels.
+    pub advance: u8,
+    /// The index in [`Font::bitmap`] of the glyph's first row.
+    pub offset: u32,
+}
+
+/// A font of the characters from [`Font::first_char`] up, each with a bitmap [`Font::height`] rows tall.
+///
+/// Each row of a glyph is stored in as many bytes as its width needs,
+/// with the leftmost pixel in the most significant bit of the first byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Font<'a> {
+    /// How many rows each glyph has.
+    pub height: u8,
+    /// How far apart lines of text are, in pixels.
+    pub line_height: u8,
+    /// The character of the first glyph.
+    pub first_char: u8,
+    pub glyphs: &'a [Glyph],
+    pub bitmap: &'a [u8],
+}
+
+impl Font<'_> {
+    /// Returns a character's glyph and its rows, using `?` for characters the font doesn't have.
+    pub fn glyph(&self, character: char) -> Option<(Glyph, &[u8])> {
+        let find = |character: char| {
+            let index = (character as u32).checked_sub(self.first_char as u32)?;
+            let glyph = *self.glyphs.get(index as usize)?;
+            if glyph.width == 0 && glyph.advance == 0 {
+                return None;
+            }
+            let len = glyph.width.div_ceil(8) as usize * self.height as usize;
+            let rows = self
+                .bitmap
+                .get(glyph.offset as usize..glyph.offset as usize + len)?;
+            Some((glyph, rows))
+        };
+        find(character).or_else(|| find('?'))
+    }
+
+    /// Returns how wide the longest line of `text` is when drawn at `scale`, in pixels.
+    pub fn text_width(&self, text: &str, scale: u8) -> i16 {
+        text.lines()
+            .map(|line| {
+                line.chars()
+                    .filter_map(|character| self.glyph(character))
+                    .map(|(glyph, _)| glyph.advance as i16)
+                    .sum::<i16>()
+            })
+            .max()
+            .unwrap_or(0)
+            * scale as i16
+    }
+
+    /// Calls `fill` with the top left corner and width of every horizontal run of pixels in `text`,
+    /// each `scale` pixels tall, with the text's top left corner at (x, y).
+    pub fn layout(
+        &self,
+        x: i16,
+        y: i16,
+        text: &str,
+        scale: u8,
+        mut fill: impl FnMut(i16, i16, i16),
+    ) {
+        let scale = scale.max(1) as i16;
+        let (mut pen_x, mut pen_y) = (x, y);
+        for character in text.chars() {
+            if character == '\n' {
+                pen_x = x;
+                pen_y += self.line_height as i16 * scale;
+                continue;
+            }
+            let Some((glyph, rows)) = self.glyph(character) else {
+                continue;
+            };
+            let row_len = glyph.width.div_ceil(8) as usize;
+            for (row_index, row) in rows.chunks_exact(row_len.max(1)).enumerate() {
+                let top = pen_y + row_index as i16 * scale;
+                let mut run_start = None;
+                // One past the end so that a run reaching the right edge is finished.
+                for column in 0..=glyph.width as usize {
+                    let lit = column < glyph.width as usize
+                        && row[column / 8] & (0x80 >> (column % 8)) != 0;
+                    match (lit, run_start) {
+                        (true, None) => run_start = Some(column),
+                        (false, Some(start)) => {
+                            fill(
+                                pen_x + start as i16 * scale,
+                                top,
+                                (column - start) as i16 * scale,
+                            );
+                            run_start = None;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            pen_x += glyph.advance as i16 * scale;
+        }
+    }
+}
+
+/// Draws text directly on the screen with its top left corner at (x, y).
+///
+/// This draws a rectangle for every run of pixels, so large amounts of text are faster drawn
+/// with [`Framebuffer::draw_text`](super::framebuffer::Framebuffer::draw_text).
+pub fn draw_text(
+    x: i16,
+    y: i16,
+    text: &str,
+    font: &Font<'_>,
+    scale: u8,
+    color: u32,
+) -> Result<(), ScreenError> {
+    super::set_pen(color)?;
+    let scale = scale.max(1) as i16;
+    let mut result = Ok(());
+    font.layout(x, y, text, scale as u8, |left, top, width| {
+        if result.is_ok() {
+            result = super::fill_rect(left, top, left + width - 1, top + scale - 1);
+        }
+    });
+    result
+}
+
+/// Returns the glyphs of a font where every character is the same size and stored one after another.
+const fn monospace<const N: usize>(width: u8, advance: u8, height: u8) -> [Glyph; N] {
+    let mut glyphs = [Glyph {
+        width,
+        advance,
+        offset: 0,
+    }; N];
+    let mut index = 0;
+    while index < N {
+        glyphs[index].offset = (index * height as usize) as u32;
+        index += 1;
+    }
+    glyphs
+}
+
+/// A 3 by 5 pixel font without lowercase letters, for labels where space is tight.
+/// Lowercase letters are drawn as uppercase ones.
+pub const SMALL: Font<'static> = Font {
+    height: 5,
+    line_height: 6,
+    first_char: b' ',
+    glyphs: &SMALL_GLYPHS,
+    bitmap: &SMALL_BITMAP,
+};
+
+/// A 5 by 7 pixel font.
+pub const MEDIUM: Font<'static> = Font {
+    height: 7,
+    line_height: 9,
+    first_char: b' ',
+    glyphs: &MEDIUM_GLYPHS,
+    bitmap: &MEDIUM_BITMAP,
+};
+
+const SMALL_GLYPHS: [Glyph; 95] = monospace(3, 4, 5);
+const MEDIUM_GLYPHS: [Glyph; 95] = monospace(5, 6, 7);
+
+const SMALL_BITMAP: [u8; 475] = [
+    /* ' ' */ 0x00, 0x00, 0x00, 0x00, 0x00, /* '!' */ 0x40, 0x40, 0x40, 0x00, 0x40,
+    /* '"' */ 0xA0, 0xA0, 0x00, 0x00, 0x00, /* '#' */ 0xA0, 0xE0, 0xA0, 0xE0, 0xA0,
+    /* '$' */ 0x60, 0xC0, 0x40, 0x60, 0xC0, /* '%' */ 0xA0, 0x20, 0x40, 0x80, 0xA0,
+    /* '&' */ 0x40, 0xA0, 0x40, 0xA0, 0x60, /* '\'' */ 0x40, 0x40, 0x00, 0x00, 0x00,
+    /* '(' */ 0x20, 0x40, 0x40, 0x40, 0x20, /* ')' */ 0x80, 0x40, 0x40, 0x40, 0x80,
+    /* '*' */ 0x00, 0xA0, 0x40, 0xA0, 0x00, /* '+' */ 0x00, 0x40, 0xE0, 0x40, 0x00,
+    /* ',' */ 0x00, 0x00, 0x00, 0x40, 0x80, /* '-' */ 0x00, 0x00, 0xE0, 0x00, 0x00,
+    /* '.' */ 0x00, 0x00, 0x00, 0x00, 0x40, /* '/' */ 0x20, 0x20, 0x40, 0x80, 0x80,
+    /* '0' */ 0xE0, 0xA0, 0xA0, 0xA0, 0xE0, /* '1' */ 0x40, 0xC0, 0x40, 0x40, 0xE0,
+    /* '2' */ 0xC0, 0x20, 0x40, 0x80, 0xE0, /* '3' */ 0xC0, 0x20, 0x40, 0x20, 0xC0,
+    /* '4' */ 0xA0, 0xA0, 0xE0, 0x20, 0x20, /* '5' */ 0xE0, 0x80, 0xC0, 0x20, 0xC0,
+    /* '6' */ 0x60, 0x80, 0xE0, 0xA0, 0xE0, /* '7' */ 0xE0, 0x20, 0x40, 0x40, 0x40,
+    /* '8' */ 0xE0, 0xA0, 0xE0, 0xA0, 0xE0, /* '9' */ 0xE0, 0xA0, 0xE0, 0x20, 0xC0,
+    /* ':' */ 0x00, 0x40, 0x00, 0x40, 0x00, /* ';' */ 0x00, 0x40, 0x00, 0x40, 0x80,
+    /* '<' */ 0x20, 0x40, 0x80, 0x40, 0x20, /* '=' */ 0x00, 0xE0, 0x00, 0xE0, 0x00,
+    /* '>' */ 0x80, 0x40, 0x20, 0x40, 0x80, /* '?' */ 0xC0, 0x20, 0x40, 0x00, 0x40,
+    /* '@' */ 0x40, 0xA0, 0xE0, 0x80, 0x60, /* 'A' */ 0x40, 0xA0, 0xE0, 0xA0, 0xA0,
+    /* 'B' */ 0xC0, 0xA0, 0xC0, 0xA0, 0xC0, /* 'C' */ 0x60, 0x80, 0x80, 0x80, 0x60,
+    /* 'D' */ 0xC0, 0xA0, 0xA0, 0xA0, 0xC0, /* 'E' */ 0xE0, 0x80, 0xC0, 0x80, 0xE0,
+    /* 'F' */ 0xE0, 0x80, 0xC0, 0x80, 0x80, /* 'G' */ 0x60, 0x80, 0xA0, 0xA0, 0x60,
+    /* 'H' */ 0xA0, 0xA0, 0xE0, 0xA0, 0xA0, /* 'I' */ 0xE0, 0x40, 0x40, 0x40, 0xE0,
+    /* 'J' */ 0x20, 0x20, 0x20, 0xA0, 0x40, /* 'K' */ 0xA0, 0xA0, 0xC0, 0xA0, 0xA0,
+    /* 'L' */ 0x80, 0x80, 0x80, 0x80, 0xE0, /* 'M' */ 0xA0, 0xE0, 0xE0, 0xA0, 0xA0,
+    /* 'N' */ 0xC0, 0xA0, 0xA0, 0xA0, 0xA0, /* 'O' */ 0x40, 0xA0, 0xA0, 0xA0, 0x40,
+    /* 'P' */ 0xC0, 0xA0, 0xC0, 0x80, 0x80, /* 'Q' */ 0x40, 0xA0, 0xA0, 0xC0, 0x60,
+    /* 'R' */ 0xC0, 0xA0, 0xC0, 0xA0, 0xA0, /* 'S' */ 0x60, 0x80, 0x40, 0x20, 0xC0,
+    /* 'T' */ 0xE0, 0x40, 0x40, 0x40, 0x40, /* 'U' */ 0xA0, 0xA0, 0xA0, 0xA0, 0x60,
+    /* 'V' */ 0xA0, 0xA0, 0xA0, 0x40, 0x40, /* 'W' */ 0xA0, 0xA0, 0xE0, 0xE0, 0xA0,
+    /* 'X' */ 0xA0, 0xA0, 0x40, 0xA0, 0xA0, /* 'Y' */ 0xA0, 0xA0, 0x40, 0x40, 0x40,
+    /* 'Z' */ 0xE0, 0x20, 0x40, 0x80, 0xE0, /* '[' */ 0xC0, 0x80, 0x80, 0x80, 0xC0,
+    /* '\\' */ 0x80, 0x80, 0x40, 0x20, 0x20, /* ']' */ 0x60, 0x20, 0x20, 0x20, 0x60,
+    /* '^' */ 0x40, 0xA0, 0x00, 0x00, 0x00, /* '_' */ 0x00, 0x00, 0x00, 0x00, 0xE0,
+    /* '`' */ 0x80, 0x40, 0x00, 0x00, 0x00, /* 'a' */ 0x40, 0xA0, 0xE0, 0xA0, 0xA0,
+    /* 'b' */ 0xC0, 0xA0, 0xC0, 0xA0, 0xC0, /* 'c' */ 0x60, 0x80, 0x80, 0x80, 0x60,
+    /* 'd' */ 0xC0, 0xA0, 0xA0, 0xA0, 0xC0, /* 'e' */ 0xE0, 0x80, 0xC0, 0x80, 0xE0,
+    /* 'f' */ 0xE0, 0x80, 0xC0, 0x80, 0x80, /* 'g' */ 0x60, 0x80, 0xA0, 0xA0, 0x60,
+    /* 'h' */ 0xA0, 0xA0, 0xE0, 0xA0, 0xA0, /* 'i' */ 0xE0, 0x40, 0x40, 0x40, 0xE0,
+    /* 'j' */ 0x20, 0x20, 0x20, 0xA0, 0x40, /* 'k' */ 0xA0, 0xA0, 0xC0, 0xA0, 0xA0,
+    /* 'l' */ 0x80, 0x80, 0x80, 0x80, 0xE0, /* 'm' */ 0xA0, 0xE0, 0xE0, 0xA0, 0xA0,
+    /* 'n' */ 0xC0, 0xA0, 0xA0, 0xA0, 0xA0, /* 'o' */ 0x40, 0xA0, 0xA0, 0xA0, 0x40,
+    /* 'p' */ 0xC0, 0xA0, 0xC0, 0x80, 0x80, /* 'q' */ 0x40, 0xA0, 0xA0, 0xC0, 0x60,
+    /* 'r' */ 0xC0, 0xA0, 0xC0, 0xA0, 0xA0, /* 's' */ 0x60, 0x80, 0x40, 0x20, 0xC0,
+    /* 't' */ 0xE0, 0x40, 0x40, 0x40, 0x40, /* 'u' */ 0xA0, 0xA0, 0xA0, 0xA0, 0x60,
+    /* 'v' */ 0xA0, 0xA0, 0xA0, 0x40, 0x40, /* 'w' */ 0xA0, 0xA0, 0xE0, 0xE0, 0xA0,
+    /* 'x' */ 0xA0, 0xA0, 0x40, 0xA0, 0xA0, /* 'y' */ 0xA0, 0xA0, 0x40, 0x40, 0x40,
+    /* 'z' */ 0xE0, 0x20, 0x40, 0x80, 0xE0, /* '{' */ 0x60, 0x40, 0xC0, 0x40, 0x60,
+    /* '|' */ 0x40, 0x40, 0x40, 0x40, 0x40, /* '}' */ 0xC0, 0x40, 0x60, 0x40, 0xC0,
+    /* '~' */ 0x00, 0xC0, 0x60, 0x00, 0x00,
+];
+
+const MEDIUM_BITMAP: [u8; 665] = [
+    /* ' ' */ 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, /* '!' */ 0x20, 0x20, 0x20, 0x20,
+    0x20, 0x00, 0x20, /* '"' */ 0x50, 0x50, 0x50, 0x00, 0x00, 0x00, 0x00, /* '#' */ 0x50,
+    0x50, 0xF8, 0x50, 0xF8, 0x50, 0x50, /* '$' */ 0x20, 0x78, 0xA0, 0x70, 0x28, 0xF0, 0x20,
+    /* '%' */ 0xC0, 0xC8, 0x10, 0x20, 0x40, 0x98, 0x18, /* '&' */ 0x60, 0x90, 0xA0, 0x40,
+    0xA8, 0x90, 0x68, /* '\'' */ 0x20, 0x20, 0x40, 0x00, 0x00, 0x00, 0x00,
+    /* '(' */ 0x10, 0x20, 0x40, 0x40, 0x40, 0x20, 0x10, /* ')' */ 0x40, 0x20, 0x10, 0x10,
+    0x10, 0x20, 0x40, /* '*' */ 0x00, 0x20, 0xA8, 0x70, 0xA8, 0x20, 0x00, /* '+' */ 0x00,
+    0x20, 0x20, 0xF8, 0x20, 0x20, 0x00, /* ',' */ 0x00, 0x00, 0x00, 0x00, 0x60, 0x20, 0x40,
+    /* '-' */ 0x00, 0x00, 0x00, 0xF8, 0x00, 0x00, 0x00, /* '.' */ 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x60, 0x60, /* '/' */ 0x00, 0x08, 0x10, 0x20, 0x40, 0x80, 0x00, /* '0' */ 0x70,
+    0x88, 0x98, 0xA8, 0xC8, 0x88, 0x70, /* '1' */ 0x20, 0x60, 0x20, 0x20, 0x20, 0x20, 0x70,
+    /* '2' */ 0x70, 0x88, 0x08, 0x10, 0x20, 0x40, 0xF8, /* '3' */ 0xF8, 0x10, 0x20, 0x10,
+    0x08, 0x88, 0x70, /* '4' */ 0x10, 0x30, 0x50, 0x90, 0xF8, 0x10, 0x10, /* '5' */ 0xF8,
+    0x80, 0xF0, 0x08, 0x08, 0x88, 0x70, /* '6' */ 0x30, 0x40, 0x80, 0xF0, 0x88, 0x88, 0x70,
+    /* '7' */ 0xF8, 0x08, 0x10, 0x20, 0x40, 0x40, 0x40, /* '8' */ 0x70, 0x88, 0x88, 0x70,
+    0x88, 0x88, 0x70, /* '9' */ 0x70, 0x88, 0x88, 0x78, 0x08, 0x10, 0x60, /* ':' */ 0x00,
+    0x60, 0x60, 0x00, 0x60, 0x60, 0x00, /* ';' */ 0x00, 0x60, 0x60, 0x00, 0x60, 0x20, 0x40,
+    /* '<' */ 0x10, 0x20, 0x40, 0x80, 0x40, 0x20, 0x10, /* '=' */ 0x00, 0x00, 0xF8, 0x00,
+    0xF8, 0x00, 0x00, /* '>' */ 0x40, 0x20, 0x10, 0x08, 0x10, 0x20, 0x40, /* '?' */ 0x70,
+    0x88, 0x08, 0x10, 0x20, 0x00, 0x20, /* '@' */ 0x70, 0x88, 0x08, 0x68, 0xA8, 0xA8, 0x70,
+    /* 'A' */ 0x70, 0x88, 0x88, 0xF8, 0x88, 0x88, 0x88, /* 'B' */ 0xF0, 0x88, 0x88, 0xF0,
+    0x88, 0x88, 0xF0, /* 'C' */ 0x70, 0x88, 0x80, 0x80, 0x80, 0x88, 0x70, /* 'D' */ 0xE0,
+    0x90, 0x88, 0x88, 0x88, 0x90, 0xE0, /* 'E' */ 0xF8, 0x80, 0x80, 0xF0, 0x80, 0x80, 0xF8,
+    /* 'F' */ 0xF8, 0x80, 0x80, 0xF0, 0x80, 0x80, 0x80, /* 'G' */ 0x70, 0x88, 0x80, 0xB8,
+    0x88, 0x88, 0x78, /* 'H' */ 0x88, 0x88, 0x88, 0xF8, 0x88, 0x88, 0x88, /* 'I' */ 0x70,
+    0x20, 0x20, 0x20, 0x20, 0x20, 0x70, /* 'J' */ 0x38, 0x10, 0x10, 0x10, 0x10, 0x90, 0x60,
+    /* 'K' */ 0x88, 0x90, 0xA0, 0xC0, 0xA0, 0x90, 0x88, /* 'L' */ 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0xF8, /* 'M' */ 0x88, 0xD8, 0xA8, 0xA8, 0x88, 0x88, 0x88, /* 'N' */ 0x88,
+    0x88, 0xC8, 0xA8, 0x98, 0x88, 0x88, /* 'O' */ 0x70, 0x88, 0x88, 0x88, 0x88, 0x88, 0x70,
+    /* 'P' */ 0xF0, 0x88, 0x88, 0xF0, 0x80, 0x80, 0x80, /* 'Q' */ 0x70, 0x88, 0x88, 0x88,
+    0xA8, 0x90, 0x68, /* 'R' */ 0xF0, 0x88, 0x88, 0xF0, 0xA0, 0x90, 0x88, /* 'S' */ 0x78,
+    0x80, 0x80, 0x70, 0x08, 0x08, 0xF0, /* 'T' */ 0xF8, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20,
+    /* 'U' */ 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x70, /* 'V' */ 0x88, 0x88, 0x88, 0x88,
+    0x88, 0x50, 0x20, /* 'W' */ 0x88, 0x88, 0x88, 0xA8, 0xA8, 0xA8, 0x50, /* 'X' */ 0x88,
+    0x88, 0x50, 0x20, 0x50, 0x88, 0x88, /* 'Y' */ 0x88, 0x88, 0x88, 0x50, 0x20, 0x20, 0x20,
+    /* 'Z' */ 0xF8, 0x08, 0x10, 0x20, 0x40, 0x80, 0xF8, /* '[' */ 0x70, 0x40, 0x40, 0x40,
+    0x40, 0x40, 0x70, /* '\\' */ 0x00, 0x80, 0x40, 0x20, 0x10, 0x08, 0x00,
+    /* ']' */ 0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x70, /* '^' */ 0x20, 0x50, 0x88, 0x00,
+    0x00, 0x00, 0x00, /* '_' */ 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, /* '`' */ 0x40,
+    0x20, 0x10, 0x00, 0x00, 0x00, 0x00, /* 'a' */ 0x00, 0x00, 0x70, 0x08, 0x78, 0x88, 0x78,
+    /* 'b' */ 0x80, 0x80, 0xB0, 0xC8, 0x88, 0x88, 0xF0, /* 'c' */ 0x00, 0x00, 0x70, 0x80,
+    0x80, 0x88, 0x70, /* 'd' */ 0x08, 0x08, 0x68, 0x98, 0x88, 0x88, 0x78, /* 'e' */ 0x00,
+    0x00, 0x70, 0x88, 0xF8, 0x80, 0x70, /* 'f' */ 0x30, 0x48, 0x40, 0xE0, 0x40, 0x40, 0x40,
+    /* 'g' */ 0x00, 0x78, 0x88, 0x88, 0x78, 0x08, 0x70, /* 'h' */ 0x80, 0x80, 0xB0, 0xC8,
+    0x88, 0x88, 0x88, /* 'i' */ 0x20, 0x00, 0x60, 0x20, 0x20, 0x20, 0x70, /* 'j' */ 0x10,
+    0x00, 0x30, 0x10, 0x10, 0x90, 0x60, /* 'k' */ 0x80, 0x80, 0x90, 0xA0, 0xC0, 0xA0, 0x90,
+    /* 'l' */ 0x60, 0x20, 0x20, 0x20, 0x20, 0x20, 0x70, /* 'm' */ 0x00, 0x00, 0xD0, 0xA8,
+    0xA8, 0x88, 0x88, /* 'n' */ 0x00, 0x00, 0xB0, 0xC8, 0x88, 0x88, 0x88, /* 'o' */ 0x00,
+    0x00, 0x70, 0x88, 0x88, 0x88, 0x70, /* 'p' */ 0x00, 0x00, 0xF0, 0x88, 0xF0, 0x80, 0x80,
+    /* 'q' */ 0x00, 0x00, 0x68, 0x98, 0x78, 0x08, 0x08, /* 'r' */ 0x00, 0x00, 0xB0, 0xC8,
+    0x80, 0x80, 0x80, /* 's' */ 0x00, 0x00, 0x70, 0x80, 0x70, 0x08, 0xF0, /* 't' */ 0x40,
+    0x40, 0xE0, 0x40, 0x40, 0x48, 0x30, /* 'u' */ 0x00, 0x00, 0x88, 0x88, 0x88, 0x98, 0x68,
+    /* 'v' */ 0x00, 0x00, 0x88, 0x88, 0x88, 0x50, 0x20, /* 'w' */ 0x00, 0x00, 0x88, 0x88,
+    0xA8, 0xA8, 0x50, /* 'x' */ 0x00, 0x00, 0x88, 0x50, 0x20, 0x50, 0x88, /* 'y' */ 0x00,
+    0x00, 0x88, 0x88, 0x78, 0x08, 0x70, /* 'z' */ 0x00, 0x00, 0xF8, 0x10, 0x20, 0x40, 0xF8,
+    /* '{' */ 0x10, 0x20, 0x20, 0x40, 0x20, 0x20, 0x10, /* '|' */ 0x20, 0x20, 0x20, 0x20,
+    0x20, 0x20, 0x20, /* '}' */ 0x40, 0x20, 0x20, 0x10, 0x20, 0x20, 0x40, /* '~' */ 0x00,
+    0x00, 0x40, 0xA8, 0x10, 0x00, 0x00,
+];
@@ -0,0 +1,193 @@
+//! Drawing off-screen and copying the result to the screen all at once.
+//!
+//! Drawing into a [`Framebuffer`] avoids the flicker of redrawing the screen piece by piece,
+//! and since the brain's screen can't be read back, it is also the only way to
+//! [save a screenshot](crate::diagnostics::postmortem::write_on_panic) of what was shown.
+
+use alloc::{vec, vec::Vec};
+
+use pros_sys::PROS_ERR;
+
+use super::{font::Font, qr::QrCode, ScreenError};
+use crate::{error::bail_on, image::Image};
+
+const PROS_ERR_U32: u32 = PROS_ERR as _;
+
+/// An off-screen image made of 24-bit RGB pixels, stored row by row from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: i16,
+    height: i16,
+    pixels: Vec<u32>,
+}
+
+impl Framebuffer {
+    /// Creates a black framebuffer.
+    pub fn new(width: i16, height: i16) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        Self {
+            width,
+            height,
+            pixels: vec![pros_sys::COLOR_BLACK; width as usize * height as usize],
+        }
+    }
+
+    /// Creates a black framebuffer the size of the screen's drawable area.
+    pub fn full_screen() -> Self {
+        Self::new(super::WIDTH, super::HEIGHT)
+    }
+
+    pub fn width(&self) -> i16 {
+        self.width
+    }
+
+    pub fn height(&self) -> i16 {
+        self.height
+    }
+
+    /// Returns every pixel, row by row from the top left.
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    fn index(&self, x: i16, y: i16) -> Option<usize> {
+        (x >= 0 && y >= 0 && x < self.width && y < self.height)
+            .then(|| y as usize * self.width as usize + x as usize)
+    }
+
+    /// Returns the color of a pixel, or `None` if it is outside of the framebuffer.
+    pub fn pixel(&self, x: i16, y: i16) -> Option<u32> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    /// Sets the color of a pixel. Pixels outside of the framebuffer are ignored.
+    pub fn set_pixel(&mut self, x: i16, y: i16, color: u32) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = color;
+        }
+    }
+
+    /// Fills the rectangle with corners at (x0, y0) and (x1, y1), clipped to the framebuffer.
+    pub fn fill_rect(&mut self, x0: i16, y0: i16, x1: i16, y1: i16, color: u32) {
+        let (left, right) = (x0.min(x1).max(0), x0.max(x1).min(self.width - 1));
+        let (top, bottom) = (y0.min(y1).max(0), y0.max(y1).min(self.height - 1));
+        for y in top..=bottom {
+            for x in left..=right {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Fills the whole framebuffer with one color.
+    pub fn clear(&mut self, color: u32) {
+        self.pixels.fill(color);
+    }
+
+    /// Draws an image with its top left corner at (x, y), blending partly transparent pixels
+    /// with what is already there. Parts of the image outside of the framebuffer are left out.
+    pub fn draw_image(&mut self, x: i16, y: i16, image: &Image<'_>) {
+        for row in 0..image.height() {
+            for column in 0..image.width() {
+                let Some(index) = self.index(x + column as i16, y + row as i16) else {
+                    continue;
+                };
+                let [alpha, red, green, blue] = image.pixels()
+                    [row as usize * image.width() as usize + column as usize]
+                    .to_be_bytes();
+                let [_, old_red, old_green, old_blue] = self.pixels[index].to_be_bytes();
+                let blend = |new: u8, old: u8| {
+                    ((new as u32 * alpha as u32 + old as u32 * (255 - alpha as u32)) / 255) as u8
+                };
+                self.pixels[index] = u32::from_be_bytes([
+                    0,
+                    blend(red, old_red),
+                    blend(green, old_green),
+                    blend(blue, old_blue),
+                ]);
+            }
+        }
+    }
+
+    /// Draws text with its top left corner at (x, y), with each of the font's pixels drawn `scale` pixels wide and tall.
+    pub fn draw_text(
+        &mut self,
+        x: i16,
+        y: i16,
+        text: &str,
+        font: &Font<'_>,
+        scale: u8,
+        color: u32,
+    ) {
+        let scale = scale.max(1);
+        font.layout(x, y, text, scale, |left, top, width| {
+            self.fill_rect(left, top, left + width - 1, top + scale as i16 - 1, color);
+        });
+    }
+
+    /// Draws a QR code with the top left corner of its quiet zone at (x, y),
+    /// with each module `scale` pixels wide and tall.
+    pub fn draw_qr_code(&mut self, x: i16, y: i16, code: &QrCode, scale: u8) {
+        let size = code.drawn_size(scale);
+        self.fill_rect(x, y, x + size - 1, y + size - 1, pros_sys::COLOR_WHITE);
+        code.dark_runs(x, y, scale, |left, top, right, bottom| {
+            self.fill_rect(left, top, right, bottom, pros_sys::COLOR_BLACK);
+        });
+    }
+
+    /// Copies the framebuffer to the screen with its top left corner at (x, y).
+    pub fn flush(&mut self, x: i16, y: i16) -> Result<(), ScreenError> {
+        if self.pixels.is_empty() {
+            return Ok(());
+        }
+        bail_on!(PROS_ERR_U32, unsafe {
+            pros_sys::screen_copy_area(
+                x,
+                y,
+                x + self.width - 1,
+                y + self.height - 1,
+                self.pixels.as_mut_ptr(),
+                self.width as i32,
+            )
+        });
+        Ok(())
+    }
+
+    /// Encodes the framebuffer as a 24-bit BMP image.
+    pub fn to_bmp(&self) -> Vec<u8> {
+        const HEADER_SIZE: u32 = 14 + 40;
+        // Each row is padded to a multiple of 4 bytes.
+        let row_size = (self.width as u32 * 3 + 3) & !3;
+        let image_size = row_size * self.height as u32;
+
+        let mut bmp = Vec::with_capacity((HEADER_SIZE + image_size) as usize);
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&(HEADER_SIZE + image_size).to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&(self.width as i32).to_le_bytes());
+        bmp.extend_from_slice(&(self.height as i32).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&24u16.to_le_bytes());
+        // No compression.
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&image_size.to_le_bytes());
+        // About 72 DPI, and no palette.
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+
+        // Rows are stored from the bottom up, with each pixel as blue, green, red.
+        let padding = row_size as usize - self.width as usize * 3;
+        for row in self.pixels.chunks_exact(self.width.max(1) as usize).rev() {
+            for &color in row {
+                bmp.extend_from_slice(&color.to_le_bytes()[..3]);
+            }
+            bmp.resize(bmp.len() + padding, 0);
+        }
+        bmp
+    }
+}
@@ -0,0 +1,185 @@
+//! A scrolling log console for reading [`logger`](crate::logger) messages at the field.
+
+use alloc::{format, vec::Vec};
+
+use log::{Level, LevelFilter};
+
+use super::{ScreenError, TextSize, Touch, TouchState};
+use crate::logger::{self, LogLine};
+
+/// The height of one line of small text, in pixels.
+const LINE_HEIGHT: i16 = 16;
+
+/// Shows recent log messages in a region of the screen.
+///
+/// The top line is a header: touching its left half changes which levels are shown,
+/// and touching its right half pauses or resumes the view.
+/// Touching the upper or lower half of the messages scrolls up or down.
+pub struct LogView {
+    x: i16,
+    y: i16,
+    width: i16,
+    height: i16,
+    level: LevelFilter,
+    paused: bool,
+    /// How many lines the view is scrolled up from the newest message.
+    scroll: usize,
+    lines: Vec<LogLine>,
+    last_press_count: Option<i32>,
+}
+
+impl LogView {
+    pub fn new(x: i16, y: i16, width: i16, height: i16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            level: LevelFilter::Trace,
+            paused: false,
+            scroll: 0,
+            lines: Vec::new(),
+            last_press_count: None,
+        }
+    }
+
+    /// Only shows messages at `level` and above.
+    pub fn set_level(&mut self, level: LevelFilter) {
+        self.level = level;
+        self.scroll = 0;
+    }
+
+    pub fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    /// Stops showing new messages until [`LogView::resume`] is called.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.scroll = 0;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.filtered().count().saturating_sub(1));
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    fn visible_lines(&self) -> usize {
+        ((self.height / LINE_HEIGHT) as usize).saturating_sub(1)
+    }
+
+    fn filtered(&self) -> impl DoubleEndedIterator<Item = &LogLine> {
+        self.lines.iter().filter(|line| line.level <= self.level)
+    }
+
+    /// Responds to a new press inside the view.
+    pub fn handle_touch(&mut self, touch: Touch) {
+        let new_press =
+            touch.state == TouchState::Pressed && self.last_press_count != Some(touch.press_count);
+        self.last_press_count = Some(touch.press_count);
+        if !new_press
+            || !(self.x..self.x + self.width).contains(&touch.x)
+            || !(self.y..self.y + self.height).contains(&touch.y)
+        {
+            return;
+        }
+
+        if touch.y < self.y + LINE_HEIGHT {
+            if touch.x < self.x + self.width / 2 {
+                self.set_level(match self.level {
+                    LevelFilter::Trace | LevelFilter::Off => LevelFilter::Debug,
+                    LevelFilter::Debug => LevelFilter::Info,
+                    LevelFilter::Info => LevelFilter::Warn,
+                    LevelFilter::Warn => LevelFilter::Error,
+                    LevelFilter::Error => LevelFilter::Trace,
+                });
+            } else if self.paused {
+                self.resume();
+            } else {
+                self.pause();
+            }
+        } else if touch.y < self.y + (self.height + LINE_HEIGHT) / 2 {
+            self.scroll_up(self.visible_lines() / 2);
+        } else {
+            self.scroll_down(self.visible_lines() / 2);
+        }
+    }
+
+    /// Picks up new messages and touches, then redraws the view.
+    pub fn update(&mut self) -> Result<(), ScreenError> {
+        if let Ok(touch) = super::touch() {
+            self.handle_touch(touch);
+        }
+        if !self.paused {
+            let new = match self.lines.last() {
+                Some(last) => logger::since(last.sequence),
+                None => logger::recent(),
+            };
+            self.lines.extend(new);
+            if self.lines.len() > logger::CAPACITY {
+                let excess = self.lines.len() - logger::CAPACITY;
+                self.lines.drain(..excess);
+            }
+        }
+        self.draw()
+    }
+
+    pub fn draw(&self) -> Result<(), ScreenError> {
+        let right = self.x + self.width - 1;
+        let bottom = self.y + self.height - 1;
+        super::erase_rect(self.x, self.y, right, bottom)?;
+
+        super::set_pen(pros_sys::COLOR_WHITE)?;
+        let header = format!(
+            "Level: {:<5}  {}",
+            self.level,
+            if self.paused { "[paused]" } else { "[live]" }
+        );
+        super::print_at(self.x + 2, self.y, TextSize::Small, &header)?;
+        super::draw_line(
+            self.x,
+            self.y + LINE_HEIGHT - 1,
+            right,
+            self.y + LINE_HEIGHT - 1,
+        )?;
+
+        let shown: Vec<&LogLine> = self
+            .filtered()
+            .rev()
+            .skip(self.scroll)
+            .take(self.visible_lines())
+            .collect();
+        for (row, line) in shown.iter().rev().enumerate() {
+            super::set_pen(match line.level {
+                Level::Error => pros_sys::COLOR_RED,
+                Level::Warn => pros_sys::COLOR_YELLOW,
+                Level::Info => pros_sys::COLOR_WHITE,
+                Level::Debug | Level::Trace => pros_sys::COLOR_GRAY,
+            })?;
+            let text = format!(
+                "{:>4}.{:01} {}",
+                line.time / 1000,
+                line.time % 1000 / 100,
+                line.message
+            );
+            super::print_at(
+                self.x + 2,
+                self.y + LINE_HEIGHT * (row as i16 + 1),
+                TextSize::Small,
+                &text,
+            )?;
+        }
+        Ok(())
+    }
+}
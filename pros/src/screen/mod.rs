@@ -0,0 +1,170 @@
+//! Drawing on the brain's screen.
+//!
+//! Coordinates are in pixels from the top left of the area below the status bar,
+//! which is [`WIDTH`] by [`HEIGHT`] pixels.
+//! Colors are 24-bit RGB values, like the `COLOR_*` constants in [`pros_sys`].
+//!
+//! The LCD emulator used by [`crate::lcd`] draws over the same screen,
+//! so avoid using both at once.
+
+use alloc::ffi::CString;
+
+use pros_sys::PROS_ERR;
+use snafu::Snafu;
+
+use crate::error::{bail_on, map_errno};
+
+pub mod field;
+pub mod font;
+pub mod framebuffer;
+pub mod log_view;
+pub mod qr;
+pub mod replay;
+
+/// The width of the drawable area in pixels.
+pub const WIDTH: i16 = 480;
+/// The height of the drawable area in pixels.
+pub const HEIGHT: i16 = 240;
+
+const PROS_ERR_U32: u32 = PROS_ERR as _;
+
+/// Sets the color used by the `draw` and `fill` functions.
+pub fn set_pen(color: impl Into<u32>) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_set_pen(color.into())
+    });
+    Ok(())
+}
+
+/// Sets the background color used by the `erase` functions.
+pub fn set_eraser(color: impl Into<u32>) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_set_eraser(color.into())
+    });
+    Ok(())
+}
+
+/// Fills the whole screen with the eraser color.
+pub fn erase() -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe { pros_sys::screen_erase() });
+    Ok(())
+}
+
+pub fn draw_pixel(x: i16, y: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe { pros_sys::screen_draw_pixel(x, y) });
+    Ok(())
+}
+
+pub fn draw_line(x0: i16, y0: i16, x1: i16, y1: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_draw_line(x0, y0, x1, y1)
+    });
+    Ok(())
+}
+
+/// Draws the outline of the rectangle with corners at (x0, y0) and (x1, y1).
+pub fn draw_rect(x0: i16, y0: i16, x1: i16, y1: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_draw_rect(x0, y0, x1, y1)
+    });
+    Ok(())
+}
+
+pub fn fill_rect(x0: i16, y0: i16, x1: i16, y1: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_fill_rect(x0, y0, x1, y1)
+    });
+    Ok(())
+}
+
+/// Fills a rectangle with the eraser color.
+pub fn erase_rect(x0: i16, y0: i16, x1: i16, y1: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_erase_rect(x0, y0, x1, y1)
+    });
+    Ok(())
+}
+
+pub fn draw_circle(x: i16, y: i16, radius: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_draw_circle(x, y, radius)
+    });
+    Ok(())
+}
+
+pub fn fill_circle(x: i16, y: i16, radius: i16) -> Result<(), ScreenError> {
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_fill_circle(x, y, radius)
+    });
+    Ok(())
+}
+
+/// The size of text drawn with [`print_at`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TextSize {
+    Small = pros_sys::E_TEXT_SMALL,
+    Medium = pros_sys::E_TEXT_MEDIUM,
+    Large = pros_sys::E_TEXT_LARGE,
+}
+
+/// Draws text with its top left corner at (x, y) in the pen color.
+pub fn print_at(x: i16, y: i16, size: TextSize, text: &str) -> Result<(), ScreenError> {
+    // The text is used as a format string, so any % in it needs escaping.
+    let text = CString::new(text.replace('%', "%%")).expect("text should not contain null bytes");
+    bail_on!(PROS_ERR_U32, unsafe {
+        pros_sys::screen_print_at(size as _, x, y, text.as_ptr())
+    });
+    Ok(())
+}
+
+/// What the last touch on the screen was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchState {
+    Released,
+    Pressed,
+    Held,
+}
+
+/// The last touch on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Touch {
+    pub state: TouchState,
+    pub x: i16,
+    pub y: i16,
+    /// How many times the screen has been pressed.
+    pub press_count: i32,
+    /// How many times the screen has been released.
+    pub release_count: i32,
+}
+
+/// Returns the last touch on the screen.
+pub fn touch() -> Result<Touch, ScreenError> {
+    let status = unsafe { pros_sys::screen_touch_status() };
+    let state = match status.touch_status {
+        pros_sys::E_TOUCH_RELEASED => TouchState::Released,
+        pros_sys::E_TOUCH_PRESSED => TouchState::Pressed,
+        pros_sys::E_TOUCH_HELD => TouchState::Held,
+        _ => return Err(ScreenError::ConcurrentAccess),
+    };
+    Ok(Touch {
+        state,
+        x: status.x,
+        y: status.y,
+        press_count: status.press_count,
+        release_count: status.release_count,
+    })
+}
+
+#[derive(Debug, Snafu)]
+pub enum ScreenError {
+    #[snafu(display("Another resource is currently using the screen."))]
+    ConcurrentAccess,
+}
+impl core::error::Error for ScreenError {}
+
+map_errno! {
+    ScreenError {
+        EACCES => Self::ConcurrentAccess,
+    }
+}
@@ -0,0 +1,593 @@
+//! Showing QR codes on the screen, so data can be copied off the brain with a phone camera
+//! without removing the SD card.
+//! ```rust
+//! let path = postmortem::write(20)?;
+//! let code = QrCode::encode(path.as_bytes(), ErrorCorrection::Medium)?;
+//! screen::erase()?;
+//! code.draw(10, 10, 4)?;
+//! ```
+//!
+//! Phones read codes most easily when each module is at least 3 or 4 pixels wide,
+//! so keep the encoded data short: a file name or a few numbers rather than a whole log.
+
+use alloc::{vec, vec::Vec};
+
+use snafu::Snafu;
+
+use super::ScreenError;
+
+/// How much of a QR code can be damaged or hidden and still be read.
+/// Higher levels make the code larger for the same data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorCorrection {
+    /// About 7% of the code can be lost.
+    Low,
+    /// About 15% of the code can be lost.
+    Medium,
+    /// About 25% of the code can be lost.
+    Quartile,
+    /// About 30% of the code can be lost.
+    High,
+}
+
+impl ErrorCorrection {
+    /// The two bits stored in the code's format information.
+    fn format_bits(self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Medium => 0,
+            Self::Quartile => 3,
+            Self::High => 2,
+        }
+    }
+}
+
+/// Error correction codewords in each block, by error correction level and version.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Error correction blocks, by error correction level and version.
+const ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+const MAX_VERSION: u8 = 40;
+
+/// How many modules of a version's code hold data and error correction,
+/// rather than finder, alignment, timing, or format patterns.
+fn raw_data_modules(version: u8) -> usize {
+    let version = version as usize;
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+/// How many bytes of a version's code hold data, after its error correction is taken out.
+fn data_codewords(version: u8, correction: ErrorCorrection) -> usize {
+    let level = correction as usize;
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[level][version as usize] as usize
+            * ERROR_CORRECTION_BLOCKS[level][version as usize] as usize
+}
+
+/// How many bytes a version's code can hold in byte mode.
+fn byte_capacity(version: u8, correction: ErrorCorrection) -> usize {
+    let length_bits = if version <= 9 { 8 } else { 16 };
+    let bits = data_codewords(version, correction) * 8 - 4 - length_bits;
+    // Byte mode lengths are limited by the size of the length field too.
+    (bits / 8).min((1 << length_bits) - 1)
+}
+
+/// Multiplies two numbers in the Galois field used by QR codes' Reed–Solomon codes.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut product = 0u32;
+    for bit in (0..8).rev() {
+        product = (product << 1) ^ ((product >> 7) * 0x11D);
+        product ^= ((y as u32 >> bit) & 1) * x as u32;
+    }
+    product as u8
+}
+
+/// Returns the coefficients of the Reed–Solomon generator polynomial of a degree,
+/// from the highest power down, leaving out the leading 1.
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0; degree];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for index in 0..degree {
+            divisor[index] = gf_multiply(divisor[index], root);
+            if index + 1 < degree {
+                divisor[index] ^= divisor[index + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+/// Returns the Reed–Solomon error correction codewords for a block of data.
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (term, &coefficient) in remainder.iter_mut().zip(divisor) {
+            *term ^= gf_multiply(coefficient, factor);
+        }
+    }
+    remainder
+}
+
+/// Bits written from the most significant end of each byte.
+struct BitBuffer {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, bits: usize) {
+        for bit in (0..bits).rev() {
+            if self.len / 8 == self.bytes.len() {
+                self.bytes.push(0);
+            }
+            if (value >> bit) & 1 != 0 {
+                self.bytes[self.len / 8] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum QrCodeError {
+    #[snafu(display(
+        "{len} bytes don't fit in a QR code with this error correction. At most {max} do."
+    ))]
+    DataTooLong { len: usize, max: usize },
+}
+impl core::error::Error for QrCodeError {}
+
+/// A square grid of dark and light modules that encodes some bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    version: u8,
+    size: u8,
+    modules: Vec<bool>,
+    /// Whether each module is part of a fixed pattern rather than data.
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// The number of light modules that should surround a code so it can be found.
+    pub const QUIET_ZONE: u8 = 4;
+
+    /// Encodes bytes in the smallest code that holds them with the given error correction.
+    pub fn encode(data: &[u8], correction: ErrorCorrection) -> Result<Self, QrCodeError> {
+        let version = (1..=MAX_VERSION)
+            .find(|&version| data.len() <= byte_capacity(version, correction))
+            .ok_or(QrCodeError::DataTooLong {
+                len: data.len(),
+                max: byte_capacity(MAX_VERSION, correction),
+            })?;
+
+        // Byte mode, the length, and then the data itself.
+        let capacity = data_codewords(version, correction);
+        let mut bits = BitBuffer {
+            bytes: Vec::with_capacity(capacity),
+            len: 0,
+        };
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, if version <= 9 { 8 } else { 16 });
+        for &byte in data {
+            bits.push(byte as u32, 8);
+        }
+        // A terminator of up to four zeros, then padding to fill the code.
+        bits.push(0, (capacity * 8 - bits.len).min(4));
+        bits.push(0, (8 - bits.len % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.bytes.len() >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let codewords = Self::add_error_correction(&bits.bytes, version, correction);
+
+        let size = version * 4 + 17;
+        let mut code = Self {
+            version,
+            size,
+            modules: vec![false; size as usize * size as usize],
+            function: vec![false; size as usize * size as usize],
+        };
+        code.draw_function_patterns(correction);
+        code.draw_codewords(&codewords);
+
+        // Use whichever mask leaves the code easiest to read.
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                code.apply_mask(mask);
+                code.draw_format_bits(correction, mask);
+                let penalty = code.penalty();
+                code.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        code.apply_mask(mask);
+        code.draw_format_bits(correction, mask);
+        Ok(code)
+    }
+
+    /// The version of the code, from 1 to 40, which determines its size.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The width and height of the code in modules, not counting the quiet zone.
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    /// Returns whether the module at (x, y) is dark. Modules outside of the code are light.
+    pub fn module(&self, x: u8, y: u8) -> bool {
+        x < self.size && y < self.size && self.modules[y as usize * self.size as usize + x as usize]
+    }
+
+    /// The width and height in pixels of the code when drawn at a scale, including the quiet zone.
+    pub fn drawn_size(&self, scale: u8) -> i16 {
+        (self.size as i16 + 2 * Self::QUIET_ZONE as i16) * scale.max(1) as i16
+    }
+
+    /// Draws the code directly on the screen with the top left corner of its quiet zone at (x, y),
+    /// with each module `scale` pixels wide and tall.
+    pub fn draw(&self, x: i16, y: i16, scale: u8) -> Result<(), ScreenError> {
+        let size = self.drawn_size(scale);
+        super::set_pen(pros_sys::COLOR_WHITE)?;
+        super::fill_rect(x, y, x + size - 1, y + size - 1)?;
+        super::set_pen(pros_sys::COLOR_BLACK)?;
+        let mut result = Ok(());
+        self.dark_runs(x, y, scale, |left, top, right, bottom| {
+            if result.is_ok() {
+                result = super::fill_rect(left, top, right, bottom);
+            }
+        });
+        result
+    }
+
+    /// Calls `fill` with the corners of each horizontal run of dark modules in screen coordinates.
+    pub(crate) fn dark_runs(
+        &self,
+        x: i16,
+        y: i16,
+        scale: u8,
+        mut fill: impl FnMut(i16, i16, i16, i16),
+    ) {
+        let scale = scale.max(1) as i16;
+        let origin_x = x + Self::QUIET_ZONE as i16 * scale;
+        let origin_y = y + Self::QUIET_ZONE as i16 * scale;
+        for row in 0..self.size {
+            let top = origin_y + row as i16 * scale;
+            let mut column = 0;
+            while column < self.size {
+                if !self.module(column, row) {
+                    column += 1;
+                    continue;
+                }
+                let start = column;
+                while column < self.size && self.module(column, row) {
+                    column += 1;
+                }
+                fill(
+                    origin_x + start as i16 * scale,
+                    top,
+                    origin_x + column as i16 * scale - 1,
+                    top + scale - 1,
+                );
+            }
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.size as usize + x
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        let index = self.index(x, y);
+        self.modules[index] = dark;
+        self.function[index] = true;
+    }
+
+    /// Splits the data into blocks, adds error correction to each one, and interleaves them.
+    fn add_error_correction(data: &[u8], version: u8, correction: ErrorCorrection) -> Vec<u8> {
+        let level = correction as usize;
+        let blocks = ERROR_CORRECTION_BLOCKS[level][version as usize] as usize;
+        let ecc_len = ECC_CODEWORDS_PER_BLOCK[level][version as usize] as usize;
+        let raw_codewords = raw_data_modules(version) / 8;
+        // Some blocks hold one more byte of data than the rest.
+        let short_blocks = blocks - raw_codewords % blocks;
+        let short_len = raw_codewords / blocks;
+
+        let divisor = rs_divisor(ecc_len);
+        let mut split = Vec::with_capacity(blocks);
+        let mut start = 0;
+        for block in 0..blocks {
+            let len = short_len - ecc_len + usize::from(block >= short_blocks);
+            let block_data = &data[start..start + len];
+            start += len;
+            let mut codewords = block_data.to_vec();
+            if block < short_blocks {
+                // A placeholder so every block lines up when interleaving.
+                codewords.push(0);
+            }
+            codewords.extend(rs_remainder(block_data, &divisor));
+            split.push(codewords);
+        }
+
+        let mut interleaved = Vec::with_capacity(raw_codewords);
+        for index in 0..=short_len {
+            for (block, codewords) in split.iter().enumerate() {
+                if index != short_len - ecc_len || block >= short_blocks {
+                    interleaved.push(codewords[index]);
+                }
+            }
+        }
+        interleaved
+    }
+
+    fn alignment_positions(&self) -> Vec<usize> {
+        if self.version == 1 {
+            return Vec::new();
+        }
+        let version = self.version as usize;
+        let count = version / 7 + 2;
+        let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+        let mut positions: Vec<usize> = (0..count - 1)
+            .map(|index| self.size as usize - 7 - index * step)
+            .collect();
+        positions.push(6);
+        positions.reverse();
+        positions
+    }
+
+    fn draw_function_patterns(&mut self, correction: ErrorCorrection) {
+        let size = self.size as usize;
+        for index in 0..size {
+            self.set_function(6, index, index % 2 == 0);
+            self.set_function(index, 6, index % 2 == 0);
+        }
+
+        for (center_x, center_y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4isize {
+                for dx in -4..=4isize {
+                    let (x, y) = (center_x as isize + dx, center_y as isize + dy);
+                    if (0..size as isize).contains(&x) && (0..size as isize).contains(&y) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = self.alignment_positions();
+        let last = positions.len().saturating_sub(1);
+        for (i, &center_x) in positions.iter().enumerate() {
+            for (j, &center_y) in positions.iter().enumerate() {
+                // These would overlap the finder patterns.
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+                for dy in -2..=2isize {
+                    for dx in -2..=2isize {
+                        self.set_function(
+                            (center_x as isize + dx) as usize,
+                            (center_y as isize + dy) as usize,
+                            dx.abs().max(dy.abs()) != 1,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Reserve the format modules; they're drawn for real once a mask is chosen.
+        self.draw_format_bits(correction, 0);
+
+        if self.version >= 7 {
+            let version = self.version as u32;
+            let mut remainder = version;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+            }
+            let bits = (version << 12) | remainder;
+            for index in 0..18 {
+                let dark = (bits >> index) & 1 != 0;
+                let a = size - 11 + index % 3;
+                let b = index / 3;
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, correction: ErrorCorrection, mask: u8) {
+        let data = (correction.format_bits() << 3) | mask as u32;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | remainder) ^ 0x5412;
+        let bit = |index: usize| (bits >> index) & 1 != 0;
+
+        let size = self.size as usize;
+        // The copy around the top left finder pattern.
+        for index in 0..6 {
+            self.set_function(8, index, bit(index));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for index in 9..15 {
+            self.set_function(14 - index, 8, bit(index));
+        }
+        // The copy split between the other two finder patterns.
+        for index in 0..8 {
+            self.set_function(size - 1 - index, 8, bit(index));
+        }
+        for index in 8..15 {
+            self.set_function(8, size - 15 + index, bit(index));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Places the codewords in the zigzag order QR codes are read in, skipping fixed patterns.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size as usize;
+        let mut bit = 0;
+        let mut right = size - 1;
+        loop {
+            // The vertical timing pattern takes up a whole column.
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    let index = self.index(x, y);
+                    if !self.function[index] && bit < codewords.len() * 8 {
+                        self.modules[index] = (codewords[bit / 8] >> (7 - bit % 8)) & 1 != 0;
+                        bit += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Flips the data modules chosen by a mask pattern. Applying a mask twice undoes it.
+    fn apply_mask(&mut self, mask: u8) {
+        let size = self.size as usize;
+        for y in 0..size {
+            for x in 0..size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = self.index(x, y);
+                if flip && !self.function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// Scores how hard the code is to read, by the rules in the QR code standard.
+    fn penalty(&self) -> u32 {
+        let size = self.size as usize;
+        let at = |x: usize, y: usize| self.modules[self.index(x, y)];
+        // Patterns that look like finder patterns, with light modules on one side.
+        const FINDER_LIKE: [[bool; 11]; 2] = [
+            [
+                true, false, true, true, true, false, true, false, false, false, false,
+            ],
+            [
+                false, false, false, false, true, false, true, true, true, false, true,
+            ],
+        ];
+
+        let mut penalty = 0;
+        for transpose in [false, true] {
+            let line = |a: usize, b: usize| if transpose { at(b, a) } else { at(a, b) };
+            for a in 0..size {
+                // Long runs of the same color.
+                let mut run = 1;
+                for b in 1..size {
+                    if line(a, b) == line(a, b - 1) {
+                        run += 1;
+                        if run == 5 {
+                            penalty += 3;
+                        } else if run > 5 {
+                            penalty += 1;
+                        }
+                    } else {
+                        run = 1;
+                    }
+                }
+                for start in 0..size.saturating_sub(10) {
+                    if FINDER_LIKE
+                        .iter()
+                        .any(|pattern| (0..11).all(|b| line(a, start + b) == pattern[b]))
+                    {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+
+        // 2 by 2 blocks of the same color.
+        for y in 1..size {
+            for x in 1..size {
+                let color = at(x, y);
+                if at(x - 1, y) == color && at(x, y - 1) == color && at(x - 1, y - 1) == color {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // How far the balance of dark and light modules is from even.
+        let total = (size * size) as i64;
+        let dark = self.modules.iter().filter(|&&dark| dark).count() as i64;
+        let steps = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + steps.max(0) as u32 * 10
+    }
+}
@@ -0,0 +1,183 @@
+//! Showing where the robot drove during autonomous once it's over,
+//! so drivers can see where a routine went wrong without pulling the SD card.
+//!
+//! ```rust
+//! let trace = Arc::new(Mutex::new(PoseTrace::new()));
+//! MatchReplay::new(FieldView::new(0, 0, 240))
+//!     .with_planned(planned_path)
+//!     .spawn_after_autonomous(trace.clone());
+//! loop {
+//!     odometry.update(&drivetrain, &imu)?;
+//!     trace.lock().record(odometry.pose());
+//!     sleep(Duration::from_millis(10));
+//! }
+//! ```
+
+use alloc::{format, sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use super::{field::FieldView, ScreenError, TextSize};
+use crate::{
+    competition,
+    fixed::Fixed,
+    pose::Pose,
+    sync::Mutex,
+    task::{self, TaskHandle},
+};
+
+/// The poses the robot passed through during autonomous, and how far into autonomous it was at each.
+#[derive(Debug, Clone)]
+pub struct PoseTrace {
+    points: Vec<(Duration, Pose)>,
+    /// The shortest time between recorded poses, which keeps a long skills run from using too much memory.
+    pub interval: Duration,
+}
+
+impl PoseTrace {
+    pub fn new() -> Self {
+        Self {
+            points: Vec::new(),
+            interval: Duration::from_millis(50),
+        }
+    }
+
+    /// Records the pose if autonomous is running, starting a new trace if autonomous has restarted.
+    pub fn record(&mut self, pose: Pose) {
+        let Some(elapsed) = competition::autonomous_elapsed() else {
+            return;
+        };
+        match self.points.last() {
+            Some(&(last, _)) if elapsed < last => self.points.clear(),
+            Some(&(last, _)) if elapsed - last < self.interval => return,
+            _ => {}
+        }
+        self.points.push((elapsed, pose));
+    }
+
+    /// Returns each recorded pose and how far into autonomous it was recorded.
+    pub fn points(&self) -> &[(Duration, Pose)] {
+        &self.points
+    }
+
+    /// Returns how long the recorded part of autonomous lasted.
+    pub fn duration(&self) -> Duration {
+        self.points
+            .last()
+            .map_or(Duration::ZERO, |&(elapsed, _)| elapsed)
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+}
+
+impl Default for PoseTrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws a [`PoseTrace`] on a [`FieldView`], with a marker every [`MatchReplay::marker_interval`]
+/// and the path the routine was meant to follow underneath.
+pub struct MatchReplay {
+    pub view: FieldView,
+    /// How often along the trace to draw a numbered marker.
+    pub marker_interval: Duration,
+    pub marker_color: u32,
+    pub planned_color: u32,
+    planned: Vec<Pose>,
+}
+
+impl MatchReplay {
+    pub fn new(view: FieldView) -> Self {
+        Self {
+            view,
+            marker_interval: Duration::from_secs(1),
+            marker_color: pros_sys::COLOR_WHITE,
+            planned_color: pros_sys::COLOR_GRAY,
+            planned: Vec::new(),
+        }
+    }
+
+    /// Draws `path` under the trace, so it is clear where the robot left it.
+    pub fn with_planned(mut self, path: Vec<Pose>) -> Self {
+        self.planned = path;
+        self
+    }
+
+    /// Redraws the view with the robot where the trace ended.
+    pub fn draw(&self, trace: &PoseTrace) -> Result<(), ScreenError> {
+        let points = trace.points();
+        let end = points
+            .last()
+            .map_or(Pose::new(0.0, 0.0, 0.0), |&(_, pose)| pose);
+        self.view.draw(end, &[])?;
+
+        super::set_pen(self.planned_color)?;
+        self.draw_path(self.planned.iter().copied())?;
+
+        super::set_pen(self.view.path_color)?;
+        self.draw_path(points.iter().map(|&(_, pose)| pose))?;
+
+        super::set_pen(self.marker_color)?;
+        let interval = self.marker_interval.as_millis().max(1);
+        let mut next_marker = interval;
+        for &(elapsed, pose) in points {
+            if elapsed.as_millis() < next_marker {
+                continue;
+            }
+            let seconds = elapsed.as_millis() / 1000;
+            next_marker = (elapsed.as_millis() / interval + 1) * interval;
+            let (x, y) = self.view.to_screen(pose.x, pose.y);
+            super::fill_circle(x, y, 2)?;
+            super::print_at(x + 3, y - 12, TextSize::Small, &format!("{seconds}"))?;
+        }
+
+        // The summary goes beside the field if there is room, and over its top left corner if not.
+        let beside = self.view.x + self.view.size + 8;
+        let summary_x = if beside < super::WIDTH - 60 {
+            beside
+        } else {
+            self.view.x + 4
+        };
+        super::print_at(
+            summary_x,
+            self.view.y + 4,
+            TextSize::Small,
+            &format!("Auton {} s", Fixed::new(trace.duration().as_secs_f64(), 1)),
+        )?;
+        Ok(())
+    }
+
+    fn draw_path(&self, path: impl Iterator<Item = Pose>) -> Result<(), ScreenError> {
+        let mut last = None;
+        for pose in path {
+            let next = self.view.to_screen(pose.x, pose.y);
+            if let Some((x, y)) = last {
+                super::draw_line(x, y, next.0, next.1)?;
+            }
+            last = Some(next);
+        }
+        Ok(())
+    }
+
+    /// Spawns a task that draws the trace every time autonomous ends.
+    /// Errors drawing are passed to [`crate::error::report`].
+    pub fn spawn_after_autonomous(self, trace: Arc<Mutex<PoseTrace>>) -> TaskHandle {
+        task::spawn(move || {
+            let mut was_running = false;
+            loop {
+                let running = competition::autonomous_elapsed().is_some();
+                if was_running && !running {
+                    // The trace is cloned so that recording isn't blocked while drawing.
+                    let trace = trace.lock().clone();
+                    if let Err(err) = self.draw(&trace) {
+                        crate::error::report(&err);
+                    }
+                }
+                was_running = running;
+                task::sleep(Duration::from_millis(50));
+            }
+        })
+    }
+}
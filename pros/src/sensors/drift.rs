@@ -0,0 +1,114 @@
+//! Estimating and removing an IMU's slow drift.
+//!
+//! An IMU's gyro reads a small rate even when it is still, and that bias changes as the sensor warms up,
+//! so a heading can wander by several degrees over a minute long skills run.
+//! [`DriftCompensated`] measures the bias whenever the drive encoders say the robot is stopped,
+//! and subtracts it from every reading, stopped or not.
+
+use core::{cell::Cell, time::Duration};
+
+use super::imu::{Gyro, ImuError};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DriftState {
+    /// The wheel distances last passed to `observe_wheels`.
+    wheels: Option<(f64, f64)>,
+    /// When the robot stopped, and the raw rotation then.
+    stopped_since: Option<(u32, f64)>,
+    /// The estimated bias in degrees per second.
+    bias: f64,
+    /// The total correction subtracted so far, and when it was last updated.
+    correction: f64,
+    last_time: Option<u32>,
+}
+
+/// A [`Gyro`] with its drift estimated while the robot is stopped and subtracted out.
+///
+/// ```rust
+/// let imu = DriftCompensated::new(InertialSensor::new(1)?);
+/// loop {
+///     imu.observe_wheels(drivetrain.left_distance()?, drivetrain.right_distance()?)?;
+///     odometry.update(&drivetrain, &imu)?;
+///     sleep(Duration::from_millis(10));
+/// }
+/// ```
+pub struct DriftCompensated<G> {
+    gyro: G,
+    /// How far either side can move between observations while still counting as stopped.
+    pub stationary_threshold: f64,
+    /// How long the robot must be stopped before the bias is measured.
+    pub settle_time: Duration,
+    /// How much of each new measurement goes into the bias estimate, from 0.0 to 1.0.
+    pub smoothing: f64,
+    state: Cell<DriftState>,
+}
+
+impl<G: Gyro> DriftCompensated<G> {
+    pub fn new(gyro: G) -> Self {
+        Self {
+            gyro,
+            stationary_threshold: 0.01,
+            settle_time: Duration::from_millis(500),
+            smoothing: 0.2,
+            state: Cell::new(DriftState::default()),
+        }
+    }
+
+    pub fn gyro(&self) -> &G {
+        &self.gyro
+    }
+
+    /// Returns the estimated drift in degrees per second, clockwise.
+    pub fn bias(&self) -> f64 {
+        self.state.get().bias
+    }
+
+    /// Tells the model how far each side of the drivetrain has traveled, so it can tell when the robot is stopped.
+    /// This should be called every loop, before the rotation is read.
+    pub fn observe_wheels(&self, left: f64, right: f64) -> Result<(), ImuError> {
+        let mut state = self.state.get();
+        let moved = match state.wheels {
+            Some((last_left, last_right)) => {
+                libm::fabs(left - last_left) > self.stationary_threshold
+                    || libm::fabs(right - last_right) > self.stationary_threshold
+            }
+            None => true,
+        };
+        state.wheels = Some((left, right));
+
+        let now = unsafe { pros_sys::millis() };
+        if moved {
+            state.stopped_since = None;
+        } else {
+            let raw = self.gyro.rotation()?;
+            match state.stopped_since {
+                None => state.stopped_since = Some((now, raw)),
+                Some((since, start)) => {
+                    let elapsed = now - since;
+                    if elapsed >= self.settle_time.as_millis() as u32 {
+                        let measured = (raw - start) / (elapsed as f64 / 1000.0);
+                        state.bias += (measured - state.bias) * self.smoothing.clamp(0.0, 1.0);
+                        // Measure again over the next settle time.
+                        state.stopped_since = Some((now, raw));
+                    }
+                }
+            }
+        }
+        self.state.set(state);
+        Ok(())
+    }
+}
+
+impl<G: Gyro> Gyro for DriftCompensated<G> {
+    fn rotation(&self) -> Result<f64, ImuError> {
+        let raw = self.gyro.rotation()?;
+        let mut state = self.state.get();
+        let now = unsafe { pros_sys::millis() };
+        if let Some(last_time) = state.last_time {
+            state.correction += state.bias * (now - last_time) as f64 / 1000.0;
+        }
+        state.last_time = Some(now);
+        self.state.set(state);
+        Ok(raw - state.correction)
+    }
+}
@@ -1,7 +1,10 @@
 use pros_sys::{PROS_ERR, PROS_ERR_F};
 use snafu::Snafu;
 
-use crate::error::{bail_on, map_errno, PortError};
+use crate::{
+    error::{bail_on, map_errno, PortError},
+    pose::Pose,
+};
 
 pub struct GpsStatus {
     pub x: f64,
@@ -65,6 +68,20 @@ impl GpsSensor {
         }
     }
 
+    /// Returns the sensor's position and heading as a [`Pose`].
+    ///
+    /// The GPS measures in meters with its heading clockwise from the positive y axis,
+    /// so the position is multiplied by `units_per_meter` (such as 39.37 for inches)
+    /// and the heading is turned to be counterclockwise from the positive x axis.
+    pub fn pose(&self, units_per_meter: f64) -> Result<Pose, GpsError> {
+        let status = self.status()?;
+        Ok(Pose::new(
+            status.x * units_per_meter,
+            status.y * units_per_meter,
+            90.0 - status.heading,
+        ))
+    }
+
     pub fn zero_rotation(&self) -> Result<(), GpsError> {
         unsafe {
             bail_on!(PROS_ERR, pros_sys::gps_tare_rotation(self.port));
@@ -0,0 +1,350 @@
+use core::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use pros_sys::{PROS_ERR, PROS_ERR_F};
+use snafu::Snafu;
+
+use crate::error::{bail_on, map_errno, take_errno, FromErrno, PortError};
+
+/// What an [`InertialSensor`] is currently doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImuStatus {
+    /// The sensor is calibrated and its readings can be used.
+    Ready,
+    /// The sensor is calibrating and its readings are not available yet.
+    Calibrating,
+    /// The sensor reported an error.
+    Error,
+}
+
+/// Pitch, roll, and yaw of an [`InertialSensor`] in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Euler {
+    pub pitch: f64,
+    pub roll: f64,
+    pub yaw: f64,
+}
+
+/// A reading along each of an [`InertialSensor`]'s axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// The V5 Inertial Sensor.
+pub struct InertialSensor {
+    port: u8,
+}
+
+impl InertialSensor {
+    /// The longest calibration is expected to take.
+    pub const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(3);
+
+    /// Creates an inertial sensor without calibrating it.
+    pub fn new(port: u8) -> Result<Self, ImuError> {
+        let sensor = Self { port };
+        sensor.status()?;
+        Ok(sensor)
+    }
+
+    /// Calibrates the sensor, blocking for about two seconds until it finishes.
+    /// The robot must not move during calibration.
+    pub fn reset(&self) -> Result<(), ImuError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::imu_reset_blocking(self.port));
+        }
+        Ok(())
+    }
+
+    /// Starts calibrating the sensor without waiting for it to finish,
+    /// so other devices can be set up in the meantime.
+    /// The returned future resolves when calibration finishes; it can also be ignored
+    /// in favor of checking [`InertialSensor::is_calibrating`].
+    /// The robot must not move during calibration.
+    pub fn reset_nonblocking(&self) -> Result<CalibrationFuture, ImuError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::imu_reset(self.port));
+        }
+        Ok(CalibrationFuture {
+            port: self.port,
+            started: unsafe { pros_sys::millis() },
+        })
+    }
+
+    pub fn status(&self) -> Result<ImuStatus, ImuError> {
+        status(self.port)
+    }
+
+    pub fn is_calibrating(&self) -> Result<bool, ImuError> {
+        Ok(self.status()? == ImuStatus::Calibrating)
+    }
+
+    /// Returns the heading in degrees, from 0 to 360, increasing clockwise.
+    pub fn heading(&self) -> Result<f64, ImuError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::imu_get_heading(self.port)) })
+    }
+
+    /// Returns the total rotation in degrees since the last reset, increasing clockwise.
+    /// Unlike [`InertialSensor::heading`], this does not wrap around.
+    pub fn rotation(&self) -> Result<f64, ImuError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::imu_get_rotation(self.port)) })
+    }
+
+    pub fn euler(&self) -> Result<Euler, ImuError> {
+        let euler = unsafe { pros_sys::imu_get_euler(self.port) };
+        let (pitch, roll, yaw) = (euler.pitch, euler.roll, euler.yaw);
+        if pitch == PROS_ERR_F {
+            bail_errno()?;
+        }
+        Ok(Euler { pitch, roll, yaw })
+    }
+
+    /// Returns the acceleration measured by the sensor in g, including gravity.
+    pub fn acceleration(&self) -> Result<Vector3, ImuError> {
+        let accel = unsafe { pros_sys::imu_get_accel(self.port) };
+        if accel.x == PROS_ERR_F {
+            bail_errno()?;
+        }
+        Ok(Vector3 {
+            x: accel.x,
+            y: accel.y,
+            z: accel.z,
+        })
+    }
+
+    /// Returns how fast the sensor is rotating around each axis in degrees per second.
+    pub fn gyro_rate(&self) -> Result<Vector3, ImuError> {
+        let rate = unsafe { pros_sys::imu_get_gyro_rate(self.port) };
+        if rate.x == PROS_ERR_F {
+            bail_errno()?;
+        }
+        Ok(Vector3 {
+            x: rate.x,
+            y: rate.y,
+            z: rate.z,
+        })
+    }
+
+    pub fn set_heading(&self, heading: f64) -> Result<(), ImuError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::imu_set_heading(self.port, heading));
+        }
+        Ok(())
+    }
+
+    pub fn set_rotation(&self, rotation: f64) -> Result<(), ImuError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::imu_set_rotation(self.port, rotation));
+        }
+        Ok(())
+    }
+
+    /// Sets the heading, rotation, and Euler angles to zero.
+    pub fn tare(&self) -> Result<(), ImuError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::imu_tare(self.port));
+        }
+        Ok(())
+    }
+}
+
+/// Something that measures how far the robot has turned, such as an [`InertialSensor`] or a [`FusedImu`].
+pub trait Gyro {
+    /// Returns the total rotation in degrees, increasing clockwise. This does not wrap around.
+    fn rotation(&self) -> Result<f64, ImuError>;
+}
+
+impl Gyro for InertialSensor {
+    fn rotation(&self) -> Result<f64, ImuError> {
+        InertialSensor::rotation(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FusionState {
+    last: [Option<f64>; 2],
+    drift: [f64; 2],
+    healthy: [bool; 2],
+    rotation: f64,
+}
+
+/// Two IMUs combined into one [`Gyro`], so the robot keeps its heading if one of them is knocked out.
+///
+/// Each reading, the change in rotation measured by both IMUs is averaged.
+/// If one IMU jumps by much more than the other, as an IMU can when the robot is hit,
+/// the jump is ignored and that IMU isn't used again.
+/// The same happens if one IMU slowly drifts away from the combined rotation,
+/// or if it stops responding.
+/// The combined rotation starts from zero at the first reading.
+pub struct FusedImu {
+    imus: [InertialSensor; 2],
+    /// How much more one IMU can turn than the other between readings before it is treated as disturbed, in degrees.
+    pub jump_tolerance: f64,
+    /// How far one IMU can drift from the combined rotation before it is no longer used, in degrees.
+    pub drift_tolerance: f64,
+    state: Cell<FusionState>,
+}
+
+impl FusedImu {
+    pub fn new(first: InertialSensor, second: InertialSensor) -> Self {
+        Self {
+            imus: [first, second],
+            jump_tolerance: 2.0,
+            drift_tolerance: 5.0,
+            state: Cell::new(FusionState {
+                last: [None; 2],
+                drift: [0.0; 2],
+                healthy: [true; 2],
+                rotation: 0.0,
+            }),
+        }
+    }
+
+    pub fn imus(&self) -> &[InertialSensor; 2] {
+        &self.imus
+    }
+
+    /// Returns whether each IMU is still being used.
+    pub fn healthy(&self) -> [bool; 2] {
+        self.state.get().healthy
+    }
+
+    /// Starts using both IMUs again, for example after recalibrating them.
+    pub fn reset_health(&self) {
+        let mut state = self.state.get();
+        state.healthy = [true; 2];
+        state.drift = [0.0; 2];
+        state.last = [None; 2];
+        self.state.set(state);
+    }
+}
+
+impl Gyro for FusedImu {
+    fn rotation(&self) -> Result<f64, ImuError> {
+        let mut state = self.state.get();
+        let readings = [self.imus[0].rotation(), self.imus[1].rotation()];
+
+        let mut deltas = [None; 2];
+        for (index, reading) in readings.iter().enumerate() {
+            match reading {
+                Ok(rotation) if state.healthy[index] => {
+                    deltas[index] = Some(state.last[index].map_or(0.0, |last| rotation - last));
+                    state.last[index] = Some(*rotation);
+                }
+                Ok(_) => {}
+                Err(_) => state.healthy[index] = false,
+            }
+        }
+
+        let delta = match deltas {
+            [Some(first), Some(second)] if libm::fabs(first - second) > self.jump_tolerance => {
+                // The IMU that moved more is the one that was disturbed.
+                let disturbed = if libm::fabs(first) > libm::fabs(second) {
+                    0
+                } else {
+                    1
+                };
+                state.healthy[disturbed] = false;
+                deltas[disturbed] = None;
+                if disturbed == 0 {
+                    second
+                } else {
+                    first
+                }
+            }
+            [Some(first), Some(second)] => (first + second) / 2.0,
+            [Some(delta), None] | [None, Some(delta)] => delta,
+            [None, None] => {
+                self.state.set(state);
+                return match readings {
+                    [Err(err), _] | [_, Err(err)] => Err(err),
+                    _ => Err(ImuError::NoHealthySensor),
+                };
+            }
+        };
+        state.rotation += delta;
+
+        for index in 0..2 {
+            if let Some(own) = deltas[index] {
+                state.drift[index] += own - delta;
+                if libm::fabs(state.drift[index]) > self.drift_tolerance {
+                    state.healthy[index] = false;
+                }
+            }
+        }
+        self.state.set(state);
+        Ok(state.rotation)
+    }
+}
+
+fn bail_errno() -> Result<(), ImuError> {
+    let errno = take_errno();
+    Err(ImuError::from_errno(errno).unwrap_or_else(|| panic!("Unknown errno code {errno}")))
+}
+
+fn status(port: u8) -> Result<ImuStatus, ImuError> {
+    const PROS_ERR_U32: u32 = PROS_ERR as _;
+
+    match unsafe { pros_sys::imu_get_status(port) } {
+        pros_sys::E_IMU_STATUS_CALIBRATING => Ok(ImuStatus::Calibrating),
+        pros_sys::E_IMU_STATUS_ERROR => Ok(ImuStatus::Error),
+        PROS_ERR_U32 => match bail_errno() {
+            Err(ImuError::StillCalibrating) => Ok(ImuStatus::Calibrating),
+            result => result.map(|_| ImuStatus::Error),
+        },
+        _ => Ok(ImuStatus::Ready),
+    }
+}
+
+/// A future that resolves once an [`InertialSensor`] finishes calibrating.
+/// Created by [`InertialSensor::reset_nonblocking`].
+pub struct CalibrationFuture {
+    port: u8,
+    started: u32,
+}
+
+impl Future for CalibrationFuture {
+    type Output = Result<(), ImuError>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match status(self.port)? {
+            ImuStatus::Ready => Poll::Ready(Ok(())),
+            ImuStatus::Error => Poll::Ready(Err(ImuError::CalibrationFailed)),
+            ImuStatus::Calibrating => {
+                let elapsed = unsafe { pros_sys::millis() } - self.started;
+                if elapsed >= InertialSensor::CALIBRATION_TIMEOUT.as_millis() as u32 {
+                    Poll::Ready(Err(ImuError::CalibrationFailed))
+                } else {
+                    Poll::Pending
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ImuError {
+    #[snafu(display("The sensor is still calibrating."))]
+    StillCalibrating,
+    #[snafu(display("The sensor failed to calibrate."))]
+    CalibrationFailed,
+    #[snafu(display("None of the fused IMUs can be trusted anymore."))]
+    NoHealthySensor,
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+impl core::error::Error for ImuError {}
+
+map_errno! {
+    ImuError {
+        EAGAIN => Self::StillCalibrating,
+    }
+    inherit PortError;
+}
@@ -1,4 +1,6 @@
 pub mod distance;
+pub mod drift;
 pub mod gps;
+pub mod imu;
 pub mod rotation;
 pub mod vision;
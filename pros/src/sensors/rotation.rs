@@ -1,3 +1,5 @@
+use core::cell::Cell;
+
 use pros_sys::PROS_ERR;
 
 use crate::{
@@ -35,7 +37,7 @@ impl RotationSensor {
         unsafe {
             bail_on!(
                 PROS_ERR,
-                pros_sys::rotation_set_position(self.port, (position.into_counts() * 100) as _)
+                pros_sys::rotation_set_position(self.port, (position.into_degrees() * 100.0) as _)
             );
         }
         Ok(())
@@ -59,11 +61,112 @@ impl RotationSensor {
         self.set_reversed(!self.reversed)
     }
 
-    //TODO: See if this is accurate enough or consider switching to get_position function.
     /// Gets the current position of the sensor.
+    /// Unlike the sensor's angle, this keeps counting past a full rotation.
+    pub fn position(&self) -> Result<Position, PortError> {
+        // The sensor reports centidegrees.
+        let centidegrees =
+            unsafe { bail_on!(PROS_ERR, pros_sys::rotation_get_position(self.port)) };
+        Ok(Position::from_degrees(centidegrees as f64 / 100.0))
+    }
+
+    /// Gets the angle the sensor is at within one rotation, from 0 to 360 degrees.
+    pub fn angle(&self) -> Result<Position, PortError> {
+        let centidegrees = unsafe { bail_on!(PROS_ERR, pros_sys::rotation_get_angle(self.port)) };
+        Ok(Position::from_degrees(centidegrees as f64 / 100.0))
+    }
+}
+
+/// Builds a continuous position out of an angle that wraps around,
+/// such as the 0 to 36000 centidegrees a rotation sensor reports.
+///
+/// Each reading is compared to the last, and a change of more than half a turn is taken to be the angle wrapping,
+/// so readings must be taken often enough that the sensor never turns half a turn between them.
+/// The total is kept in a 64-bit integer, so it won't overflow however long the sensor turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AngleAccumulator {
+    period: i64,
+    last: Option<i64>,
+    total: i64,
+}
+
+impl AngleAccumulator {
+    /// Creates an accumulator for an angle that wraps back to zero at `period`, starting at zero.
+    pub const fn new(period: i32) -> Self {
+        Self {
+            period: period as i64,
+            last: None,
+            total: 0,
+        }
+    }
+
+    /// Adds a reading and returns the total distance turned since the first one.
+    pub fn update(&mut self, angle: i32) -> i64 {
+        let angle = angle as i64;
+        if let Some(last) = self.last {
+            let mut delta = (angle - last) % self.period;
+            if delta > self.period / 2 {
+                delta -= self.period;
+            } else if delta < -self.period / 2 {
+                delta += self.period;
+            }
+            self.total += delta;
+        }
+        self.last = Some(angle);
+        self.total
+    }
+
+    /// Returns the total distance turned.
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    /// Sets the total back to zero without forgetting the last reading.
+    pub fn zero(&mut self) {
+        self.total = 0;
+    }
+}
+
+/// A rotation sensor whose position is built from its angle by an [`AngleAccumulator`].
+///
+/// The sensor's own position count is reset when it loses power or is unplugged and plugged back in,
+/// which makes anything tracking it, like odometry, jump.
+/// The angle is absolute, so a position built from changes in the angle carries on smoothly instead.
+pub struct ContinuousRotation {
+    sensor: RotationSensor,
+    accumulator: Cell<AngleAccumulator>,
+}
+
+impl ContinuousRotation {
+    /// Starts tracking the sensor's position from zero.
+    pub fn new(sensor: RotationSensor) -> Result<Self, PortError> {
+        let rotation = Self {
+            sensor,
+            accumulator: Cell::new(AngleAccumulator::new(36000)),
+        };
+        rotation.position()?;
+        Ok(rotation)
+    }
+
+    pub fn sensor(&self) -> &RotationSensor {
+        &self.sensor
+    }
+
+    /// Returns how far the sensor has turned since it was created or zeroed.
+    /// This should be called often enough that the sensor never turns half a rotation between calls.
     pub fn position(&self) -> Result<Position, PortError> {
-        Ok(unsafe {
-            Position::from_degrees(bail_on!(PROS_ERR, pros_sys::rotation_get_angle(self.port)) as _)
-        })
+        let centidegrees =
+            unsafe { bail_on!(PROS_ERR, pros_sys::rotation_get_angle(self.sensor.port)) };
+        let mut accumulator = self.accumulator.get();
+        let total = accumulator.update(centidegrees);
+        self.accumulator.set(accumulator);
+        Ok(Position::from_degrees(total as f64 / 100.0))
+    }
+
+    /// Sets the position to zero.
+    pub fn zero(&self) {
+        let mut accumulator = self.accumulator.get();
+        accumulator.zero();
+        self.accumulator.set(accumulator);
     }
 }
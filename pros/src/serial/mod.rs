@@ -0,0 +1,191 @@
+//! Generic serial communication over smart ports.
+//!
+//! Any smart port can be used as a UART, for example to talk to a coprocessor
+//! such as a Raspberry Pi or Jetson through an RS-485 adapter.
+//! Use [`protocol::Bridge`] for typed messages on top of a port.
+
+use no_std_io::io;
+use pros_sys::PROS_ERR;
+use snafu::Snafu;
+
+use crate::error::{bail_on, map_errno};
+
+#[cfg(feature = "alloc")]
+pub mod protocol;
+#[cfg(feature = "alloc")]
+pub mod shared;
+pub mod timesync;
+
+/// A smart port configured for generic serial.
+pub struct SerialPort {
+    port: u8,
+}
+
+impl SerialPort {
+    /// Enables generic serial on the given port at the given baud rate.
+    pub fn new(port: u8, baud_rate: u32) -> Result<Self, SerialError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::serial_enable(port));
+        }
+        let serial = Self { port };
+        serial.set_baud_rate(baud_rate)?;
+        Ok(serial)
+    }
+
+    pub fn port(&self) -> u8 {
+        self.port
+    }
+
+    pub fn set_baud_rate(&self, baud_rate: u32) -> Result<(), SerialError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::serial_set_baudrate(self.port, baud_rate as _)
+            );
+        }
+        Ok(())
+    }
+
+    /// Discards everything in the input and output buffers.
+    pub fn clear(&self) -> Result<(), SerialError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::serial_flush(self.port));
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bytes waiting to be read.
+    pub fn bytes_to_read(&self) -> Result<usize, SerialError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::serial_get_read_avail(self.port)) } as _)
+    }
+
+    /// Returns the number of bytes that can be written without overflowing the output buffer.
+    pub fn bytes_free(&self) -> Result<usize, SerialError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::serial_get_write_free(self.port)) } as _)
+    }
+
+    /// Reads as many bytes as are available into `buf`, without blocking.
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, SerialError> {
+        Ok(unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::serial_read(self.port, buf.as_mut_ptr(), buf.len() as _)
+            )
+        } as _)
+    }
+
+    /// Queues as many bytes from `buf` as fit in the output buffer, returning how many were queued.
+    pub fn write(&self, buf: &[u8]) -> Result<usize, SerialError> {
+        // PROS takes a mutable pointer but never writes through it.
+        Ok(unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::serial_write(self.port, buf.as_ptr().cast_mut(), buf.len() as _)
+            )
+        } as _)
+    }
+}
+
+impl io::Read for SerialPort {
+    fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        SerialPort::read(self, dst)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to read from serial port"))
+    }
+}
+
+impl io::Write for SerialPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        SerialPort::write(self, buf)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to write to serial port"))
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl embedded_io::ErrorType for SerialPort {
+    type Error = SerialError;
+}
+
+/// Blocks until at least one byte is available, as [`embedded_io::Read`] requires.
+#[cfg(feature = "embedded-hal")]
+impl embedded_io::Read for SerialPort {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = SerialPort::read(self, buf)?;
+            if read > 0 {
+                return Ok(read);
+            }
+            crate::task::sleep(core::time::Duration::from_millis(1));
+        }
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl embedded_io::ReadReady for SerialPort {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.bytes_to_read()? > 0)
+    }
+}
+
+/// Blocks until at least one byte fits in the output buffer, as [`embedded_io::Write`] requires.
+#[cfg(feature = "embedded-hal")]
+impl embedded_io::Write for SerialPort {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let written = SerialPort::write(self, buf)?;
+            if written > 0 {
+                return Ok(written);
+            }
+            crate::task::sleep(core::time::Duration::from_millis(1));
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl embedded_io::WriteReady for SerialPort {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.bytes_free()? > 0)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SerialError {
+    #[snafu(display("The port you specified is outside of the allowed range!"))]
+    PortOutOfRange,
+    #[snafu(display("Another resource is currently using the port."))]
+    PortTaken,
+    #[snafu(display("An internal error occurred while writing to the port."))]
+    Io,
+}
+impl core::error::Error for SerialError {}
+
+#[cfg(feature = "embedded-hal")]
+impl embedded_io::Error for SerialError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            Self::PortOutOfRange => embedded_io::ErrorKind::InvalidInput,
+            Self::PortTaken => embedded_io::ErrorKind::AddrInUse,
+            Self::Io => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+map_errno! {
+    SerialError {
+        EINVAL => Self::PortOutOfRange,
+        EACCES => Self::PortTaken,
+        EIO => Self::Io,
+    }
+}
@@ -0,0 +1,508 @@
+//! A framed, checksummed protocol for talking to a coprocessor over serial.
+//!
+//! Packets are wrapped with [`encode::frame`] and carry a topic from [`Message::TOPIC`].
+//! Both sides can publish messages to topics, register handlers for topics,
+//! and make requests that the other side answers with a response message.
+//! Heartbeats are exchanged so that a coprocessor that reboots or is unplugged
+//! is noticed, and the bridge resynchronizes with it when it comes back.
+//!
+//! Before any messages are exchanged, both sides send a [`Handshake`] with their versions
+//! and capabilities, and refuse to talk to a peer that is incompatible.
+//! This lets the robot and coprocessor code be updated separately.
+//!
+//! Once connected, the bridge also periodically estimates the offset between the two clocks
+//! (see [`ClockSync`]) so that timestamps from the coprocessor can be converted to brain time.
+
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use core::{mem, time::Duration};
+
+use snafu::Snafu;
+
+use super::{shared::SharedRegion, timesync::ClockSync, SerialError, SerialPort};
+pub use crate::encode::Message;
+use crate::encode::{self, Decode, DecodeError, Decoder, Encode, Encoder, FrameReader};
+
+const KIND_PUBLISH: u8 = 0;
+const KIND_REQUEST: u8 = 1;
+const KIND_RESPONSE: u8 = 2;
+const KIND_HEARTBEAT: u8 = 3;
+const KIND_HANDSHAKE: u8 = 4;
+const KIND_TIME_SYNC: u8 = 5;
+
+/// The version of the packet format itself. Peers must match exactly.
+pub const BRIDGE_PROTOCOL_VERSION: u16 = 1;
+
+/// What each side of a bridge tells the other about itself when connecting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Handshake {
+    /// The version of the messages this side sends, chosen by the user.
+    pub version: u16,
+    /// The oldest peer message version this side can talk to.
+    pub min_peer_version: u16,
+    /// Bit flags for optional features this side supports.
+    pub capabilities: u32,
+    /// A checksum of configuration both sides must agree on, such as field or camera calibration.
+    /// It is only compared if both sides set it.
+    pub config_checksum: Option<u32>,
+}
+
+impl Encode for Handshake {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(
+            self.version,
+            self.min_peer_version,
+            self.capabilities,
+            self.config_checksum,
+        ));
+    }
+}
+
+impl Decode for Handshake {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let (version, min_peer_version, capabilities, config_checksum) = decoder.read()?;
+        Ok(Self {
+            version,
+            min_peer_version,
+            capabilities,
+            config_checksum,
+        })
+    }
+}
+
+/// Timing settings for a [`Bridge`].
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeConfig {
+    /// How often to send a heartbeat.
+    pub heartbeat_interval: Duration,
+    /// How long without hearing anything before the coprocessor is considered disconnected.
+    pub timeout: Duration,
+    /// The longest packet that will be accepted. Raise this for large shared region payloads.
+    pub max_payload_len: usize,
+    /// What this side sends the coprocessor when connecting.
+    pub handshake: Handshake,
+    /// Capability flags the coprocessor must have to be accepted.
+    pub required_capabilities: u32,
+    /// How often to measure the offset between the brain's and coprocessor's clocks,
+    /// or `None` to not measure it.
+    pub time_sync_interval: Option<Duration>,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_millis(100),
+            timeout: Duration::from_millis(500),
+            max_payload_len: FrameReader::DEFAULT_MAX_PAYLOAD_LEN,
+            handshake: Handshake::default(),
+            required_capabilities: 0,
+            time_sync_interval: Some(Duration::from_secs(1)),
+        }
+    }
+}
+
+type Handler = Box<dyn FnMut(&[u8]) -> Result<(), DecodeError> + Send>;
+type RequestHandler = Box<dyn FnMut(&[u8]) -> Result<Vec<u8>, DecodeError> + Send>;
+
+/// A connection to a coprocessor over a [`SerialPort`].
+/// [`Bridge::poll`] must be called periodically to run handlers and keep the connection alive.
+pub struct Bridge {
+    serial: SerialPort,
+    config: BridgeConfig,
+    reader: FrameReader,
+    payload: Vec<u8>,
+    handlers: BTreeMap<u16, Handler>,
+    regions: BTreeMap<u16, Arc<SharedRegion>>,
+    request_handlers: BTreeMap<u16, RequestHandler>,
+    /// The id of the request [`Bridge::request`] is waiting on. Only one request can be outstanding,
+    /// so responses to anything else, such as requests that already timed out, are dropped.
+    pending_request: Option<u16>,
+    response: Option<Vec<u8>>,
+    next_request: u16,
+    last_heard: Option<u32>,
+    last_heartbeat: Option<u32>,
+    connected: bool,
+    peer: Option<Handshake>,
+    clock: ClockSync,
+    last_time_sync: Option<u32>,
+    decode_errors: u32,
+}
+
+impl Bridge {
+    pub fn new(serial: SerialPort) -> Self {
+        Self::with_config(serial, BridgeConfig::default())
+    }
+
+    pub fn with_config(serial: SerialPort, config: BridgeConfig) -> Self {
+        Self {
+            serial,
+            config,
+            reader: FrameReader::with_max_payload_len(config.max_payload_len),
+            payload: Vec::new(),
+            handlers: BTreeMap::new(),
+            regions: BTreeMap::new(),
+            request_handlers: BTreeMap::new(),
+            pending_request: None,
+            response: None,
+            next_request: 0,
+            last_heard: None,
+            last_heartbeat: None,
+            connected: false,
+            peer: None,
+            clock: ClockSync::new(),
+            last_time_sync: None,
+            decode_errors: 0,
+        }
+    }
+
+    fn send_handshake(&self, reply: bool) -> Result<(), BridgeError> {
+        let data = encode::to_checked_vec(&(BRIDGE_PROTOCOL_VERSION, self.config.handshake));
+        self.send_packet(KIND_HANDSHAKE, reply as u16, 0, &data)
+    }
+
+    /// Sends a probe to measure the clock offset. The answer is handled by [`Bridge::poll`].
+    pub fn sync_time(&self) -> Result<(), BridgeError> {
+        let sent = unsafe { pros_sys::micros() };
+        self.send_packet(KIND_TIME_SYNC, 0, 0, &encode::to_vec(&sent))
+    }
+
+    fn handle_time_sync(&mut self, id: u16, data: &[u8]) -> Result<(), BridgeError> {
+        let now = unsafe { pros_sys::micros() };
+        if id == 0 {
+            // The coprocessor is measuring its own offset, so answer with our clock.
+            let sent: u64 = encode::from_slice(data)?;
+            let answer = encode::to_vec(&(sent, now, unsafe { pros_sys::micros() }));
+            self.send_packet(KIND_TIME_SYNC, 1, 0, &answer)
+        } else {
+            let (sent, peer_received, peer_sent) = encode::from_slice(data)?;
+            self.clock.record(sent, peer_received, peer_sent, now);
+            Ok(())
+        }
+    }
+
+    /// Checks a handshake from the coprocessor against this side's requirements.
+    fn check_handshake(&self, data: &[u8]) -> Result<Handshake, BridgeError> {
+        let (protocol_version, peer): (u16, Handshake) = encode::from_checked_slice(data)?;
+        let local = self.config.handshake;
+        if protocol_version != BRIDGE_PROTOCOL_VERSION {
+            return Err(BridgeError::IncompatibleVersion {
+                local: BRIDGE_PROTOCOL_VERSION,
+                peer: protocol_version,
+            });
+        }
+        if peer.version < local.min_peer_version || local.version < peer.min_peer_version {
+            return Err(BridgeError::IncompatibleVersion {
+                local: local.version,
+                peer: peer.version,
+            });
+        }
+        let missing = self.config.required_capabilities & !peer.capabilities;
+        if missing != 0 {
+            return Err(BridgeError::MissingCapabilities { missing });
+        }
+        if let (Some(local), Some(peer)) = (local.config_checksum, peer.config_checksum) {
+            if local != peer {
+                return Err(BridgeError::ConfigMismatch { local, peer });
+            }
+        }
+        Ok(peer)
+    }
+
+    fn send_packet(&self, kind: u8, id: u16, topic: u16, data: &[u8]) -> Result<(), BridgeError> {
+        let mut encoder = encode::Encoder::new();
+        encoder.write(&(kind, id, topic));
+        encoder.write(data);
+        let frame = encode::frame(&encoder.into_inner());
+
+        // The serial port's transmit buffer can fill up, so give it time to drain between writes.
+        let mut written = 0;
+        let mut last_progress = unsafe { pros_sys::millis() };
+        loop {
+            let count = self.serial.write(&frame[written..])?;
+            written += count;
+            if written >= frame.len() {
+                return Ok(());
+            }
+            let now = unsafe { pros_sys::millis() };
+            if count > 0 {
+                last_progress = now;
+            } else if now - last_progress >= self.config.timeout.as_millis() as u32 {
+                return Err(BridgeError::TimedOut);
+            }
+            crate::task::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Calls `handler` with every message of type `M` the coprocessor publishes.
+    /// Registering a second handler for the same type replaces the first.
+    pub fn subscribe<M: Message>(&mut self, mut handler: impl FnMut(M) + Send + 'static) {
+        self.handlers.insert(
+            M::TOPIC,
+            Box::new(move |data| {
+                handler(encode::from_slice(data)?);
+                Ok(())
+            }),
+        );
+    }
+
+    /// Keeps the latest message of type `M` in a [`SharedRegion`] instead of decoding it,
+    /// for large, high-rate messages like vision detections that should be parsed in place.
+    /// Messages longer than `capacity` bytes are dropped.
+    /// Messages on this topic no longer go to a handler registered with [`Bridge::subscribe`].
+    pub fn subscribe_shared<M: Message>(&mut self, capacity: usize) -> Arc<SharedRegion> {
+        let region = Arc::new(SharedRegion::new(capacity));
+        self.regions.insert(M::TOPIC, region.clone());
+        region
+    }
+
+    /// Answers requests of type `Req` from the coprocessor with the response `handler` returns.
+    pub fn serve<Req: Message, Resp: Message>(
+        &mut self,
+        mut handler: impl FnMut(Req) -> Resp + Send + 'static,
+    ) {
+        self.request_handlers.insert(
+            Req::TOPIC,
+            Box::new(move |data| Ok(encode::to_vec(&handler(encode::from_slice(data)?)))),
+        );
+    }
+
+    /// Sends a message to the coprocessor without waiting for a reply.
+    pub fn publish<M: Message>(&self, message: &M) -> Result<(), BridgeError> {
+        self.send_packet(KIND_PUBLISH, 0, M::TOPIC, &encode::to_vec(message))
+    }
+
+    /// Sends a request to the coprocessor and blocks until it responds or `timeout` passes.
+    /// Handlers registered on this bridge keep running while waiting.
+    /// Fails immediately if the handshake with the coprocessor has not finished, so call [`Bridge::poll`] first.
+    pub fn request<Req: Message, Resp: Message>(
+        &mut self,
+        request: &Req,
+        timeout: Duration,
+    ) -> Result<Resp, BridgeError> {
+        if !self.connected {
+            return Err(BridgeError::Disconnected);
+        }
+
+        let id = self.next_request;
+        self.next_request = self.next_request.wrapping_add(1);
+        self.pending_request = Some(id);
+        self.response = None;
+        let result = self.wait_for_response(id, request, timeout);
+        self.pending_request = None;
+        self.response = None;
+        result
+    }
+
+    fn wait_for_response<Req: Message, Resp: Message>(
+        &mut self,
+        id: u16,
+        request: &Req,
+        timeout: Duration,
+    ) -> Result<Resp, BridgeError> {
+        self.send_packet(KIND_REQUEST, id, Req::TOPIC, &encode::to_vec(request))?;
+
+        let start = unsafe { pros_sys::millis() };
+        loop {
+            self.poll()?;
+            if let Some(data) = self.response.take() {
+                return Ok(encode::from_slice(&data)?);
+            }
+            if !self.connected {
+                return Err(BridgeError::Disconnected);
+            }
+            if unsafe { pros_sys::millis() } - start >= timeout.as_millis() as u32 {
+                return Err(BridgeError::TimedOut);
+            }
+            crate::task::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Reads incoming packets, runs handlers, and sends heartbeats.
+    ///
+    /// Until the coprocessor has sent a compatible [`Handshake`], this sends handshakes instead of heartbeats
+    /// and ignores other packets. An incompatible handshake is returned as an error.
+    pub fn poll(&mut self) -> Result<(), BridgeError> {
+        let now = unsafe { pros_sys::millis() };
+
+        let mut buf = [0; 64];
+        loop {
+            let read = self.serial.read(&mut buf)?;
+            if read == 0 {
+                break;
+            }
+            self.reader.push(&buf[..read]);
+        }
+
+        // The payload buffer is reused between frames so that receiving doesn't allocate.
+        let mut payload = mem::take(&mut self.payload);
+        while self.reader.next_frame_into(&mut payload) {
+            let mut decoder = encode::Decoder::new(&payload);
+            let Ok((kind, id, topic)) = decoder.read::<(u8, u16, u16)>() else {
+                self.decode_errors += 1;
+                continue;
+            };
+            let Ok(data) = decoder
+                .read::<usize>()
+                .and_then(|len| decoder.read_raw(len))
+            else {
+                self.decode_errors += 1;
+                continue;
+            };
+            self.last_heard = Some(now);
+
+            if kind == KIND_HANDSHAKE {
+                let result = self.check_handshake(data).and_then(|peer| {
+                    self.peer = Some(peer);
+                    self.connected = true;
+                    // Answer handshakes that aren't answers themselves, so a peer that restarted
+                    // finds out we are still here.
+                    if id == 0 {
+                        self.send_handshake(true)?;
+                    }
+                    Ok(())
+                });
+                if let Err(err) = result {
+                    self.connected = false;
+                    self.peer = None;
+                    self.payload = payload;
+                    return Err(err);
+                }
+                continue;
+            }
+            if !self.connected {
+                continue;
+            }
+
+            match kind {
+                KIND_PUBLISH => {
+                    if let Some(region) = self.regions.get(&topic) {
+                        region.write(data);
+                    } else if let Some(handler) = self.handlers.get_mut(&topic) {
+                        if handler(data).is_err() {
+                            self.decode_errors += 1;
+                        }
+                    }
+                }
+                KIND_REQUEST => {
+                    if let Some(handler) = self.request_handlers.get_mut(&topic) {
+                        match handler(data) {
+                            Ok(response) => {
+                                if let Err(err) =
+                                    self.send_packet(KIND_RESPONSE, id, topic, &response)
+                                {
+                                    self.payload = payload;
+                                    return Err(err);
+                                }
+                            }
+                            Err(_) => self.decode_errors += 1,
+                        }
+                    }
+                }
+                KIND_RESPONSE => {
+                    if self.pending_request == Some(id) {
+                        self.response = Some(data.to_vec());
+                    }
+                }
+                KIND_TIME_SYNC => match self.handle_time_sync(id, data) {
+                    Ok(()) => {}
+                    Err(BridgeError::Decode { .. }) => self.decode_errors += 1,
+                    Err(err) => {
+                        self.payload = payload;
+                        return Err(err);
+                    }
+                },
+                _ => {}
+            }
+        }
+        self.payload = payload;
+
+        if self.connected
+            && self
+                .last_heard
+                .is_some_and(|last| now - last >= self.config.timeout.as_millis() as u32)
+        {
+            // The coprocessor may come back mid-packet, so start over from a clean stream.
+            self.connected = false;
+            self.peer = None;
+            self.clock.reset();
+            self.last_time_sync = None;
+            self.reader = FrameReader::with_max_payload_len(self.config.max_payload_len);
+            self.response = None;
+            self.serial.clear()?;
+        }
+
+        let heartbeat_due = match self.last_heartbeat {
+            Some(last) => now - last >= self.config.heartbeat_interval.as_millis() as u32,
+            None => true,
+        };
+        if heartbeat_due {
+            if self.connected {
+                self.send_packet(KIND_HEARTBEAT, 0, 0, &[])?;
+            } else {
+                self.send_handshake(false)?;
+            }
+            self.last_heartbeat = Some(now);
+        }
+
+        if let (true, Some(interval)) = (self.connected, self.config.time_sync_interval) {
+            let due = match self.last_time_sync {
+                Some(last) => now - last >= interval.as_millis() as u32,
+                None => true,
+            };
+            if due {
+                self.sync_time()?;
+                self.last_time_sync = Some(now);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the coprocessor has completed the handshake and been heard from within the timeout.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Returns the handshake the connected coprocessor sent.
+    pub fn peer(&self) -> Option<&Handshake> {
+        self.peer.as_ref()
+    }
+
+    /// Returns the estimated offset between the brain's and coprocessor's clocks.
+    /// Use [`ClockSync::to_brain_millis`] to convert coprocessor timestamps, such as when a camera frame was captured.
+    pub fn clock(&self) -> &ClockSync {
+        &self.clock
+    }
+
+    /// Returns how many packets or messages could not be decoded.
+    pub fn decode_errors(&self) -> u32 {
+        self.decode_errors
+    }
+
+    /// Returns the underlying serial port.
+    pub fn into_inner(self) -> SerialPort {
+        self.serial
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum BridgeError {
+    #[snafu(display("The coprocessor did not respond in time."))]
+    TimedOut,
+    #[snafu(display("The coprocessor is not connected."))]
+    Disconnected,
+    #[snafu(display(
+        "The coprocessor uses protocol version {peer}, which is incompatible with version {local}."
+    ))]
+    IncompatibleVersion { local: u16, peer: u16 },
+    #[snafu(display("The coprocessor is missing required capabilities {missing:#x}."))]
+    MissingCapabilities { missing: u32 },
+    #[snafu(display(
+        "The coprocessor's configuration checksum {peer:#x} does not match {local:#x}."
+    ))]
+    ConfigMismatch { local: u32, peer: u32 },
+    #[snafu(display("{source}"), context(false))]
+    Decode { source: DecodeError },
+    #[snafu(display("{source}"), context(false))]
+    Serial { source: SerialError },
+}
+impl core::error::Error for BridgeError {}
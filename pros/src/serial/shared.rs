@@ -0,0 +1,79 @@
+//! Double-buffered regions for large, high-rate payloads from a coprocessor.
+//!
+//! A [`SharedRegion`] holds the latest payload of a topic in preallocated memory.
+//! The bridge writes each new payload into the back buffer and then swaps it to the front,
+//! so readers can parse the front buffer in place without copying or allocating,
+//! and a slow reader never blocks the bridge for long.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use crate::sync::Mutex;
+
+/// The latest payload published to a topic, stored in two preallocated buffers.
+#[derive(Debug)]
+pub struct SharedRegion {
+    buffers: [Mutex<Vec<u8>>; 2],
+    front: AtomicUsize,
+    capacity: usize,
+    version: AtomicU32,
+    overflows: AtomicU32,
+}
+
+impl SharedRegion {
+    /// Creates a region that can hold payloads up to `capacity` bytes long.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffers: [
+                Mutex::new(Vec::with_capacity(capacity)),
+                Mutex::new(Vec::with_capacity(capacity)),
+            ],
+            front: AtomicUsize::new(0),
+            capacity,
+            version: AtomicU32::new(0),
+            overflows: AtomicU32::new(0),
+        }
+    }
+
+    /// The largest payload this region can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Copies a payload into the back buffer and makes it the latest.
+    /// Payloads longer than the capacity are dropped and counted in [`SharedRegion::overflows`].
+    pub fn write(&self, payload: &[u8]) -> bool {
+        if payload.len() > self.capacity {
+            self.overflows.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+
+        let back = 1 - self.front.load(Ordering::Acquire);
+        {
+            let mut buffer = self.buffers[back].lock();
+            buffer.clear();
+            buffer.extend_from_slice(payload);
+        }
+        self.front.store(back, Ordering::Release);
+        self.version.fetch_add(1, Ordering::Release);
+        true
+    }
+
+    /// Calls `f` with the latest payload, which is empty until one has been written.
+    /// The bridge can keep writing new payloads while `f` runs.
+    pub fn read<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        let front = self.front.load(Ordering::Acquire);
+        let buffer = self.buffers[front].lock();
+        f(&buffer)
+    }
+
+    /// Counts how many payloads have been written, to tell whether there is a new one.
+    pub fn version(&self) -> u32 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Returns how many payloads were dropped for being longer than the capacity.
+    pub fn overflows(&self) -> u32 {
+        self.overflows.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,83 @@
+//! Estimating the offset between the brain's clock and a coprocessor's clock.
+//!
+//! Like NTP, the brain sends a probe with its send time, and the coprocessor answers with
+//! when it received the probe and when it sent the answer, both in its own clock.
+//! Assuming the trip takes as long each way, that gives the offset between the clocks.
+//! Probes delayed by a busy link give bad estimates, so the one with the shortest round trip
+//! out of the last few is used.
+
+use core::time::Duration;
+
+/// A single offset measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSample {
+    /// The coprocessor's clock minus the brain's clock, in microseconds.
+    pub offset: i64,
+    /// How long the probe took to get there and back, not counting time spent on the coprocessor.
+    pub round_trip: Duration,
+}
+
+/// Keeps track of the offset between the brain's clock and a coprocessor's clock.
+/// All times are in microseconds.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSync {
+    samples: [Option<ClockSample>; Self::SAMPLES],
+    next: usize,
+}
+
+impl ClockSync {
+    /// How many recent samples are kept.
+    pub const SAMPLES: usize = 8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a probe's timestamps: `sent` and `received` are in the brain's clock,
+    /// while `peer_received` and `peer_sent` are in the coprocessor's clock.
+    pub fn record(&mut self, sent: u64, peer_received: u64, peer_sent: u64, received: u64) {
+        let sent = sent as i64;
+        let received = received as i64;
+        let peer_received = peer_received as i64;
+        let peer_sent = peer_sent as i64;
+
+        let round_trip = (received - sent) - (peer_sent - peer_received);
+        let offset = ((peer_received - sent) + (peer_sent - received)) / 2;
+        self.samples[self.next] = Some(ClockSample {
+            offset,
+            round_trip: Duration::from_micros(round_trip.max(0) as u64),
+        });
+        self.next = (self.next + 1) % Self::SAMPLES;
+    }
+
+    /// Returns the best recent sample, or `None` if no probes have been answered.
+    pub fn best(&self) -> Option<ClockSample> {
+        self.samples
+            .iter()
+            .flatten()
+            .min_by_key(|sample| sample.round_trip)
+            .copied()
+    }
+
+    /// Returns the coprocessor's clock minus the brain's clock, in microseconds.
+    pub fn offset(&self) -> Option<i64> {
+        self.best().map(|sample| sample.offset)
+    }
+
+    /// Converts a time in the coprocessor's clock to microseconds since the brain started.
+    pub fn to_brain_micros(&self, peer_time: u64) -> Option<u64> {
+        self.offset()
+            .map(|offset| (peer_time as i64 - offset).max(0) as u64)
+    }
+
+    /// Converts a time in the coprocessor's clock to the brain's `millis()` clock.
+    pub fn to_brain_millis(&self, peer_time: u64) -> Option<u32> {
+        self.to_brain_micros(peer_time)
+            .map(|micros| (micros / 1000) as u32)
+    }
+
+    /// Forgets all samples, for when the coprocessor restarts.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
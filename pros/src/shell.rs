@@ -0,0 +1,352 @@
+//! A small command shell over the USB serial connection, for debugging at the field or on the bench.
+//!
+//! Each line sent from the computer is split on whitespace, and the first word picks a registered command.
+//! Commands pull typed arguments from [`Args`] and write their reply to a string that is sent back.
+//! [Tunable configs](crate::config::registry) can always be read and changed with the built-in `get` and `set` commands.
+//!
+//! ```rust
+//! let odometry = Arc::new(Mutex::new(odometry));
+//! let shell = Shell::new()
+//!     .command("pose", "Prints the robot's pose.", {
+//!         let odometry = odometry.clone();
+//!         move |_, out| {
+//!             let pose = odometry.lock().pose();
+//!             let (x, y) = (Fixed::new(pose.x, 2), Fixed::new(pose.y, 2));
+//!             writeln!(out, "{x} {y} {}", Fixed::new(pose.heading, 1))?;
+//!             Ok(())
+//!         }
+//!     })
+//!     .command("drive", "drive <inches>: Drives straight.", move |args, out| {
+//!         let distance: f64 = args.next("inches")?;
+//!         args.finish()?;
+//!         writeln!(out, "driving {distance}")?;
+//!         Ok(())
+//!     });
+//! shell.spawn();
+//! ```
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Write, str::SplitWhitespace, time::Duration};
+
+use snafu::Snafu;
+
+use crate::{
+    config::{registry, Value},
+    task::{self, TaskHandle},
+};
+
+/// A type that can be parsed from a single shell argument.
+pub trait Arg: Sized {
+    /// A short description of what the argument should look like, used in error messages.
+    const EXPECTED: &'static str;
+
+    fn parse(text: &str) -> Option<Self>;
+}
+
+macro_rules! impl_arg_from_str {
+    ($expected:literal: $($ty:ty),*) => {
+        $(
+            impl Arg for $ty {
+                const EXPECTED: &'static str = $expected;
+
+                fn parse(text: &str) -> Option<Self> {
+                    text.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_arg_from_str!("an integer": i8, i16, i32, i64, u8, u16, u32, u64, usize);
+impl_arg_from_str!("a number": f32, f64);
+
+impl Arg for bool {
+    const EXPECTED: &'static str = "true, false, on, or off";
+
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "true" | "on" | "1" => Some(true),
+            "false" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl Arg for String {
+    const EXPECTED: &'static str = "a word";
+
+    fn parse(text: &str) -> Option<Self> {
+        Some(text.to_string())
+    }
+}
+
+impl Arg for Value {
+    const EXPECTED: &'static str = "a boolean or number";
+
+    fn parse(text: &str) -> Option<Self> {
+        Value::parse(text)
+    }
+}
+
+/// The arguments after a command's name.
+pub struct Args<'a> {
+    words: SplitWhitespace<'a>,
+}
+
+impl<'a> Args<'a> {
+    /// Parses the next argument, failing if it is missing or isn't a `T`.
+    /// `name` is shown in the error message.
+    pub fn next<T: Arg>(&mut self, name: &'static str) -> Result<T, ShellError> {
+        self.optional(name)?
+            .ok_or(ShellError::MissingArgument { name })
+    }
+
+    /// Parses the next argument if there is one.
+    pub fn optional<T: Arg>(&mut self, name: &'static str) -> Result<Option<T>, ShellError> {
+        let Some(text) = self.words.next() else {
+            return Ok(None);
+        };
+        T::parse(text)
+            .map(Some)
+            .ok_or_else(|| ShellError::InvalidArgument {
+                name,
+                expected: T::EXPECTED,
+                text: text.to_string(),
+            })
+    }
+
+    /// Returns the remaining arguments without parsing them.
+    pub fn rest(&mut self) -> Vec<&'a str> {
+        self.words.by_ref().collect()
+    }
+
+    /// Fails if there are arguments left over, which usually means the command was mistyped.
+    pub fn finish(&mut self) -> Result<(), ShellError> {
+        match self.words.next() {
+            Some(text) => Err(ShellError::ExtraArgument {
+                text: text.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+type Handler = Box<dyn FnMut(&mut Args<'_>, &mut String) -> crate::Result + Send>;
+
+struct Command {
+    name: String,
+    help: String,
+    handler: Handler,
+}
+
+/// The longest line the shell reads. Longer lines are discarded with an error,
+/// so a stream of input without newlines can't use up memory.
+pub const MAX_LINE_LEN: usize = 1024;
+
+/// A set of commands that can be run from lines of text.
+pub struct Shell {
+    commands: Vec<Command>,
+    /// Sent before every line is read, or nothing if empty.
+    pub prompt: String,
+}
+
+impl Shell {
+    /// Creates a shell with the built-in `help`, `get`, `set`, and `tasks` commands.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            prompt: String::from("> "),
+        }
+        .command(
+            "get",
+            "get [name.field]: Prints tunable config values.",
+            get,
+        )
+        .command(
+            "set",
+            "set <name.field> <value>: Changes a tunable config value.",
+            set,
+        )
+        .command("tasks", "Prints how many tasks are running.", tasks)
+    }
+
+    /// Registers a command, replacing any command with the same name.
+    ///
+    /// `help` is printed by the `help` command, and conventionally starts with the command's usage
+    /// if it takes arguments, like `"set <name.field> <value>: Changes a tunable config value."`.
+    pub fn command(
+        mut self,
+        name: impl Into<String>,
+        help: impl Into<String>,
+        handler: impl FnMut(&mut Args<'_>, &mut String) -> crate::Result + Send + 'static,
+    ) -> Self {
+        let name = name.into();
+        self.commands.retain(|command| command.name != name);
+        self.commands.push(Command {
+            name,
+            help: help.into(),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    /// Runs one line of input and returns what should be printed in reply.
+    /// Errors are printed rather than returned so that one bad command doesn't stop the shell.
+    pub fn execute(&mut self, line: &str) -> String {
+        let mut out = String::new();
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return out;
+        };
+
+        if name == "help" {
+            for command in &self.commands {
+                _ = writeln!(out, "{:<8} {}", command.name, command.help);
+            }
+            return out;
+        }
+
+        let result = match self
+            .commands
+            .iter_mut()
+            .find(|command| command.name == name)
+        {
+            Some(command) => (command.handler)(&mut Args { words }, &mut out),
+            None => Err(Box::new(ShellError::UnknownCommand {
+                name: name.to_string(),
+            }) as _),
+        };
+        if let Err(error) = result {
+            _ = writeln!(out, "error: {error}");
+        }
+        out
+    }
+
+    /// Spawns a task that reads lines from the USB serial connection and runs them.
+    pub fn spawn(mut self) -> TaskHandle {
+        task::spawn(move || {
+            let mut line = Vec::new();
+            let mut too_long = false;
+            write_stdout(self.prompt.as_bytes());
+            loop {
+                let mut buffer = [0u8; 64];
+                let read = unsafe {
+                    pros_sys::read(
+                        pros_sys::STDIN_FILENO,
+                        buffer.as_mut_ptr().cast(),
+                        buffer.len(),
+                    )
+                };
+                if read <= 0 {
+                    task::sleep(Duration::from_millis(10));
+                    continue;
+                }
+
+                for &byte in &buffer[..read as usize] {
+                    if byte != b'\n' {
+                        if line.len() < MAX_LINE_LEN {
+                            line.push(byte);
+                        } else {
+                            too_long = true;
+                        }
+                        continue;
+                    }
+                    let reply = if too_long {
+                        format!("error: {}\n", ShellError::LineTooLong { max: MAX_LINE_LEN })
+                    } else {
+                        self.execute(String::from_utf8_lossy(&line).trim_end_matches('\r'))
+                    };
+                    line.clear();
+                    too_long = false;
+                    write_stdout(reply.as_bytes());
+                    write_stdout(self.prompt.as_bytes());
+                }
+            }
+        })
+    }
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes all of `bytes` to stdout, which is sent over the USB serial connection.
+///
+/// Waits for the output buffer to drain when it is full,
+/// and gives up if writing fails or makes no progress for 100 ms, such as when nothing is connected.
+pub(crate) fn write_stdout(mut bytes: &[u8]) {
+    let mut last_progress = unsafe { pros_sys::millis() };
+    while !bytes.is_empty() {
+        let written =
+            unsafe { pros_sys::write(pros_sys::STDOUT_FILENO, bytes.as_ptr().cast(), bytes.len()) };
+        if written < 0 {
+            return;
+        }
+        let now = unsafe { pros_sys::millis() };
+        if written > 0 {
+            bytes = &bytes[written as usize..];
+            last_progress = now;
+        } else if now.wrapping_sub(last_progress) >= 100 {
+            return;
+        } else {
+            task::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+fn get(args: &mut Args<'_>, out: &mut String) -> crate::Result {
+    match args.optional::<String>("name.field")? {
+        Some(path) => {
+            args.finish()?;
+            writeln!(out, "{path} = {}", registry::get(&path)?)?;
+        }
+        None => {
+            for (path, value) in registry::fields() {
+                writeln!(out, "{path} = {value}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set(args: &mut Args<'_>, out: &mut String) -> crate::Result {
+    let path: String = args.next("name.field")?;
+    let value: Value = args.next("value")?;
+    args.finish()?;
+    registry::set(&path, value)?;
+    writeln!(out, "{path} = {}", registry::get(&path)?)?;
+    Ok(())
+}
+
+fn tasks(args: &mut Args<'_>, out: &mut String) -> crate::Result {
+    args.finish()?;
+    let count = unsafe { pros_sys::task_get_count() };
+    writeln!(out, "{count} tasks")?;
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+pub enum ShellError {
+    #[snafu(display("Unknown command `{name}`. Type `help` for a list of commands."))]
+    UnknownCommand { name: String },
+    #[snafu(display("Missing argument `{name}`."))]
+    MissingArgument { name: &'static str },
+    #[snafu(display("Expected `{name}` to be {expected}, but got `{text}`."))]
+    InvalidArgument {
+        name: &'static str,
+        expected: &'static str,
+        text: String,
+    },
+    #[snafu(display("Unexpected argument `{text}`."))]
+    ExtraArgument { text: String },
+    #[snafu(display("Lines can be at most {max} bytes long."))]
+    LineTooLong { max: usize },
+}
+impl core::error::Error for ShellError {}
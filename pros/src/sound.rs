@@ -0,0 +1,158 @@
+//! Beeps and short melodies, for alarms like a low battery or an overheating motor.
+//!
+//! PROS doesn't expose the brain's speaker, so sounds are played on the outputs set with [`set_output`]:
+//! the controller's rumble motor, which the driver can hear and feel, and optionally a flash of the brain's screen.
+//! The controller only distinguishes short and long rumbles, so a note's frequency only changes the flash color.
+
+use core::time::Duration;
+
+use snafu::Snafu;
+
+use crate::{
+    adi::Rgb,
+    controller::{Controller, ControllerError},
+    screen::{self, ScreenError},
+    sync::Mutex,
+    task::{self, TaskHandle},
+};
+
+/// Notes at least this long are played as a long rumble instead of a short one.
+const LONG_RUMBLE: Duration = Duration::from_millis(250);
+
+/// Where sounds are played.
+#[derive(Debug, Clone, Copy)]
+pub struct SoundOutput {
+    /// The controller to rumble, if any.
+    pub rumble: Option<Controller>,
+    /// Whether to flash the brain's screen for each note.
+    /// This draws over anything else on the screen.
+    pub flash_screen: bool,
+}
+
+impl Default for SoundOutput {
+    fn default() -> Self {
+        Self {
+            rumble: Some(Controller::Master),
+            flash_screen: false,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref OUTPUT: Mutex<SoundOutput> = Mutex::new(SoundOutput::default());
+}
+
+/// Sets where sounds are played. By default, they rumble the master controller.
+pub fn set_output(output: SoundOutput) {
+    *OUTPUT.lock() = output;
+}
+
+/// A note in a melody.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    /// The frequency in hertz, or 0 for a rest.
+    pub frequency: f32,
+    pub duration: Duration,
+}
+
+impl Note {
+    pub const fn new(frequency: f32, duration: Duration) -> Self {
+        Self {
+            frequency,
+            duration,
+        }
+    }
+
+    /// A silent pause.
+    pub const fn rest(duration: Duration) -> Self {
+        Self::new(0.0, duration)
+    }
+
+    pub fn is_rest(&self) -> bool {
+        self.frequency <= 0.0
+    }
+
+    /// The color the screen flashes for this note.
+    /// Each octave goes around the color wheel once, so the same note is always the same color.
+    fn color(&self) -> Rgb {
+        Rgb::from_hsv(libm::log2f(self.frequency / 440.0) * 360.0, 1.0, 1.0)
+    }
+}
+
+/// Three short beeps, repeated by the caller as long as the battery is low.
+pub const LOW_BATTERY: &[Note] = &[
+    Note::new(880.0, Duration::from_millis(100)),
+    Note::rest(Duration::from_millis(100)),
+    Note::new(880.0, Duration::from_millis(100)),
+    Note::rest(Duration::from_millis(100)),
+    Note::new(880.0, Duration::from_millis(100)),
+];
+
+/// A long low tone followed by a short high one.
+pub const OVER_TEMPERATURE: &[Note] = &[
+    Note::new(220.0, Duration::from_millis(500)),
+    Note::rest(Duration::from_millis(100)),
+    Note::new(1760.0, Duration::from_millis(150)),
+];
+
+/// Plays a single tone, blocking until it finishes.
+pub fn beep(frequency: f32, duration: Duration) -> Result<(), SoundError> {
+    play_note(&Note::new(frequency, duration), *OUTPUT.lock())
+}
+
+/// Plays the notes of a melody in order, blocking until it finishes.
+///
+/// The controller can't keep up with very fast rumble changes,
+/// so notes and rests shorter than about 50 ms may be merged or dropped.
+pub fn play(melody: &[Note]) -> Result<(), SoundError> {
+    let output = *OUTPUT.lock();
+    for note in melody {
+        play_note(note, output)?;
+    }
+    Ok(())
+}
+
+/// Plays a melody in a background task.
+/// Errors are sent to [`crate::error::report`].
+pub fn play_in_background(melody: &'static [Note]) -> TaskHandle {
+    task::spawn(move || {
+        if let Err(err) = play(melody) {
+            crate::error::report(&err);
+        }
+    })
+}
+
+fn play_note(note: &Note, output: SoundOutput) -> Result<(), SoundError> {
+    if note.is_rest() {
+        task::sleep(note.duration);
+        return Ok(());
+    }
+
+    if let Some(controller) = output.rumble {
+        controller.rumble(if note.duration >= LONG_RUMBLE {
+            "-"
+        } else {
+            "."
+        })?;
+    }
+    if output.flash_screen {
+        screen::set_pen(note.color().to_hex())?;
+        screen::fill_rect(0, 0, screen::WIDTH - 1, screen::HEIGHT - 1)?;
+    }
+
+    task::sleep(note.duration);
+
+    if output.flash_screen {
+        screen::erase()?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+pub enum SoundError {
+    #[snafu(display("{source}"), context(false))]
+    Controller { source: ControllerError },
+    #[snafu(display("{source}"), context(false))]
+    Screen { source: ScreenError },
+}
+impl core::error::Error for SoundError {}
@@ -0,0 +1,160 @@
+//! A slip gear catapult or puncher that reloads itself after every shot.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use snafu::Snafu;
+
+use crate::{
+    adi::{AdiDigitalIn, AdiError},
+    error::PortError,
+    motor::{MotorError, MotorGroup},
+    sensors::rotation::RotationSensor,
+};
+
+/// How the catapult knows that it is pulled back and ready to fire.
+pub enum LoadSensor {
+    /// A rotation sensor on the arm. The catapult is loaded when the sensor is within
+    /// `tolerance` of `loaded_position`, both in the units returned by [`RotationSensor::position`].
+    Rotation {
+        sensor: RotationSensor,
+        loaded_position: f64,
+        tolerance: f64,
+    },
+    /// A limit switch that is pressed while the catapult is loaded.
+    LimitSwitch(AdiDigitalIn),
+}
+
+impl LoadSensor {
+    fn is_loaded(&self) -> Result<bool, CatapultError> {
+        Ok(match self {
+            Self::Rotation {
+                sensor,
+                loaded_position,
+                tolerance,
+            } => libm::fabs(sensor.position()?.into_degrees() - loaded_position) <= *tolerance,
+            Self::LimitSwitch(switch) => switch.is_high()?,
+        })
+    }
+}
+
+/// What the catapult is currently doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatapultState {
+    /// The motors are pulling the arm back to the loaded position.
+    Reloading,
+    /// The arm is pulled back and ready to fire.
+    Loaded,
+    /// The motors are turning the slip gear past its teeth to release the arm.
+    Firing,
+}
+
+/// A catapult driven through a slip gear.
+///
+/// The motors always turn the same way: from the loaded position they slip the gear to fire,
+/// and then keep turning until the arm is pulled back to the loaded position again.
+pub struct Catapult {
+    motors: MotorGroup,
+    sensor: LoadSensor,
+    reload_voltage: f32,
+    state: CatapultState,
+    shots: u32,
+}
+
+impl Catapult {
+    /// Creates a catapult, which starts reloading on the first update.
+    /// `reload_voltage` is the voltage the motors are run at while firing and reloading.
+    pub fn new(motors: MotorGroup, sensor: LoadSensor, reload_voltage: f32) -> Self {
+        Self {
+            motors,
+            sensor,
+            reload_voltage,
+            state: CatapultState::Reloading,
+            shots: 0,
+        }
+    }
+
+    /// Returns what the catapult is currently doing.
+    pub fn state(&self) -> CatapultState {
+        self.state
+    }
+
+    /// Returns how many times the catapult has fired and reloaded.
+    pub fn shots(&self) -> u32 {
+        self.shots
+    }
+
+    /// Reads the load sensor and drives the motors, reloading automatically after firing.
+    /// This should be called periodically when the catapult is not being awaited through [`Catapult::fire`].
+    pub fn update(&mut self) -> Result<(), CatapultError> {
+        let loaded = self.sensor.is_loaded()?;
+
+        self.state = match self.state {
+            CatapultState::Reloading if loaded => CatapultState::Loaded,
+            CatapultState::Loaded if !loaded => CatapultState::Reloading,
+            CatapultState::Firing if !loaded => {
+                self.shots += 1;
+                CatapultState::Reloading
+            }
+            state => state,
+        };
+
+        match self.state {
+            CatapultState::Loaded => self.motors.brake()?,
+            CatapultState::Reloading | CatapultState::Firing => {
+                self.motors.set_voltage(self.reload_voltage)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fires the catapult as soon as it is loaded.
+    /// The returned future drives the catapult and resolves once it has fired and reloaded.
+    pub fn fire(&mut self) -> FireFuture<'_> {
+        FireFuture {
+            target_shots: self.shots + 1,
+            catapult: self,
+        }
+    }
+}
+
+/// A future that fires a [`Catapult`] and resolves once it is loaded again.
+pub struct FireFuture<'a> {
+    catapult: &'a mut Catapult,
+    target_shots: u32,
+}
+
+impl Future for FireFuture<'_> {
+    type Output = Result<(), CatapultError>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let catapult = &mut *this.catapult;
+
+        if catapult.shots < this.target_shots && catapult.state == CatapultState::Loaded {
+            catapult.state = CatapultState::Firing;
+        }
+        catapult.update()?;
+
+        if catapult.shots >= this.target_shots && catapult.state == CatapultState::Loaded {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum CatapultError {
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+    #[snafu(display("{source}"), context(false))]
+    Adi { source: AdiError },
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+impl core::error::Error for CatapultError {}
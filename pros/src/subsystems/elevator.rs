@@ -0,0 +1,214 @@
+//! A linear lift driven by one or more motors.
+
+use core::time::Duration;
+
+use snafu::Snafu;
+
+use crate::{
+    motor::{MotorError, MotorGroup},
+    pid::PidController,
+    profile::TrapezoidProfile,
+    task::sleep,
+};
+
+/// How long to ignore current readings after starting to home, so the spin-up spike isn't mistaken for the hard stop.
+const HOMING_GRACE_PERIOD: Duration = Duration::from_millis(250);
+/// How many consecutive high current readings are needed to consider the elevator homed.
+const HOMING_SAMPLES: u32 = 3;
+
+/// Settings describing an elevator's geometry and tuning.
+/// All heights are in whatever linear unit `units_per_degree` converts to.
+#[derive(Debug, Clone, Copy)]
+pub struct ElevatorConfig {
+    /// How far the carriage travels per degree of motor rotation.
+    pub units_per_degree: f64,
+    /// The lowest height the elevator is allowed to move to.
+    pub min_height: f64,
+    /// The highest height the elevator is allowed to move to.
+    pub max_height: f64,
+    /// Maximum carriage velocity used when moving to a height, in units per second.
+    pub max_velocity: f64,
+    /// Maximum carriage acceleration used when moving to a height, in units per second squared.
+    pub max_acceleration: f64,
+    /// Voltage needed to hold the carriage still against gravity.
+    pub gravity_voltage: f32,
+    /// Feedforward voltage applied per unit per second of desired velocity.
+    pub velocity_voltage: f32,
+    /// Voltage used to drive the carriage down into its hard stop while homing.
+    pub homing_voltage: f32,
+    /// Combined current draw in milliamps that means the carriage has hit its hard stop.
+    pub homing_current: i32,
+}
+
+/// A linear lift that can home itself and follow motion profiles to a height.
+///
+/// Heights are measured from the bottom hard stop, which is found by [`Elevator::home`].
+/// Until the elevator is homed only manual control is available and soft limits are not enforced.
+pub struct Elevator {
+    motors: MotorGroup,
+    config: ElevatorConfig,
+    pid: PidController,
+    profile: Option<(TrapezoidProfile, u32)>,
+    target: f64,
+    homed: bool,
+    overriding: bool,
+}
+
+impl Elevator {
+    pub fn new(motors: MotorGroup, config: ElevatorConfig, pid: PidController) -> Self {
+        Self {
+            motors,
+            config,
+            pid,
+            profile: None,
+            target: 0.0,
+            homed: false,
+            overriding: false,
+        }
+    }
+
+    /// Returns the height of the carriage above the hard stop.
+    pub fn height(&self) -> Result<f64, ElevatorError> {
+        Ok(self.motors.position()?.into_degrees() * self.config.units_per_degree)
+    }
+
+    /// Returns the height the elevator is currently moving to or holding.
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    /// Returns true if the elevator has found its hard stop.
+    pub fn is_homed(&self) -> bool {
+        self.homed
+    }
+
+    /// Returns true if the elevator is done following its profile and is within `tolerance` of its target.
+    pub fn is_settled(&self, tolerance: f64) -> Result<bool, ElevatorError> {
+        Ok(self.profile.is_none() && libm::fabs(self.height()? - self.target) <= tolerance)
+    }
+
+    /// Slowly drives the carriage down until the motors draw enough current to indicate it hit the hard stop,
+    /// and then zeroes the motors there.
+    /// Blocks the current task until homing succeeds or `timeout` elapses.
+    pub fn home(&mut self, timeout: Duration) -> Result<(), ElevatorError> {
+        let start = unsafe { pros_sys::millis() };
+        let mut high_samples = 0;
+
+        self.homed = false;
+        self.profile = None;
+        self.motors.set_voltage(self.config.homing_voltage)?;
+
+        loop {
+            let elapsed = Duration::from_millis((unsafe { pros_sys::millis() } - start) as u64);
+            if elapsed > timeout {
+                self.motors.set_voltage(0.0)?;
+                return Err(ElevatorError::HomingTimedOut);
+            }
+
+            if elapsed > HOMING_GRACE_PERIOD
+                && self.motors.current_draw()? >= self.config.homing_current
+            {
+                high_samples += 1;
+            } else {
+                high_samples = 0;
+            }
+
+            if high_samples >= HOMING_SAMPLES {
+                break;
+            }
+
+            sleep(Duration::from_millis(10));
+        }
+
+        self.motors.set_voltage(0.0)?;
+        self.motors.zero()?;
+        self.homed = true;
+        self.target = self.config.min_height.max(0.0);
+        Ok(())
+    }
+
+    /// Starts moving the carriage to the given height along a trapezoidal profile.
+    /// The height is clamped to the soft limits.
+    /// The move is carried out by [`Elevator::update`].
+    pub fn go_to(&mut self, height: f64) -> Result<(), ElevatorError> {
+        if !self.homed {
+            return Err(ElevatorError::NotHomed);
+        }
+
+        let goal = height.clamp(self.config.min_height, self.config.max_height);
+        let profile = TrapezoidProfile::new(
+            self.height()?,
+            goal,
+            self.config.max_velocity,
+            self.config.max_acceleration,
+        );
+
+        self.target = goal;
+        self.profile = Some((profile, unsafe { pros_sys::millis() }));
+        Ok(())
+    }
+
+    /// Runs the elevator's control loop. This should be called periodically.
+    ///
+    /// `operator_input` is a value from -1 to 1, usually from a joystick.
+    /// Any nonzero input is blended with the closed loop output in proportion to its magnitude,
+    /// cancelling the current move. Once the input returns to zero the elevator holds where it was left.
+    pub fn update(&mut self, operator_input: f32) -> Result<(), ElevatorError> {
+        let height = self.height()?;
+        let manual_weight = libm::fabsf(operator_input).min(1.0);
+
+        if manual_weight > 0.0 {
+            self.overriding = true;
+            self.profile = None;
+            self.target = height;
+        } else if self.overriding {
+            self.overriding = false;
+            self.target = height;
+        }
+
+        let closed_loop = if self.homed {
+            let (setpoint, velocity) = match self.profile {
+                Some((profile, start)) => {
+                    let time = (unsafe { pros_sys::millis() } - start) as f64 / 1000.0;
+                    if profile.is_finished(time) {
+                        self.profile = None;
+                    }
+                    let state = profile.sample(time);
+                    (state.position, state.velocity)
+                }
+                None => (self.target, 0.0),
+            };
+
+            self.config.gravity_voltage
+                + self.config.velocity_voltage * velocity as f32
+                + self.pid.update(setpoint as f32, height as f32)
+        } else {
+            0.0
+        };
+
+        let mut voltage =
+            (1.0 - manual_weight) * closed_loop + manual_weight * operator_input * 12.0;
+
+        if self.homed {
+            if height >= self.config.max_height {
+                voltage = voltage.min(self.config.gravity_voltage);
+            } else if height <= self.config.min_height {
+                voltage = voltage.max(0.0);
+            }
+        }
+
+        self.motors.set_voltage(voltage.clamp(-12.0, 12.0))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ElevatorError {
+    #[snafu(display("The elevator did not reach its hard stop before the homing timeout."))]
+    HomingTimedOut,
+    #[snafu(display("The elevator must be homed before it can move to a height."))]
+    NotHomed,
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+}
+impl core::error::Error for ElevatorError {}
@@ -0,0 +1,178 @@
+//! A roller intake that detects game pieces with a distance sensor.
+
+use core::time::Duration;
+
+use snafu::Snafu;
+
+use crate::{
+    error::PortError,
+    motor::{MotorError, MotorGroup},
+    sensors::distance::DistanceSensor,
+    task::sleep,
+};
+
+/// Settings for an intake's speeds, piece detection, and jam handling.
+#[derive(Debug, Clone, Copy)]
+pub struct IntakeConfig {
+    /// Voltage used to pull pieces in.
+    pub intake_voltage: f32,
+    /// Voltage used to spit pieces out. Should be negative.
+    pub outtake_voltage: f32,
+    /// A piece is detected when the distance sensor reads closer than this many millimeters.
+    pub detect_distance: u32,
+    /// Combined current draw in milliamps that indicates the rollers may be jammed.
+    pub jam_current: i32,
+    /// How long the current must stay above `jam_current` before the intake is considered jammed.
+    pub jam_time: Duration,
+    /// How long to reverse the rollers for when a jam is detected.
+    pub unjam_time: Duration,
+}
+
+/// What the intake rollers are currently doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntakeState {
+    Idle,
+    Intaking,
+    Outtaking,
+    /// The rollers are briefly running backwards to clear a jam before intaking again.
+    Unjamming,
+}
+
+/// An intake that counts the pieces it collects and clears jams automatically.
+pub struct Intake {
+    motors: MotorGroup,
+    sensor: DistanceSensor,
+    config: IntakeConfig,
+    state: IntakeState,
+    holding: bool,
+    count: u32,
+    high_current_since: Option<u32>,
+    unjam_until: u32,
+}
+
+impl Intake {
+    pub fn new(motors: MotorGroup, sensor: DistanceSensor, config: IntakeConfig) -> Self {
+        Self {
+            motors,
+            sensor,
+            config,
+            state: IntakeState::Idle,
+            holding: false,
+            count: 0,
+            high_current_since: None,
+            unjam_until: 0,
+        }
+    }
+
+    /// Returns what the rollers are currently doing.
+    pub fn state(&self) -> IntakeState {
+        self.state
+    }
+
+    /// Returns true if a piece was in front of the sensor during the last update.
+    pub fn is_holding(&self) -> bool {
+        self.holding
+    }
+
+    /// Returns how many pieces have been collected since the count was last reset.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Sets the collected piece count back to zero.
+    pub fn reset_count(&mut self) {
+        self.count = 0;
+    }
+
+    /// Starts running the rollers inwards.
+    pub fn intake(&mut self) {
+        self.state = IntakeState::Intaking;
+        self.high_current_since = None;
+    }
+
+    /// Starts running the rollers outwards.
+    pub fn outtake(&mut self) {
+        self.state = IntakeState::Outtaking;
+        self.high_current_since = None;
+    }
+
+    /// Stops the rollers.
+    pub fn stop(&mut self) {
+        self.state = IntakeState::Idle;
+        self.high_current_since = None;
+    }
+
+    /// Reads the sensor, counts new pieces, handles jams, and drives the rollers.
+    /// This should be called periodically.
+    pub fn update(&mut self) -> Result<(), IntakeError> {
+        let now = unsafe { pros_sys::millis() };
+
+        let holding = self.sensor.distance()? < self.config.detect_distance;
+        if holding && !self.holding && self.state == IntakeState::Intaking {
+            self.count += 1;
+        }
+        self.holding = holding;
+
+        match self.state {
+            IntakeState::Intaking => {
+                if self.motors.current_draw()? >= self.config.jam_current {
+                    let since = *self.high_current_since.get_or_insert(now);
+                    if now - since >= self.config.jam_time.as_millis() as u32 {
+                        self.state = IntakeState::Unjamming;
+                        self.unjam_until = now + self.config.unjam_time.as_millis() as u32;
+                        self.high_current_since = None;
+                    }
+                } else {
+                    self.high_current_since = None;
+                }
+            }
+            IntakeState::Unjamming if now >= self.unjam_until => {
+                self.state = IntakeState::Intaking;
+            }
+            _ => {}
+        }
+
+        let voltage = match self.state {
+            IntakeState::Idle => 0.0,
+            IntakeState::Intaking => self.config.intake_voltage,
+            IntakeState::Outtaking | IntakeState::Unjamming => self.config.outtake_voltage,
+        };
+        self.motors.set_voltage(voltage)?;
+
+        Ok(())
+    }
+
+    /// Runs the intake until a piece is detected in front of the sensor, and then stops it.
+    /// Blocks the current task until a piece is held or `timeout` elapses.
+    pub fn intake_until_holding(&mut self, timeout: Duration) -> Result<(), IntakeError> {
+        let start = unsafe { pros_sys::millis() };
+        self.intake();
+
+        loop {
+            self.update()?;
+            if self.holding {
+                break;
+            }
+            if unsafe { pros_sys::millis() } - start > timeout.as_millis() as u32 {
+                self.stop();
+                self.update()?;
+                return Err(IntakeError::TimedOut);
+            }
+            sleep(Duration::from_millis(10));
+        }
+
+        self.stop();
+        self.update()
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum IntakeError {
+    #[snafu(display("No game piece was detected before the timeout."))]
+    TimedOut,
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+impl core::error::Error for IntakeError {}
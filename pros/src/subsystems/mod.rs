@@ -0,0 +1,10 @@
+//! Ready-made mechanisms built on top of the device wrappers.
+//!
+//! Subsystems are plain structs that own their devices.
+//! Call their `update` method periodically (for example every 10 milliseconds in opcontrol)
+//! so they can run their control loops.
+
+pub mod catapult;
+pub mod elevator;
+pub mod intake;
+pub mod turret;
@@ -0,0 +1,186 @@
+//! A rotating turret that can hold a field-relative heading while the robot turns.
+
+use snafu::Snafu;
+
+use crate::{
+    math::angle,
+    motor::{MotorError, MotorGroup},
+    pid::PidController,
+};
+
+/// Settings for a turret's gearing, travel, and feedforward.
+#[derive(Debug, Clone, Copy)]
+pub struct TurretConfig {
+    /// Degrees the turret turns per degree of motor rotation.
+    pub gear_ratio: f64,
+    /// The minimum and maximum angle in degrees the turret may turn to, relative to the front of the robot.
+    /// `None` if the turret can rotate continuously (for example through a slip ring).
+    pub limits: Option<(f64, f64)>,
+    /// Feedforward voltage applied per degree per second the turret needs to turn
+    /// to cancel out the robot's own rotation.
+    pub velocity_voltage: f32,
+}
+
+/// What the turret is trying to point at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TurretTarget {
+    /// An angle in degrees relative to the front of the robot.
+    Robot(f64),
+    /// A heading in degrees on the field, which is held as the robot turns.
+    Field(f64),
+}
+
+/// A turret with closed loop position control.
+///
+/// Angles are in degrees, measured from where the turret pointed when its motors were last zeroed.
+pub struct Turret {
+    motors: MotorGroup,
+    config: TurretConfig,
+    pid: PidController,
+    target: TurretTarget,
+    /// Recent field headings of the turret and when they were measured, for latency compensation.
+    history: [(u32, f64); HISTORY_LEN],
+    history_next: usize,
+}
+
+const HISTORY_LEN: usize = 32;
+
+impl Turret {
+    pub fn new(motors: MotorGroup, config: TurretConfig, pid: PidController) -> Self {
+        Self {
+            motors,
+            config,
+            pid,
+            target: TurretTarget::Robot(0.0),
+            history: [(0, f64::NAN); HISTORY_LEN],
+            history_next: 0,
+        }
+    }
+
+    /// Returns the angle of the turret relative to the front of the robot.
+    /// This is not wrapped, so it keeps counting up past a full rotation.
+    pub fn angle(&self) -> Result<f64, TurretError> {
+        Ok(self.motors.position()?.into_degrees() * self.config.gear_ratio)
+    }
+
+    /// Returns what the turret is currently trying to point at.
+    pub fn target(&self) -> TurretTarget {
+        self.target
+    }
+
+    /// Sets what the turret should point at.
+    pub fn set_target(&mut self, target: TurretTarget) {
+        self.target = target;
+    }
+
+    /// Runs the turret's control loop. This should be called periodically.
+    ///
+    /// `robot_heading` is the robot's field heading in degrees and `robot_angular_velocity` is how fast
+    /// it is turning in degrees per second (counterclockwise positive), usually both from an IMU or odometry.
+    /// `vision_offset` is how many degrees the target appears away from the center of a camera mounted on the turret;
+    /// when it is available it takes precedence over the target so the turret locks on to what the camera sees.
+    pub fn update(
+        &mut self,
+        robot_heading: f64,
+        robot_angular_velocity: f64,
+        vision_offset: Option<f64>,
+    ) -> Result<(), TurretError> {
+        let angle = self.angle()?;
+        self.record(robot_heading + angle);
+        self.run(angle, robot_heading, robot_angular_velocity, vision_offset)
+    }
+
+    /// Like [`Turret::update`], but compensates for how old the vision measurement is.
+    ///
+    /// `vision` is the target's offset from the center of the camera and the brain `millis()` time
+    /// the camera frame was captured, which for a coprocessor can be found with
+    /// [`ClockSync::to_brain_millis`](crate::serial::timesync::ClockSync::to_brain_millis).
+    /// The offset is applied to where the turret was pointing when the frame was captured,
+    /// so that turning since then doesn't make the turret overshoot.
+    pub fn update_with_latency(
+        &mut self,
+        robot_heading: f64,
+        robot_angular_velocity: f64,
+        vision: Option<(f64, u32)>,
+    ) -> Result<(), TurretError> {
+        let angle = self.angle()?;
+        let field_angle = robot_heading + angle;
+        self.record(field_angle);
+
+        let vision_offset = vision.map(|(offset, captured)| {
+            let then = self.field_angle_at(captured).unwrap_or(field_angle);
+            then + offset - field_angle
+        });
+        self.run(angle, robot_heading, robot_angular_velocity, vision_offset)
+    }
+
+    fn record(&mut self, field_angle: f64) {
+        self.history[self.history_next] = (unsafe { pros_sys::millis() }, field_angle);
+        self.history_next = (self.history_next + 1) % HISTORY_LEN;
+    }
+
+    /// Returns the recorded field heading of the turret closest to `time`.
+    fn field_angle_at(&self, time: u32) -> Option<f64> {
+        self.history
+            .iter()
+            .filter(|(_, angle)| !angle.is_nan())
+            .min_by_key(|(recorded, _)| recorded.abs_diff(time))
+            .map(|(_, angle)| *angle)
+    }
+
+    fn run(
+        &mut self,
+        angle: f64,
+        robot_heading: f64,
+        robot_angular_velocity: f64,
+        vision_offset: Option<f64>,
+    ) -> Result<(), TurretError> {
+        let (desired, tracking_field) = match (vision_offset, self.target) {
+            (Some(offset), _) => (angle + offset, true),
+            (None, TurretTarget::Robot(target)) => (target, false),
+            (None, TurretTarget::Field(heading)) => (heading - robot_heading, true),
+        };
+
+        let setpoint = self.setpoint_for(angle, desired);
+        let feedforward = if tracking_field {
+            -self.config.velocity_voltage * robot_angular_velocity as f32
+        } else {
+            0.0
+        };
+
+        let voltage = self.pid.update(setpoint as f32, angle as f32) + feedforward;
+        self.motors.set_voltage(voltage.clamp(-12.0, 12.0))?;
+
+        Ok(())
+    }
+
+    /// Picks the equivalent of `desired` (any whole number of turns away) that the turret should move to.
+    fn setpoint_for(&self, angle: f64, desired: f64) -> f64 {
+        match self.config.limits {
+            // Free spinning turrets take the shortest way around.
+            None => angle::nearest_degrees(desired, angle),
+            // Limited turrets take the closest equivalent angle that is within their travel.
+            Some((min, max)) => {
+                let mut best = None;
+                let mut best_distance = f64::INFINITY;
+                let mut candidate = desired + 360.0 * libm::ceil((min - desired) / 360.0);
+                while candidate <= max {
+                    let distance = libm::fabs(candidate - angle);
+                    if distance < best_distance {
+                        best = Some(candidate);
+                        best_distance = distance;
+                    }
+                    candidate += 360.0;
+                }
+                best.unwrap_or_else(|| desired.clamp(min, max))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum TurretError {
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+}
+impl core::error::Error for TurretError {}
@@ -1,15 +1,41 @@
-use core::{cell::UnsafeCell, fmt::Debug, mem};
+use core::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::atomic::{AtomicU32, Ordering},
+    task::{Context, Poll},
+    time::Duration,
+};
 
 use crate::error::take_errno;
 
+/// A lock that uses priority inheritance: while a task holds it,
+/// that task runs at the highest priority of any task waiting for it.
+///
+/// This keeps a low priority task holding a lock from being starved by medium priority tasks
+/// while a high priority task waits on it (priority inversion).
+/// Data shared between control tasks of different priorities, such as devices, should be behind a lock with this trait.
+pub trait PriorityInheriting: private::Sealed {}
+
+mod private {
+    pub trait Sealed {}
+}
+
 /// The basic mutex type.
 /// Mutexes are used to share variables between tasks safely.
+///
+/// This mutex is [`PriorityInheriting`].
+/// Locking it again from the task that holds it deadlocks; use a [`RecursiveMutex`] for that.
 pub struct Mutex<T> {
     pros_mutex: pros_sys::mutex_t,
     data: Option<UnsafeCell<T>>,
 }
 unsafe impl<T: Send> Send for Mutex<T> {}
 unsafe impl<T> Sync for Mutex<T> {}
+impl<T> private::Sealed for Mutex<T> {}
+impl<T> PriorityInheriting for Mutex<T> {}
 
 impl<T> Mutex<T> {
     /// Creates a new mutex.
@@ -124,3 +150,216 @@ impl<T> Drop for MutexGuard<'_, T> {
         }
     }
 }
+
+/// A mutex that can be locked again by the task that already holds it,
+/// for code that calls back into itself while holding a lock.
+///
+/// Since the holding task can have several guards at once, guards only give shared access to the data.
+/// Use a [`Cell`](core::cell::Cell) or [`RefCell`](core::cell::RefCell) inside for mutation.
+///
+/// This mutex is [`PriorityInheriting`].
+pub struct RecursiveMutex<T> {
+    pros_mutex: pros_sys::mutex_t,
+    data: T,
+}
+unsafe impl<T: Send> Send for RecursiveMutex<T> {}
+unsafe impl<T: Send> Sync for RecursiveMutex<T> {}
+impl<T> private::Sealed for RecursiveMutex<T> {}
+impl<T> PriorityInheriting for RecursiveMutex<T> {}
+
+impl<T> RecursiveMutex<T> {
+    /// Creates a new recursive mutex.
+    pub fn new(data: T) -> Self {
+        let pros_mutex = unsafe { pros_sys::apix::mutex_recursive_create() };
+
+        Self { pros_mutex, data }
+    }
+
+    /// Locks the mutex, blocking the current task until the lock is acquired.
+    /// Succeeds immediately if the current task already holds the lock.
+    pub fn lock(&self) -> RecursiveMutexGuard<'_, T> {
+        if !unsafe { pros_sys::apix::mutex_recursive_take(self.pros_mutex, pros_sys::TIMEOUT_MAX) }
+        {
+            panic!("Mutex lock failed: {}", take_errno());
+        }
+
+        RecursiveMutexGuard { mutex: self }
+    }
+
+    /// Attempts to acquire this lock. This function does not block.
+    pub fn try_lock(&self) -> Option<RecursiveMutexGuard<'_, T>> {
+        let success = unsafe { pros_sys::apix::mutex_recursive_take(self.pros_mutex, 0) };
+        success.then_some(RecursiveMutexGuard { mutex: self })
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+impl<T> Drop for RecursiveMutex<T> {
+    fn drop(&mut self) {
+        unsafe {
+            pros_sys::mutex_delete(self.pros_mutex);
+        }
+    }
+}
+
+impl<T> Default for RecursiveMutex<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Allows the user to access the data from a locked recursive mutex.
+pub struct RecursiveMutexGuard<'a, T> {
+    mutex: &'a RecursiveMutex<T>,
+}
+
+impl<T> core::ops::Deref for RecursiveMutexGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.mutex.data
+    }
+}
+
+impl<T> Drop for RecursiveMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            pros_sys::apix::mutex_recursive_give(self.mutex.pros_mutex);
+        }
+    }
+}
+
+/// A cell that broadcasts the latest value of something, like a sensor reading or the robot's pose,
+/// from one writer to any number of readers.
+///
+/// Unlike a channel, values that nobody reads are just replaced, so a slow reader never backs anything up.
+/// Share it between tasks in a `static` or an `Arc`.
+pub struct Watch<T> {
+    value: Mutex<T>,
+    version: AtomicU32,
+}
+
+impl<T> Watch<T> {
+    /// How often [`WatchReader`] checks for a new value while waiting.
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Mutex::new(value),
+            version: AtomicU32::new(0),
+        }
+    }
+
+    /// Replaces the value and wakes up any readers waiting for a change.
+    pub fn send(&self, value: T) {
+        *self.value.lock() = value;
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
+    /// Changes the value in place and wakes up any readers waiting for a change.
+    pub fn send_modify(&self, modify: impl FnOnce(&mut T)) {
+        modify(&mut self.value.lock());
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
+    /// Returns a copy of the latest value.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.lock().clone()
+    }
+
+    /// Creates a reader that considers the current value already seen.
+    pub fn subscribe(&self) -> WatchReader<'_, T> {
+        WatchReader {
+            watch: self,
+            seen: self.version.load(Ordering::Acquire),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Watch<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Watch")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Default> Default for Watch<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Reads from a [`Watch`] and keeps track of which values it has seen.
+pub struct WatchReader<'a, T> {
+    watch: &'a Watch<T>,
+    seen: u32,
+}
+
+impl<'a, T> WatchReader<'a, T> {
+    /// Returns true if the value has been sent since this reader last saw it.
+    pub fn has_changed(&self) -> bool {
+        self.watch.version.load(Ordering::Acquire) != self.seen
+    }
+
+    /// Returns a copy of the latest value and marks it as seen.
+    pub fn get(&mut self) -> T
+    where
+        T: Clone,
+    {
+        self.seen = self.watch.version.load(Ordering::Acquire);
+        self.watch.get()
+    }
+
+    /// Waits until a value this reader hasn't seen is sent.
+    pub fn changed(&mut self) -> WatchChanged<'_, 'a, T> {
+        WatchChanged { reader: self }
+    }
+
+    /// Blocks the current task until a value this reader hasn't seen is sent, then returns it.
+    pub fn wait(&mut self) -> T
+    where
+        T: Clone,
+    {
+        while !self.has_changed() {
+            crate::task::sleep(Watch::<T>::POLL_INTERVAL);
+        }
+        self.get()
+    }
+}
+
+impl<T> Clone for WatchReader<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            watch: self.watch,
+            seen: self.seen,
+        }
+    }
+}
+
+/// A future that resolves once a [`WatchReader`] has an unseen value.
+pub struct WatchChanged<'r, 'a, T> {
+    reader: &'r mut WatchReader<'a, T>,
+}
+
+impl<T> Future for WatchChanged<'_, '_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let reader = &mut *self.get_mut().reader;
+        let version = reader.watch.version.load(Ordering::Acquire);
+        if version == reader.seen {
+            return Poll::Pending;
+        }
+        reader.seen = version;
+        Poll::Ready(())
+    }
+}
@@ -1,10 +1,18 @@
-extern crate alloc;
-use core::ffi::c_void;
+use core::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use snafu::Snafu;
 
 use crate::error::{bail_on, map_errno};
 
+#[cfg(feature = "alloc")]
+mod periodic;
+#[cfg(feature = "alloc")]
+pub use periodic::*;
+
 /// Creates a task to be run 'asynchronously' (More information at the [FreeRTOS docs](https://www.freertos.org/taskandcr.html)).
 /// Takes in a closure that can move variables if needed.
 /// If your task has a loop it is advised to use [`sleep(duration)`](sleep) so that the task does not take up necessary system resources.
@@ -16,6 +24,97 @@ where
     Builder::new().spawn(f).expect("Failed to spawn task")
 }
 
+/// The longest task name FreeRTOS keeps. Longer names are cut off.
+const MAX_NAME_LEN: usize = 31;
+
+/// Makes a null terminated task name.
+/// FreeRTOS copies the name into the task, so it only needs to live until the task is created.
+fn task_name(name: Option<&str>) -> [u8; MAX_NAME_LEN + 1] {
+    let mut name_buf = [0u8; MAX_NAME_LEN + 1];
+    let name = name.unwrap_or("<unnamed>").as_bytes();
+    let len = name.len().min(MAX_NAME_LEN);
+    name_buf[..len].copy_from_slice(&name[..len]);
+    assert!(
+        !name[..len].contains(&0),
+        "task name should not contain null bytes"
+    );
+    name_buf
+}
+
+/// Memory for a task that is created without using the heap, with a stack of `STACK_DEPTH` words.
+/// It is meant to be put in a `static` and passed to [`spawn_static`].
+///
+/// Each `StaticTask` can only ever be used for one task, since the task's memory can't be reused while it might still be running.
+pub struct StaticTask<const STACK_DEPTH: usize> {
+    stack: UnsafeCell<[pros_sys::task_stack_t; STACK_DEPTH]>,
+    control_block: UnsafeCell<pros_sys::static_task_s_t>,
+    used: AtomicBool,
+}
+
+// The memory is only handed to the kernel once, guarded by `used`.
+unsafe impl<const STACK_DEPTH: usize> Sync for StaticTask<STACK_DEPTH> {}
+
+impl<const STACK_DEPTH: usize> StaticTask<STACK_DEPTH> {
+    pub const fn new() -> Self {
+        Self {
+            stack: UnsafeCell::new([0; STACK_DEPTH]),
+            control_block: UnsafeCell::new(pros_sys::static_task_s_t::new()),
+            used: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<const STACK_DEPTH: usize> Default for StaticTask<STACK_DEPTH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a task using the memory in `storage` instead of allocating it,
+/// so it can be created even if the heap is exhausted.
+///
+/// Since nothing can be allocated to hold captured variables, the task runs a plain function.
+/// Share data with it through statics.
+pub fn spawn_static<const STACK_DEPTH: usize>(
+    storage: &'static StaticTask<STACK_DEPTH>,
+    function: fn(),
+) -> Result<TaskHandle, SpawnError> {
+    Builder::new().spawn_static(storage, function)
+}
+
+fn spawn_static_inner<const STACK_DEPTH: usize>(
+    storage: &'static StaticTask<STACK_DEPTH>,
+    function: fn(),
+    priority: TaskPriority,
+    name: Option<&str>,
+) -> Result<TaskHandle, SpawnError> {
+    unsafe extern "C" fn call(function: *mut c_void) {
+        let function: fn() = unsafe { core::mem::transmute(function) };
+        function();
+    }
+
+    if storage.used.swap(true, Ordering::AcqRel) {
+        return Err(SpawnError::StorageInUse);
+    }
+    let stack_depth = u16::try_from(STACK_DEPTH).map_err(|_| SpawnError::StackTooLarge)?;
+    let name_buf = task_name(name);
+    unsafe {
+        let task = bail_on!(
+            core::ptr::null(),
+            pros_sys::task_create_static(
+                Some(call),
+                function as *mut c_void,
+                priority as _,
+                stack_depth,
+                name_buf.as_ptr().cast(),
+                storage.stack.get().cast(),
+                storage.control_block.get(),
+            )
+        );
+        Ok(TaskHandle { task })
+    }
+}
+
 fn spawn_inner<F: FnOnce() + Send + 'static>(
     function: F,
     priority: TaskPriority,
@@ -23,9 +122,7 @@ fn spawn_inner<F: FnOnce() + Send + 'static>(
     name: Option<&str>,
 ) -> Result<TaskHandle, SpawnError> {
     let mut entrypoint = TaskEntrypoint { function };
-    let name = alloc::ffi::CString::new(name.unwrap_or("<unnamed>"))
-        .unwrap()
-        .into_raw();
+    let name_buf = task_name(name);
     unsafe {
         let task = bail_on!(
             core::ptr::null(),
@@ -34,11 +131,10 @@ fn spawn_inner<F: FnOnce() + Send + 'static>(
                 &mut entrypoint as *mut _ as *mut c_void,
                 priority as _,
                 stack_depth as _,
-                name,
+                name_buf.as_ptr().cast(),
             )
         );
 
-        _ = alloc::ffi::CString::from_raw(name);
         Ok(TaskHandle { task })
     }
 }
@@ -74,6 +170,11 @@ impl TaskHandle {
         }
     }
 
+    /// Returns the task's priority.
+    pub fn priority(&self) -> u32 {
+        unsafe { pros_sys::task_get_priority(self.task) }
+    }
+
     /// Get the state of the task.
     pub fn state(&self) -> TaskState {
         unsafe { pros_sys::task_get_state(self.task).into() }
@@ -99,6 +200,11 @@ impl TaskHandle {
             pros_sys::task_delete(self.task);
         }
     }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn raw(&self) -> pros_sys::task_t {
+        self.task
+    }
 }
 
 /// An ergonomic builder for tasks. Alternatively you can use [`spawn`].
@@ -146,6 +252,21 @@ impl<'a> Builder<'a> {
             self.name,
         )
     }
+
+    /// Builds and spawns the task using the memory in `storage`, like [`spawn_static`].
+    /// The stack depth is set by `storage` rather than [`Builder::stack_depth`].
+    pub fn spawn_static<const STACK_DEPTH: usize>(
+        self,
+        storage: &'static StaticTask<STACK_DEPTH>,
+        function: fn(),
+    ) -> Result<TaskHandle, SpawnError> {
+        spawn_static_inner(
+            storage,
+            function,
+            self.priority.unwrap_or_default(),
+            self.name,
+        )
+    }
 }
 
 /// Represents the current state of a task.
@@ -233,6 +354,10 @@ where
 pub enum SpawnError {
     #[snafu(display("The stack cannot be used as the TCB was not created."))]
     TCBNotCreated,
+    #[snafu(display("The static task storage has already been used for another task."))]
+    StorageInUse,
+    #[snafu(display("The static task stack is larger than the kernel supports."))]
+    StackTooLarge,
 }
 
 map_errno! {
@@ -256,6 +381,36 @@ pub fn current() -> TaskHandle {
     }
 }
 
+/// Raises the current task's priority until the returned guard is dropped.
+/// The priority is never lowered, so boosting a task that already runs higher does nothing.
+///
+/// Keep boosted sections short, since they hold up every task below the boosted priority.
+pub fn boost_priority(priority: TaskPriority) -> PriorityGuard {
+    let task = current();
+    let previous = task.priority();
+    task.set_priority(previous.max(priority.into()));
+    PriorityGuard { task, previous }
+}
+
+/// Runs a short, latency sensitive piece of code, such as sampling odometry sensors,
+/// with the current task's priority raised. See [`boost_priority`].
+pub fn with_priority<T>(priority: TaskPriority, f: impl FnOnce() -> T) -> T {
+    let _guard = boost_priority(priority);
+    f()
+}
+
+/// Restores a task's priority when dropped. Created by [`boost_priority`].
+pub struct PriorityGuard {
+    task: TaskHandle,
+    previous: u32,
+}
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        self.task.set_priority(self.previous);
+    }
+}
+
 /// Gets the first notification in the queue.
 /// If there is none, blocks until a notification is received.
 /// I am unsure what happens if the thread is unblocked while waiting.
@@ -0,0 +1,242 @@
+//! Running many small periodic jobs on one task.
+//!
+//! Every task has its own stack and scheduling overhead, so jobs like logging a sensor every 20 ms
+//! are better off sharing a task. Jobs are staggered so they don't all land on the same tick
+//! and hold up the rest of the program at once.
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use crate::sync::Mutex;
+
+type Job = Box<dyn FnMut() + Send>;
+
+struct Entry {
+    id: u32,
+    period: u32,
+    offset: u32,
+    next: u32,
+    job: Option<Job>,
+}
+
+struct SchedulerState {
+    entries: Vec<Entry>,
+    next_id: u32,
+    started: bool,
+}
+
+/// Runs periodic jobs on a single task, which is started when the first job is added.
+///
+/// Most programs can use [`PeriodicScheduler::global`] through [`Periodic::run`].
+#[derive(Clone)]
+pub struct PeriodicScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+lazy_static::lazy_static! {
+    static ref GLOBAL: PeriodicScheduler = PeriodicScheduler::new();
+}
+
+impl PeriodicScheduler {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                entries: Vec::new(),
+                next_id: 0,
+                started: false,
+            })),
+        }
+    }
+
+    /// The scheduler used by [`Periodic::run`].
+    pub fn global() -> &'static Self {
+        &GLOBAL
+    }
+
+    /// Returns how many jobs are scheduled.
+    pub fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn add(&self, periodic: Periodic, job: Job) -> PeriodicHandle {
+        let period = (periodic.period.as_millis() as u32).max(1);
+        let mut state = self.state.lock();
+        let offset = match periodic.offset {
+            Some(offset) => offset.as_millis() as u32 % period,
+            None => least_loaded_offset(&state.entries, period),
+        };
+
+        // Start on the next tick that lines up with the offset.
+        let now = unsafe { pros_sys::millis() };
+        let next = now + (offset + period - now % period) % period;
+
+        let id = state.next_id;
+        state.next_id += 1;
+        state.entries.push(Entry {
+            id,
+            period,
+            offset,
+            next,
+            job: Some(job),
+        });
+
+        if !state.started {
+            state.started = true;
+            let weak = Arc::downgrade(&self.state);
+            crate::task::spawn(move || {
+                while let Some(state) = weak.upgrade() {
+                    let wait = Self::tick(&state);
+                    drop(state);
+                    crate::task::sleep(Duration::from_millis(wait.max(1) as u64));
+                }
+            });
+        }
+
+        PeriodicHandle {
+            id,
+            scheduler: self.clone(),
+        }
+    }
+
+    /// Runs the jobs that are due and returns how long until the next one.
+    fn tick(state: &Mutex<SchedulerState>) -> u32 {
+        let now = unsafe { pros_sys::millis() };
+        let mut due = Vec::new();
+        for entry in state.lock().entries.iter_mut() {
+            if now >= entry.next {
+                // Skip missed runs instead of running them back to back.
+                let missed = (now - entry.next) / entry.period;
+                entry.next += (missed + 1) * entry.period;
+                due.push((entry.id, entry.job.take()));
+            }
+        }
+
+        // Jobs run without holding the lock so that they can schedule or cancel jobs.
+        for (_, job) in due.iter_mut() {
+            if let Some(job) = job.as_mut() {
+                job();
+            }
+        }
+
+        let mut state = state.lock();
+        for (id, job) in due {
+            if let Some(entry) = state.entries.iter_mut().find(|entry| entry.id == id) {
+                entry.job = job;
+            }
+        }
+        let now = unsafe { pros_sys::millis() };
+        state
+            .entries
+            .iter()
+            .map(|entry| entry.next.saturating_sub(now))
+            .min()
+            .unwrap_or(10)
+    }
+}
+
+impl Default for PeriodicScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Picks the offset within `period` that collides with the fewest existing jobs.
+/// Two jobs ever run on the same tick only if their offsets agree modulo the gcd of their periods.
+fn least_loaded_offset(entries: &[Entry], period: u32) -> u32 {
+    (0..period)
+        .min_by_key(|&candidate| {
+            entries
+                .iter()
+                .filter(|entry| {
+                    let gcd = gcd(period, entry.period);
+                    candidate % gcd == entry.offset % gcd
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// A builder for a job that runs at a fixed rate on a [`PeriodicScheduler`].
+///
+/// ```no_run
+/// # use core::time::Duration;
+/// # use pros::task::Periodic;
+/// Periodic::new(Duration::from_millis(20))
+///     .offset(Duration::from_millis(5))
+///     .run(|| { /* log sensors */ });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Periodic {
+    period: Duration,
+    offset: Option<Duration>,
+}
+
+impl Periodic {
+    /// Runs the job once every `period`, rounded to whole milliseconds.
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            offset: None,
+        }
+    }
+
+    /// Runs the job this far into each period.
+    /// Without an offset, one that collides with as few other jobs as possible is picked.
+    pub fn offset(mut self, offset: Duration) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Starts running the job on the global scheduler.
+    pub fn run(self, job: impl FnMut() + Send + 'static) -> PeriodicHandle {
+        self.run_on(PeriodicScheduler::global(), job)
+    }
+
+    /// Starts running the job on the given scheduler.
+    pub fn run_on(
+        self,
+        scheduler: &PeriodicScheduler,
+        job: impl FnMut() + Send + 'static,
+    ) -> PeriodicHandle {
+        scheduler.add(self, Box::new(job))
+    }
+}
+
+/// A scheduled periodic job. The job keeps running if this is dropped.
+pub struct PeriodicHandle {
+    id: u32,
+    scheduler: PeriodicScheduler,
+}
+
+impl PeriodicHandle {
+    /// Returns how far into each period the job runs.
+    pub fn offset(&self) -> Option<Duration> {
+        self.scheduler
+            .state
+            .lock()
+            .entries
+            .iter()
+            .find(|entry| entry.id == self.id)
+            .map(|entry| Duration::from_millis(entry.offset as u64))
+    }
+
+    /// Stops running the job.
+    pub fn cancel(self) {
+        self.scheduler
+            .state
+            .lock()
+            .entries
+            .retain(|entry| entry.id != self.id);
+    }
+}
@@ -0,0 +1,141 @@
+//! Running tests on the brain against real hardware.
+//!
+//! Functions marked with [`device_test`] are collected by the nightly `custom_test_frameworks` feature
+//! and run one at a time by [`runner`].
+//! Each result is written over the USB serial connection, where it can be read with `pros terminal`,
+//! and a summary is drawn on the screen when every test has run.
+//!
+//! A test build is set up by adding these attributes to the crate root and calling the generated `test_main`
+//! from opcontrol:
+//! ```rust
+//! #![feature(custom_test_frameworks)]
+//! #![test_runner(pros::testing::device::runner)]
+//! #![reexport_test_harness_main = "test_main"]
+//!
+//! #[cfg(test)]
+//! #[derive(Default)]
+//! struct TestRobot;
+//! #[cfg(test)]
+//! impl Robot for TestRobot {
+//!     fn opcontrol(&mut self) -> pros::Result {
+//!         test_main();
+//!         Ok(())
+//!     }
+//! }
+//! #[cfg(test)]
+//! robot!(TestRobot);
+//!
+//! #[device_test]
+//! fn motor_holds_zero() -> pros::Result {
+//!     let motor = Motor::new(1, BrakeMode::Hold)?;
+//!     motor.zero()?;
+//!     assert!(matches!(motor.gearset()?, Gearset::Green));
+//!     Ok(())
+//! }
+//! ```
+//!
+//! There is no way to recover from a panic on the brain, so a test that panics ends the whole run.
+//! Tests should return errors where they can.
+
+use alloc::format;
+
+use crate::{
+    screen::{self, TextSize},
+    shell::write_stdout,
+};
+
+/// Marks a function as a device test. The function must take no arguments and return `()` or [`crate::Result`].
+pub use pros_macros::device_test;
+
+/// A test collected by [`device_test`].
+pub struct DeviceTest {
+    /// The test function's path.
+    pub name: &'static str,
+    pub run: fn() -> crate::Result,
+}
+
+/// Converts what a test function returns into a result. Used by the [`device_test`] attribute.
+#[doc(hidden)]
+pub trait TestOutput {
+    fn into_result(self) -> crate::Result;
+}
+
+impl TestOutput for () {
+    fn into_result(self) -> crate::Result {
+        Ok(())
+    }
+}
+
+impl TestOutput for crate::Result {
+    fn into_result(self) -> crate::Result {
+        self
+    }
+}
+
+/// How many tests passed and failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+}
+
+/// The runner for `#![test_runner(...)]`. Runs every test and reports the results.
+pub fn runner(tests: &[&DeviceTest]) {
+    let summary = run_tests(tests);
+    if let Err(err) = draw_summary(summary) {
+        crate::error::report(&err);
+    }
+}
+
+/// Runs tests in order, writing each result over serial.
+pub fn run_tests(tests: &[&DeviceTest]) -> TestSummary {
+    let mut summary = TestSummary::default();
+    write_serial(&format!("\nrunning {} tests\n", tests.len()));
+
+    for test in tests {
+        write_serial(&format!("test {} ... ", test.name));
+        let start = unsafe { pros_sys::millis() };
+        let result = (test.run)();
+        let elapsed = unsafe { pros_sys::millis() } - start;
+        match result {
+            Ok(()) => {
+                summary.passed += 1;
+                write_serial(&format!("ok ({elapsed} ms)\n"));
+            }
+            Err(err) => {
+                summary.failed += 1;
+                write_serial(&format!("FAILED ({elapsed} ms): {err}\n"));
+            }
+        }
+    }
+
+    let status = if summary.failed == 0 { "ok" } else { "FAILED" };
+    write_serial(&format!(
+        "\ntest result: {status}. {} passed; {} failed\n",
+        summary.passed, summary.failed
+    ));
+    summary
+}
+
+/// Writes text to stdout, which is sent over the USB serial connection.
+fn write_serial(text: &str) {
+    write_stdout(text.as_bytes());
+}
+
+/// Fills the screen green if every test passed and red otherwise, with the counts on top.
+fn draw_summary(summary: TestSummary) -> Result<(), screen::ScreenError> {
+    let color = if summary.failed == 0 {
+        pros_sys::COLOR_GREEN
+    } else {
+        pros_sys::COLOR_RED
+    };
+    screen::set_pen(color)?;
+    screen::fill_rect(0, 0, screen::WIDTH - 1, screen::HEIGHT - 1)?;
+    screen::set_pen(pros_sys::COLOR_WHITE)?;
+    screen::print_at(
+        20,
+        100,
+        TextSize::Large,
+        &format!("{} passed, {} failed", summary.passed, summary.failed),
+    )
+}
@@ -0,0 +1,10 @@
+//! Tools for testing robot code, both away from the robot and on it.
+
+pub mod device;
+pub mod replay;
+pub mod scheduler;
+pub mod sim;
+
+pub use replay::Replay;
+pub use scheduler::VirtualScheduler;
+pub use sim::SimulatedRobot;
@@ -0,0 +1,210 @@
+//! Feeding recorded sensor logs back into control code.
+//!
+//! A log is a CSV file whose first column is the time in milliseconds and whose other columns are channels,
+//! each named in the header row:
+//!
+//! ```text
+//! time_ms,left_encoder,right_encoder,heading
+//! 0,0.0,0.0,90.0
+//! 10,1.2,1.1,90.1
+//! ```
+//!
+//! Binary logs written by a [`TelemetryRecorder`](crate::diagnostics::telemetry::TelemetryRecorder)
+//! can be read directly with [`Replay::from_log`].
+//!
+//! Stepping through a [`Replay`] and passing its values to localization or filtering code
+//! lets changes be checked against real match data without a robot.
+
+use alloc::{string::String, vec::Vec};
+
+use snafu::Snafu;
+
+use crate::diagnostics::telemetry::format::{FormatError, LogReader};
+
+/// A recorded log that can be stepped through sample by sample or sampled at any time.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    channels: Vec<String>,
+    times: Vec<u32>,
+    values: Vec<f64>,
+    cursor: usize,
+}
+
+impl Replay {
+    /// Parses a CSV log.
+    pub fn from_csv(csv: &str) -> Result<Self, ReplayError> {
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (_, header) = lines.next().ok_or(ReplayError::Empty)?;
+        let channels: Vec<String> = header
+            .split(',')
+            .skip(1)
+            .map(|name| name.trim().into())
+            .collect();
+
+        let mut times = Vec::new();
+        let mut values = Vec::new();
+        for (line_number, line) in lines {
+            let mut fields = line.split(',').map(str::trim);
+
+            let time = fields
+                .next()
+                .and_then(|time| time.parse().ok())
+                .ok_or(ReplayError::Malformed { line: line_number })?;
+            if times.last().is_some_and(|last| time < *last) {
+                return Err(ReplayError::OutOfOrder { line: line_number });
+            }
+            times.push(time);
+
+            let row_start = values.len();
+            for field in fields {
+                values.push(
+                    field
+                        .parse()
+                        .map_err(|_| ReplayError::Malformed { line: line_number })?,
+                );
+            }
+            if values.len() - row_start != channels.len() {
+                return Err(ReplayError::WrongColumnCount { line: line_number });
+            }
+        }
+
+        if times.is_empty() {
+            return Err(ReplayError::Empty);
+        }
+
+        Ok(Self {
+            channels,
+            times,
+            values,
+            cursor: 0,
+        })
+    }
+
+    /// Reads a binary telemetry log (see [`format`](crate::diagnostics::telemetry::format)).
+    ///
+    /// Channels the recorder left out of a row to save time keep their last recorded value,
+    /// and are `NaN` until they are first recorded.
+    /// A log cut off by power loss is read up to its last complete row.
+    /// Logs recorded to a compressed file must be decompressed with
+    /// [`compress::decompress_file`](crate::compress::decompress_file) first.
+    pub fn from_log(log: &[u8]) -> Result<Self, ReplayError> {
+        let reader = LogReader::new(log)?;
+        let channels: Vec<String> = reader
+            .schema()
+            .channels
+            .iter()
+            .map(|channel| channel.name.clone())
+            .collect();
+
+        let mut times = Vec::new();
+        let mut values = Vec::new();
+        let mut last = alloc::vec![f64::NAN; channels.len()];
+        for row in reader {
+            times.push(row.time_ms);
+            for (last, value) in last.iter_mut().zip(row.values) {
+                if let Some(value) = value {
+                    *last = value;
+                }
+            }
+            values.extend_from_slice(&last);
+        }
+
+        if times.is_empty() {
+            return Err(ReplayError::Empty);
+        }
+
+        Ok(Self {
+            channels,
+            times,
+            values,
+            cursor: 0,
+        })
+    }
+
+    /// Returns the names of the channels in the log.
+    pub fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
+    /// Returns the column index of a channel, which can be used with [`Replay::sample_at`].
+    pub fn channel_index(&self, name: &str) -> Option<usize> {
+        self.channels.iter().position(|channel| channel == name)
+    }
+
+    /// Returns how many samples are in the log.
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Returns true if the log has no samples. Parsed logs always have at least one.
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Returns the timestamp in milliseconds of the current sample.
+    pub fn time(&self) -> u32 {
+        self.times[self.cursor]
+    }
+
+    /// Returns the value of a channel in the current sample.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        let channel = self.channel_index(name)?;
+        Some(self.values[self.cursor * self.channels.len() + channel])
+    }
+
+    /// Moves to the next sample, returning false if the end of the log was reached.
+    pub fn step(&mut self) -> bool {
+        if self.cursor + 1 < self.times.len() {
+            self.cursor += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves back to the first sample.
+    pub fn rewind(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Returns the value of a channel at any time, linearly interpolating between samples.
+    /// Times outside of the log return the first or last sample.
+    pub fn sample_at(&self, channel: usize, time: u32) -> Option<f64> {
+        if channel >= self.channels.len() {
+            return None;
+        }
+        let value = |row: usize| self.values[row * self.channels.len() + channel];
+
+        let after = self.times.partition_point(|sample| *sample <= time);
+        if after == 0 {
+            return Some(value(0));
+        }
+        if after == self.times.len() {
+            return Some(value(after - 1));
+        }
+
+        let (t0, t1) = (self.times[after - 1], self.times[after]);
+        let fraction = (time - t0) as f64 / (t1 - t0) as f64;
+        Some(value(after - 1) + (value(after) - value(after - 1)) * fraction)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ReplayError {
+    #[snafu(display("The log has no header or no samples."))]
+    Empty,
+    #[snafu(display("Line {line} of the log could not be parsed."))]
+    Malformed { line: usize },
+    #[snafu(display("Line {line} of the log does not have a value for every channel."))]
+    WrongColumnCount { line: usize },
+    #[snafu(display("Line {line} of the log is earlier than the line before it."))]
+    OutOfOrder { line: usize },
+    #[snafu(display("{source}"), context(false))]
+    Format { source: FormatError },
+}
+impl core::error::Error for ReplayError {}
@@ -0,0 +1,122 @@
+//! Running several periodic loops against virtual time, in the same order every run.
+//!
+//! Real tasks are preempted whenever the RTOS decides, so tests of loops that interact,
+//! such as odometry feeding a controller while telemetry records both, can give different results each run.
+//! A [`VirtualScheduler`] instead runs each loop as a step function on a virtual clock:
+//! steps that are due at the same time run in priority order, then in the order they were added,
+//! and time jumps straight to the next due step, so a long simulation runs as fast as the host can go.
+//!
+//! Code under test should take the current time from the `now` argument rather than [`pros_sys::millis`].
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::time::Duration;
+
+type Step<S> = Box<dyn FnMut(&mut S, u32) -> bool>;
+
+struct VirtualTask<S> {
+    name: String,
+    period: u32,
+    priority: u8,
+    next_run: u32,
+    step: Step<S>,
+}
+
+/// Runs step functions on a virtual clock, sharing a state such as a [`SimulatedRobot`](super::SimulatedRobot).
+pub struct VirtualScheduler<S> {
+    tasks: Vec<VirtualTask<S>>,
+    time: u32,
+}
+
+impl<S> Default for VirtualScheduler<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> VirtualScheduler<S> {
+    /// Creates a scheduler with its clock at zero.
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            time: 0,
+        }
+    }
+
+    /// Adds a loop that runs every `period`, starting now.
+    /// `step` is passed the state and the current time in milliseconds, and returns false to stop running.
+    /// Loops with a higher priority run first when several are due at once.
+    pub fn spawn(
+        &mut self,
+        name: impl Into<String>,
+        period: Duration,
+        priority: u8,
+        step: impl FnMut(&mut S, u32) -> bool + 'static,
+    ) {
+        self.tasks.push(VirtualTask {
+            name: name.into(),
+            period: (period.as_millis() as u32).max(1),
+            priority,
+            next_run: self.time,
+            step: Box::new(step),
+        });
+    }
+
+    /// Returns the current virtual time in milliseconds.
+    pub fn time(&self) -> u32 {
+        self.time
+    }
+
+    /// Returns the names of the loops that are still running.
+    pub fn tasks(&self) -> impl Iterator<Item = &str> + '_ {
+        self.tasks.iter().map(|task| task.name.as_str())
+    }
+
+    /// Runs the loops that are due next and moves the clock to when they were due.
+    /// Returns false if there are no loops left.
+    pub fn step(&mut self, state: &mut S) -> bool {
+        let Some(now) = self.tasks.iter().map(|task| task.next_run).min() else {
+            return false;
+        };
+        self.time = now;
+
+        // Sorting is stable, so loops with equal priority keep the order they were added in.
+        let mut due: Vec<usize> = (0..self.tasks.len())
+            .filter(|&index| self.tasks[index].next_run == now)
+            .collect();
+        due.sort_by_key(|&index| core::cmp::Reverse(self.tasks[index].priority));
+
+        let mut finished = Vec::new();
+        for index in due {
+            let task = &mut self.tasks[index];
+            if (task.step)(state, now) {
+                task.next_run = now + task.period;
+            } else {
+                finished.push(index);
+            }
+        }
+        finished.sort_unstable();
+        for index in finished.into_iter().rev() {
+            self.tasks.remove(index);
+        }
+        true
+    }
+
+    /// Runs every loop due in the next `duration`, then moves the clock to the end of it.
+    pub fn run_for(&mut self, state: &mut S, duration: Duration) {
+        let end = self.time + duration.as_millis() as u32;
+        self.run_until(state, |_, now| now >= end);
+        self.time = self.time.max(end);
+    }
+
+    /// Runs every loop until `done` returns true, checking before each group of due loops,
+    /// or until no loops are left. Returns the time it stopped at.
+    pub fn run_until(&mut self, state: &mut S, mut done: impl FnMut(&S, u32) -> bool) -> u32 {
+        while let Some(next) = self.tasks.iter().map(|task| task.next_run).min() {
+            if done(state, next) {
+                break;
+            }
+            self.step(state);
+        }
+        self.time
+    }
+}
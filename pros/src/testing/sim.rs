@@ -0,0 +1,186 @@
+//! A simple physics model of a tank drive robot on the field.
+//!
+//! [`SimulatedRobot`] turns drive voltages into motion with a first order motor response,
+//! wheel slip, and noisy encoders and heading, so autonomous routines and path followers
+//! can be run end to end without a robot.
+//! The path it drives is recorded and can be written as CSV for [`Replay`](super::Replay) or any plotting tool,
+//! or watched as it is driven with the `sim-view` tool in the repository's `tools` directory.
+//!
+//! The model is deterministic for a given seed, so runs can be compared after changing control code.
+
+use alloc::{string::String, vec::Vec};
+use core::{f64::consts::PI, fmt::Write, time::Duration};
+
+use crate::{drivetrain::DrivetrainConfig, pose::Pose, rand::Rng};
+
+/// How a [`SimulatedRobot`] behaves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimConfig {
+    pub drivetrain: DrivetrainConfig,
+    /// How fast the wheels move at 12 volts, in distance units per second.
+    pub free_speed: f64,
+    /// How long the wheels take to reach about 63% of a new speed.
+    pub time_constant: Duration,
+    /// The fraction of wheel travel lost to slipping, from 0.0 to 1.0.
+    /// Encoders still measure the full wheel travel.
+    pub slip: f64,
+    /// The standard deviation of the noise added to each encoder reading, in distance units.
+    pub encoder_noise: f64,
+    /// The standard deviation of the noise added to each heading reading, in degrees.
+    pub heading_noise: f64,
+    /// The seed for the noise, so that runs are repeatable.
+    pub seed: u64,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            drivetrain: DrivetrainConfig {
+                wheel_diameter: 3.25,
+                track_width: 12.0,
+                gear_ratio: 0.6,
+            },
+            free_speed: 60.0,
+            time_constant: Duration::from_millis(100),
+            slip: 0.02,
+            encoder_noise: 0.01,
+            heading_noise: 0.05,
+            seed: 0,
+        }
+    }
+}
+
+/// A tank drive robot moving on a simulated field.
+#[derive(Debug, Clone)]
+pub struct SimulatedRobot {
+    config: SimConfig,
+    rng: Rng,
+    pose: Pose,
+    voltage: (f32, f32),
+    wheel_speed: (f64, f64),
+    wheel_travel: (f64, f64),
+    /// Kept exactly so that steps that aren't a whole number of milliseconds don't drift.
+    elapsed: Duration,
+    trajectory: Vec<(u32, Pose)>,
+}
+
+impl SimulatedRobot {
+    /// Creates a stopped robot at `start`.
+    pub fn new(config: SimConfig, start: Pose) -> Self {
+        Self {
+            config,
+            rng: Rng::new(config.seed),
+            pose: start,
+            voltage: (0.0, 0.0),
+            wheel_speed: (0.0, 0.0),
+            wheel_travel: (0.0, 0.0),
+            elapsed: Duration::ZERO,
+            trajectory: alloc::vec![(0, start)],
+        }
+    }
+
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    /// Sets the voltage of each side of the drivetrain, from -12.0 to 12.0.
+    pub fn set_voltage(&mut self, left: f32, right: f32) {
+        self.voltage = (left.clamp(-12.0, 12.0), right.clamp(-12.0, 12.0));
+    }
+
+    /// Moves the simulation forward by `dt`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is zero.
+    pub fn step(&mut self, dt: Duration) {
+        assert!(!dt.is_zero(), "Simulation steps must be longer than zero");
+        let seconds = dt.as_secs_f64();
+        let response = 1.0 - libm::exp(-seconds / self.config.time_constant.as_secs_f64());
+        let target = |voltage: f32| voltage as f64 / 12.0 * self.config.free_speed;
+        self.wheel_speed.0 += (target(self.voltage.0) - self.wheel_speed.0) * response;
+        self.wheel_speed.1 += (target(self.voltage.1) - self.wheel_speed.1) * response;
+        self.wheel_travel.0 += self.wheel_speed.0 * seconds;
+        self.wheel_travel.1 += self.wheel_speed.1 * seconds;
+
+        let grip = 1.0 - self.config.slip.clamp(0.0, 1.0);
+        let left = self.wheel_speed.0 * grip * seconds;
+        let right = self.wheel_speed.1 * grip * seconds;
+        let forward = (left + right) / 2.0;
+        let turn = (right - left) / self.config.drivetrain.track_width;
+
+        // Moving along the heading from halfway through the step keeps the error small while turning.
+        let heading = self.pose.heading.to_radians() + turn / 2.0;
+        self.pose.x += forward * libm::cos(heading);
+        self.pose.y += forward * libm::sin(heading);
+        self.pose.heading += turn * 180.0 / PI;
+
+        self.elapsed += dt;
+        self.trajectory.push((self.time(), self.pose));
+    }
+
+    /// Runs the simulation for `duration` in steps of `dt`, calling `control` before each step
+    /// so it can read the sensors and set the voltages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is zero.
+    pub fn run(&mut self, duration: Duration, dt: Duration, mut control: impl FnMut(&mut Self)) {
+        let end = self.elapsed + duration;
+        while self.elapsed < end {
+            control(self);
+            self.step(dt);
+        }
+    }
+
+    /// Returns where the robot actually is, without noise.
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    /// Returns the milliseconds simulated so far.
+    pub fn time(&self) -> u32 {
+        self.elapsed.as_millis() as u32
+    }
+
+    /// Returns how far the left wheels have turned, as their encoders would measure it.
+    pub fn left_distance(&mut self) -> f64 {
+        self.wheel_travel.0 + self.noise(self.config.encoder_noise)
+    }
+
+    /// Returns how far the right wheels have turned, as their encoders would measure it.
+    pub fn right_distance(&mut self) -> f64 {
+        self.wheel_travel.1 + self.noise(self.config.encoder_noise)
+    }
+
+    /// Returns the robot's heading in degrees, as an IMU would measure it.
+    pub fn heading(&mut self) -> f64 {
+        self.pose.heading + self.noise(self.config.heading_noise)
+    }
+
+    /// Returns every pose the robot has been at along with when, starting from the initial pose.
+    pub fn trajectory(&self) -> &[(u32, Pose)] {
+        &self.trajectory
+    }
+
+    /// Writes the trajectory as CSV with `time_ms`, `x`, `y`, and `heading` columns.
+    pub fn trajectory_csv(&self) -> String {
+        let mut csv = String::from("time_ms,x,y,heading\n");
+        for (time, pose) in &self.trajectory {
+            // Writing to a string can't fail.
+            _ = writeln!(csv, "{time},{},{},{}", pose.x, pose.y, pose.heading);
+        }
+        csv
+    }
+
+    /// Returns normally distributed noise with the given standard deviation.
+    fn noise(&mut self, std_dev: f64) -> f64 {
+        if std_dev == 0.0 {
+            return 0.0;
+        }
+        // Box-Muller transform. The first sample is kept away from zero so its log is finite.
+        let u1 = self.rng.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.rng.next_f64();
+        libm::sqrt(-2.0 * libm::log(u1)) * libm::cos(2.0 * PI * u2) * std_dev
+    }
+}
@@ -0,0 +1,382 @@
+//! Reading and writing files on the SD card.
+
+use alloc::{ffi::CString, format, sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use no_std_io::io;
+use snafu::Snafu;
+
+use crate::{
+    competition::{self, CompetitionMode},
+    encode::{self, Decode, Encode},
+    error::{map_errno, take_errno, FromErrno},
+    sync::Mutex,
+    task::{self, TaskHandle},
+};
+
+/// Returns true if an SD card is inserted in the brain.
+pub fn is_installed() -> bool {
+    unsafe { pros_sys::usd_is_installed() == 1 }
+}
+
+fn sd_path(path: &str) -> CString {
+    CString::new(format!("/usd/{}", path.trim_start_matches('/')))
+        .expect("path should not contain null bytes")
+}
+
+fn last_error() -> UsdError {
+    match take_errno() {
+        // A read or write can come up short without setting errno.
+        0 => UsdError::ShortIo,
+        errno => UsdError::from_errno(errno).unwrap_or(UsdError::Unknown { errno }),
+    }
+}
+
+/// An open file on the SD card. The file is closed when this is dropped.
+pub struct File {
+    file: *mut pros_sys::FILE,
+}
+unsafe impl Send for File {}
+
+impl File {
+    /// Opens a file with a C `fopen` mode, which must be null terminated.
+    fn open_with_mode(path: &str, mode: &'static [u8]) -> Result<Self, UsdError> {
+        if !is_installed() {
+            return Err(UsdError::NotInstalled);
+        }
+
+        let path = sd_path(path);
+        let file = unsafe { pros_sys::fopen(path.as_ptr(), mode.as_ptr().cast()) };
+        if file.is_null() {
+            return Err(last_error());
+        }
+        Ok(Self { file })
+    }
+
+    /// Opens an existing file for reading. Paths are relative to the root of the SD card.
+    pub fn open(path: &str) -> Result<Self, UsdError> {
+        Self::open_with_mode(path, b"rb\0")
+    }
+
+    /// Creates a file for writing, replacing its contents if it already exists.
+    pub fn create(path: &str) -> Result<Self, UsdError> {
+        Self::open_with_mode(path, b"wb\0")
+    }
+
+    /// Opens a file for writing at its end, creating it if it doesn't exist.
+    pub fn append(path: &str) -> Result<Self, UsdError> {
+        Self::open_with_mode(path, b"ab\0")
+    }
+
+    /// Reads the rest of the file.
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>, UsdError> {
+        let mut contents = Vec::new();
+        let mut chunk = [0; 512];
+        loop {
+            let read = self.read_chunk(&mut chunk)?;
+            if read == 0 {
+                return Ok(contents);
+            }
+            contents.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8]) -> Result<usize, UsdError> {
+        let read = unsafe { pros_sys::fread(buf.as_mut_ptr().cast(), 1, buf.len(), self.file) };
+        if read < buf.len() && unsafe { pros_sys::ferror(self.file) } != 0 {
+            return Err(last_error());
+        }
+        Ok(read)
+    }
+
+    /// Writes all of `buf` to the file.
+    pub fn write_all(&mut self, buf: &[u8]) -> Result<(), UsdError> {
+        let written = unsafe { pros_sys::fwrite(buf.as_ptr().cast(), 1, buf.len(), self.file) };
+        if written < buf.len() {
+            return Err(last_error());
+        }
+        Ok(())
+    }
+
+    /// Writes any buffered data to the SD card.
+    pub fn flush(&mut self) -> Result<(), UsdError> {
+        if unsafe { pros_sys::fflush(self.file) } != 0 {
+            return Err(last_error());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        unsafe {
+            pros_sys::fclose(self.file);
+        }
+    }
+}
+
+impl io::Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_chunk(buf)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to read from SD card"))
+    }
+}
+
+impl io::Write for File {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_all(buf)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to write to SD card"))?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        File::flush(self)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to flush to SD card"))
+    }
+}
+
+/// A file that collects writes in memory and writes them to the SD card in large blocks.
+///
+/// Writing to the card can block for several milliseconds, so writing every sample directly
+/// stalls whatever task is doing it. Flash is also erased and rewritten a block at a time,
+/// so many small writes wear the card more than a few large ones.
+///
+/// Whole [`BufferedFile::BLOCK_SIZE`] blocks are written once the buffer is full,
+/// everything buffered is written once the flush interval passes, and anything left is written when this is dropped.
+/// Data still buffered when power is cut is lost, so use [`flush_on_disable`]
+/// to write everything out at the end of each match.
+///
+/// With the `compression` feature, [`BufferedFile::compressed`] compresses each flush with [`crate::compress`].
+pub struct BufferedFile {
+    file: File,
+    buf: Vec<u8>,
+    #[cfg(feature = "compression")]
+    compressed: Option<Vec<u8>>,
+    capacity: usize,
+    flush_interval: Duration,
+    last_flush: u32,
+}
+
+impl BufferedFile {
+    /// The SD card's block size. Writes are grouped into multiples of this.
+    pub const BLOCK_SIZE: usize = 512;
+
+    /// Buffers writes to `file`, holding up to `capacity` bytes
+    /// and writing them out at least every `flush_interval`.
+    pub fn new(file: File, capacity: usize, flush_interval: Duration) -> Self {
+        let capacity = capacity.max(Self::BLOCK_SIZE);
+        Self {
+            file,
+            buf: Vec::with_capacity(capacity),
+            #[cfg(feature = "compression")]
+            compressed: None,
+            capacity,
+            flush_interval,
+            last_flush: unsafe { pros_sys::millis() },
+        }
+    }
+
+    /// Creates a file and buffers writes to it with 4 KiB of memory, flushing at least once a second.
+    pub fn create(path: &str) -> Result<Self, UsdError> {
+        Ok(Self::new(File::create(path)?, 4096, Duration::from_secs(1)))
+    }
+
+    /// Like [`BufferedFile::new`], but compresses the data.
+    /// Read the file back with [`compress::decompress_file`](crate::compress::decompress_file).
+    ///
+    /// Each flush is compressed separately, so longer flush intervals and larger capacities compress better.
+    #[cfg(feature = "compression")]
+    pub fn compressed(
+        mut file: File,
+        capacity: usize,
+        flush_interval: Duration,
+    ) -> Result<Self, UsdError> {
+        file.write_all(crate::compress::MAGIC)?;
+        let mut this = Self::new(
+            file,
+            capacity.min(crate::compress::MAX_CHUNK_LEN),
+            flush_interval,
+        );
+        this.compressed = Some(Vec::new());
+        Ok(this)
+    }
+
+    fn write_out(&mut self, len: usize) -> Result<(), UsdError> {
+        #[cfg(feature = "compression")]
+        if let Some(compressed) = &mut self.compressed {
+            compressed.clear();
+            crate::compress::write_chunk(&self.buf[..len], compressed);
+            self.file.write_all(compressed)?;
+            self.file.flush()?;
+            self.buf.drain(..len);
+            return Ok(());
+        }
+
+        self.file.write_all(&self.buf[..len])?;
+        self.file.flush()?;
+        self.buf.drain(..len);
+        Ok(())
+    }
+
+    /// Adds `buf` to the buffer, writing out whole blocks if it is full,
+    /// and everything including a partial block if the flush interval has passed.
+    pub fn write_all(&mut self, mut buf: &[u8]) -> Result<(), UsdError> {
+        while !buf.is_empty() {
+            let space = self.capacity - self.buf.len();
+            let (now, rest) = buf.split_at(space.min(buf.len()));
+            self.buf.extend_from_slice(now);
+            buf = rest;
+            if self.buf.len() == self.capacity {
+                self.write_blocks()?;
+            }
+        }
+        if unsafe { pros_sys::millis() } - self.last_flush >= self.flush_interval.as_millis() as u32
+        {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the complete blocks in the buffer, keeping any partial block for later.
+    fn write_blocks(&mut self) -> Result<(), UsdError> {
+        let len = self.buf.len() / Self::BLOCK_SIZE * Self::BLOCK_SIZE;
+        if len > 0 {
+            self.write_out(len)?;
+        }
+        self.last_flush = unsafe { pros_sys::millis() };
+        Ok(())
+    }
+
+    /// Writes everything buffered to the SD card, including a partial block.
+    pub fn flush(&mut self) -> Result<(), UsdError> {
+        if !self.buf.is_empty() {
+            self.write_out(self.buf.len())?;
+        }
+        self.last_flush = unsafe { pros_sys::millis() };
+        Ok(())
+    }
+
+    /// Returns how many bytes are waiting to be written.
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+impl Drop for BufferedFile {
+    fn drop(&mut self) {
+        _ = self.flush();
+    }
+}
+
+impl io::Write for BufferedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_all(buf)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to write to SD card"))?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        BufferedFile::flush(self)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to flush to SD card"))
+    }
+}
+
+/// Something that holds data in memory until it is flushed to the SD card.
+pub trait Flush {
+    fn flush(&mut self) -> Result<(), UsdError>;
+}
+
+impl Flush for File {
+    fn flush(&mut self) -> Result<(), UsdError> {
+        File::flush(self)
+    }
+}
+
+impl Flush for BufferedFile {
+    fn flush(&mut self) -> Result<(), UsdError> {
+        BufferedFile::flush(self)
+    }
+}
+
+/// Flushes a file or writer every time the robot is disabled, such as at the end of autonomous and of the match,
+/// so that the data survives the robot being turned off.
+///
+/// The returned task stops at the first transition after the writer is dropped.
+pub fn flush_on_disable<T: Flush + Send + 'static>(file: &Arc<Mutex<T>>) -> TaskHandle {
+    let file = Arc::downgrade(file);
+    task::spawn(move || {
+        for update in competition::updates() {
+            let Some(file) = file.upgrade() else {
+                return;
+            };
+            if update.current == CompetitionMode::Disabled {
+                if let Err(err) = file.lock().flush() {
+                    crate::error::report(&err);
+                }
+            }
+        }
+    })
+}
+
+/// Reads the entire contents of a file.
+pub fn read(path: &str) -> Result<Vec<u8>, UsdError> {
+    File::open(path)?.read_to_end()
+}
+
+/// Writes `contents` to a file, replacing it if it already exists.
+pub fn write(path: &str, contents: &[u8]) -> Result<(), UsdError> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.flush()
+}
+
+/// Saves a value to a file in the checksummed binary format, replacing the file if it exists.
+pub fn save<T: Encode + ?Sized>(path: &str, value: &T) -> Result<(), UsdError> {
+    write(path, &encode::to_checked_vec(value))
+}
+
+/// Loads a value saved with [`save`], returning `None` if the file doesn't exist or is corrupted.
+pub fn load<T: Decode>(path: &str) -> Result<Option<T>, UsdError> {
+    match read(path) {
+        Ok(contents) => Ok(encode::from_checked_slice(&contents).ok()),
+        Err(UsdError::NotFound) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Deletes a file.
+pub fn remove(path: &str) -> Result<(), UsdError> {
+    if unsafe { pros_sys::remove(sd_path(path).as_ptr()) } != 0 {
+        return Err(last_error());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+pub enum UsdError {
+    #[snafu(display("No SD card is inserted."))]
+    NotInstalled,
+    #[snafu(display("The file does not exist."))]
+    NotFound,
+    #[snafu(display("The file could not be accessed."))]
+    PermissionDenied,
+    #[snafu(display("Too many files are open."))]
+    TooManyOpenFiles,
+    #[snafu(display("The SD card is full."))]
+    Full,
+    #[snafu(display("The SD card could not be read from or written to."))]
+    Io,
+    #[snafu(display("Less data was read or written than expected."))]
+    ShortIo,
+    #[snafu(display("The SD card reported an unknown error (errno {errno})."))]
+    Unknown { errno: i32 },
+}
+impl core::error::Error for UsdError {}
+
+map_errno! {
+    UsdError {
+        ENOENT => Self::NotFound,
+        EACCES | EROFS => Self::PermissionDenied,
+        ENFILE | EMFILE => Self::TooManyOpenFiles,
+        ENOSPC => Self::Full,
+        EIO | EINVAL | EBADF => Self::Io,
+    }
+}
@@ -1,12 +1,26 @@
+#[cfg(feature = "alloc")]
 use crate::println;
-use core::{
-    alloc::{GlobalAlloc, Layout},
-    panic::PanicInfo,
-};
+#[cfg(feature = "alloc")]
+use core::alloc::{GlobalAlloc, Layout};
+use core::panic::PanicInfo;
 
 #[panic_handler]
 pub fn panic(_info: &PanicInfo) -> ! {
+    // Without a heap there is no console to print to.
+    // Panic messages can contain formatted floats, so with minimal-fmt only the location is printed.
+    #[cfg(all(feature = "alloc", feature = "minimal-fmt"))]
+    match _info.location() {
+        Some(location) => {
+            println!("Panicked at {location}");
+        }
+        None => {
+            println!("Panicked!");
+        }
+    }
+    #[cfg(all(feature = "alloc", not(feature = "minimal-fmt")))]
     println!("Panicked! {_info}");
+    #[cfg(feature = "alloc")]
+    crate::diagnostics::postmortem::__write_panic_bundle();
     let panicking_task = crate::task::current();
     // Make sure we eat up every cycle to stop execution
     panicking_task.set_priority(crate::task::TaskPriority::High);
@@ -18,7 +32,9 @@ pub fn panic(_info: &PanicInfo) -> ! {
     }
 }
 
+#[cfg(feature = "alloc")]
 struct Allocator;
+#[cfg(feature = "alloc")]
 unsafe impl GlobalAlloc for Allocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         pros_sys::memalign(layout.align() as _, layout.size() as _) as *mut u8
@@ -28,5 +44,6 @@ unsafe impl GlobalAlloc for Allocator {
     }
 }
 
+#[cfg(feature = "alloc")]
 #[global_allocator]
 static ALLOCATOR: Allocator = Allocator;
@@ -0,0 +1,63 @@
+//! Converts binary telemetry logs recorded by `pros::diagnostics::telemetry` to CSV or MCAP.
+//!
+//! ```text
+//! log-convert telemetry.bin telemetry.csv
+//! log-convert telemetry.bin telemetry.mcap
+//! ```
+//!
+//! The output format is picked from the output file's extension.
+//! Logs written with compression are decompressed automatically.
+//! MCAP files contain one JSON channel named `telemetry` and can be opened in Foxglove Studio.
+//!
+//! This is a host program, so build it from this directory with the host target, for example
+//! `cargo run --target x86_64-unknown-linux-gnu -- telemetry.bin telemetry.csv`.
+
+extern crate alloc;
+
+// Only the reading halves of these are used here; the brain does the writing.
+#[allow(dead_code)]
+#[path = "../../../pros/src/compress.rs"]
+mod compress;
+#[allow(dead_code)]
+#[path = "../../../pros/src/diagnostics/telemetry/format.rs"]
+mod format;
+#[allow(dead_code)]
+#[path = "../../../pros/src/encode/varint.rs"]
+mod varint;
+// Mirrors the crate's module layout so the `crate::encode` paths in `format` resolve here too.
+mod encode {
+    pub(crate) use super::varint;
+}
+mod mcap;
+
+use std::{fs, path::Path, process::ExitCode};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [input, output] = args.as_slice() else {
+        eprintln!("usage: log-convert <input.bin> <output.csv|output.mcap>");
+        return ExitCode::FAILURE;
+    };
+
+    match convert(Path::new(input), Path::new(output)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("log-convert: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn convert(input: &Path, output: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut log = fs::read(input)?;
+    if log.starts_with(compress::MAGIC) {
+        log = compress::decompress_file(&log)?;
+    }
+    let converted = match output.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => format::to_csv(&log)?.into_bytes(),
+        Some("mcap") => mcap::to_mcap(&log)?,
+        _ => return Err("the output file must end in .csv or .mcap".into()),
+    };
+    fs::write(output, converted)?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! A minimal, unindexed MCAP writer.
+//!
+//! See <https://mcap.dev/spec> for the format. Only the records needed for a single channel
+//! of JSON messages are written; viewers rebuild the index when they open the file.
+
+use std::fmt::Write;
+
+use crate::format::{FormatError, LogReader};
+
+const MAGIC: &[u8] = b"\x89MCAP0\r\n";
+
+const OP_HEADER: u8 = 0x01;
+const OP_FOOTER: u8 = 0x02;
+const OP_SCHEMA: u8 = 0x03;
+const OP_CHANNEL: u8 = 0x04;
+const OP_MESSAGE: u8 = 0x05;
+const OP_DATA_END: u8 = 0x0F;
+
+/// Converts a binary telemetry log to MCAP, with each row as a JSON message on the `telemetry` topic.
+pub fn to_mcap(log: &[u8]) -> Result<Vec<u8>, FormatError> {
+    let reader = LogReader::new(log)?;
+    let names: Vec<String> = reader
+        .schema()
+        .channels
+        .iter()
+        .map(|channel| channel.name.clone())
+        .collect();
+
+    let mut out = MAGIC.to_vec();
+
+    let mut header = Vec::new();
+    write_str(&mut header, "");
+    write_str(&mut header, "pros-rs log-convert");
+    write_record(&mut out, OP_HEADER, &header);
+
+    let mut json_schema =
+        String::from(r#"{"type":"object","properties":{"time_ms":{"type":"integer"}"#);
+    for name in &names {
+        _ = write!(json_schema, r#","{}":{{"type":"number"}}"#, escape(name));
+    }
+    json_schema.push_str("}}");
+
+    let mut schema = Vec::new();
+    schema.extend_from_slice(&1u16.to_le_bytes());
+    write_str(&mut schema, "telemetry");
+    write_str(&mut schema, "jsonschema");
+    write_bytes(&mut schema, json_schema.as_bytes());
+    write_record(&mut out, OP_SCHEMA, &schema);
+
+    let mut channel = Vec::new();
+    channel.extend_from_slice(&1u16.to_le_bytes());
+    channel.extend_from_slice(&1u16.to_le_bytes());
+    write_str(&mut channel, "telemetry");
+    write_str(&mut channel, "json");
+    // No metadata.
+    channel.extend_from_slice(&0u32.to_le_bytes());
+    write_record(&mut out, OP_CHANNEL, &channel);
+
+    for (sequence, row) in reader.enumerate() {
+        let mut json = format!(r#"{{"time_ms":{}"#, row.time_ms);
+        for (name, value) in names.iter().zip(&row.values) {
+            // JSON has no NaN, so channels that weren't recorded are left out.
+            if let Some(value) = value {
+                _ = write!(json, r#","{}":{value}"#, escape(name));
+            }
+        }
+        json.push('}');
+
+        let time_ns = row.time_ms as u64 * 1_000_000;
+        let mut message = Vec::new();
+        message.extend_from_slice(&1u16.to_le_bytes());
+        message.extend_from_slice(&(sequence as u32).to_le_bytes());
+        message.extend_from_slice(&time_ns.to_le_bytes());
+        message.extend_from_slice(&time_ns.to_le_bytes());
+        message.extend_from_slice(json.as_bytes());
+        write_record(&mut out, OP_MESSAGE, &message);
+    }
+
+    // A zero CRC means it wasn't calculated.
+    write_record(&mut out, OP_DATA_END, &0u32.to_le_bytes());
+
+    let mut footer = Vec::new();
+    footer.extend_from_slice(&0u64.to_le_bytes());
+    footer.extend_from_slice(&0u64.to_le_bytes());
+    footer.extend_from_slice(&0u32.to_le_bytes());
+    write_record(&mut out, OP_FOOTER, &footer);
+
+    out.extend_from_slice(MAGIC);
+    Ok(out)
+}
+
+fn write_record(out: &mut Vec<u8>, opcode: u8, content: &[u8]) {
+    out.push(opcode);
+    out.extend_from_slice(&(content.len() as u64).to_le_bytes());
+    out.extend_from_slice(content);
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_bytes(out, s.as_bytes());
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,211 @@
+//! Defines named paths on the host and writes them in the format read by `pros::path`.
+//!
+//! This is usually used from a build script, so the paths are written every time the program is built
+//! and can be embedded with `include_bytes!`:
+//! ```rust,no_run
+//! // build.rs
+//! use pros_paths::PathBook;
+//!
+//! fn main() {
+//!     let out = std::env::var("OUT_DIR").unwrap();
+//!     PathBook::new()
+//!         .path("left_side", |path| {
+//!             path.start(-60.0, -36.0, 0.0)
+//!                 .to(-24.0, -36.0)
+//!                 .speed(30.0)
+//!                 .curve_to(0.0, -12.0, 90.0)
+//!                 .full_speed()
+//!                 .to_facing(0.0, 24.0, 180.0)
+//!         })
+//!         .write_file(format!("{out}/paths.bin"))
+//!         .unwrap();
+//! }
+//! ```
+//!
+//! Coordinates and speeds are in whatever units the robot's code uses, with headings in degrees
+//! counterclockwise from the positive x axis, matching `pros::pose::Pose`.
+//!
+//! This is a host crate, so depend on it with a path from a build script's `[build-dependencies]`,
+//! which are built for the host.
+
+extern crate alloc;
+
+#[allow(dead_code)]
+#[path = "../../../pros/src/encode/crc.rs"]
+mod crc;
+#[allow(dead_code)]
+#[path = "../../../pros/src/path/format.rs"]
+mod format;
+#[allow(dead_code)]
+#[path = "../../../pros/src/encode/varint.rs"]
+mod varint;
+// Mirrors the crate's module layout so the `crate::encode` paths in `format` resolve here too.
+mod encode {
+    pub(crate) use super::{crc, varint};
+}
+
+use std::{fs, io};
+
+pub use format::{FormatError, Path, Waypoint};
+
+/// Builds the waypoints of one path.
+#[derive(Debug, Clone)]
+pub struct PathBuilder {
+    waypoints: Vec<Waypoint>,
+    max_speed: Option<f64>,
+    spacing: f64,
+}
+
+impl PathBuilder {
+    fn new() -> Self {
+        Self {
+            waypoints: Vec::new(),
+            max_speed: None,
+            spacing: 4.0,
+        }
+    }
+
+    /// Sets where the path starts. This replaces any waypoints already added.
+    pub fn start(mut self, x: f64, y: f64, heading: f64) -> Self {
+        self.waypoints.clear();
+        self.push(x, y, heading);
+        self
+    }
+
+    /// Drives straight to a point, facing the way the robot travels.
+    pub fn to(mut self, x: f64, y: f64) -> Self {
+        let (from_x, from_y) = self.last();
+        self.push(x, y, heading_between(from_x, from_y, x, y));
+        self
+    }
+
+    /// Drives straight to a point and ends up facing `heading`.
+    pub fn to_facing(mut self, x: f64, y: f64, heading: f64) -> Self {
+        self.push(x, y, heading);
+        self
+    }
+
+    /// Drives a smooth curve to a point, leaving the last waypoint along its heading
+    /// and arriving facing `heading`. Curves are split into waypoints [`spacing`](Self::spacing) apart.
+    pub fn curve_to(mut self, x: f64, y: f64, heading: f64) -> Self {
+        let Some(start) = self.waypoints.last().copied() else {
+            return self.start(x, y, heading);
+        };
+        let length = (x - start.x).hypot(y - start.y);
+        let (start_dx, start_dy) = direction(start.heading, length);
+        let (end_dx, end_dy) = direction(heading, length);
+        let steps = (length / self.spacing.max(1e-3)).ceil().max(1.0) as usize;
+
+        let mut previous = (start.x, start.y);
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            let (h00, h10, h01, h11) = (
+                2.0 * t * t * t - 3.0 * t * t + 1.0,
+                t * t * t - 2.0 * t * t + t,
+                -2.0 * t * t * t + 3.0 * t * t,
+                t * t * t - t * t,
+            );
+            let point_x = h00 * start.x + h10 * start_dx + h01 * x + h11 * end_dx;
+            let point_y = h00 * start.y + h10 * start_dy + h01 * y + h11 * end_dy;
+            let point_heading = if step == steps {
+                heading
+            } else {
+                heading_between(previous.0, previous.1, point_x, point_y)
+            };
+            self.push(point_x, point_y, point_heading);
+            previous = (point_x, point_y);
+        }
+        self
+    }
+
+    /// Limits the speed of the waypoints added after this.
+    pub fn speed(mut self, max_speed: f64) -> Self {
+        self.max_speed = Some(max_speed);
+        self
+    }
+
+    /// Removes the speed limit from the waypoints added after this.
+    pub fn full_speed(mut self) -> Self {
+        self.max_speed = None;
+        self
+    }
+
+    /// Sets how far apart the waypoints of curves added after this are. The default is 4 units.
+    pub fn spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    fn push(&mut self, x: f64, y: f64, heading: f64) {
+        self.waypoints.push(Waypoint {
+            x,
+            y,
+            heading,
+            max_speed: self.max_speed,
+        });
+    }
+
+    /// The position of the last waypoint, or the origin if there isn't one.
+    fn last(&self) -> (f64, f64) {
+        self.waypoints
+            .last()
+            .map_or((0.0, 0.0), |waypoint| (waypoint.x, waypoint.y))
+    }
+}
+
+/// Returns the heading in degrees of travel from one point to another.
+fn heading_between(from_x: f64, from_y: f64, to_x: f64, to_y: f64) -> f64 {
+    (to_y - from_y).atan2(to_x - from_x).to_degrees()
+}
+
+/// Returns a vector of the given length pointing along a heading in degrees.
+fn direction(heading: f64, length: f64) -> (f64, f64) {
+    let (sin, cos) = heading.to_radians().sin_cos();
+    (cos * length, sin * length)
+}
+
+/// A set of named paths to write to one file.
+#[derive(Debug, Clone, Default)]
+pub struct PathBook {
+    paths: Vec<Path>,
+}
+
+impl PathBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a path built by `build`, replacing any path with the same name.
+    pub fn path(mut self, name: &str, build: impl FnOnce(PathBuilder) -> PathBuilder) -> Self {
+        let path = Path {
+            name: name.to_string(),
+            waypoints: build(PathBuilder::new()).waypoints,
+        };
+        match self.paths.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = path,
+            None => self.paths.push(path),
+        }
+        self
+    }
+
+    pub fn paths(&self) -> &[Path] {
+        &self.paths
+    }
+
+    /// Encodes the paths for `pros::path::PathSet::parse`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        format::write(&self.paths)
+    }
+
+    /// Writes the paths to a file, such as one in a build script's `OUT_DIR` or on an SD card.
+    pub fn write_file(&self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Reads paths from a file written by [`write_file`](Self::write_file).
+    pub fn read_file(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        let paths = format::read(&fs::read(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        Ok(Self { paths })
+    }
+}
@@ -0,0 +1,271 @@
+//! Draws a robot's trajectory on the field in a window, updating live as it is written.
+//!
+//! ```text
+//! sim-view trajectory.csv
+//! cargo test drive_to_goal -- --nocapture | sim-view
+//! sim-view --path planned.csv trajectory.csv
+//! ```
+//!
+//! Trajectories are CSV with `time_ms,x,y,heading` rows, as written by
+//! `pros::testing::SimulatedRobot::trajectory_csv`, and are read from stdin if no file is given.
+//! Lines that aren't rows, such as a header or other program output, are skipped,
+//! so a simulation can print its rows as it runs and be watched in real time.
+//! A planned path to draw underneath can be given with `--path`, as CSV with `x,y` rows.
+//!
+//! Coordinates are in inches from the center of a 12 foot field, with the heading in degrees
+//! counterclockwise from the positive x axis, matching `pros::pose::Pose`.
+//!
+//! This is a host program, so build it from this directory with the host target, for example
+//! `cargo run --target x86_64-unknown-linux-gnu -- trajectory.csv`.
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    process::ExitCode,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use minifb::{Key, Window, WindowOptions};
+
+const SIZE: usize = 720;
+const FIELD_SIZE: f64 = 144.0;
+const TILES: usize = 6;
+const ROBOT_SIZE: f64 = 18.0;
+
+const BACKGROUND_COLOR: u32 = 0x000000;
+const TILE_COLOR: u32 = 0x404040;
+const PLANNED_COLOR: u32 = 0x00A0A0;
+const PATH_COLOR: u32 = 0xFFFF00;
+const ROBOT_COLOR: u32 = 0xFF0000;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    time_ms: u32,
+    x: f64,
+    y: f64,
+    heading: f64,
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut planned_path = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--path" => planned_path = args.next(),
+            "-h" | "--help" => {
+                eprintln!("usage: sim-view [--path planned.csv] [trajectory.csv]");
+                return ExitCode::SUCCESS;
+            }
+            _ => input = Some(arg),
+        }
+    }
+
+    let planned = match planned_path.map(fs::read_to_string) {
+        Some(Ok(text)) => text.lines().filter_map(parse_point).collect(),
+        Some(Err(err)) => {
+            eprintln!("sim-view: couldn't read the planned path: {err}");
+            return ExitCode::FAILURE;
+        }
+        None => Vec::new(),
+    };
+
+    let reader: Box<dyn BufRead + Send> = match input {
+        Some(path) => match fs::File::open(&path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(err) => {
+                eprintln!("sim-view: couldn't open {path}: {err}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+
+    match run(spawn_reader(reader), &planned) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("sim-view: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reads samples on another thread so the window stays responsive while waiting for input.
+fn spawn_reader(reader: Box<dyn BufRead + Send>) -> Receiver<Sample> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            if let Some(sample) = parse_sample(&line) {
+                if sender.send(sample).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    receiver
+}
+
+fn parse_sample(line: &str) -> Option<Sample> {
+    let mut fields = line.split(',').map(str::trim);
+    let sample = Sample {
+        time_ms: fields.next()?.parse().ok()?,
+        x: fields.next()?.parse().ok()?,
+        y: fields.next()?.parse().ok()?,
+        heading: fields.next()?.parse().ok()?,
+    };
+    Some(sample)
+}
+
+fn parse_point(line: &str) -> Option<(f64, f64)> {
+    let mut fields = line.split(',').map(str::trim);
+    Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+}
+
+fn run(samples: Receiver<Sample>, planned: &[(f64, f64)]) -> Result<(), minifb::Error> {
+    let mut window = Window::new("sim-view", SIZE, SIZE, WindowOptions::default())?;
+    window.set_target_fps(60);
+
+    let mut canvas = Canvas::new();
+    let mut trajectory: Vec<Sample> = Vec::new();
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        let received = trajectory.len();
+        trajectory.extend(samples.try_iter());
+        if trajectory.len() != received {
+            if let Some(last) = trajectory.last() {
+                window.set_title(&format!(
+                    "sim-view - {:.2} s - ({:.1}, {:.1}) {:.1}°",
+                    last.time_ms as f64 / 1000.0,
+                    last.x,
+                    last.y,
+                    last.heading
+                ));
+            }
+        }
+
+        canvas.draw(&trajectory, planned);
+        window.update_with_buffer(&canvas.pixels, SIZE, SIZE)?;
+        thread::sleep(Duration::from_millis(1));
+    }
+    Ok(())
+}
+
+/// The window's pixels, drawn the same way as `pros::screen::field::FieldView` draws on the brain.
+struct Canvas {
+    pixels: Vec<u32>,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self {
+            pixels: vec![BACKGROUND_COLOR; SIZE * SIZE],
+        }
+    }
+
+    fn draw(&mut self, trajectory: &[Sample], planned: &[(f64, f64)]) {
+        self.pixels.fill(BACKGROUND_COLOR);
+
+        let last = SIZE as i32 - 1;
+        for tile in 0..=TILES {
+            let offset = ((SIZE * tile / TILES) as i32).min(last);
+            self.line((offset, 0), (offset, last), TILE_COLOR);
+            self.line((0, offset), (last, offset), TILE_COLOR);
+        }
+
+        for pair in planned.windows(2) {
+            self.line(to_screen(pair[0]), to_screen(pair[1]), PLANNED_COLOR);
+        }
+        for pair in trajectory.windows(2) {
+            self.line(
+                to_screen((pair[0].x, pair[0].y)),
+                to_screen((pair[1].x, pair[1].y)),
+                PATH_COLOR,
+            );
+        }
+
+        if let Some(robot) = trajectory.last() {
+            let center = to_screen((robot.x, robot.y));
+            let radius = (ROBOT_SIZE / 2.0 * SIZE as f64 / FIELD_SIZE) as i32;
+            self.circle(center, radius, ROBOT_COLOR);
+            let heading = robot.heading.to_radians();
+            let nose = to_screen((
+                robot.x + heading.cos() * ROBOT_SIZE / 2.0,
+                robot.y + heading.sin() * ROBOT_SIZE / 2.0,
+            ));
+            self.line(center, nose, ROBOT_COLOR);
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: u32) {
+        if (0..SIZE as i32).contains(&x) && (0..SIZE as i32).contains(&y) {
+            self.pixels[y as usize * SIZE + x as usize] = color;
+        }
+    }
+
+    /// Bresenham's line algorithm.
+    fn line(&mut self, (mut x0, mut y0): (i32, i32), (x1, y1): (i32, i32), color: u32) {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x0, y0, color);
+            if x0 == x1 && y0 == y1 {
+                return;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    /// The midpoint circle algorithm.
+    fn circle(&mut self, (cx, cy): (i32, i32), radius: i32, color: u32) {
+        let (mut x, mut y) = (radius, 0);
+        let mut err = 1 - radius;
+        while x >= y {
+            for (px, py) in [
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ] {
+                self.set(cx + px, cy + py, color);
+            }
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+}
+
+/// Converts field coordinates to pixels, with positive y up.
+/// Points far off the field are pulled in so that lines to them don't take long to draw.
+fn to_screen((x, y): (f64, f64)) -> (i32, i32) {
+    let scale = SIZE as f64 / FIELD_SIZE;
+    let center = SIZE as f64 / 2.0;
+    let limit = SIZE as f64 * 2.0;
+    (
+        (center + x * scale).clamp(-limit, limit) as i32,
+        (center - y * scale).clamp(-limit, limit) as i32,
+    )
+}